@@ -114,6 +114,11 @@ pub struct Config {
     #[partial_struct(ty = "PartialMempool")]
     #[partial_struct(serde(default))]
     pub mempool: Mempool,
+
+    /// Transport configuration for the HTTP requests performed by data request retrievals
+    #[partial_struct(ty = "PartialRetrievalTransport")]
+    #[partial_struct(serde(default))]
+    pub retrieval_transport: RetrievalTransport,
 }
 
 /// Log-specific configuration.
@@ -283,6 +288,20 @@ pub struct Storage {
     #[partial_struct(skip)]
     #[partial_struct(serde(default))]
     pub master_key_import_path: Option<PathBuf>,
+    /// Maintain an index of the transactions and unspent outputs of every address, which is
+    /// needed for the `getAddressTransactions` and `getAddressUtxos` JSON-RPC methods. The index
+    /// takes additional disk space.
+    pub address_index: bool,
+    /// Maintain an index of data requests by requester, RAD request, stage and epoch, which is
+    /// needed for the `queryDataRequests` JSON-RPC method. The index takes additional disk space.
+    pub data_request_index: bool,
+    /// Run in pruned mode: after consolidating a superblock, delete the blocks, transactions,
+    /// superblocks and data request reports and audit records that are older than the window of
+    /// recent superblocks set by `pruning_keep_superblocks`. The UTXO set, the reputation state and the unresolved
+    /// data requests are always kept, as they are part of the chain state.
+    pub pruning: bool,
+    /// Number of recent consolidated superblocks whose blocks are kept in pruned mode
+    pub pruning_keep_superblocks: u32,
 }
 
 /// JsonRPC API configuration
@@ -319,6 +338,12 @@ pub struct Mining {
         rename = "data_request_timeout_milliseconds"
     ))]
     pub data_request_timeout: Duration,
+    /// Number of threads dedicated to running the RADON scripts of the data requests that the
+    /// node is resolving, so that script execution never blocks the rest of the node.
+    pub data_request_workers: u16,
+    /// Persist the raw responses of the sources, the execution traces of the scripts and the
+    /// committed values of every data request that the node commits to.
+    pub data_request_audit_log: bool,
     /// Genesis block path
     pub genesis_path: String,
     /// Percentage to redistribute mint reward in another address
@@ -332,6 +357,70 @@ pub struct Mining {
     /// Minimum value transfer transaction fee that allows being included into a block
     #[partial_struct(serde(default, rename = "minimum_vtt_fee_nanowits"))]
     pub minimum_vtt_fee: u64,
+    /// Restrictions on the data sources that the node is willing to contact
+    #[partial_struct(ty = "PartialRetrievalPolicy")]
+    #[partial_struct(serde(default))]
+    pub retrieval_policy: RetrievalPolicy,
+    /// Criteria to decide which of the data requests that the node is eligible for are worth
+    /// committing to
+    #[partial_struct(ty = "PartialDataRequestSelection")]
+    #[partial_struct(serde(default))]
+    pub data_request_selection: DataRequestSelection,
+    /// Custom RADON operators to enable among the ones that ship with the RAD engine. They are
+    /// ignored in mainnet.
+    #[partial_struct(skip)]
+    #[partial_struct(serde(default))]
+    pub custom_radon_operators: Vec<CustomRadonOperator>,
+}
+
+/// Custom RADON operator enabled under an operator code
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CustomRadonOperator {
+    /// Operator code, in the range reserved for custom operators
+    pub code: u8,
+    /// Name of the operator
+    pub name: String,
+}
+
+/// Policy applied to the sources of a data request before committing to resolve it
+#[derive(PartialStruct, Debug, Clone, PartialEq)]
+#[partial_struct(derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq))]
+pub struct RetrievalPolicy {
+    /// If not empty, only hosts matching one of these patterns will be contacted.
+    /// A pattern is either a host name or IP address, or `*.` followed by a domain, which matches
+    /// any of its subdomains.
+    pub allowed_hosts: Vec<String>,
+    /// Hosts matching any of these patterns will never be contacted
+    pub denied_hosts: Vec<String>,
+    /// Refuse to contact loopback, private and link-local addresses, as well as `localhost`.
+    /// Host names are checked again after resolving them, when retrieving the sources.
+    pub deny_private_addresses: bool,
+    /// Maximum number of retrievals from the same host during a single epoch.
+    /// Set to 0 to disable this limit.
+    pub max_retrievals_per_host_per_epoch: u16,
+    /// Maximum number of retrievals that can be in progress at the same time.
+    /// Set to 0 to disable this limit.
+    pub max_concurrent_retrievals: u16,
+}
+
+/// Criteria to select which eligible data requests to commit to
+#[derive(PartialStruct, Debug, Clone, PartialEq)]
+#[partial_struct(derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq))]
+pub struct DataRequestSelection {
+    /// Minimum witness reward per source of the data request.
+    /// Set to 0 to disable this criterion.
+    #[partial_struct(serde(default, rename = "min_reward_per_retrieval_nanowits"))]
+    pub min_reward_per_retrieval: u64,
+    /// Maximum total collateral locked in commitments that are waiting for their tally.
+    /// Set to 0 to disable this criterion.
+    #[partial_struct(serde(default, rename = "max_collateral_exposure_nanowits"))]
+    pub max_collateral_exposure: u64,
+    /// Maximum number of commitments that are waiting for their tally.
+    /// Set to 0 to disable this criterion.
+    pub max_open_commitments: u32,
+    /// If not empty, only data requests with at least one source from these domains will be
+    /// selected. Patterns follow the same syntax as in `RetrievalPolicy`.
+    pub source_domains: Vec<String>,
 }
 
 /// NTP-related configuration
@@ -364,6 +453,50 @@ pub struct Mempool {
     pub max_reinserted_transactions: u32,
 }
 
+/// Transport configuration for the HTTP requests performed by data request retrievals, used
+/// both by the node and by the wallet
+#[derive(PartialStruct, Debug, Clone, PartialEq)]
+#[partial_struct(derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq))]
+pub struct RetrievalTransport {
+    /// Proxy used for retrievals of `http://` URLs, e.g. `http://127.0.0.1:3128`
+    #[partial_struct(skip)]
+    #[partial_struct(serde(default))]
+    pub http_proxy: Option<String>,
+    /// Proxy used for retrievals of `https://` URLs, e.g. `http://127.0.0.1:3128`
+    #[partial_struct(skip)]
+    #[partial_struct(serde(default))]
+    pub https_proxy: Option<String>,
+    /// SOCKS5 proxy used for all retrievals, e.g. `127.0.0.1:9050` for Tor.
+    /// Takes precedence over `http_proxy` and `https_proxy`. Host names are resolved by the
+    /// proxy unless the `socks5://` scheme is explicitly used.
+    #[partial_struct(skip)]
+    #[partial_struct(serde(default))]
+    pub socks5_proxy: Option<String>,
+    /// Maximum time to establish the connection with the data source.
+    /// Set to 0 to use the default of the HTTP client.
+    #[partial_struct(serde(
+        default,
+        deserialize_with = "from_millis",
+        serialize_with = "to_millis",
+        rename = "connect_timeout_milliseconds"
+    ))]
+    pub connect_timeout: Duration,
+    /// Maximum time for a whole retrieval, including reading the response body.
+    /// Set to 0 to disable timeouts.
+    #[partial_struct(serde(
+        default,
+        deserialize_with = "from_millis",
+        serialize_with = "to_millis",
+        rename = "timeout_milliseconds"
+    ))]
+    pub timeout: Duration,
+    /// Path to a PEM file with the certificate authorities used to validate the TLS
+    /// certificates of the data sources, replacing the ones of the system
+    #[partial_struct(skip)]
+    #[partial_struct(serde(default))]
+    pub ca_bundle: Option<PathBuf>,
+}
+
 fn to_partial_consensus_constants(c: &ConsensusConstants) -> PartialConsensusConstants {
     PartialConsensusConstants {
         checkpoint_zero_timestamp: Some(c.checkpoint_zero_timestamp),
@@ -420,6 +553,10 @@ impl Config {
             rocksdb: Rocksdb::from_partial(&config.rocksdb, defaults),
             ntp: Ntp::from_partial(&config.ntp, defaults),
             mempool: Mempool::from_partial(&config.mempool, defaults),
+            retrieval_transport: RetrievalTransport::from_partial(
+                &config.retrieval_transport,
+                defaults,
+            ),
         }
     }
 
@@ -436,6 +573,7 @@ impl Config {
             rocksdb: self.rocksdb.to_partial(),
             ntp: self.ntp.to_partial(),
             mempool: self.mempool.to_partial(),
+            retrieval_transport: self.retrieval_transport.to_partial(),
         }
     }
 }
@@ -684,6 +822,22 @@ impl Storage {
                 .to_owned()
                 .unwrap_or_else(|| defaults.storage_db_path()),
             master_key_import_path: config.master_key_import_path.clone(),
+            address_index: config
+                .address_index
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_address_index()),
+            data_request_index: config
+                .data_request_index
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_data_request_index()),
+            pruning: config
+                .pruning
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_pruning()),
+            pruning_keep_superblocks: config
+                .pruning_keep_superblocks
+                .to_owned()
+                .unwrap_or_else(|| defaults.storage_pruning_keep_superblocks()),
         }
     }
 
@@ -694,6 +848,10 @@ impl Storage {
             password: None,
             db_path: Some(self.db_path.clone()),
             master_key_import_path: self.master_key_import_path.clone(),
+            address_index: Some(self.address_index),
+            data_request_index: Some(self.data_request_index),
+            pruning: Some(self.pruning),
+            pruning_keep_superblocks: Some(self.pruning_keep_superblocks),
         }
     }
 }
@@ -740,6 +898,14 @@ impl Mining {
                 .data_request_max_retrievals_per_epoch
                 .to_owned()
                 .unwrap_or_else(|| defaults.mining_data_request_max_retrievals_per_epoch()),
+            data_request_workers: config
+                .data_request_workers
+                .to_owned()
+                .unwrap_or_else(|| defaults.mining_data_request_workers()),
+            data_request_audit_log: config
+                .data_request_audit_log
+                .to_owned()
+                .unwrap_or_else(|| defaults.mining_data_request_audit_log()),
             genesis_path: config
                 .genesis_path
                 .clone()
@@ -757,6 +923,12 @@ impl Mining {
                 .minimum_vtt_fee
                 .to_owned()
                 .unwrap_or_else(|| defaults.mining_minimum_vtt_fee()),
+            retrieval_policy: RetrievalPolicy::from_partial(&config.retrieval_policy, defaults),
+            data_request_selection: DataRequestSelection::from_partial(
+                &config.data_request_selection,
+                defaults,
+            ),
+            custom_radon_operators: config.custom_radon_operators.clone(),
         }
     }
 
@@ -765,11 +937,85 @@ impl Mining {
             enabled: Some(self.enabled),
             data_request_timeout: Some(self.data_request_timeout),
             data_request_max_retrievals_per_epoch: Some(self.data_request_max_retrievals_per_epoch),
+            data_request_workers: Some(self.data_request_workers),
+            data_request_audit_log: Some(self.data_request_audit_log),
             genesis_path: Some(self.genesis_path.clone()),
             mint_external_percentage: Some(self.mint_external_percentage),
             mint_external_address: self.mint_external_address.clone(),
             transactions_pool_total_weight_limit: Some(self.transactions_pool_total_weight_limit),
             minimum_vtt_fee: Some(self.minimum_vtt_fee),
+            retrieval_policy: self.retrieval_policy.to_partial(),
+            data_request_selection: self.data_request_selection.to_partial(),
+            custom_radon_operators: self.custom_radon_operators.clone(),
+        }
+    }
+}
+
+impl RetrievalPolicy {
+    pub fn from_partial(config: &PartialRetrievalPolicy, defaults: &dyn Defaults) -> Self {
+        RetrievalPolicy {
+            allowed_hosts: config
+                .allowed_hosts
+                .clone()
+                .unwrap_or_else(|| defaults.mining_retrieval_policy_allowed_hosts()),
+            denied_hosts: config
+                .denied_hosts
+                .clone()
+                .unwrap_or_else(|| defaults.mining_retrieval_policy_denied_hosts()),
+            deny_private_addresses: config
+                .deny_private_addresses
+                .to_owned()
+                .unwrap_or_else(|| defaults.mining_retrieval_policy_deny_private_addresses()),
+            max_retrievals_per_host_per_epoch: config
+                .max_retrievals_per_host_per_epoch
+                .to_owned()
+                .unwrap_or_else(|| {
+                    defaults.mining_retrieval_policy_max_retrievals_per_host_per_epoch()
+                }),
+            max_concurrent_retrievals: config
+                .max_concurrent_retrievals
+                .to_owned()
+                .unwrap_or_else(|| defaults.mining_retrieval_policy_max_concurrent_retrievals()),
+        }
+    }
+
+    pub fn to_partial(&self) -> PartialRetrievalPolicy {
+        PartialRetrievalPolicy {
+            allowed_hosts: Some(self.allowed_hosts.clone()),
+            denied_hosts: Some(self.denied_hosts.clone()),
+            deny_private_addresses: Some(self.deny_private_addresses),
+            max_retrievals_per_host_per_epoch: Some(self.max_retrievals_per_host_per_epoch),
+            max_concurrent_retrievals: Some(self.max_concurrent_retrievals),
+        }
+    }
+}
+
+impl DataRequestSelection {
+    pub fn from_partial(config: &PartialDataRequestSelection, defaults: &dyn Defaults) -> Self {
+        DataRequestSelection {
+            min_reward_per_retrieval: config.min_reward_per_retrieval.to_owned().unwrap_or_else(
+                || defaults.mining_data_request_selection_min_reward_per_retrieval(),
+            ),
+            max_collateral_exposure: config.max_collateral_exposure.to_owned().unwrap_or_else(
+                || defaults.mining_data_request_selection_max_collateral_exposure(),
+            ),
+            max_open_commitments: config
+                .max_open_commitments
+                .to_owned()
+                .unwrap_or_else(|| defaults.mining_data_request_selection_max_open_commitments()),
+            source_domains: config
+                .source_domains
+                .clone()
+                .unwrap_or_else(|| defaults.mining_data_request_selection_source_domains()),
+        }
+    }
+
+    pub fn to_partial(&self) -> PartialDataRequestSelection {
+        PartialDataRequestSelection {
+            min_reward_per_retrieval: Some(self.min_reward_per_retrieval),
+            max_collateral_exposure: Some(self.max_collateral_exposure),
+            max_open_commitments: Some(self.max_open_commitments),
+            source_domains: Some(self.source_domains.clone()),
         }
     }
 }
@@ -823,6 +1069,36 @@ impl Mempool {
     }
 }
 
+impl RetrievalTransport {
+    pub fn from_partial(config: &PartialRetrievalTransport, defaults: &dyn Defaults) -> Self {
+        RetrievalTransport {
+            http_proxy: config.http_proxy.clone(),
+            https_proxy: config.https_proxy.clone(),
+            socks5_proxy: config.socks5_proxy.clone(),
+            connect_timeout: config
+                .connect_timeout
+                .to_owned()
+                .unwrap_or_else(|| defaults.retrieval_transport_connect_timeout()),
+            timeout: config
+                .timeout
+                .to_owned()
+                .unwrap_or_else(|| defaults.retrieval_transport_timeout()),
+            ca_bundle: config.ca_bundle.clone(),
+        }
+    }
+
+    pub fn to_partial(&self) -> PartialRetrievalTransport {
+        PartialRetrievalTransport {
+            http_proxy: self.http_proxy.clone(),
+            https_proxy: self.https_proxy.clone(),
+            socks5_proxy: self.socks5_proxy.clone(),
+            connect_timeout: Some(self.connect_timeout),
+            timeout: Some(self.timeout),
+            ca_bundle: self.ca_bundle.clone(),
+        }
+    }
+}
+
 /// Wallet-specific configuration.
 #[derive(PartialStruct, Serialize, Debug, Clone, PartialEq)]
 #[partial_struct(derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq))]
@@ -1157,6 +1433,16 @@ mod tests {
         let config = Storage::from_partial(&partial_config, &Testnet);
 
         assert_eq!(config.db_path.to_str(), Testnet.storage_db_path().to_str());
+        assert_eq!(config.address_index, Testnet.storage_address_index());
+        assert_eq!(
+            config.data_request_index,
+            Testnet.storage_data_request_index()
+        );
+        assert_eq!(config.pruning, Testnet.storage_pruning());
+        assert_eq!(
+            config.pruning_keep_superblocks,
+            Testnet.storage_pruning_keep_superblocks()
+        );
     }
 
     #[test]
@@ -1166,10 +1452,18 @@ mod tests {
             password: None, // password should not be exported
             db_path: Some(PathBuf::from("other")),
             master_key_import_path: None,
+            address_index: Some(true),
+            data_request_index: Some(true),
+            pruning: Some(true),
+            pruning_keep_superblocks: Some(10),
         };
         let config = Storage::from_partial(&partial_config, &Testnet);
 
         assert_eq!(config.db_path.to_str(), Some("other"));
+        assert!(config.address_index);
+        assert!(config.data_request_index);
+        assert!(config.pruning);
+        assert_eq!(config.pruning_keep_superblocks, 10);
     }
 
     #[test]
@@ -1246,6 +1540,85 @@ mod tests {
         assert_eq!(config.requested_blocks_batch_limit, 99);
     }
 
+    #[test]
+    fn test_retrieval_policy_default_from_partial() {
+        let partial_config = PartialMining::default();
+        let config = Mining::from_partial(&partial_config, &Testnet);
+
+        assert!(config.retrieval_policy.allowed_hosts.is_empty());
+        assert!(config.retrieval_policy.denied_hosts.is_empty());
+        assert_eq!(config.retrieval_policy.deny_private_addresses, false);
+        assert_eq!(config.retrieval_policy.max_retrievals_per_host_per_epoch, 0);
+        assert_eq!(config.retrieval_policy.max_concurrent_retrievals, 0);
+    }
+
+    #[test]
+    fn test_retrieval_policy_from_partial() {
+        let partial_config = PartialMining {
+            retrieval_policy: PartialRetrievalPolicy {
+                denied_hosts: Some(vec!["*.example.com".to_string()]),
+                deny_private_addresses: Some(true),
+                max_retrievals_per_host_per_epoch: Some(3),
+                ..PartialRetrievalPolicy::default()
+            },
+            ..PartialMining::default()
+        };
+        let config = Mining::from_partial(&partial_config, &Testnet);
+
+        assert!(config.retrieval_policy.allowed_hosts.is_empty());
+        assert_eq!(
+            config.retrieval_policy.denied_hosts,
+            vec!["*.example.com".to_string()]
+        );
+        assert_eq!(config.retrieval_policy.deny_private_addresses, true);
+        assert_eq!(config.retrieval_policy.max_retrievals_per_host_per_epoch, 3);
+        assert_eq!(config.retrieval_policy.max_concurrent_retrievals, 0);
+        assert_eq!(
+            config
+                .to_partial()
+                .retrieval_policy
+                .max_concurrent_retrievals,
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_retrieval_transport_default_from_partial() {
+        let partial_config = PartialRetrievalTransport::default();
+        let config = RetrievalTransport::from_partial(&partial_config, &Testnet);
+
+        assert_eq!(config.http_proxy, None);
+        assert_eq!(config.https_proxy, None);
+        assert_eq!(config.socks5_proxy, None);
+        assert_eq!(
+            config.connect_timeout,
+            Testnet.retrieval_transport_connect_timeout()
+        );
+        assert_eq!(config.timeout, Testnet.retrieval_transport_timeout());
+        assert_eq!(config.ca_bundle, None);
+    }
+
+    #[test]
+    fn test_retrieval_transport_from_partial() {
+        let partial_config = PartialRetrievalTransport {
+            socks5_proxy: Some("127.0.0.1:9050".to_string()),
+            connect_timeout: Some(Duration::from_millis(500)),
+            ca_bundle: Some(PathBuf::from("ca.pem")),
+            ..PartialRetrievalTransport::default()
+        };
+        let config = RetrievalTransport::from_partial(&partial_config, &Testnet);
+
+        assert_eq!(config.http_proxy, None);
+        assert_eq!(config.socks5_proxy, Some("127.0.0.1:9050".to_string()));
+        assert_eq!(config.connect_timeout, Duration::from_millis(500));
+        assert_eq!(config.timeout, Testnet.retrieval_transport_timeout());
+        assert_eq!(config.ca_bundle, Some(PathBuf::from("ca.pem")));
+        assert_eq!(
+            config.to_partial().connect_timeout,
+            Some(Duration::from_millis(500))
+        );
+    }
+
     #[test]
     fn test_jsonrpc_default_from_partial() {
         let partial_config = PartialJsonRPC::default();
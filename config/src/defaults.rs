@@ -45,6 +45,26 @@ pub trait Defaults {
     /// Default path for the database
     fn storage_db_path(&self) -> PathBuf;
 
+    /// Address index of the transactions of every address: disabled
+    fn storage_address_index(&self) -> bool {
+        false
+    }
+
+    /// Index of data requests by requester, RAD request, stage and epoch: disabled
+    fn storage_data_request_index(&self) -> bool {
+        false
+    }
+
+    /// Pruned mode: disabled, all the blocks are kept
+    fn storage_pruning(&self) -> bool {
+        false
+    }
+
+    /// Recent superblocks whose blocks are kept in pruned mode: 1000, around five days in mainnet
+    fn storage_pruning_keep_superblocks(&self) -> u32 {
+        1000
+    }
+
     /// Default period for bootstrap peers
     fn connections_bootstrap_peers_period(&self) -> Duration {
         Duration::from_secs(5)
@@ -192,6 +212,16 @@ pub trait Defaults {
         core::u16::MAX
     }
 
+    /// Number of threads for running RADON scripts: 2
+    fn mining_data_request_workers(&self) -> u16 {
+        2
+    }
+
+    /// Audit log of the resolved data requests: disabled
+    fn mining_data_request_audit_log(&self) -> bool {
+        false
+    }
+
     /// Genesis block path, "./genesis_block.json" by default
     fn mining_genesis_path(&self) -> String {
         "genesis_block.json".to_string()
@@ -220,6 +250,51 @@ pub trait Defaults {
         0
     }
 
+    /// Hosts allowed by the retrieval policy: empty, meaning that any host is allowed
+    fn mining_retrieval_policy_allowed_hosts(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Hosts denied by the retrieval policy: none
+    fn mining_retrieval_policy_denied_hosts(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Private addresses are allowed by default
+    fn mining_retrieval_policy_deny_private_addresses(&self) -> bool {
+        false
+    }
+
+    /// No limit of retrievals per host and epoch
+    fn mining_retrieval_policy_max_retrievals_per_host_per_epoch(&self) -> u16 {
+        0
+    }
+
+    /// No limit of concurrent retrievals
+    fn mining_retrieval_policy_max_concurrent_retrievals(&self) -> u16 {
+        0
+    }
+
+    /// No minimum reward per retrieval for selecting data requests
+    fn mining_data_request_selection_min_reward_per_retrieval(&self) -> u64 {
+        0
+    }
+
+    /// No limit of collateral locked in open commitments
+    fn mining_data_request_selection_max_collateral_exposure(&self) -> u64 {
+        0
+    }
+
+    /// No limit of open commitments
+    fn mining_data_request_selection_max_open_commitments(&self) -> u32 {
+        0
+    }
+
+    /// Data requests with sources from any domain are selected
+    fn mining_data_request_selection_source_domains(&self) -> Vec<String> {
+        vec![]
+    }
+
     fn consensus_constants_max_vt_weight(&self) -> u32 {
         20_000
     }
@@ -407,6 +482,16 @@ pub trait Defaults {
     fn mempool_max_reinserted_transactions(&self) -> u32 {
         100
     }
+
+    /// Connect timeout for retrievals, 0 means the default of the HTTP client
+    fn retrieval_transport_connect_timeout(&self) -> Duration {
+        Duration::from_secs(0)
+    }
+
+    /// Timeout for retrievals, 0 means no timeout
+    fn retrieval_transport_timeout(&self) -> Duration {
+        Duration::from_secs(0)
+    }
 }
 
 /// Struct that will implement all the development defaults
@@ -0,0 +1,306 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chain::{
+        Block, Epoch, Hash, Hashable, Input, OutputPointer, PointerToBlock, PublicKeyHash,
+        ValueTransferOutput,
+    },
+    transaction::Transaction,
+};
+
+/// Whether an address spent value in a transaction, or only received value in it
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TransactionDirection {
+    /// The address only received outputs
+    Incoming,
+    /// The address spent some of its outputs
+    Outgoing,
+}
+
+/// Transaction that touched an address, as stored in the address index
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AddressTransaction {
+    /// Hash of the transaction
+    pub transaction: Hash,
+    /// Block that contains the transaction, and position of the transaction inside it
+    pub pointer: PointerToBlock,
+    /// Epoch of the block that contains the transaction
+    pub epoch: Epoch,
+    /// Whether the address spent value in the transaction, or only received value in it
+    pub direction: TransactionDirection,
+    /// For incoming transactions, value received by the address. For outgoing transactions, value
+    /// spent by the address minus the value returned to it, which includes the fees.
+    pub value: u64,
+    /// Indexes of the outputs of the transaction that pay to the address
+    pub outputs: Vec<u32>,
+}
+
+impl AddressTransaction {
+    /// Pointers to the outputs of the transaction that pay to the address
+    pub fn output_pointers(&self) -> impl Iterator<Item = OutputPointer> + '_ {
+        self.outputs.iter().map(move |output_index| OutputPointer {
+            transaction_id: self.transaction,
+            output_index: *output_index,
+        })
+    }
+}
+
+/// Value spent and received by an address in a single transaction
+#[derive(Default)]
+struct AddressTotals {
+    spent: u64,
+    received: u64,
+    outputs: Vec<u32>,
+}
+
+/// Outputs created by a transaction
+pub fn transaction_outputs(transaction: &Transaction) -> &[ValueTransferOutput] {
+    match transaction {
+        Transaction::ValueTransfer(tx) => &tx.body.outputs,
+        Transaction::DataRequest(tx) => &tx.body.outputs,
+        Transaction::Commit(tx) => &tx.body.outputs,
+        Transaction::Reveal(_) => &[],
+        Transaction::Tally(tx) => &tx.outputs,
+        Transaction::Mint(tx) => &tx.outputs,
+    }
+}
+
+/// Inputs and outputs of all the transactions of a block, in the same order as
+/// `BlockTransactions::create_pointers_to_transactions`
+fn block_inputs_and_outputs<'a>(
+    block: &'a Block,
+) -> impl Iterator<Item = (&'a [Input], &'a [ValueTransferOutput])> {
+    let txns = &block.txns;
+    let no_inputs: &[Input] = &[];
+    let no_outputs: &[ValueTransferOutput] = &[];
+
+    std::iter::once((no_inputs, &txns.mint.outputs[..]))
+        .chain(
+            txns.value_transfer_txns
+                .iter()
+                .map(|tx| (&tx.body.inputs[..], &tx.body.outputs[..])),
+        )
+        .chain(
+            txns.data_request_txns
+                .iter()
+                .map(|tx| (&tx.body.inputs[..], &tx.body.outputs[..])),
+        )
+        .chain(
+            txns.commit_txns
+                .iter()
+                .map(|tx| (&tx.body.collateral[..], &tx.body.outputs[..])),
+        )
+        .chain(
+            txns.reveal_txns
+                .iter()
+                .map(move |_tx| (no_inputs, no_outputs)),
+        )
+        .chain(
+            txns.tally_txns
+                .iter()
+                .map(move |tx| (no_inputs, &tx.outputs[..])),
+        )
+}
+
+/// Outputs spent by the inputs of a block that were not created in that same block
+pub fn block_spent_outputs(block: &Block) -> Vec<OutputPointer> {
+    let created: Vec<Hash> = block
+        .txns
+        .create_pointers_to_transactions(block.hash())
+        .into_iter()
+        .map(|(tx_hash, _pointer)| tx_hash)
+        .collect();
+
+    block_inputs_and_outputs(block)
+        .flat_map(|(inputs, _outputs)| inputs)
+        .map(|input| input.output_pointer().clone())
+        .filter(|output_pointer| !created.contains(&output_pointer.transaction_id))
+        .collect()
+}
+
+/// Entries of the address index for all the transactions of a block: one entry for every address
+/// that spent or received value in each transaction.
+///
+/// `spent_output` must resolve the outputs spent by the inputs of the block, except those created
+/// in the block itself. Inputs whose outputs cannot be resolved are ignored.
+pub fn block_entries<F>(block: &Block, spent_output: F) -> Vec<(PublicKeyHash, AddressTransaction)>
+where
+    F: Fn(&OutputPointer) -> Option<ValueTransferOutput>,
+{
+    let epoch = block.block_header.beacon.checkpoint;
+    let pointers = block.txns.create_pointers_to_transactions(block.hash());
+    let mut block_outputs: HashMap<OutputPointer, ValueTransferOutput> = HashMap::new();
+    let mut entries = vec![];
+
+    for ((tx_hash, pointer), (inputs, outputs)) in
+        pointers.into_iter().zip(block_inputs_and_outputs(block))
+    {
+        let mut totals: BTreeMap<PublicKeyHash, AddressTotals> = BTreeMap::new();
+
+        for input in inputs {
+            let output_pointer = input.output_pointer();
+            let output = block_outputs
+                .get(output_pointer)
+                .cloned()
+                .or_else(|| spent_output(output_pointer));
+            if let Some(output) = output {
+                totals.entry(output.pkh).or_default().spent += output.value;
+            }
+        }
+
+        for (i, output) in outputs.iter().enumerate() {
+            let output_index = u32::try_from(i).unwrap();
+            let address_totals = totals.entry(output.pkh).or_default();
+            address_totals.received += output.value;
+            address_totals.outputs.push(output_index);
+            block_outputs.insert(
+                OutputPointer {
+                    transaction_id: tx_hash,
+                    output_index,
+                },
+                output.clone(),
+            );
+        }
+
+        for (pkh, address_totals) in totals {
+            let (direction, value) = if address_totals.spent > 0 {
+                (
+                    TransactionDirection::Outgoing,
+                    address_totals.spent.saturating_sub(address_totals.received),
+                )
+            } else {
+                (TransactionDirection::Incoming, address_totals.received)
+            };
+
+            entries.push((
+                pkh,
+                AddressTransaction {
+                    transaction: tx_hash,
+                    pointer: pointer.clone(),
+                    epoch,
+                    direction,
+                    value,
+                    outputs: address_totals.outputs,
+                },
+            ));
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chain::{BlockTransactions, TransactionPointer},
+        transaction::{MintTransaction, VTTransaction, VTTransactionBody},
+    };
+
+    fn pkh(byte: u8) -> PublicKeyHash {
+        PublicKeyHash::from_bytes(&[byte; 20]).unwrap()
+    }
+
+    fn output(byte: u8, value: u64) -> ValueTransferOutput {
+        ValueTransferOutput {
+            pkh: pkh(byte),
+            value,
+            time_lock: 0,
+        }
+    }
+
+    #[test]
+    fn entries_of_mint_and_value_transfer() {
+        let spent = OutputPointer {
+            transaction_id: Hash::SHA256([1; 32]),
+            output_index: 0,
+        };
+        let vt = VTTransaction::new(
+            VTTransactionBody::new(
+                vec![Input::new(spent.clone())],
+                vec![output(2, 30), output(1, 65)],
+            ),
+            vec![],
+        );
+        let vt_hash = vt.hash();
+        let block = Block::new(
+            Default::default(),
+            Default::default(),
+            BlockTransactions {
+                mint: MintTransaction::new(0, vec![output(3, 250)]),
+                value_transfer_txns: vec![vt],
+                ..BlockTransactions::default()
+            },
+        );
+
+        assert_eq!(block_spent_outputs(&block), vec![spent.clone()]);
+
+        let entries = block_entries(&block, |output_pointer| {
+            if output_pointer == &spent {
+                Some(output(1, 100))
+            } else {
+                None
+            }
+        });
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|(pkh, entry)| (*pkh, entry.direction, entry.value, entry.outputs.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (pkh(3), TransactionDirection::Incoming, 250, vec![0]),
+                (pkh(1), TransactionDirection::Outgoing, 35, vec![1]),
+                (pkh(2), TransactionDirection::Incoming, 30, vec![0]),
+            ]
+        );
+
+        let (_pkh, sender_entry) = &entries[1];
+        assert_eq!(sender_entry.transaction, vt_hash);
+        assert_eq!(
+            sender_entry.pointer.transaction_index,
+            TransactionPointer::ValueTransfer(0)
+        );
+        assert_eq!(
+            sender_entry.output_pointers().collect::<Vec<_>>(),
+            vec![OutputPointer {
+                transaction_id: vt_hash,
+                output_index: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn unresolved_inputs_are_ignored() {
+        let vt = VTTransaction::new(
+            VTTransactionBody::new(
+                vec![Input::new(OutputPointer::default())],
+                vec![output(2, 30)],
+            ),
+            vec![],
+        );
+        let block = Block::new(
+            Default::default(),
+            Default::default(),
+            BlockTransactions {
+                value_transfer_txns: vec![vt],
+                ..BlockTransactions::default()
+            },
+        );
+
+        let entries = block_entries(&block, |_| None);
+        let directions: Vec<_> = entries
+            .iter()
+            .map(|(pkh, entry)| (*pkh, entry.direction, entry.value))
+            .collect();
+        assert_eq!(
+            directions,
+            vec![(pkh(2), TransactionDirection::Incoming, 30)]
+        );
+    }
+}
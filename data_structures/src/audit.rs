@@ -0,0 +1,84 @@
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    chain::{Epoch, Hash, RADType},
+    radon_report::{RadonReport, TypeLike},
+};
+
+/// Trace of the execution of a RADON script
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScriptTrace {
+    /// Result of each of the calls in the script, starting with the input of the script
+    pub partial_results: Vec<String>,
+    /// Result of the script
+    pub result: String,
+    /// Time spent running the script
+    pub running_time: Duration,
+}
+
+impl ScriptTrace {
+    /// Create a trace from the report of a script executed with partial results and timing enabled
+    pub fn from_report<RT>(report: &RadonReport<RT>) -> Self
+    where
+        RT: TypeLike + fmt::Display,
+    {
+        Self {
+            partial_results: report
+                .partial_results
+                .iter()
+                .flatten()
+                .map(ToString::to_string)
+                .collect(),
+            result: report.result.to_string(),
+            running_time: report.running_time,
+        }
+    }
+}
+
+/// Trace of the retrieval of a source and the execution of its script
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SourceTrace {
+    /// Kind of retrieval
+    pub kind: RADType,
+    /// URL of the source
+    pub url: String,
+    /// Raw response of the source: the body of the HTTP response, or the hex-encoded random bytes
+    /// of a RNG source
+    pub response: Option<String>,
+    /// Error found when retrieving the source, if any
+    pub error: Option<String>,
+    /// Time spent waiting for the response
+    pub retrieval_time: Duration,
+    /// Trace of the script of the source, if the retrieval succeeded
+    pub script: Option<ScriptTrace>,
+}
+
+/// Trace of the retrieval and aggregation stages of a data request
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecutionTrace {
+    /// Trace of each of the sources, in the same order as in the data request
+    pub sources: Vec<SourceTrace>,
+    /// Trace of the aggregation script, if it was executed
+    pub aggregation: Option<ScriptTrace>,
+}
+
+/// Audit record of a data request resolved by this node
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DataRequestAudit {
+    /// Data request pointer
+    pub dr_pointer: Hash,
+    /// Epoch in which the data request was resolved
+    pub epoch: Epoch,
+    /// Trace of the retrieval and aggregation stages
+    pub trace: ExecutionTrace,
+    /// Result of the retrieval and aggregation stages
+    pub result: String,
+    /// CBOR-encoded value committed to, and later revealed
+    pub reveal_value: Vec<u8>,
+    /// Hash of the commit transaction
+    pub commit_transaction: Option<Hash>,
+    /// Hash of the reveal transaction
+    pub reveal_transaction: Option<Hash>,
+}
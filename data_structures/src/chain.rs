@@ -622,6 +622,12 @@ impl Hashable for DataRequestOutput {
     }
 }
 
+impl Hashable for RADRequest {
+    fn hash(&self) -> Hash {
+        calculate_sha256(&self.to_pb_bytes().unwrap()).into()
+    }
+}
+
 impl Hashable for PublicKey {
     fn hash(&self) -> Hash {
         let mut v = vec![];
@@ -1538,6 +1544,12 @@ pub enum RADType {
     /// HTTP GET request
     #[serde(rename = "HTTP-GET")]
     HttpGet,
+    /// HTTP POST request
+    #[serde(rename = "HTTP-POST")]
+    HttpPost,
+    /// Random number generation, performed locally by each witness
+    #[serde(rename = "RNG")]
+    Rng,
 }
 
 impl Default for RADType {
@@ -1580,7 +1592,7 @@ impl RADRequest {
 }
 
 /// Retrieve script and source
-#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize, ProtobufConvert, Hash, Default)]
+#[derive(Debug, Eq, PartialEq, Clone, ProtobufConvert, Hash, Default)]
 #[protobuf_convert(
     pb = "witnet::DataRequestOutput_RADRequest_RADRetrieve",
     crate = "crate"
@@ -1592,6 +1604,10 @@ pub struct RADRetrieve {
     pub url: String,
     /// Serialized RADON script
     pub script: Vec<u8>,
+    /// Body of the HTTP request (only allowed in `HttpPost` retrievals)
+    pub body: Vec<u8>,
+    /// Extra HTTP headers, as (name, value) pairs
+    pub headers: Vec<(String, String)>,
 }
 
 impl RADRetrieve {
@@ -1600,8 +1616,19 @@ impl RADRetrieve {
         // RADType: 1 byte
         let script_weight = u32::try_from(self.script.len()).unwrap_or(u32::MAX);
         let url_weight = u32::try_from(self.url.len()).unwrap_or(u32::MAX);
+        let body_weight = u32::try_from(self.body.len()).unwrap_or(u32::MAX);
+        let headers_weight = self.headers.iter().fold(0u32, |acc, (name, value)| {
+            let name_weight = u32::try_from(name.len()).unwrap_or(u32::MAX);
+            let value_weight = u32::try_from(value.len()).unwrap_or(u32::MAX);
+
+            acc.saturating_add(name_weight).saturating_add(value_weight)
+        });
 
-        script_weight.saturating_add(url_weight).saturating_add(1)
+        script_weight
+            .saturating_add(url_weight)
+            .saturating_add(body_weight)
+            .saturating_add(headers_weight)
+            .saturating_add(1)
     }
 }
 
@@ -2913,7 +2940,7 @@ impl Default for DataRequestStage {
 pub type Blockchain = BTreeMap<Epoch, Hash>;
 
 /// Node stats
-#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct NodeStats {
     /// Number of proposed blocks
     pub block_proposed_count: u32,
@@ -2921,6 +2948,19 @@ pub struct NodeStats {
     pub block_mined_count: u32,
     /// Number of times we were eligible to participate in a Data Request
     pub dr_eligibility_count: u32,
+    /// Number of data requests that we were eligible for, but skipped because some of their
+    /// sources were not allowed by the retrieval policy. Not persisted, so it counts since the
+    /// node was started
+    pub dr_skipped_by_policy_count: u32,
+    /// Number of data requests that we were eligible for, but did not meet the selection
+    /// criteria. Not persisted, so it counts since the node was started
+    pub dr_skipped_by_selection_count: u32,
+    /// Number of sources whose response was shared with another data request in the same epoch.
+    /// Not persisted, so it counts since the node was started
+    pub retrieval_cache_hits: u32,
+    /// Number of sources that were retrieved for the first time in their epoch. Not persisted, so
+    /// it counts since the node was started
+    pub retrieval_cache_misses: u32,
     /// Number of proposed commits
     pub commits_proposed_count: u32,
     /// Number of commits included in a data request
@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+use crate::chain::{DataRequestStage, DataRequestState, Epoch, Hash, Hashable, PublicKeyHash};
+
+/// Default maximum number of data requests returned by a query
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Stage of the life cycle of a data request: the stages of the data requests that are being
+/// resolved, plus the final stage of the data requests that were resolved
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum DataRequestLifecycleStage {
+    /// Expecting commitments for data request
+    COMMIT,
+    /// Expecting reveals to previously published commitments
+    REVEAL,
+    /// Expecting tally to be included in block
+    TALLY,
+    /// The tally was included in a block
+    FINISHED,
+}
+
+impl DataRequestLifecycleStage {
+    /// All the stages, in the order in which data requests go through them
+    pub const ALL: [DataRequestLifecycleStage; 4] = [
+        DataRequestLifecycleStage::COMMIT,
+        DataRequestLifecycleStage::REVEAL,
+        DataRequestLifecycleStage::TALLY,
+        DataRequestLifecycleStage::FINISHED,
+    ];
+}
+
+impl From<DataRequestStage> for DataRequestLifecycleStage {
+    fn from(stage: DataRequestStage) -> Self {
+        match stage {
+            DataRequestStage::COMMIT => DataRequestLifecycleStage::COMMIT,
+            DataRequestStage::REVEAL => DataRequestLifecycleStage::REVEAL,
+            DataRequestStage::TALLY => DataRequestLifecycleStage::TALLY,
+        }
+    }
+}
+
+/// Outcome of the tally of a finished data request
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DataRequestTallyStatus {
+    /// Hash of the tally transaction
+    pub tally_tx: Hash,
+    /// Hash of the block that contains the tally transaction
+    pub block_hash: Option<Hash>,
+    /// Whether the tally result is a value, as opposed to an error
+    pub success: bool,
+    /// Human readable tally result
+    pub result: String,
+}
+
+/// Entry of the data request index
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DataRequestRecord {
+    /// Hash of the data request transaction
+    pub dr_pointer: Hash,
+    /// Address of the creator of the data request
+    pub requester: PublicKeyHash,
+    /// Hash of the RAD request, which is the same for all the instances of the same request
+    pub rad_hash: Hash,
+    /// Epoch at which the data request is unlocked, which is the epoch of the block that contains
+    /// the data request transaction unless the data request is time-locked
+    pub epoch: Epoch,
+    /// Current stage of the data request
+    pub stage: DataRequestLifecycleStage,
+    /// Outcome of the tally, once the data request is finished
+    pub tally: Option<DataRequestTallyStatus>,
+}
+
+impl DataRequestRecord {
+    /// Create the record of a data request that is being resolved
+    pub fn from_state(dr_pointer: Hash, state: &DataRequestState) -> Self {
+        Self {
+            dr_pointer,
+            requester: state.pkh,
+            rad_hash: state.data_request.data_request.hash(),
+            epoch: state.epoch,
+            stage: state.stage.into(),
+            tally: None,
+        }
+    }
+}
+
+/// Query over the data request index. All the criteria are optional, and only the data requests
+/// that match all of them are returned, most recent first.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(default)]
+pub struct DataRequestQuery {
+    /// Address of the creator of the data requests
+    pub requester: Option<PublicKeyHash>,
+    /// Hash of the RAD request of the data requests
+    pub rad_hash: Option<Hash>,
+    /// Current stage of the data requests
+    pub stage: Option<DataRequestLifecycleStage>,
+    /// Include only data requests unlocked at this epoch or later
+    pub from_epoch: Option<Epoch>,
+    /// Include only data requests unlocked at this epoch or earlier
+    pub to_epoch: Option<Epoch>,
+    /// Number of matching data requests to skip
+    pub offset: usize,
+    /// Maximum number of data requests to return
+    pub limit: usize,
+}
+
+impl Default for DataRequestQuery {
+    fn default() -> Self {
+        Self {
+            requester: None,
+            rad_hash: None,
+            stage: None,
+            from_epoch: None,
+            to_epoch: None,
+            offset: 0,
+            limit: DEFAULT_QUERY_LIMIT,
+        }
+    }
+}
+
+impl DataRequestQuery {
+    /// Returns `true` if the record matches all the criteria of the query
+    pub fn matches(&self, record: &DataRequestRecord) -> bool {
+        self.requester.map_or(true, |pkh| record.requester == pkh)
+            && self.rad_hash.map_or(true, |hash| record.rad_hash == hash)
+            && self.stage.map_or(true, |stage| record.stage == stage)
+            && self.from_epoch.map_or(true, |epoch| record.epoch >= epoch)
+            && self.to_epoch.map_or(true, |epoch| record.epoch <= epoch)
+    }
+
+    /// Select the page of the matching records requested by the query, most recent first
+    pub fn paginate(&self, records: Vec<DataRequestRecord>) -> DataRequestQueryResult {
+        let mut records: Vec<_> = records
+            .into_iter()
+            .filter(|record| self.matches(record))
+            .collect();
+        records.sort_by(|a, b| {
+            b.epoch
+                .cmp(&a.epoch)
+                .then_with(|| a.dr_pointer.cmp(&b.dr_pointer))
+        });
+        records.dedup_by_key(|record| record.dr_pointer);
+        let total = records.len();
+        let data_requests = records
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect();
+
+        DataRequestQueryResult {
+            total,
+            data_requests,
+        }
+    }
+}
+
+/// Page of the data requests that match a query
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct DataRequestQueryResult {
+    /// Total number of data requests that match the query
+    pub total: usize,
+    /// Matching data requests, most recent first, after applying the offset and limit of the query
+    pub data_requests: Vec<DataRequestRecord>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(byte: u8, requester: u8, epoch: Epoch) -> DataRequestRecord {
+        DataRequestRecord {
+            dr_pointer: Hash::SHA256([byte; 32]),
+            requester: PublicKeyHash::from_bytes(&[requester; 20]).unwrap(),
+            rad_hash: Hash::default(),
+            epoch,
+            stage: DataRequestLifecycleStage::COMMIT,
+            tally: None,
+        }
+    }
+
+    #[test]
+    fn query_filters_and_sorts() {
+        let records = vec![
+            record(1, 1, 10),
+            record(2, 2, 20),
+            record(3, 1, 30),
+            record(4, 1, 40),
+        ];
+        let query = DataRequestQuery {
+            requester: Some(PublicKeyHash::from_bytes(&[1; 20]).unwrap()),
+            from_epoch: Some(20),
+            ..DataRequestQuery::default()
+        };
+
+        let result = query.paginate(records);
+        assert_eq!(result.total, 2);
+        assert_eq!(
+            result.data_requests,
+            vec![record(4, 1, 40), record(3, 1, 30)]
+        );
+    }
+
+    #[test]
+    fn query_pagination() {
+        let records: Vec<_> = (1..=5).map(|i| record(i, 1, Epoch::from(i))).collect();
+        let query = DataRequestQuery {
+            offset: 1,
+            limit: 2,
+            ..DataRequestQuery::default()
+        };
+
+        let result = query.paginate(records);
+        assert_eq!(result.total, 5);
+        assert_eq!(result.data_requests, vec![record(4, 1, 4), record(3, 1, 3)]);
+    }
+
+    #[test]
+    fn query_defaults_from_json() {
+        let query: DataRequestQuery = serde_json::from_str(r#"{"stage": "FINISHED"}"#).unwrap();
+
+        assert_eq!(query.stage, Some(DataRequestLifecycleStage::FINISHED));
+        assert_eq!(query.limit, DEFAULT_QUERY_LIMIT);
+    }
+}
@@ -3,7 +3,9 @@
 use failure::Fail;
 use std::num::ParseIntError;
 
-use crate::chain::{DataRequestOutput, Epoch, Hash, HashParseError, OutputPointer, PublicKeyHash};
+use crate::chain::{
+    DataRequestOutput, Epoch, Hash, HashParseError, OutputPointer, PublicKeyHash, RADType,
+};
 
 /// The error type for operations on a [`ChainInfo`](ChainInfo)
 #[derive(Debug, PartialEq, Fail)]
@@ -479,6 +481,15 @@ pub enum DataRequestError {
     UnfinishedDataRequest,
     #[fail(display = "The data request is not valid since it has no retrieval sources")]
     NoRetrievalSources,
+    #[fail(
+        display = "The data request is not valid since a retrieval of kind {:?} cannot have a body",
+        kind
+    )]
+    UnexpectedRetrievalBody { kind: RADType },
+    #[fail(
+        display = "The data request is not valid since RNG retrievals cannot have an URL, a body or headers"
+    )]
+    MalformedRngRetrieval,
 }
 
 /// Possible errors when converting between epoch and timestamp
@@ -59,6 +59,18 @@ pub mod radon_error;
 /// Module containing RadonReport structures
 pub mod radon_report;
 
+/// Module containing the audit records of the data requests resolved by the node
+pub mod audit;
+
+/// Module containing the entries of the index of the transactions of every address
+pub mod address_index;
+
+/// Module containing the entries of the index of data requests and the queries over it
+pub mod data_request_index;
+
+/// Module containing the versions of the layout of the data structures persisted in storage
+pub mod storage_layout;
+
 /// Serialization boilerplate to allow serializing some data structures as
 /// strings or bytes depending on the serializer.
 mod serialization_helpers;
@@ -48,16 +48,36 @@ impl ProtobufConvert for chain::RADType {
     fn to_pb(&self) -> Self::ProtoStruct {
         match self {
             chain::RADType::HttpGet => witnet::DataRequestOutput_RADRequest_RADType::HttpGet,
+            chain::RADType::HttpPost => witnet::DataRequestOutput_RADRequest_RADType::HttpPost,
+            chain::RADType::Rng => witnet::DataRequestOutput_RADRequest_RADType::Rng,
         }
     }
 
     fn from_pb(pb: Self::ProtoStruct) -> Result<Self, Error> {
         Ok(match pb {
             witnet::DataRequestOutput_RADRequest_RADType::HttpGet => chain::RADType::HttpGet,
+            witnet::DataRequestOutput_RADRequest_RADType::HttpPost => chain::RADType::HttpPost,
+            witnet::DataRequestOutput_RADRequest_RADType::Rng => chain::RADType::Rng,
         })
     }
 }
 
+impl ProtobufConvert for (String, String) {
+    type ProtoStruct = witnet::StringPair;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pair = witnet::StringPair::new();
+        pair.set_left(self.0.clone());
+        pair.set_right(self.1.clone());
+
+        pair
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> Result<Self, Error> {
+        Ok((pb.take_left(), pb.take_right()))
+    }
+}
+
 impl ProtobufConvert for chain::PublicKey {
     type ProtoStruct = witnet::PublicKey;
 
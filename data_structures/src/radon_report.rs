@@ -1,5 +1,5 @@
 use std::convert::TryFrom;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use serde::Serialize;
 
@@ -125,6 +125,9 @@ where
     pub call_operator: Option<u8>,
     /// The timestamp when the execution of the script finished.
     pub completion_time: Option<SystemTime>,
+    /// The instant after which the execution of the script, including any subscripts, is aborted.
+    #[serde(skip)]
+    pub deadline: Option<Instant>,
     /// Metadata that is specific to the stage of the script.
     pub stage: Stage<RT>,
     /// The timestamp when the execution of the script began.
@@ -144,6 +147,7 @@ where
             call_index: None,
             call_operator: None,
             completion_time: None,
+            deadline: None,
             stage: Stage::Contextless,
             start_time: None,
             script_index: None,
@@ -166,6 +170,13 @@ where
         self.completion_time = Some(SystemTime::now())
     }
 
+    /// Whether the deadline for the execution of the script, if any, has already passed.
+    pub fn deadline_elapsed(&self) -> bool {
+        self.deadline
+            .map(|deadline| Instant::now() >= deadline)
+            .unwrap_or(false)
+    }
+
     /// Compute difference between start and completion time.
     fn duration(&self) -> Duration {
         match (self.start_time, self.completion_time) {
@@ -7,9 +7,10 @@
 // Ideally all this code would be generated with a `#[serde(human_readable_string)]` macro.
 
 use crate::chain::{
-    GenesisBlockInfo, Hash, OutputPointer, PublicKeyHash, ValueTransferOutput, SHA256,
+    GenesisBlockInfo, Hash, NodeStats, OutputPointer, PublicKeyHash, RADRetrieve, RADType,
+    ValueTransferOutput, SHA256,
 };
-use crate::get_environment;
+use crate::{get_environment, storage_layout::is_legacy_layout};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::{fmt::Display, str::FromStr};
 
@@ -150,6 +151,184 @@ impl<'de> Deserialize<'de> for OutputPointer {
     }
 }
 
+// The body and headers of retrievals are omitted from the JSON when they are empty, so the JSON
+// of the retrievals that do not use them stays the same. Bincode does not store field names, so
+// no field can be omitted from its serialization.
+#[derive(Serialize)]
+#[serde(rename = "RADRetrieve")]
+struct RADRetrieveSerializationHelper<'a> {
+    kind: &'a RADType,
+    url: &'a String,
+    script: &'a Vec<u8>,
+    body: &'a Vec<u8>,
+    headers: &'a Vec<(String, String)>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "RADRetrieve")]
+struct RADRetrieveJsonSerializationHelper<'a> {
+    kind: &'a RADType,
+    url: &'a String,
+    script: &'a Vec<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    body: &'a Vec<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    headers: &'a Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "RADRetrieve")]
+struct RADRetrieveDeserializationHelper {
+    kind: RADType,
+    url: String,
+    script: Vec<u8>,
+    #[serde(default)]
+    body: Vec<u8>,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+}
+
+/// Layout of `RADRetrieve` persisted in storage before it had a body and headers
+#[derive(Deserialize)]
+#[serde(rename = "RADRetrieve")]
+struct LegacyRADRetrieveDeserializationHelper {
+    kind: RADType,
+    url: String,
+    script: Vec<u8>,
+}
+
+impl Serialize for RADRetrieve {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            RADRetrieveJsonSerializationHelper {
+                kind: &self.kind,
+                url: &self.url,
+                script: &self.script,
+                body: &self.body,
+                headers: &self.headers,
+            }
+            .serialize(serializer)
+        } else {
+            RADRetrieveSerializationHelper {
+                kind: &self.kind,
+                url: &self.url,
+                script: &self.script,
+                body: &self.body,
+                headers: &self.headers,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RADRetrieve {
+    fn deserialize<D>(deserializer: D) -> Result<RADRetrieve, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() && is_legacy_layout() {
+            LegacyRADRetrieveDeserializationHelper::deserialize(deserializer).map(|x| RADRetrieve {
+                kind: x.kind,
+                url: x.url,
+                script: x.script,
+                body: vec![],
+                headers: vec![],
+            })
+        } else {
+            RADRetrieveDeserializationHelper::deserialize(deserializer).map(|x| RADRetrieve {
+                kind: x.kind,
+                url: x.url,
+                script: x.script,
+                body: x.body,
+                headers: x.headers,
+            })
+        }
+    }
+}
+
+/// Serialization helper for `NodeStats` in JSON, which includes all the counters
+#[derive(Deserialize, Serialize)]
+#[serde(remote = "NodeStats", rename = "NodeStats")]
+struct NodeStatsJsonSerializationHelper {
+    block_proposed_count: u32,
+    block_mined_count: u32,
+    dr_eligibility_count: u32,
+    dr_skipped_by_policy_count: u32,
+    dr_skipped_by_selection_count: u32,
+    retrieval_cache_hits: u32,
+    retrieval_cache_misses: u32,
+    commits_proposed_count: u32,
+    commits_count: u32,
+    last_block_proposed: Hash,
+    slashed_count: u32,
+}
+
+/// Layout of `NodeStats` persisted in storage as part of the `ChainState`. The counters that are
+/// not part of it start from zero every time the node is started.
+#[derive(Deserialize, Serialize)]
+#[serde(rename = "NodeStats")]
+struct NodeStatsSerializationHelper {
+    block_proposed_count: u32,
+    block_mined_count: u32,
+    dr_eligibility_count: u32,
+    commits_proposed_count: u32,
+    commits_count: u32,
+    last_block_proposed: Hash,
+    slashed_count: u32,
+}
+
+impl From<&NodeStats> for NodeStatsSerializationHelper {
+    fn from(x: &NodeStats) -> Self {
+        NodeStatsSerializationHelper {
+            block_proposed_count: x.block_proposed_count,
+            block_mined_count: x.block_mined_count,
+            dr_eligibility_count: x.dr_eligibility_count,
+            commits_proposed_count: x.commits_proposed_count,
+            commits_count: x.commits_count,
+            last_block_proposed: x.last_block_proposed,
+            slashed_count: x.slashed_count,
+        }
+    }
+}
+
+impl Into<NodeStats> for NodeStatsSerializationHelper {
+    fn into(self) -> NodeStats {
+        NodeStats {
+            block_proposed_count: self.block_proposed_count,
+            block_mined_count: self.block_mined_count,
+            dr_eligibility_count: self.dr_eligibility_count,
+            commits_proposed_count: self.commits_proposed_count,
+            commits_count: self.commits_count,
+            last_block_proposed: self.last_block_proposed,
+            slashed_count: self.slashed_count,
+            ..NodeStats::default()
+        }
+    }
+}
+
+impl Serialize for NodeStats {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            NodeStatsJsonSerializationHelper::serialize(self, serializer)
+        } else {
+            NodeStatsSerializationHelper::from(self).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NodeStats {
+    fn deserialize<D>(deserializer: D) -> Result<NodeStats, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            NodeStatsJsonSerializationHelper::deserialize(deserializer)
+        } else {
+            NodeStatsSerializationHelper::deserialize(deserializer).map(Into::into)
+        }
+    }
+}
+
 /// Serialization helper for `GenesisBlockInfo`.
 #[derive(Deserialize)]
 pub struct GenesisBlock {
@@ -0,0 +1,43 @@
+//! Versions of the layout of the data structures that the node persists in storage.
+//!
+//! Storage is serialized with bincode, which does not store field names, so adding a field to a
+//! persisted data structure changes its layout. The data that was persisted with a previous layout
+//! is read by deserializing it inside `with_legacy_layout`, and then migrated to the current one.
+
+use std::cell::Cell;
+
+/// Current version of the layout of the persisted data structures:
+///
+/// * 0: initial layout
+/// * 1: `RADRetrieve` has a body and headers
+pub const STORAGE_LAYOUT_VERSION: u32 = 1;
+
+thread_local! {
+    static LEGACY_LAYOUT: Cell<bool> = Cell::new(false);
+}
+
+/// Restores the previous value of the flag even if the deserialization panics
+struct LegacyLayoutGuard(bool);
+
+impl Drop for LegacyLayoutGuard {
+    fn drop(&mut self) {
+        LEGACY_LAYOUT.with(|legacy_layout| legacy_layout.set(self.0));
+    }
+}
+
+/// Run a function that deserializes data persisted with the layout version 0, before
+/// `RADRetrieve` had a body and headers. Only the deserializations that run in the same thread
+/// are affected.
+pub fn with_legacy_layout<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = LegacyLayoutGuard(LEGACY_LAYOUT.with(|legacy_layout| legacy_layout.replace(true)));
+
+    f()
+}
+
+/// Whether the data being deserialized in this thread was persisted with the layout version 0
+pub(crate) fn is_legacy_layout() -> bool {
+    LEGACY_LAYOUT.with(Cell::get)
+}
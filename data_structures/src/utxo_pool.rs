@@ -297,6 +297,32 @@ pub fn get_utxo_info(
     }
 }
 
+/// Get Utxo Information of a list of outputs, such as the outputs received by an address according
+/// to the address index. Outputs that are already spent are skipped.
+pub fn get_utxo_info_of_outputs<I>(
+    output_pointers: I,
+    all_utxos: &UnspentOutputsPool,
+    collateral_min: u64,
+    block_number_limit: u32,
+) -> UtxoInfo
+where
+    I: IntoIterator<Item = OutputPointer>,
+{
+    let utxos = output_pointers
+        .into_iter()
+        .filter_map(|o| {
+            all_utxos
+                .get(&o)
+                .map(|vto| create_utxo_metadata(vto, &o, all_utxos, block_number_limit))
+        })
+        .collect();
+
+    UtxoInfo {
+        utxos,
+        collateral_min,
+    }
+}
+
 /// Diffs to apply to an utxo set. This type does not contains a
 /// reference to the original utxo set.
 #[derive(Debug)]
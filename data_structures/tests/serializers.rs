@@ -1,5 +1,7 @@
+use serde::Serialize;
 use witnet_data_structures::{
     proto::ProtobufConvert,
+    storage_layout::with_legacy_layout,
     {chain::*, types::*},
 };
 
@@ -543,3 +545,145 @@ fn message_transaction_encode_decode() {
 
     assert_eq!(cloned_msg, Message::from_pb_bytes(&result).unwrap());
 }
+
+#[test]
+fn rad_retrieve_http_post_encode_decode() {
+    let retrieve = RADRetrieve {
+        kind: RADType::HttpPost,
+        url: "https://api.example.com/graphql".to_string(),
+        script: vec![128],
+        body: br#"{"query":"{ price }"}"#.to_vec(),
+        headers: vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("X-Api-Key".to_string(), "secret".to_string()),
+        ],
+    };
+    let cloned_retrieve = retrieve.clone();
+    let result: Vec<u8> = retrieve.to_pb_bytes().unwrap();
+
+    assert_eq!(
+        cloned_retrieve,
+        RADRetrieve::from_pb_bytes(&result).unwrap()
+    );
+}
+
+#[test]
+fn rad_retrieve_json_omits_empty_body_and_headers() {
+    let retrieve = RADRetrieve {
+        kind: RADType::HttpGet,
+        url: "https://api.example.com".to_string(),
+        script: vec![128],
+        ..RADRetrieve::default()
+    };
+    let json = serde_json::to_string(&retrieve).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"kind":"HTTP-GET","url":"https://api.example.com","script":[128]}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<RADRetrieve>(&json).unwrap(),
+        retrieve
+    );
+}
+
+#[test]
+fn rad_retrieve_legacy_storage_layout() {
+    // Layout of `RADRetrieve` before it had a body and headers
+    #[derive(Serialize)]
+    struct LegacyRADRetrieve {
+        kind: RADType,
+        url: String,
+        script: Vec<u8>,
+    }
+
+    let legacy = vec![LegacyRADRetrieve {
+        kind: RADType::HttpGet,
+        url: "https://api.example.com".to_string(),
+        script: vec![128],
+    }];
+    let bytes = bincode::serialize(&legacy).unwrap();
+    let retrieve: Vec<RADRetrieve> = with_legacy_layout(|| bincode::deserialize(&bytes)).unwrap();
+    assert_eq!(
+        retrieve,
+        vec![RADRetrieve {
+            kind: RADType::HttpGet,
+            url: "https://api.example.com".to_string(),
+            script: vec![128],
+            ..RADRetrieve::default()
+        }]
+    );
+
+    // The current layout always includes the body and headers
+    let post = RADRetrieve {
+        kind: RADType::HttpPost,
+        body: b"{}".to_vec(),
+        headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+        ..retrieve[0].clone()
+    };
+    let retrievals = vec![retrieve[0].clone(), post];
+    let bytes = bincode::serialize(&retrievals).unwrap();
+    assert_eq!(
+        bincode::deserialize::<Vec<RADRetrieve>>(&bytes).unwrap(),
+        retrievals
+    );
+}
+
+#[test]
+fn node_stats_storage_layout() {
+    // Layout of `NodeStats` persisted in storage
+    #[derive(Serialize)]
+    struct PersistedNodeStats {
+        block_proposed_count: u32,
+        block_mined_count: u32,
+        dr_eligibility_count: u32,
+        commits_proposed_count: u32,
+        commits_count: u32,
+        last_block_proposed: Hash,
+        slashed_count: u32,
+    }
+
+    let node_stats = NodeStats {
+        block_proposed_count: 1,
+        block_mined_count: 2,
+        dr_eligibility_count: 3,
+        dr_skipped_by_policy_count: 4,
+        dr_skipped_by_selection_count: 9,
+        retrieval_cache_hits: 10,
+        retrieval_cache_misses: 11,
+        commits_proposed_count: 5,
+        commits_count: 6,
+        last_block_proposed: Hash::SHA256([7; 32]),
+        slashed_count: 8,
+    };
+    let persisted = PersistedNodeStats {
+        block_proposed_count: 1,
+        block_mined_count: 2,
+        dr_eligibility_count: 3,
+        commits_proposed_count: 5,
+        commits_count: 6,
+        last_block_proposed: Hash::SHA256([7; 32]),
+        slashed_count: 8,
+    };
+    let bytes = bincode::serialize(&node_stats).unwrap();
+    assert_eq!(bytes, bincode::serialize(&persisted).unwrap());
+
+    // The counters that are not persisted start from zero
+    assert_eq!(
+        bincode::deserialize::<NodeStats>(&bytes).unwrap(),
+        NodeStats {
+            dr_skipped_by_policy_count: 0,
+            dr_skipped_by_selection_count: 0,
+            retrieval_cache_hits: 0,
+            retrieval_cache_misses: 0,
+            ..node_stats.clone()
+        }
+    );
+
+    // But they are included in the JSON
+    let json = serde_json::to_string(&node_stats).unwrap();
+    assert_eq!(
+        serde_json::from_str::<NodeStats>(&json).unwrap(),
+        node_stats
+    );
+}
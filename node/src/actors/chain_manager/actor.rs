@@ -1,7 +1,15 @@
 use actix::prelude::*;
 use std::{str::FromStr, time::Duration};
 
-use super::{handlers::EveryEpochPayload, ChainManager};
+use super::{
+    address_index::AddressIndex,
+    data_request_index::DataRequestIndex,
+    data_request_selection::DataRequestSelector,
+    handlers::EveryEpochPayload,
+    pruning::{self, Pruning, PruningState},
+    retrieval_policy::RetrievalPolicyEnforcer,
+    ChainManager,
+};
 use crate::{
     actors::{
         epoch_manager::{EpochManager, EpochManagerError::CheckpointZeroInTheFuture},
@@ -63,7 +71,7 @@ impl ChainManager {
             .map_err(|err, _act, _ctx| {
                 log::error!("Couldn't get config: {}", err);
             })
-            .and_then(|config, act, _ctx| {
+            .and_then(|config, act, ctx| {
                 let consensus_constants = config.consensus_constants.clone();
 
                 if config.mining.data_request_timeout == Duration::new(0, 0) {
@@ -75,11 +83,47 @@ impl ChainManager {
                 // Set the retrievals limit per epoch, as read from the configuration
                 act.data_request_max_retrievals_per_epoch = config.mining.data_request_max_retrievals_per_epoch;
 
+                // Set the retrieval policy, as read from the configuration
+                act.retrieval_policy = RetrievalPolicyEnforcer::new(config.mining.retrieval_policy.clone());
+
+                // Set the criteria to select data requests, as read from the configuration
+                act.data_request_selector = DataRequestSelector::from_config(&config.mining.data_request_selection);
+
+                act.data_request_audit_log = config.mining.data_request_audit_log;
+
+                // On reorganizations, this also discards the pending entries of reverted blocks
+                if config.storage.address_index {
+                    act.address_index = Some(AddressIndex::default());
+                }
+
+                if config.storage.data_request_index {
+                    act.data_request_index = Some(DataRequestIndex::default());
+                }
+
                 act.tx_pending_timeout = config.mempool.tx_pending_timeout;
 
                 let magic = consensus_constants.get_magic();
                 act.set_magic(magic);
 
+                if config.storage.pruning {
+                    act.pruning = Pruning::new(config.storage.pruning_keep_superblocks);
+                }
+
+                // Read what was pruned in previous runs before consolidating any superblock. It is
+                // read even if pruned mode is disabled, because the pruned blocks are still missing.
+                pruning::load(magic)
+                    .into_actor(act)
+                    .map(|state, act, _ctx| {
+                        if act.pruning.enabled {
+                            log::info!("Running in pruned mode, the oldest stored block is from epoch #{}", state.first_block_epoch);
+                        } else if state != PruningState::default() {
+                            log::warn!("Pruned mode is disabled, but the blocks older than epoch #{} were pruned in previous runs", state.first_block_epoch);
+                        }
+                        act.pruning.state = state;
+                    })
+                    .map_err(|e, _act, _ctx| log::error!("Failed to read the pruning state from storage: {}", e))
+                    .wait(ctx);
+
                 // Do not start the MiningManager if the configuration disables it
                 act.mining_enabled = config.mining.enabled;
 
@@ -242,6 +286,10 @@ impl ChainManager {
 
                 act.chain_state = chain_state;
 
+                // The records of the data requests that are being resolved are not persisted, so
+                // they must be rebuilt before their tallies are consolidated
+                act.update_data_request_index();
+
                 // initialize_from_storage is also used to implement reorganizations
                 // In that case, we must clear some fields to avoid forks
                 act.best_candidate = None;
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use actix::SystemService;
+use futures::{future, stream, Future, Stream};
+
+use witnet_data_structures::{
+    address_index::{block_entries, block_spent_outputs, transaction_outputs, AddressTransaction},
+    chain::{
+        Block, Blockchain, Hash, Hashable, OutputPointer, PointerToBlock, PublicKeyHash,
+        ValueTransferOutput,
+    },
+    utxo_pool::UnspentOutputsPool,
+};
+
+use crate::{
+    actors::{
+        chain_manager::ChainManagerError,
+        inventory_manager::{InventoryManager, InventoryManagerError},
+        messages::GetItemBlock,
+    },
+    storage_mngr,
+};
+
+/// Tag of the storage keys of the entries of the address index
+const ADDRESS_INDEX_TAG: &str = "ADDRESS-INDEX";
+/// Maximum number of transactions of an address read from the address index at once
+const MAX_ADDRESS_TRANSACTIONS: usize = 100_000;
+
+/// Storage key of the entry of the address index for a transaction that touched an address. The
+/// keys of all the entries of an address start with the serialization of `(ADDRESS_INDEX_TAG, pkh)`.
+fn entry_key<'a>(
+    pkh: &'a PublicKeyHash,
+    transaction: &'a Hash,
+) -> (&'static str, &'a PublicKeyHash, &'a Hash) {
+    (ADDRESS_INDEX_TAG, pkh, transaction)
+}
+
+/// Entries of the address index for the consolidated blocks that have not been persisted yet
+#[derive(Debug, Default)]
+pub struct AddressIndex {
+    pending: HashMap<Hash, Vec<(PublicKeyHash, AddressTransaction)>>,
+}
+
+impl AddressIndex {
+    /// Compute the entries of a block that is being consolidated. This must be done before
+    /// updating the UTXO set with the block, while the outputs spent by the block are still in it.
+    pub fn index_block(&mut self, block: &Block, utxo_set: &UnspentOutputsPool) {
+        let entries = block_entries(block, |output_pointer| {
+            utxo_set.get(output_pointer).cloned()
+        });
+        self.pending.insert(block.hash(), entries);
+    }
+
+    /// Add the pending entries of a block to the storage batch that persists that block
+    pub fn add_block_to_batch(
+        &mut self,
+        block_hash: &Hash,
+        batch: &mut storage_mngr::WriteBatch,
+    ) -> Result<(), failure::Error> {
+        match self.pending.remove(block_hash) {
+            Some(entries) => add_entries_to_batch(batch, &entries),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Add entries of the address index to a storage batch
+fn add_entries_to_batch(
+    batch: &mut storage_mngr::WriteBatch,
+    entries: &[(PublicKeyHash, AddressTransaction)],
+) -> Result<(), failure::Error> {
+    for (pkh, entry) in entries {
+        batch.put(&entry_key(pkh, &entry.transaction), entry)?;
+    }
+
+    Ok(())
+}
+
+/// Get all the transactions that touched an address from the address index, in chronological
+/// order. Fails if there are more than `MAX_ADDRESS_TRANSACTIONS`.
+pub fn get_address_transactions(
+    pkh: PublicKeyHash,
+) -> impl Future<Item = Vec<AddressTransaction>, Error = failure::Error> {
+    storage_mngr::get_prefix::<_, (String, PublicKeyHash, Hash), AddressTransaction>(
+        &(ADDRESS_INDEX_TAG, pkh),
+        MAX_ADDRESS_TRANSACTIONS + 1,
+    )
+    .and_then(|pairs| {
+        if pairs.len() > MAX_ADDRESS_TRANSACTIONS {
+            return Err(ChainManagerError::TooManyIndexEntries {
+                limit: MAX_ADDRESS_TRANSACTIONS,
+            }
+            .into());
+        }
+        let mut transactions: Vec<_> = pairs.into_iter().map(|(_key, entry)| entry).collect();
+        transactions.sort_by_key(|entry| entry.epoch);
+
+        Ok(transactions)
+    })
+}
+
+/// Whether the block that contains a transaction of the address index is still in the block chain.
+/// The entries of the blocks reverted by a rollback are not deleted from storage, so they must be
+/// left out when reading the index.
+pub fn in_block_chain(entry: &AddressTransaction, block_chain: &Blockchain) -> bool {
+    block_chain.get(&entry.epoch) == Some(&entry.pointer.block_hash)
+}
+
+/// Rebuild the address index from the stored blocks with the given hashes. Blocks are indexed one
+/// by one, in the given order, and the outputs they spend are read from the stored blocks that
+/// created them.
+///
+/// Blocks that are not stored are skipped, and so are the spent outputs created by them.
+///
+/// Existing entries are overwritten, so the index can be rebuilt while new blocks are being
+/// consolidated.
+pub fn rebuild(block_hashes: Vec<Hash>) -> impl Future<Item = (), Error = failure::Error> {
+    let total = block_hashes.len();
+
+    stream::iter_ok(block_hashes.into_iter().enumerate()).for_each(move |(i, block_hash)| {
+        get_block(block_hash)
+            .and_then(move |block| match block {
+                Some(block) => future::Either::A(index_stored_block(block)),
+                None => {
+                    log::warn!(
+                        "Block {} is not stored, it is left out of the address index",
+                        block_hash
+                    );
+                    future::Either::B(future::ok(()))
+                }
+            })
+            .map(move |()| {
+                if (i + 1) % 1000 == 0 || i + 1 == total {
+                    log::info!("Rebuilding address index: {}/{} blocks", i + 1, total);
+                }
+            })
+    })
+}
+
+/// Write the entries of the address index for a stored block
+fn index_stored_block(block: Block) -> impl Future<Item = (), Error = failure::Error> {
+    get_spent_outputs(block_spent_outputs(&block)).and_then(move |outputs| {
+        let entries = block_entries(&block, |output_pointer| {
+            outputs.get(output_pointer).cloned()
+        });
+        let mut batch = storage_mngr::WriteBatch::new();
+
+        future::result(add_entries_to_batch(&mut batch, &entries))
+            .and_then(move |()| storage_mngr::write(batch))
+    })
+}
+
+/// Get a stored block, or `None` if it is not stored
+fn get_block(hash: Hash) -> impl Future<Item = Option<Block>, Error = failure::Error> {
+    InventoryManager::from_registry()
+        .send(GetItemBlock { hash })
+        .map_err(failure::Error::from)
+        .and_then(|res| match res {
+            Ok(block) => Ok(Some(block)),
+            Err(InventoryManagerError::ItemNotFound) => Ok(None),
+            Err(e) => Err(failure::Error::from(e)),
+        })
+}
+
+/// Get the pointer to the block that contains a stored transaction, without reading that block
+fn get_transaction_pointer(
+    hash: Hash,
+) -> impl Future<Item = Option<PointerToBlock>, Error = failure::Error> {
+    let key = match hash {
+        Hash::SHA256(x) => x.to_vec(),
+    };
+
+    storage_mngr::get::<_, PointerToBlock>(&key)
+}
+
+/// Get the given outputs from the stored transactions that created them. Only the pointers to
+/// those transactions are read one by one, and then every block that contains some of them is read
+/// once. Outputs whose transaction or block is not stored are left out.
+fn get_spent_outputs(
+    output_pointers: Vec<OutputPointer>,
+) -> impl Future<Item = HashMap<OutputPointer, ValueTransferOutput>, Error = failure::Error> {
+    let mut tx_hashes: Vec<Hash> = output_pointers
+        .iter()
+        .map(|output_pointer| output_pointer.transaction_id)
+        .collect();
+    tx_hashes.sort();
+    tx_hashes.dedup();
+
+    let tx_pointers = tx_hashes.into_iter().map(|tx_hash| {
+        get_transaction_pointer(tx_hash).map(move |pointer| pointer.map(|p| (tx_hash, p)))
+    });
+
+    future::join_all(tx_pointers).and_then(move |tx_pointers| {
+        let tx_pointers: HashMap<Hash, PointerToBlock> =
+            tx_pointers.into_iter().flatten().collect();
+        let mut block_hashes: Vec<Hash> = tx_pointers
+            .values()
+            .map(|pointer| pointer.block_hash)
+            .collect();
+        block_hashes.sort();
+        block_hashes.dedup();
+
+        let blocks = block_hashes.into_iter().map(|block_hash| {
+            get_block(block_hash).map(move |block| block.map(|b| (block_hash, b)))
+        });
+
+        future::join_all(blocks).map(move |blocks| {
+            let blocks: HashMap<Hash, Block> = blocks.into_iter().flatten().collect();
+
+            output_pointers
+                .into_iter()
+                .filter_map(|output_pointer| {
+                    let pointer = tx_pointers.get(&output_pointer.transaction_id)?;
+                    let transaction = blocks
+                        .get(&pointer.block_hash)?
+                        .txns
+                        .get(pointer.transaction_index)
+                        .filter(|tx| tx.hash() == output_pointer.transaction_id)?;
+                    let output = transaction_outputs(&transaction)
+                        .get(output_pointer.output_index as usize)?
+                        .clone();
+
+                    Some((output_pointer, output))
+                })
+                .collect()
+        })
+    })
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+};
+
+use futures::Future;
+use serde::{de::DeserializeOwned, Serialize};
+
+use witnet_data_structures::{
+    chain::{DataRequestInfo, Hash, Hashable},
+    data_request::DataRequestPool,
+    data_request_index::{
+        DataRequestLifecycleStage, DataRequestQuery, DataRequestQueryResult, DataRequestRecord,
+        DataRequestTallyStatus,
+    },
+    radon_report::{RadonReport, ReportContext},
+    transaction::TallyTransaction,
+};
+use witnet_rad::types::RadonTypes;
+
+use crate::{actors::chain_manager::ChainManagerError, storage_mngr};
+
+/// Tag of the storage keys of the index of data requests by requester
+const REQUESTER_TAG: &str = "DR-INDEX-REQUESTER";
+/// Tag of the storage keys of the index of data requests by RAD request hash
+const RAD_HASH_TAG: &str = "DR-INDEX-RAD";
+/// Tag of the storage keys of the index of data requests by stage
+const STAGE_TAG: &str = "DR-INDEX-STAGE";
+/// Tag of the storage keys of the index of data requests by epoch
+const EPOCH_TAG: &str = "DR-INDEX-EPOCH";
+/// Maximum number of records read from an index at once
+const MAX_INDEX_RECORDS: usize = 100_000;
+
+/// Storage key of an entry of the data request index. The keys of all the entries of an index
+/// start with the serialization of `(tag, field)`, followed by the epoch in big endian, so the
+/// entries with the same field are sorted by epoch.
+type IndexKey<F> = (String, F, [u8; 4], Hash);
+
+type RecordsFuture = Box<dyn Future<Item = Vec<DataRequestRecord>, Error = failure::Error>>;
+
+/// Records of the data requests whose changes have not been persisted yet
+#[derive(Debug, Default)]
+pub struct DataRequestIndex {
+    /// Records of the data requests that are being resolved
+    records: HashMap<Hash, DataRequestRecord>,
+    /// Data requests that are being resolved whose record changed since it was last persisted
+    changed: HashSet<Hash>,
+    /// Records of the finished data requests
+    finished: Vec<DataRequestRecord>,
+}
+
+impl DataRequestIndex {
+    /// Update the records of the data requests that are being resolved, after consolidating a
+    /// block or restoring the chain state from storage.
+    ///
+    /// Data requests are assigned the epoch at which they are unlocked, which never changes, so
+    /// the keys of their entries are the same after restarting the node.
+    pub fn update(&mut self, pool: &DataRequestPool) {
+        for (dr_pointer, state) in &pool.data_request_pool {
+            let stage = DataRequestLifecycleStage::from(state.stage);
+            match self.records.get_mut(dr_pointer) {
+                Some(record) => {
+                    if record.stage != stage {
+                        record.stage = stage;
+                        self.changed.insert(*dr_pointer);
+                    }
+                }
+                None => {
+                    self.records.insert(
+                        *dr_pointer,
+                        DataRequestRecord::from_state(*dr_pointer, state),
+                    );
+                    self.changed.insert(*dr_pointer);
+                }
+            }
+        }
+    }
+
+    /// Mark data requests as finished, with the outcome of their tallies
+    pub fn finish(&mut self, dr_infos: &[DataRequestInfo]) {
+        for dr_info in dr_infos {
+            let tally = match &dr_info.tally {
+                Some(tally) => tally,
+                None => continue,
+            };
+            self.changed.remove(&tally.dr_pointer);
+            match self.records.remove(&tally.dr_pointer) {
+                Some(mut record) => {
+                    record.stage = DataRequestLifecycleStage::FINISHED;
+                    record.tally = Some(tally_status(tally, dr_info.block_hash_tally_tx));
+                    self.finished.push(record);
+                }
+                None => log::debug!(
+                    "Data request {} finished before being indexed",
+                    tally.dr_pointer
+                ),
+            }
+        }
+    }
+
+    /// Add the records that changed since they were last persisted to a storage batch
+    pub fn add_to_batch(
+        &mut self,
+        batch: &mut storage_mngr::WriteBatch,
+    ) -> Result<(), failure::Error> {
+        for dr_pointer in self.changed.drain() {
+            if let Some(record) = self.records.get(&dr_pointer) {
+                add_record_to_batch(batch, record)?;
+            }
+        }
+        for record in self.finished.drain(..) {
+            add_record_to_batch(batch, &record)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of a tally, with the result formatted like in the logs
+fn tally_status(tally: &TallyTransaction, block_hash: Option<Hash>) -> DataRequestTallyStatus {
+    let result = RadonTypes::try_from(tally.tally.as_slice());
+    let success = match &result {
+        Ok(RadonTypes::RadonError(_)) | Err(_) => false,
+        Ok(_) => true,
+    };
+    let result = RadonReport::from_result(result, &ReportContext::default())
+        .into_inner()
+        .to_string();
+
+    DataRequestTallyStatus {
+        tally_tx: tally.hash(),
+        block_hash,
+        success,
+        result,
+    }
+}
+
+/// Add a record to all the indexes, removing it from the index of the stages it left
+fn add_record_to_batch(
+    batch: &mut storage_mngr::WriteBatch,
+    record: &DataRequestRecord,
+) -> Result<(), failure::Error> {
+    let epoch = record.epoch.to_be_bytes();
+    let dr_pointer = record.dr_pointer;
+
+    batch.put(
+        &(REQUESTER_TAG, record.requester, epoch, dr_pointer),
+        record,
+    )?;
+    batch.put(&(RAD_HASH_TAG, record.rad_hash, epoch, dr_pointer), record)?;
+    batch.put(&(EPOCH_TAG, (), epoch, dr_pointer), record)?;
+    for stage in DataRequestLifecycleStage::ALL.iter() {
+        let key = (STAGE_TAG, *stage, epoch, dr_pointer);
+        if *stage == record.stage {
+            batch.put(&key, record)?;
+        } else {
+            batch.delete(&key)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the records of an index whose field has the given value, restricted to the epoch range of
+/// the query
+fn get_index<F>(tag: &'static str, field: F, query: &DataRequestQuery) -> RecordsFuture
+where
+    F: Serialize + DeserializeOwned + Clone + 'static,
+{
+    let start = query.from_epoch.unwrap_or(0).to_be_bytes();
+    // Without an upper bound, the range ends at the greatest key of the entries with this field
+    let (end, end_hash) = match query.to_epoch.and_then(|epoch| epoch.checked_add(1)) {
+        Some(end) => (end.to_be_bytes(), Hash::default()),
+        None => ([0xFF; 4], Hash::SHA256([0xFF; 32])),
+    };
+    let pairs = storage_mngr::get_range::<IndexKey<F>, DataRequestRecord>(
+        &(tag.to_string(), field.clone(), start, Hash::default()),
+        &(tag.to_string(), field, end, end_hash),
+        MAX_INDEX_RECORDS + 1,
+    );
+
+    Box::new(pairs.and_then(|pairs| {
+        if pairs.len() > MAX_INDEX_RECORDS {
+            return Err(ChainManagerError::TooManyIndexEntries {
+                limit: MAX_INDEX_RECORDS,
+            }
+            .into());
+        }
+
+        Ok(pairs.into_iter().map(|(_key, record)| record).collect())
+    }))
+}
+
+/// Get the page of the indexed data requests that match a query. The most selective index among
+/// the criteria of the query is used, and the rest of the criteria are checked on its records.
+pub fn query(
+    query: DataRequestQuery,
+) -> impl Future<Item = DataRequestQueryResult, Error = failure::Error> {
+    let records = if let Some(requester) = query.requester {
+        get_index(REQUESTER_TAG, requester, &query)
+    } else if let Some(rad_hash) = query.rad_hash {
+        get_index(RAD_HASH_TAG, rad_hash, &query)
+    } else if let Some(stage) = query.stage {
+        get_index(STAGE_TAG, stage, &query)
+    } else {
+        get_index(EPOCH_TAG, (), &query)
+    };
+
+    records.map(move |records| query.paginate(records))
+}
@@ -0,0 +1,401 @@
+//! Selection of the data requests to commit to
+//!
+//! Being eligible for a data request does not mean that it is worth resolving it. Before committing
+//! to a data request, the node evaluates it against a list of selection criteria, which are built
+//! from the `[mining.data_request_selection]` section of the configuration file. Additional
+//! criteria can be plugged in by implementing the `SelectionCriterion` trait.
+use std::{collections::HashMap, fmt};
+
+use failure::Fail;
+
+use witnet_config::config::DataRequestSelection;
+use witnet_data_structures::chain::{DataRequestOutput, Hash};
+
+use crate::actors::chain_manager::retrieval_policy::{host_matches, source_host};
+
+/// Reasons for not selecting a data request
+#[derive(Debug, PartialEq, Eq, Fail)]
+pub enum SelectionRejection {
+    /// The reward is too low for the number of sources to retrieve
+    #[fail(
+        display = "the reward per retrieval ({} nanowits) is below the minimum ({} nanowits)",
+        reward_per_retrieval, minimum
+    )]
+    RewardTooLow {
+        reward_per_retrieval: u64,
+        minimum: u64,
+    },
+    /// The collateral would exceed the maximum collateral locked in open commitments
+    #[fail(
+        display = "{} nanowits are locked as collateral, and {} more would exceed the maximum ({})",
+        exposure, collateral, maximum
+    )]
+    CollateralExposure {
+        exposure: u64,
+        collateral: u64,
+        maximum: u64,
+    },
+    /// There are too many open commitments
+    #[fail(
+        display = "there are already {} open commitments, which is the maximum",
+        maximum
+    )]
+    TooManyOpenCommitments { maximum: u32 },
+    /// None of the sources belongs to the selected domains
+    #[fail(display = "none of the sources belongs to the selected domains")]
+    SourceDomains,
+    /// Rejected by a custom criterion
+    #[fail(display = "{}", _0)]
+    Other(String),
+}
+
+/// Data request that the node is eligible for, along with the commitments that the node already
+/// has open
+#[derive(Debug)]
+pub struct Candidate<'a> {
+    /// Data request output
+    pub dr_output: &'a DataRequestOutput,
+    /// Collateral that would be locked by committing to this data request
+    pub collateral: u64,
+    /// Number of commitments waiting for their tally, including those being prepared
+    pub open_commitments: usize,
+    /// Collateral locked in commitments waiting for their tally, including those being prepared
+    pub collateral_exposure: u64,
+}
+
+/// A criterion that data requests must meet in order to be selected
+pub trait SelectionCriterion: fmt::Debug + Send {
+    /// Return an error if the data request must not be selected
+    fn evaluate(&self, candidate: &Candidate) -> Result<(), SelectionRejection>;
+}
+
+/// Minimum witness reward per source of the data request
+#[derive(Debug)]
+pub struct MinRewardPerRetrieval(pub u64);
+
+impl SelectionCriterion for MinRewardPerRetrieval {
+    fn evaluate(&self, candidate: &Candidate) -> Result<(), SelectionRejection> {
+        let retrievals = candidate.dr_output.data_request.retrieve.len().max(1);
+        let reward_per_retrieval = candidate.dr_output.witness_reward / retrievals as u64;
+
+        if reward_per_retrieval < self.0 {
+            Err(SelectionRejection::RewardTooLow {
+                reward_per_retrieval,
+                minimum: self.0,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Maximum collateral locked in open commitments
+#[derive(Debug)]
+pub struct MaxCollateralExposure(pub u64);
+
+impl SelectionCriterion for MaxCollateralExposure {
+    fn evaluate(&self, candidate: &Candidate) -> Result<(), SelectionRejection> {
+        if candidate
+            .collateral_exposure
+            .saturating_add(candidate.collateral)
+            > self.0
+        {
+            Err(SelectionRejection::CollateralExposure {
+                exposure: candidate.collateral_exposure,
+                collateral: candidate.collateral,
+                maximum: self.0,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Maximum number of open commitments
+#[derive(Debug)]
+pub struct MaxOpenCommitments(pub u32);
+
+impl SelectionCriterion for MaxOpenCommitments {
+    fn evaluate(&self, candidate: &Candidate) -> Result<(), SelectionRejection> {
+        if candidate.open_commitments >= self.0 as usize {
+            Err(SelectionRejection::TooManyOpenCommitments { maximum: self.0 })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// At least one of the sources must belong to one of these domains
+#[derive(Debug)]
+pub struct SourceDomains(pub Vec<String>);
+
+impl SelectionCriterion for SourceDomains {
+    fn evaluate(&self, candidate: &Candidate) -> Result<(), SelectionRejection> {
+        let any_source_matches = candidate
+            .dr_output
+            .data_request
+            .retrieve
+            .iter()
+            .filter_map(|source| source_host(source).ok().flatten())
+            .any(|host| {
+                let host = host.to_string().to_lowercase();
+
+                self.0.iter().any(|pattern| host_matches(pattern, &host))
+            });
+
+        if any_source_matches {
+            Ok(())
+        } else {
+            Err(SelectionRejection::SourceDomains)
+        }
+    }
+}
+
+/// Decides which eligible data requests to commit to
+#[derive(Debug, Default)]
+pub struct DataRequestSelector {
+    /// Criteria that data requests must meet in order to be selected
+    criteria: Vec<Box<dyn SelectionCriterion>>,
+    /// Collateral of the selected data requests whose commitments are still being prepared
+    pending: HashMap<Hash, u64>,
+}
+
+impl DataRequestSelector {
+    /// Create a selector with the criteria enabled in the configuration
+    pub fn from_config(config: &DataRequestSelection) -> Self {
+        let mut selector = Self::default();
+
+        if config.min_reward_per_retrieval != 0 {
+            selector =
+                selector.with_criterion(MinRewardPerRetrieval(config.min_reward_per_retrieval));
+        }
+        if config.max_collateral_exposure != 0 {
+            selector =
+                selector.with_criterion(MaxCollateralExposure(config.max_collateral_exposure));
+        }
+        if config.max_open_commitments != 0 {
+            selector = selector.with_criterion(MaxOpenCommitments(config.max_open_commitments));
+        }
+        if !config.source_domains.is_empty() {
+            selector = selector.with_criterion(SourceDomains(config.source_domains.clone()));
+        }
+
+        selector
+    }
+
+    /// Add a criterion to the selector
+    pub fn with_criterion<C: SelectionCriterion + 'static>(mut self, criterion: C) -> Self {
+        self.criteria.push(Box::new(criterion));
+
+        self
+    }
+
+    /// Evaluate a data request against every criterion. `open_commitments` and
+    /// `collateral_exposure` refer to the commitments that have already been sent, and will be
+    /// increased with the commitments being prepared. If the data request is selected, its
+    /// collateral counts as exposed until `release` is called.
+    pub fn select(
+        &mut self,
+        dr_pointer: Hash,
+        dr_output: &DataRequestOutput,
+        collateral: u64,
+        open_commitments: usize,
+        collateral_exposure: u64,
+    ) -> Result<(), SelectionRejection> {
+        let candidate = Candidate {
+            dr_output,
+            collateral,
+            open_commitments: open_commitments + self.pending.len(),
+            collateral_exposure: self
+                .pending
+                .values()
+                .fold(collateral_exposure, |acc, x| acc.saturating_add(*x)),
+        };
+
+        for criterion in &self.criteria {
+            criterion.evaluate(&candidate)?;
+        }
+        self.pending.insert(dr_pointer, collateral);
+
+        Ok(())
+    }
+
+    /// The commitment to a selected data request has been sent, or it has been abandoned
+    pub fn release(&mut self, dr_pointer: &Hash) {
+        self.pending.remove(dr_pointer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use witnet_data_structures::chain::{RADRequest, RADRetrieve, RADType};
+
+    use super::*;
+
+    fn dr_output(witness_reward: u64, urls: &[&str]) -> DataRequestOutput {
+        DataRequestOutput {
+            witness_reward,
+            data_request: RADRequest {
+                retrieve: urls
+                    .iter()
+                    .map(|url| RADRetrieve {
+                        kind: RADType::HttpGet,
+                        url: url.to_string(),
+                        ..RADRetrieve::default()
+                    })
+                    .collect(),
+                ..RADRequest::default()
+            },
+            ..DataRequestOutput::default()
+        }
+    }
+
+    fn config() -> DataRequestSelection {
+        DataRequestSelection {
+            min_reward_per_retrieval: 0,
+            max_collateral_exposure: 0,
+            max_open_commitments: 0,
+            source_domains: vec![],
+        }
+    }
+
+    fn dr_pointer(n: u8) -> Hash {
+        Hash::SHA256([n; 32])
+    }
+
+    #[test]
+    fn default_config_selects_everything() {
+        let mut selector = DataRequestSelector::from_config(&config());
+        let dr_output = dr_output(0, &["https://api.example.com/"]);
+
+        for i in 0..100 {
+            assert_eq!(
+                selector.select(dr_pointer(i), &dr_output, 1_000_000_000, 1000, u64::MAX),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn min_reward_per_retrieval() {
+        let mut selector = DataRequestSelector::from_config(&DataRequestSelection {
+            min_reward_per_retrieval: 1_000,
+            ..config()
+        });
+        let urls = ["https://a.example.com/", "https://b.example.com/"];
+
+        assert_eq!(
+            selector.select(dr_pointer(0), &dr_output(2_000, &urls), 0, 0, 0),
+            Ok(())
+        );
+        assert_eq!(
+            selector.select(dr_pointer(1), &dr_output(1_999, &urls), 0, 0, 0),
+            Err(SelectionRejection::RewardTooLow {
+                reward_per_retrieval: 999,
+                minimum: 1_000,
+            })
+        );
+    }
+
+    #[test]
+    fn max_collateral_exposure() {
+        let mut selector = DataRequestSelector::from_config(&DataRequestSelection {
+            max_collateral_exposure: 10_000,
+            ..config()
+        });
+        let dr_output = dr_output(0, &[]);
+
+        assert_eq!(
+            selector.select(dr_pointer(0), &dr_output, 4_000, 1, 2_000),
+            Ok(())
+        );
+        // The collateral of the pending commitment is taken into account
+        assert_eq!(
+            selector.select(dr_pointer(1), &dr_output, 4_001, 1, 2_000),
+            Err(SelectionRejection::CollateralExposure {
+                exposure: 6_000,
+                collateral: 4_001,
+                maximum: 10_000,
+            })
+        );
+        assert_eq!(
+            selector.select(dr_pointer(1), &dr_output, 4_000, 1, 2_000),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn max_open_commitments() {
+        let mut selector = DataRequestSelector::from_config(&DataRequestSelection {
+            max_open_commitments: 3,
+            ..config()
+        });
+        let dr_output = dr_output(0, &[]);
+
+        assert_eq!(selector.select(dr_pointer(0), &dr_output, 0, 1, 0), Ok(()));
+        assert_eq!(selector.select(dr_pointer(1), &dr_output, 0, 1, 0), Ok(()));
+        assert_eq!(
+            selector.select(dr_pointer(2), &dr_output, 0, 1, 0),
+            Err(SelectionRejection::TooManyOpenCommitments { maximum: 3 })
+        );
+        // Once sent, commitments are counted by the caller instead
+        selector.release(&dr_pointer(0));
+        selector.release(&dr_pointer(1));
+        assert_eq!(selector.select(dr_pointer(2), &dr_output, 0, 2, 0), Ok(()));
+    }
+
+    #[test]
+    fn source_domains() {
+        let mut selector = DataRequestSelector::from_config(&DataRequestSelection {
+            source_domains: vec!["*.example.com".to_string()],
+            ..config()
+        });
+
+        assert_eq!(
+            selector.select(
+                dr_pointer(0),
+                &dr_output(0, &["https://other.org/", "https://api.example.com/"]),
+                0,
+                0,
+                0
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            selector.select(
+                dr_pointer(1),
+                &dr_output(0, &["https://other.org/"]),
+                0,
+                0,
+                0
+            ),
+            Err(SelectionRejection::SourceDomains)
+        );
+    }
+
+    #[test]
+    fn custom_criterion() {
+        #[derive(Debug)]
+        struct MaxWitnesses(u16);
+
+        impl SelectionCriterion for MaxWitnesses {
+            fn evaluate(&self, candidate: &Candidate) -> Result<(), SelectionRejection> {
+                if candidate.dr_output.witnesses > self.0 {
+                    Err(SelectionRejection::Other("too many witnesses".to_string()))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let mut selector =
+            DataRequestSelector::from_config(&config()).with_criterion(MaxWitnesses(2));
+        let mut dr_output = dr_output(0, &[]);
+        dr_output.witnesses = 3;
+
+        assert_eq!(
+            selector.select(dr_pointer(0), &dr_output, 0, 0, 0),
+            Err(SelectionRejection::Other("too many witnesses".to_string()))
+        );
+    }
+}
@@ -1,37 +1,49 @@
 use actix::{fut::WrapFuture, prelude::*};
 use futures::future::Future;
 use std::{
-    collections::BTreeMap, collections::HashSet, convert::TryFrom, net::SocketAddr, time::Duration,
+    collections::BTreeMap, collections::HashMap, collections::HashSet, convert::TryFrom,
+    net::SocketAddr, time::Duration,
 };
 
 use witnet_data_structures::{
+    address_index::AddressTransaction,
+    audit::DataRequestAudit,
     chain::{
         Block, ChainState, CheckpointBeacon, DataRequestInfo, Epoch, Hash, Hashable, NodeStats,
-        SuperBlockVote,
+        PublicKeyHash, SuperBlockVote,
     },
+    data_request_index::DataRequestQueryResult,
     error::{ChainInfoError, TransactionError::DataRequestNotFound},
     transaction::{DRTransaction, Transaction, VTTransaction},
     transaction_factory,
     types::LastBeacon,
-    utxo_pool::{get_utxo_info, UtxoInfo},
+    utxo_pool::{get_utxo_info, get_utxo_info_of_outputs, UtxoInfo},
 };
 use witnet_util::timestamp::get_timestamp;
-use witnet_validations::validations::validate_rad_request;
+use witnet_validations::{
+    estimation::{estimate_data_request, DataRequestEstimate, NetworkStats},
+    validations::validate_rad_request,
+};
 
 use super::{ChainManager, ChainManagerError, StateMachine, SyncTarget};
 use crate::{
     actors::{
-        chain_manager::{handlers::BlockBatches::*, BlockCandidate},
+        chain_manager::{
+            address_index, data_request_index, handlers::BlockBatches::*, pruning::PruningState,
+            BlockCandidate,
+        },
         messages::{
             AddBlocks, AddCandidates, AddCommitReveal, AddSuperBlock, AddSuperBlockVote,
-            AddTransaction, Broadcast, BuildDrt, BuildVtt, EpochNotification, GetBalance,
-            GetBlocksEpochRange, GetDataRequestInfo, GetHighestCheckpointBeacon,
-            GetMemoryTransaction, GetMempool, GetMempoolResult, GetNodeStats, GetReputation,
-            GetReputationResult, GetState, GetSuperBlockVotes, GetUtxoInfo, IsConfirmedBlock,
-            PeersBeacons, ReputationStats, SendLastBeacon, SessionUnitResult, SetLastBeacon,
-            TryMineBlock,
+            AddTransaction, AvailableRange, Broadcast, BuildDrt, BuildVtt, EpochNotification,
+            EstimateDrt, GetAddressTransactions, GetAddressUtxos, GetAvailableRange, GetBalance,
+            GetBlocksEpochRange, GetDataRequestAudit, GetDataRequestInfo,
+            GetHighestCheckpointBeacon, GetMemoryTransaction, GetMempool, GetMempoolResult,
+            GetNodeStats, GetReputation, GetReputationResult, GetState, GetSuperBlockVotes,
+            GetUtxoInfo, IsConfirmedBlock, PeersBeacons, QueryDataRequests, RebuildAddressIndex,
+            ReputationStats, SendLastBeacon, SessionUnitResult, SetLastBeacon, TryMineBlock,
         },
         sessions_manager::SessionsManager,
+        storage_keys,
     },
     signature_mngr, storage_mngr,
     utils::mode_consensus,
@@ -409,18 +421,16 @@ impl Handler<AddBlocks> for ChainManager {
                             return;
                         }
 
-                        // Persist blocks batch when target not reached
-                        self.persist_blocks_batch(ctx, blocks);
+                        // Persist blocks batch, finished data requests and current chain state
+                        // together when target not reached
                         let to_be_stored =
                             self.chain_state.data_request_pool.finished_data_requests();
-                        self.persist_data_requests(ctx, to_be_stored);
+                        let chain_state = self.chain_state_to_persist(None);
+                        self.persist_batch(ctx, chain_state, blocks, to_be_stored);
 
                         log::debug!("TargetNotReached: superblock target #{} not reached, requesting more blocks. ({} processed blocks)",
                             sync_target.superblock.checkpoint, num_processed_blocks);
                         self.request_blocks_batch(ctx);
-
-                        // Copy current chain state into previous chain state, and persist it
-                        self.persist_chain_state(None, ctx);
                     }
                     // SyncWithoutCandidate:
                     // 1. process blocks
@@ -444,10 +454,9 @@ impl Handler<AddBlocks> for ChainManager {
                         if let Some(consolidate_epoch) = self.superblock_consolidation_is_needed(&sync_target, superblock_period) {
                             // We need to persist blocks in order to be able to construct the
                             // superblock
-                            self.persist_blocks_batch(ctx, consolidate_blocks);
                             let to_be_stored =
                                 self.chain_state.data_request_pool.finished_data_requests();
-                            self.persist_data_requests(ctx, to_be_stored);
+                            self.persist_batch(ctx, None, consolidate_blocks, to_be_stored);
                             // Create superblocks while synchronizing but do not broadcast them
                             // This is needed to ensure that we can validate the received superblocks later on
                             log::debug!("Will construct superblock during synchronization. Superblock index: {} Epoch {}", sync_target.superblock.checkpoint, consolidate_epoch);
@@ -500,10 +509,9 @@ impl Handler<AddBlocks> for ChainManager {
                         if let Some(consolidate_superblock_epoch) = self.superblock_consolidation_is_needed(&sync_target, superblock_period) {
                             // We need to persist blocks in order to be able to construct the
                             // superblock
-                            self.persist_blocks_batch(ctx, consolidate_blocks);
                             let to_be_stored =
                                 self.chain_state.data_request_pool.finished_data_requests();
-                            self.persist_data_requests(ctx, to_be_stored);
+                            self.persist_batch(ctx, None, consolidate_blocks, to_be_stored);
                             // Create superblocks while synchronizing but do not broadcast them
                             // This is needed to ensure that we can validate the received superblocks later on
                             log::debug!("Will construct superblock during synchronization. Superblock index: {} Epoch {}", sync_target.superblock.checkpoint, consolidate_superblock_epoch);
@@ -533,10 +541,9 @@ impl Handler<AddBlocks> for ChainManager {
 
                                     // We need to persist blocks in order to be able to construct the
                                     // superblock
-                                    act.persist_blocks_batch(ctx, candidate_blocks);
                                     let to_be_stored =
                                         act.chain_state.data_request_pool.finished_data_requests();
-                                    act.persist_data_requests(ctx, to_be_stored);
+                                    act.persist_batch(ctx, None, candidate_blocks, to_be_stored);
 
                                     log::info!("Block sync target achieved");
                                     // Target achieved, go back to state 1
@@ -669,11 +676,35 @@ impl Handler<AddTransaction> for ChainManager {
 impl Handler<GetBlocksEpochRange> for ChainManager {
     type Result = Result<Vec<(Epoch, Hash)>, ChainManagerError>;
 
-    fn handle(&mut self, msg: GetBlocksEpochRange, _ctx: &mut Context<Self>) -> Self::Result {
+    fn handle(&mut self, mut msg: GetBlocksEpochRange, _ctx: &mut Context<Self>) -> Self::Result {
+        // Blocks that were pruned cannot be served, so they are not listed, even if pruned mode
+        // was disabled after pruning them
+        msg.range.0 = self.pruning.state.stored_blocks_start(msg.range.0);
+
         self.get_blocks_epoch_range(msg)
     }
 }
 
+impl Handler<GetAvailableRange> for ChainManager {
+    type Result = <GetAvailableRange as Message>::Result;
+
+    fn handle(&mut self, _msg: GetAvailableRange, _ctx: &mut Context<Self>) -> Self::Result {
+        let chain_info = match &self.chain_state.chain_info {
+            Some(chain_info) => chain_info,
+            None => return Err(ChainInfoError::ChainInfoNotFound.into()),
+        };
+        let pruning_state = self.pruning.state;
+
+        Ok(AvailableRange {
+            pruned: self.pruning.enabled,
+            first_block_epoch: pruning_state.first_block_epoch,
+            last_block_epoch: chain_info.highest_block_checkpoint.checkpoint,
+            first_superblock_index: pruning_state.first_superblock_index,
+            last_superblock_index: chain_info.highest_superblock_checkpoint.checkpoint,
+        })
+    }
+}
+
 impl PeersBeacons {
     /// Pretty-print a map {beacon: [peers]}
     pub fn pretty_format(&self) -> String {
@@ -1286,20 +1317,96 @@ impl Handler<GetDataRequestInfo> for ChainManager {
         {
             Box::new(futures::finished(dr_info))
         } else {
-            let dr_pointer_string = format!("DR-REPORT-{}", dr_pointer);
             // Otherwise, try to get it from storage
-            let fut = storage_mngr::get::<_, DataRequestInfo>(&dr_pointer_string).and_then(
-                move |dr_info| match dr_info {
-                    Some(x) => futures::finished(x),
-                    None => futures::failed(DataRequestNotFound { hash: dr_pointer }.into()),
-                },
-            );
+            let fut = storage_mngr::get::<_, DataRequestInfo>(
+                &storage_keys::data_request_report_key(&dr_pointer),
+            )
+            .and_then(move |dr_info| match dr_info {
+                Some(x) => futures::finished(x),
+                None => futures::failed(DataRequestNotFound { hash: dr_pointer }.into()),
+            });
 
             Box::new(fut)
         }
     }
 }
 
+impl Handler<EstimateDrt> for ChainManager {
+    type Result = Result<DataRequestEstimate, failure::Error>;
+
+    fn handle(&mut self, msg: EstimateDrt, _ctx: &mut Self::Context) -> Self::Result {
+        if self.sm_state != StateMachine::Synced {
+            return Err(ChainManagerError::NotSynced {
+                current_state: self.sm_state,
+            }
+            .into());
+        }
+
+        let rep_eng = match self.chain_state.reputation_engine.as_ref() {
+            Some(x) => x,
+            None => return Err(ChainManagerError::ChainNotReady.into()),
+        };
+        let consensus_constants = self.consensus_constants();
+        let collateral = if msg.dro.collateral == 0 {
+            consensus_constants.collateral_minimum
+        } else {
+            msg.dro.collateral
+        };
+
+        // Only the outputs that are old enough can be used as collateral
+        let block_number_limit = self
+            .chain_state
+            .block_number()
+            .saturating_sub(consensus_constants.collateral_age);
+        let mut mature_balances: HashMap<PublicKeyHash, u64> = HashMap::new();
+        for (_output_pointer, (output, block_number)) in
+            self.chain_state.unspent_outputs_pool.iter()
+        {
+            if *block_number <= block_number_limit {
+                let balance = mature_balances.entry(output.pkh).or_default();
+                *balance = balance.saturating_add(output.value);
+            }
+        }
+        let collateralized_identities = mature_balances
+            .values()
+            .filter(|balance| **balance >= collateral)
+            .count();
+
+        let network_stats = NetworkStats {
+            active_identities: u32::try_from(rep_eng.ars().active_identities_number())?,
+            collateralized_identities: u32::try_from(collateralized_identities)?,
+        };
+
+        Ok(estimate_data_request(
+            &msg.dro,
+            msg.fee,
+            consensus_constants.collateral_minimum,
+            consensus_constants.max_dr_weight,
+            Some(network_stats),
+        ))
+    }
+}
+
+impl Handler<GetDataRequestAudit> for ChainManager {
+    type Result = ResponseFuture<DataRequestAudit, failure::Error>;
+
+    fn handle(&mut self, msg: GetDataRequestAudit, _ctx: &mut Self::Context) -> Self::Result {
+        let dr_pointer = msg.dr_pointer;
+
+        let fut = storage_mngr::get::<_, DataRequestAudit>(&storage_keys::data_request_audit_key(
+            &dr_pointer,
+        ))
+        .and_then(move |audit| match audit {
+            Some(x) => futures::finished(x),
+            None => {
+                futures::failed(ChainManagerError::DataRequestAuditNotFound { dr_pointer }.into())
+            }
+        });
+
+        Box::new(fut)
+    }
+}
+
 impl Handler<GetBalance> for ChainManager {
     type Result = Result<u64, failure::Error>;
 
@@ -1355,6 +1462,142 @@ impl Handler<GetUtxoInfo> for ChainManager {
     }
 }
 
+impl Handler<GetAddressTransactions> for ChainManager {
+    type Result = ResponseActFuture<Self, Vec<AddressTransaction>, failure::Error>;
+
+    fn handle(
+        &mut self,
+        GetAddressTransactions { pkh }: GetAddressTransactions,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        if self.address_index.is_none() {
+            return Box::new(actix::fut::err(
+                ChainManagerError::AddressIndexDisabled.into(),
+            ));
+        }
+
+        let fut = address_index::get_address_transactions(pkh)
+            .into_actor(self)
+            .map(|mut transactions, act, _ctx| {
+                // The transactions of pruned blocks are left out, but their unspent outputs are
+                // still returned by `GetAddressUtxos`
+                let first_block_epoch = act.pruning.state.first_block_epoch;
+                transactions.retain(|transaction| {
+                    transaction.epoch >= first_block_epoch
+                        && address_index::in_block_chain(transaction, &act.chain_state.block_chain)
+                });
+
+                transactions
+            });
+
+        Box::new(fut)
+    }
+}
+
+impl Handler<GetAddressUtxos> for ChainManager {
+    type Result = ResponseActFuture<Self, UtxoInfo, failure::Error>;
+
+    fn handle(
+        &mut self,
+        GetAddressUtxos { pkh }: GetAddressUtxos,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        if self.address_index.is_none() {
+            return Box::new(actix::fut::err(
+                ChainManagerError::AddressIndexDisabled.into(),
+            ));
+        }
+        if self.sm_state != StateMachine::Synced {
+            return Box::new(actix::fut::err(
+                ChainManagerError::NotSynced {
+                    current_state: self.sm_state,
+                }
+                .into(),
+            ));
+        }
+
+        // The index tells which outputs were paid to the address, and the UTXO set tells which of
+        // them are still unspent
+        let fut = address_index::get_address_transactions(pkh)
+            .into_actor(self)
+            .and_then(|transactions, act, _ctx| {
+                let chain_info = act.chain_state.chain_info.as_ref().unwrap();
+                let block_number_limit = act
+                    .chain_state
+                    .block_number()
+                    .saturating_sub(chain_info.consensus_constants.collateral_age);
+                let output_pointers = transactions
+                    .iter()
+                    .flat_map(|transaction| transaction.output_pointers());
+
+                actix::fut::ok(get_utxo_info_of_outputs(
+                    output_pointers,
+                    &act.chain_state.unspent_outputs_pool,
+                    chain_info.consensus_constants.collateral_minimum,
+                    block_number_limit,
+                ))
+            });
+
+        Box::new(fut)
+    }
+}
+
+impl Handler<RebuildAddressIndex> for ChainManager {
+    type Result = <RebuildAddressIndex as Message>::Result;
+
+    fn handle(&mut self, _msg: RebuildAddressIndex, ctx: &mut Self::Context) -> Self::Result {
+        if self.address_index.is_none() {
+            return Err(ChainManagerError::AddressIndexDisabled.into());
+        }
+        // The index of the stored blocks would be incomplete without the outputs created by the
+        // pruned blocks
+        if self.pruning.enabled || self.pruning.state != PruningState::default() {
+            return Err(ChainManagerError::AddressIndexPruned.into());
+        }
+
+        let block_hashes: Vec<Hash> = self.chain_state.block_chain.values().cloned().collect();
+        let blocks_len = block_hashes.len();
+        log::info!("Rebuilding address index from {} blocks", blocks_len);
+
+        address_index::rebuild(block_hashes)
+            .into_actor(self)
+            .map_err(|e, _, _| log::error!("Failed to rebuild address index: {}", e))
+            .map(|(), _, _| log::info!("Successfully rebuilt address index"))
+            .spawn(ctx);
+
+        Ok(blocks_len)
+    }
+}
+
+impl Handler<QueryDataRequests> for ChainManager {
+    type Result = ResponseFuture<DataRequestQueryResult, failure::Error>;
+
+    fn handle(
+        &mut self,
+        QueryDataRequests(mut query): QueryDataRequests,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        if self.data_request_index.is_none() {
+            return Box::new(futures::failed(
+                ChainManagerError::DataRequestIndexDisabled.into(),
+            ));
+        }
+
+        // The records of the data requests unlocked before the oldest stored block are left out,
+        // as they may refer to pruned blocks and reports
+        let first_block_epoch = self.pruning.state.first_block_epoch;
+        if first_block_epoch > 0 {
+            query.from_epoch = Some(
+                query
+                    .from_epoch
+                    .map_or(first_block_epoch, |epoch| epoch.max(first_block_epoch)),
+            );
+        }
+
+        Box::new(data_request_index::query(query))
+    }
+}
+
 impl Handler<GetReputation> for ChainManager {
     type Result = Result<GetReputationResult, failure::Error>;
 
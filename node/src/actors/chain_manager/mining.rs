@@ -21,13 +21,15 @@ use witnet_validations::validations::{
 use crate::{
     actors::{
         chain_manager::{ChainManager, StateMachine},
-        messages::{AddCommitReveal, ResolveRA, RunTally},
+        messages::{AddCommitReveal, ResolveRA, ResolveRAOutput, RunTally},
         rad_manager::RadManager,
+        storage_keys,
     },
-    signature_mngr,
+    signature_mngr, storage_mngr,
 };
 use witnet_data_structures::chain::Hash;
 use witnet_data_structures::{
+    audit::DataRequestAudit,
     chain::{
         Block, BlockHeader, BlockMerkleRoots, BlockTransactions, Bn256PublicKey, CheckpointBeacon,
         CheckpointVRF, DataRequestOutput, EpochConstants, Hashable, Input, PublicKeyHash,
@@ -291,6 +293,14 @@ impl ChainManager {
         let current_retrieval_count = Arc::new(AtomicU16::new(0u16));
         let maximum_retrieval_count = self.data_request_max_retrievals_per_epoch;
 
+        let collateral_minimum = self
+            .chain_state
+            .chain_info
+            .as_ref()
+            .unwrap()
+            .consensus_constants
+            .collateral_minimum;
+
         for (dr_pointer, dr_state) in dr_pointers.into_iter().filter_map(|dr_pointer| {
             // Filter data requests that are not in data_request_pool
             self.chain_state
@@ -312,17 +322,12 @@ impl ChainManager {
             // Grab a reference to `current_retrieval_count`
             let cloned_retrieval_count = Arc::clone(&current_retrieval_count);
             let cloned_retrieval_count2 = Arc::clone(&current_retrieval_count);
-            let added_retrieval_count =
-                u16::try_from(dr_state.data_request.data_request.retrieve.len())
-                    .unwrap_or(core::u16::MAX);
+            let dr_output = dr_state.data_request.clone();
+            let retrieve = dr_state.data_request.data_request.retrieve.clone();
+            let scheduled_retrieve = retrieve.clone();
 
             let collateral_amount = if dr_state.data_request.collateral == 0 {
-                self.chain_state
-                    .chain_info
-                    .as_ref()
-                    .unwrap()
-                    .consensus_constants
-                    .collateral_minimum
+                collateral_minimum
             } else {
                 dr_state.data_request.collateral
             };
@@ -365,17 +370,60 @@ impl ChainManager {
                 })
                 .flatten()
                 .into_actor(self)
+                // Refrain from resolving requests that do not meet the selection criteria
+                .and_then(move |vrf_proof, act, _| {
+                    act.chain_state.node_stats.dr_eligibility_count += 1;
+
+                    let (commitments_count, collateral_exposure) =
+                        open_commitments(&act.chain_state.data_request_pool, collateral_minimum);
+                    match act.data_request_selector.select(dr_pointer, &dr_output, collateral_amount, commitments_count, collateral_exposure) {
+                        Ok(()) => actix::fut::ok(vrf_proof),
+                        Err(e) => {
+                            log::info!("{} Refrained from resolving data request {} for epoch #{} because it does not \
+                            meet the selection criteria: {}. The criteria can be adjusted in the \
+                            `[mining.data_request_selection]` section in the `witnet.toml` configuration file.",
+                                Yellow.bold().paint("[Mining]"),
+                                Yellow.bold().paint(dr_pointer.to_string()),
+                                Yellow.bold().paint(current_epoch.to_string()),
+                                e
+                            );
+                            act.chain_state.node_stats.dr_skipped_by_selection_count += 1;
+
+                            actix::fut::err(())
+                        }
+                    }
+                })
+                // Refrain from resolving requests with sources that the retrieval policy does not
+                // allow to contact, or that would exceed its limits.
+                .and_then(move |vrf_proof, act, _| {
+                    match act.retrieval_policy.acquire(current_epoch, dr_pointer, &retrieve) {
+                        Ok(()) => actix::fut::ok(vrf_proof),
+                        Err(e) => {
+                            log::info!("{} Refrained from resolving data request {} for epoch #{} because of the \
+                            retrieval policy: {}. The policy can be adjusted in the `[mining.retrieval_policy]` section \
+                            in the `witnet.toml` configuration file.",
+                                Yellow.bold().paint("[Mining]"),
+                                Yellow.bold().paint(dr_pointer.to_string()),
+                                Yellow.bold().paint(current_epoch.to_string()),
+                                e
+                            );
+                            act.chain_state.node_stats.dr_skipped_by_policy_count += 1;
+
+                            actix::fut::err(())
+                        }
+                    }
+                })
                 // Refrain from trying to resolve any more requests if we have already hit the limit
-                // of retrievals per epoch.
+                // of retrievals per epoch. Sources already scheduled by other data requests in this
+                // epoch are retrieved only once, so they do not count again.
                 .and_then(move |vrf_proof, act, _| {
+                    let added_retrieval_count = act.retrieval_schedule.new_retrievals(current_epoch, &scheduled_retrieve);
                     let mut start_retrieval_count = cloned_retrieval_count.load(atomic::Ordering::Relaxed);
                     let mut final_retrieval_count = start_retrieval_count.saturating_add(added_retrieval_count);
 
-                    act.chain_state.node_stats.dr_eligibility_count += 1;
-
                     if final_retrieval_count > maximum_retrieval_count {
                         log::info!("{} Refrained from resolving data request {} for epoch #{} because it contains {} \
-                        sources, which added to the sources that have already been retrieved ({}) would total {} \
+                        new sources, which added to the sources that have already been retrieved ({}) would total {} \
                         retrievals, which exceed current limit per epoch ({}). This limit exists for performance and \
                         security reasons. You can increase the limit (AT YOUR OWN RISK) by adjusting the \
                         `data_request_max_retrievals_per_epoch` inside the `[mining]` section in the `witnet.toml` \
@@ -388,6 +436,7 @@ impl ChainManager {
                             Yellow.bold().paint(final_retrieval_count.to_string()),
                             Yellow.bold().paint(maximum_retrieval_count.to_string())
                         );
+                        act.retrieval_policy.cancel(&dr_pointer);
 
                         actix::fut::err(())
                     } else {
@@ -399,6 +448,7 @@ impl ChainManager {
                             let internal_retrieval_count = cloned_retrieval_count.compare_and_swap(start_retrieval_count, final_retrieval_count, atomic::Ordering::Relaxed);
                             if internal_retrieval_count == start_retrieval_count {
                                 // The counter update was updated successfully, we can move on.
+                                act.retrieval_schedule.schedule(current_epoch, dr_pointer, &scheduled_retrieve);
                                 break actix::fut::ok(vrf_proof);
                             } else {
                                 // The counter was updated somewhere else, addition must be retried
@@ -408,6 +458,7 @@ impl ChainManager {
                                 final_retrieval_count = start_retrieval_count.saturating_add(added_retrieval_count);
 
                                 if final_retrieval_count > maximum_retrieval_count {
+                                    act.retrieval_policy.cancel(&dr_pointer);
                                     break actix::fut::err(());
                                 }
                             }
@@ -421,6 +472,8 @@ impl ChainManager {
                         Some(x) => (x.consensus_constants.collateral_age, x.consensus_constants.checkpoints_period),
                         None => {
                             log::error!("ChainInfo is None");
+                            act.retrieval_policy.cancel(&dr_pointer);
+                            act.retrieval_schedule.cancel(&dr_pointer);
                             return actix::fut::err(());
                         }
                     };
@@ -453,11 +506,15 @@ impl ChainManager {
                             );
                             // Decrease the retrieval limit hoping that some other, cheaper,
                             // data request can be resolved instead
+                            let added_retrieval_count = act.retrieval_schedule.cancel(&dr_pointer);
                             cloned_retrieval_count2.fetch_sub(added_retrieval_count, atomic::Ordering::Relaxed);
+                            act.retrieval_policy.cancel(&dr_pointer);
                             actix::fut::err(())
                         }
                         Err(e) => {
                             log::error!("Unexpected error when trying to select UTXOs to be used for collateral in data request {}: {}", dr_pointer, e);
+                            act.retrieval_policy.cancel(&dr_pointer);
+                            act.retrieval_schedule.cancel(&dr_pointer);
                             actix::fut::err(())
                         }
                     }
@@ -465,12 +522,19 @@ impl ChainManager {
                 .and_then(move |(vrf_proof, collateral), act, _| {
                     let rad_request = dr_state.data_request.data_request.clone();
 
+                    if let Some(scheduled) = act.retrieval_schedule.start(&dr_pointer) {
+                        act.chain_state.node_stats.retrieval_cache_hits += scheduled.cache_hits;
+                        act.chain_state.node_stats.retrieval_cache_misses += scheduled.cache_misses;
+                    }
+
                     // Send ResolveRA message to RADManager
                     let rad_manager_addr = RadManager::from_registry();
                     rad_manager_addr
                         .send(ResolveRA {
                             rad_request,
                             timeout: data_request_timeout,
+                            epoch: Some(current_epoch),
+                            trace: act.data_request_audit_log,
                         })
                         .map(move |result| match result {
                             Ok(value) => Ok((vrf_proof, collateral, value)),
@@ -484,7 +548,10 @@ impl ChainManager {
                         })
                         .into_actor(act)
                 })
-                .then(|res, _, _| {
+                .then(move |res, act, _| {
+                    // The sources have been retrieved, or the retrieval has been abandoned
+                    act.retrieval_policy.finish(&dr_pointer);
+
                     // This is .flatten()
                     match res {
                         Ok(Ok(x)) => actix::fut::ok(x),
@@ -492,18 +559,35 @@ impl ChainManager {
                         Err(()) => actix::fut::err(()),
                     }
                 })
-                .and_then(move |(vrf_proof, collateral, reveal_value), _, _| {
+                .and_then(move |(vrf_proof, collateral, output), act, _| {
                     let vrf_proof_dr = DataRequestEligibilityClaim { proof: vrf_proof };
+                    let ResolveRAOutput { report: reveal_value, trace } = output;
 
                     match Vec::<u8>::try_from(&reveal_value) {
-                        Ok(reveal_bytes) => actix::fut::ok((reveal_bytes, vrf_proof_dr, collateral)),
+                        Ok(reveal_bytes) => {
+                            let audit = if act.data_request_audit_log {
+                                Some(DataRequestAudit {
+                                    dr_pointer,
+                                    epoch: current_epoch,
+                                    trace: trace.unwrap_or_default(),
+                                    result: reveal_value.result.to_string(),
+                                    reveal_value: reveal_bytes.clone(),
+                                    commit_transaction: None,
+                                    reveal_transaction: None,
+                                })
+                            } else {
+                                None
+                            };
+
+                            actix::fut::ok((reveal_bytes, vrf_proof_dr, collateral, audit))
+                        }
                         Err(e) => {
                             log::error!("Couldn't decode tally value from bytes: {}", e);
                             actix::fut::err(())
                         }
                     }
                 })
-                .and_then(move |(reveal_bytes, vrf_proof_dr, collateral), act, _| {
+                .and_then(move |(reveal_bytes, vrf_proof_dr, collateral, audit), act, _| {
                     let reveal_body = RevealTransactionBody::new(dr_pointer, reveal_bytes, own_pkh);
 
                     // If pkh is in ARS, no need to send bn256 public key
@@ -529,13 +613,25 @@ impl ChainManager {
                                         CommitTransaction::new(commit_body, commit_signatures);
                                     let reveal_transaction =
                                         RevealTransaction::new(reveal_body, reveal_signatures);
-                                    (commit_transaction, reveal_transaction)
+                                    (commit_transaction, reveal_transaction, audit)
                                 })
                                 .map_err(|e| log::error!("Couldn't sign commit body: {}", e))
                         })
                         .into_actor(act)
                 })
-                .and_then(move |(commit_transaction, reveal_transaction), act, ctx| {
+                .and_then(move |(commit_transaction, reveal_transaction, audit), act, ctx| {
+                    if let Some(mut audit) = audit {
+                        audit.commit_transaction = Some(commit_transaction.hash());
+                        audit.reveal_transaction = Some(reveal_transaction.hash());
+
+                        storage_mngr::put(&storage_keys::data_request_audit_key(&dr_pointer), &audit)
+                            .into_actor(act)
+                            .map_err(move |e, _, _| {
+                                log::error!("Failed to persist audit record of data request {} into storage: {}", dr_pointer, e)
+                            })
+                            .spawn(ctx);
+                    }
+
                     ctx.notify(AddCommitReveal {
                         commit_transaction,
                         reveal_transaction,
@@ -545,6 +641,13 @@ impl ChainManager {
 
                     actix::fut::ok(())
                 })
+                .then(move |res, act, _| {
+                    // From now on, the commitment is counted as open in the data request pool,
+                    // unless it has been abandoned
+                    act.data_request_selector.release(&dr_pointer);
+
+                    actix::fut::result(res)
+                })
                 .spawn(ctx);
         }
     }
@@ -974,6 +1077,24 @@ fn calculate_mining_probability(
     }
 }
 
+/// Number of commitments sent by this node that are waiting for their tally, along with the total
+/// collateral locked in them
+fn open_commitments(dr_pool: &DataRequestPool, collateral_minimum: u64) -> (usize, u64) {
+    dr_pool
+        .waiting_for_reveal
+        .keys()
+        .filter_map(|dr_pointer| dr_pool.data_request_pool.get(dr_pointer))
+        .fold((0, 0), |(count, exposure), dr_state| {
+            let collateral = if dr_state.data_request.collateral == 0 {
+                collateral_minimum
+            } else {
+                dr_state.data_request.collateral
+            };
+
+            (count + 1, exposure.saturating_add(collateral))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::{TryFrom, TryInto};
@@ -1772,4 +1893,45 @@ mod tests {
             (0.0 as f64 * 100.0).round() as u32
         );
     }
+
+    #[test]
+    fn open_commitments_and_collateral_exposure() {
+        let mut dr_pool = DataRequestPool::default();
+        let block_hash = Hash::default();
+        let mut dr_pointers = vec![];
+        for collateral in &[0, 5_000_000_000, 7_000_000_000] {
+            let dr_transaction = DRTransaction::new(
+                DRTransactionBody::new(
+                    vec![Input::default()],
+                    vec![],
+                    DataRequestOutput {
+                        collateral: *collateral,
+                        ..DataRequestOutput::default()
+                    },
+                ),
+                vec![KeyedSignature::default()],
+            );
+            dr_pointers.push(dr_transaction.hash());
+            dr_pool
+                .add_data_request(0, dr_transaction, &block_hash)
+                .unwrap();
+        }
+
+        assert_eq!(open_commitments(&dr_pool, 1_000_000_000), (0, 0));
+
+        // Only the data requests that we have committed to count towards the exposure
+        dr_pool.insert_reveal(dr_pointers[0], RevealTransaction::default());
+        dr_pool.insert_reveal(dr_pointers[2], RevealTransaction::default());
+        assert_eq!(
+            open_commitments(&dr_pool, 1_000_000_000),
+            (2, 8_000_000_000)
+        );
+
+        // Commitments to data requests that are no longer in the pool are ignored
+        dr_pool.data_request_pool.remove(&dr_pointers[2]);
+        assert_eq!(
+            open_commitments(&dr_pool, 1_000_000_000),
+            (1, 1_000_000_000)
+        );
+    }
 }
@@ -70,11 +70,19 @@ use witnet_validations::validations::{
 
 use crate::{
     actors::{
-        chain_manager::handlers::SYNCED_BANNER,
-        inventory_manager::InventoryManager,
+        chain_manager::{
+            address_index::AddressIndex,
+            data_request_index::DataRequestIndex,
+            data_request_selection::DataRequestSelector,
+            handlers::SYNCED_BANNER,
+            pruning::{self, Pruning},
+            retrieval_policy::RetrievalPolicyEnforcer,
+            retrieval_schedule::RetrievalSchedule,
+        },
+        inventory_manager::{add_items_to_batch, InventoryManager},
         json_rpc::JsonRpcServer,
         messages::{
-            AddItem, AddItems, AddTransaction, Anycast, BlockNotify, Broadcast, DropOutboundPeers,
+            AddItem, AddTransaction, Anycast, BlockNotify, Broadcast, DropOutboundPeers,
             GetBlocksEpochRange, GetItemBlock, NodeStatusNotify, RemoveAddressesFromTried,
             SendInventoryItem, SendInventoryRequest, SendLastBeacon, SendSuperBlockVote,
             StoreInventoryItem, SuperBlockNotify,
@@ -87,9 +95,21 @@ use crate::{
 };
 
 mod actor;
+/// Index of the transactions of every address
+pub mod address_index;
+/// Index of data requests by requester, RAD request, stage and epoch
+pub mod data_request_index;
+/// Selection of the data requests to commit to
+pub mod data_request_selection;
 mod handlers;
 /// Block and data request mining
 pub mod mining;
+/// Deletion of old blocks and superblocks in pruned mode
+pub mod pruning;
+/// Enforcement of the retrieval policy
+pub mod retrieval_policy;
+/// Accounting of the sources retrieved in each epoch
+pub mod retrieval_schedule;
 
 /// Maximum blocks number to be sent during synchronization process
 pub const MAX_BLOCKS_SYNC: usize = 500;
@@ -117,6 +137,15 @@ pub enum ChainManagerError {
         /// not possible at this time.
         current_state: StateMachine,
     },
+    /// No audit record was persisted for a data request
+    #[fail(
+        display = "No audit record found for data request {}. Is `data_request_audit_log` enabled, and did this node commit to it?",
+        dr_pointer
+    )]
+    DataRequestAuditNotFound {
+        /// Data request pointer
+        dr_pointer: Hash,
+    },
     /// The node is trying to mine a block so commits are not allowed
     #[fail(display = "Commit received while node is trying to mine a block")]
     TooLateToCommit,
@@ -133,6 +162,30 @@ pub enum ChainManagerError {
         /// Tells what the current epoch was
         current_superblock_index: u32,
     },
+    /// The address index is not enabled in the configuration
+    #[fail(
+        display = "The address index is disabled. Enable `storage.address_index` in the configuration to use it"
+    )]
+    AddressIndexDisabled,
+    /// The address index cannot be rebuilt while pruned mode may have deleted some blocks
+    #[fail(
+        display = "The address index cannot be rebuilt in pruned mode, or after running in pruned mode"
+    )]
+    AddressIndexPruned,
+    /// The data request index is not enabled in the configuration
+    #[fail(
+        display = "The data request index is disabled. Enable `storage.data_request_index` in the configuration to use it"
+    )]
+    DataRequestIndexDisabled,
+    /// A request to an index matches more entries than the ones that can be read at once
+    #[fail(
+        display = "The request matches more than {} entries of the index, which is the maximum",
+        limit
+    )]
+    TooManyIndexEntries {
+        /// Maximum number of entries read at once
+        limit: usize,
+    },
 }
 
 /// Synchronization target determined by the beacons received from outbound peers
@@ -198,6 +251,21 @@ pub struct ChainManager {
     data_request_max_retrievals_per_epoch: u16,
     /// Timeout for data request retrieval and aggregation execution
     data_request_timeout: Option<Duration>,
+    /// Retrieval policy, and the retrievals that count towards its limits
+    retrieval_policy: RetrievalPolicyEnforcer,
+    /// Sources scheduled for retrieval in the current epoch
+    retrieval_schedule: RetrievalSchedule,
+    /// Criteria to decide which eligible data requests to commit to
+    data_request_selector: DataRequestSelector,
+    /// Persist an audit record of every data request committed to by this node
+    data_request_audit_log: bool,
+    /// Address index entries that are pending to be persisted, if the address index is enabled
+    address_index: Option<AddressIndex>,
+    /// Data request index records that are pending to be persisted, if the data request index is
+    /// enabled
+    data_request_index: Option<DataRequestIndex>,
+    /// Pruned mode, and what was pruned in this or previous runs
+    pruning: Pruning,
     /// Pending transaction timeout
     tx_pending_timeout: u64,
     /// Magic number from ConsensusConstants
@@ -243,14 +311,17 @@ impl ChainManager {
     /// Persist previous chain state into storage
     /// None case: persist current chain state into storage (during synchronization)
     fn persist_chain_state(&mut self, superblock_index: Option<u32>, ctx: &mut Context<Self>) {
-        let previous_chain_state = if let Some(superblock_index) = superblock_index {
-            let chain_state_snapshot = self.chain_state_snapshot.restore(superblock_index);
-
-            if chain_state_snapshot.is_none() {
-                return;
-            }
+        if let Some(state) = self.chain_state_to_persist(superblock_index) {
+            self.persist_batch(ctx, Some(state), vec![], vec![]);
+        }
+    }
 
-            chain_state_snapshot.unwrap()
+    /// Build the chain state to be persisted into storage: the previous chain state of the
+    /// superblock with the given index, or the current chain state in the None case (during
+    /// synchronization). Returns `None` if there is no chain state for that superblock.
+    fn chain_state_to_persist(&mut self, superblock_index: Option<u32>) -> Option<ChainState> {
+        let previous_chain_state = if let Some(superblock_index) = superblock_index {
+            self.chain_state_snapshot.restore(superblock_index)?
         } else {
             // None case is used to persist chain_state during synchronization
             self.chain_state.clone()
@@ -287,19 +358,65 @@ impl ChainManager {
             );
         }
 
-        storage_mngr::put(&storage_keys::chain_state_key(self.get_magic()), &state)
+        Some(state)
+    }
+
+    /// In pruned mode, delete the blocks and superblocks that fell out of the window of recent
+    /// superblocks after persisting the chain state of the superblock with the given index. The
+    /// deletion runs in the background, and it is skipped if the previous one is still running.
+    fn prune_storage(&mut self, superblock_index: u32, ctx: &mut Context<Self>) {
+        let magic = self.get_magic();
+        let superblock_period = self.consensus_constants().superblock_period;
+        let pruning = &mut self.pruning;
+        if !pruning.enabled || pruning.in_progress {
+            return;
+        }
+        let target = match pruning.target(superblock_index, superblock_period) {
+            Some(target) => target,
+            None => return,
+        };
+        let from = pruning.state;
+        let blocks: Vec<(Epoch, Hash)> = self
+            .chain_state
+            .block_chain
+            .range(from.first_block_epoch..target.first_block_epoch)
+            .map(|(epoch, hash)| (*epoch, *hash))
+            .collect();
+        let blocks_len = blocks.len();
+        pruning.in_progress = true;
+        log::debug!(
+            "Pruning {} blocks older than epoch #{} and superblocks older than #{}",
+            blocks_len,
+            target.first_block_epoch,
+            target.first_superblock_index
+        );
+
+        pruning::prune(magic, from, blocks, target)
             .into_actor(self)
-            .and_then(|_, _, _| {
-                log::debug!("Successfully persisted previous_chain_info into storage");
+            .then(move |res, act, _ctx| {
+                act.pruning.in_progress = false;
+                match res {
+                    Ok(state) if state == target => {
+                        act.pruning.state = state;
+                        log::info!(
+                            "Pruned {} blocks, the oldest stored block is now from epoch #{}",
+                            blocks_len,
+                            state.first_block_epoch
+                        );
+                    }
+                    Ok(state) => {
+                        act.pruning.state = state;
+                        log::info!(
+                            "Pruned the blocks older than epoch #{}, the next ones are not stored yet",
+                            state.first_block_epoch
+                        );
+                    }
+                    Err(e) => log::error!("Failed to prune old blocks: {}", e),
+                }
+
                 fut::ok(())
             })
-            .map_err(|err, _, _| {
-                log::error!(
-                    "Failed to persist previous_chain_info into storage: {}",
-                    err
-                )
-            })
-            .wait(ctx);
+            .spawn(ctx);
     }
 
     /// Replace `previous_chain_state` with current `chain_state`
@@ -308,51 +425,57 @@ impl ChainManager {
             .take(superblock_index, &self.chain_state);
     }
 
-    /// Method to Send items to Inventory Manager
-    fn persist_items(&self, ctx: &mut Context<Self>, items: Vec<StoreInventoryItem>) {
-        // Get InventoryManager address
-        let inventory_manager_addr = InventoryManager::from_registry();
-
-        // Persist block into storage through InventoryManager. `AsyncContext::wait` registers
-        // future within context, but context waits until this future resolves
-        // before processing any other events.
-        inventory_manager_addr
-            .send(AddItems { items })
-            .into_actor(self)
-            .then(|res, _act, _ctx| match res {
-                // Process the response from InventoryManager
-                Err(e) => {
-                    // Error when sending message
-                    log::error!("Unsuccessful communication with InventoryManager: {}", e);
-                    actix::fut::err(())
-                }
-                Ok(_) => actix::fut::ok(()),
-            })
-            .wait(ctx)
-    }
-
-    /// Method to persist a Data Request into the Storage
-    fn persist_data_requests(&self, ctx: &mut Context<Self>, dr_infos: Vec<DataRequestInfo>) {
-        let kvs: Vec<_> = dr_infos
-            .into_iter()
-            .map(|dr_info| {
-                let dr_pointer = &dr_info.tally.as_ref().unwrap().dr_pointer;
-                let dr_pointer_string = format!("DR-REPORT-{}", dr_pointer);
+    /// Persist blocks, the reports of their finished data requests and, optionally, the chain
+    /// state into storage, all of them in a single atomic write.
+    ///
+    /// The chain state is always written after, and never without, the blocks and data request
+    /// reports it refers to: either in the same batch or in a later one. So if the node crashes
+    /// while consolidating, the chain state found in storage on restart never refers to blocks or
+    /// data request reports that were not persisted.
+    fn persist_batch(
+        &mut self,
+        ctx: &mut Context<Self>,
+        chain_state: Option<ChainState>,
+        blocks: Vec<Block>,
+        dr_infos: Vec<DataRequestInfo>,
+    ) {
+        let blocks_len = blocks.len();
+        let dr_infos_len = dr_infos.len();
+        let with_chain_state = chain_state.is_some();
+
+        let batch = match build_persistence_batch(
+            self.get_magic(),
+            chain_state,
+            blocks,
+            dr_infos,
+            self.address_index.as_mut(),
+            self.data_request_index.as_mut(),
+        ) {
+            Ok(batch) => batch,
+            Err(e) => {
+                log::error!(
+                    "Failed to serialize the items to persist into storage: {}",
+                    e
+                );
+                return;
+            }
+        };
 
-                (dr_pointer_string, dr_info)
-            })
-            .collect();
-        let kvs_len = kvs.len();
-        storage_mngr::put_batch(&kvs)
+        // `AsyncContext::wait` registers future within context, but context waits until this
+        // future resolves before processing any other events, so the batches are written in the
+        // same order as they are created.
+        storage_mngr::write(batch)
             .into_actor(self)
-            .map_err(|e, _, _| {
-                log::error!("Failed to persist data request report into storage: {}", e)
-            })
+            .map_err(|e, _, _| log::error!("Failed to persist batch into storage: {}", e))
             .and_then(move |_, _, _| {
                 log::trace!(
-                    "Successfully persisted reports for {} data requests into storage",
-                    kvs_len
+                    "Successfully persisted {} blocks and reports for {} data requests into storage",
+                    blocks_len,
+                    dr_infos_len
                 );
+                if with_chain_state {
+                    log::debug!("Successfully persisted previous_chain_info into storage");
+                }
                 fut::ok(())
             })
             .wait(ctx);
@@ -534,15 +657,6 @@ impl ChainManager {
         }
     }
 
-    fn persist_blocks_batch(&self, ctx: &mut Context<Self>, blocks: Vec<Block>) {
-        let mut to_persist = Vec::with_capacity(blocks.len());
-        for block in blocks {
-            to_persist.push(StoreInventoryItem::Block(Box::new(block)));
-        }
-
-        self.persist_items(ctx, to_persist);
-    }
-
     fn consolidate_block(&mut self, ctx: &mut Context<Self>, block: Block, utxo_diff: Diff) {
         // Update chain_info and reputation_engine
         let epoch_constants = match self.epoch_constants {
@@ -614,6 +728,12 @@ impl ChainManager {
                 chain_info.highest_block_checkpoint = beacon;
                 chain_info.highest_vrf_output = vrf_input;
 
+                // The address index needs the outputs spent by the block, so it has to be updated
+                // before removing them from the UTXO set
+                if let Some(address_index) = self.address_index.as_mut() {
+                    address_index.index_block(&block, &self.chain_state.unspent_outputs_pool);
+                }
+
                 let rep_info = update_pools(
                     &block,
                     &mut self.chain_state.unspent_outputs_pool,
@@ -654,14 +774,14 @@ impl ChainManager {
                         // Persist finished data requests into storage
                         let to_be_stored =
                             self.chain_state.data_request_pool.finished_data_requests();
-                        self.persist_data_requests(ctx, to_be_stored);
 
                         let _reveals = self
                             .chain_state
                             .data_request_pool
                             .update_data_request_stages();
+                        self.update_data_request_index();
 
-                        self.persist_items(ctx, vec![StoreInventoryItem::Block(Box::new(block))]);
+                        self.persist_batch(ctx, None, vec![block], to_be_stored);
                     }
                     StateMachine::Synchronizing => {
                         // In Synchronizing stage, blocks and data requests are persisted
@@ -670,6 +790,7 @@ impl ChainManager {
                             .chain_state
                             .data_request_pool
                             .update_data_request_stages();
+                        self.update_data_request_index();
                     }
                     StateMachine::AlmostSynced | StateMachine::Synced => {
                         // Persist finished data requests into storage
@@ -678,12 +799,12 @@ impl ChainManager {
                         for dr_info in &to_be_stored {
                             show_tally_info(&dr_info.tally.as_ref().unwrap(), block_epoch);
                         }
-                        self.persist_data_requests(ctx, to_be_stored);
 
                         let reveals = self
                             .chain_state
                             .data_request_pool
                             .update_data_request_stages();
+                        self.update_data_request_index();
 
                         show_info_dr(&self.chain_state.data_request_pool, &block);
 
@@ -695,17 +816,14 @@ impl ChainManager {
                                 broadcast_flag: true,
                             })
                         }
-                        // Persist blocks and transactions but do not persist chain_state, it will
-                        // be persisted on superblock consolidation
+                        // Persist blocks, transactions and finished data requests but do not
+                        // persist chain_state, it will be persisted on superblock consolidation
                         // FIXME(#1663): discard persisted and non-consolidated blocks
                         // This means that after a reorganization a call to getBlock or
                         // getTransaction will show the content without any warning that the block
                         // is not on the main chain. To fix this we could remove forked blocks when
                         // a reorganization is detected.
-                        self.persist_items(
-                            ctx,
-                            vec![StoreInventoryItem::Block(Box::new(block.clone()))],
-                        );
+                        self.persist_batch(ctx, None, vec![block.clone()], to_be_stored);
 
                         // Send notification to JsonRpcServer
                         JsonRpcServer::from_registry().do_send(BlockNotify { block })
@@ -728,6 +846,14 @@ impl ChainManager {
         }
     }
 
+    /// Update the data request index, if enabled, with the stages of the data requests after
+    /// consolidating a block or restoring the chain state from storage
+    fn update_data_request_index(&mut self) {
+        if let Some(data_request_index) = self.data_request_index.as_mut() {
+            data_request_index.update(&self.chain_state.data_request_pool);
+        }
+    }
+
     /// Create a superblock, sign a superblock vote and broadcast it
     fn create_and_broadcast_superblock(&mut self, ctx: &mut Context<Self>, current_epoch: u32) {
         self.construct_superblock(current_epoch, None)
@@ -1168,6 +1294,7 @@ impl ChainManager {
                     // Copy current chain state into previous chain state, and persist it
                     act.move_chain_state_forward(sync_target.superblock.checkpoint);
                     act.persist_chain_state(Some(sync_target.superblock.checkpoint), ctx);
+                    act.prune_storage(sync_target.superblock.checkpoint, ctx);
 
                     actix::fut::ok(())
                 } else {
@@ -1312,6 +1439,7 @@ impl ChainManager {
                             // Persist previous_chain_state with current superblock_state
                             act.persist_chain_state(Some(voted_superblock_beacon.checkpoint), ctx);
                             act.move_chain_state_forward(superblock_index);
+                            act.prune_storage(voted_superblock_beacon.checkpoint, ctx);
                         }
 
                         if let Some(consolidated_superblock) = act.chain_state.superblock_state.get_current_superblock() {
@@ -2384,6 +2512,43 @@ fn update_reputation(
     rep_eng.set_current_alpha(new_alpha);
 }
 
+/// Build the storage batch that persists the given chain state, blocks and reports of finished data
+/// requests, along with the address index entries of the blocks and the data request index records
+/// that changed.
+fn build_persistence_batch(
+    magic: u16,
+    chain_state: Option<ChainState>,
+    blocks: Vec<Block>,
+    dr_infos: Vec<DataRequestInfo>,
+    address_index: Option<&mut AddressIndex>,
+    data_request_index: Option<&mut DataRequestIndex>,
+) -> Result<storage_mngr::WriteBatch, failure::Error> {
+    let mut batch = storage_mngr::WriteBatch::new();
+    if let Some(address_index) = address_index {
+        for block in &blocks {
+            address_index.add_block_to_batch(&block.hash(), &mut batch)?;
+        }
+    }
+    if let Some(data_request_index) = data_request_index {
+        data_request_index.finish(&dr_infos);
+        data_request_index.add_to_batch(&mut batch)?;
+    }
+    let items = blocks
+        .into_iter()
+        .map(|block| StoreInventoryItem::Block(Box::new(block)))
+        .collect();
+    add_items_to_batch(&mut batch, items)?;
+    for dr_info in dr_infos {
+        let dr_pointer = &dr_info.tally.as_ref().unwrap().dr_pointer;
+        batch.put(&storage_keys::data_request_report_key(dr_pointer), &dr_info)?;
+    }
+    if let Some(chain_state) = chain_state {
+        batch.put(&storage_keys::chain_state_key(magic), &chain_state)?;
+    }
+
+    Ok(batch)
+}
+
 fn show_tally_info(tally_tx: &TallyTransaction, block_epoch: Epoch) {
     let result = RadonTypes::try_from(tally_tx.tally.as_slice());
     let result_str = RadonReport::from_result(result, &ReportContext::default())
@@ -0,0 +1,518 @@
+use std::ops::Bound;
+
+use actix::SystemService;
+use futures::{future, stream, Future, Stream};
+use serde::{Deserialize, Serialize};
+
+use witnet_data_structures::chain::{Block, Epoch, Hash};
+
+use crate::{
+    actors::{
+        inventory_manager::{
+            remove_block_from_batch, remove_superblock_from_batch, InventoryManager,
+            InventoryManagerError,
+        },
+        messages::GetItemBlock,
+        storage_keys,
+    },
+    storage_mngr,
+};
+
+/// Minimum number of recent superblocks whose blocks are kept in pruned mode. The blocks of the
+/// last superblocks are needed to revert the chain to the last consolidated superblock.
+pub const MIN_KEEP_SUPERBLOCKS: u32 = 2;
+
+/// Maximum number of blocks deleted in a single storage batch
+const BLOCKS_PER_BATCH: usize = 100;
+
+/// Oldest blocks and superblocks that are still stored: everything older than them was pruned
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PruningState {
+    /// All the consolidated blocks from this epoch onwards are stored
+    pub first_block_epoch: Epoch,
+    /// All the consolidated superblocks from this index onwards are stored
+    pub first_superblock_index: u32,
+}
+
+impl PruningState {
+    /// Move the start of a range of epochs forward, if needed, so that the range leaves out the
+    /// blocks that were pruned
+    pub fn stored_blocks_start(self, start: Bound<Epoch>) -> Bound<Epoch> {
+        let includes_pruned = match start {
+            Bound::Included(epoch) => epoch < self.first_block_epoch,
+            Bound::Excluded(epoch) => epoch.saturating_add(1) < self.first_block_epoch,
+            Bound::Unbounded => true,
+        };
+
+        if includes_pruned {
+            Bound::Included(self.first_block_epoch)
+        } else {
+            start
+        }
+    }
+}
+
+/// Pruned mode: deletion of the blocks and superblocks that are older than a window of recent
+/// superblocks
+#[derive(Debug, Default)]
+pub struct Pruning {
+    /// Whether pruned mode is enabled
+    pub enabled: bool,
+    /// Number of recent consolidated superblocks whose blocks are kept
+    keep_superblocks: u32,
+    /// What has already been pruned, in this or previous runs. The blocks and superblocks that
+    /// were pruned are missing from storage even if pruned mode is disabled afterwards.
+    pub state: PruningState,
+    /// Whether there is a pruning run writing to storage
+    pub in_progress: bool,
+}
+
+impl Pruning {
+    /// Create a pruned mode that keeps the blocks of the given number of recent superblocks, but
+    /// never less than `MIN_KEEP_SUPERBLOCKS`
+    pub fn new(keep_superblocks: u32) -> Self {
+        Self {
+            enabled: true,
+            keep_superblocks: keep_superblocks.max(MIN_KEEP_SUPERBLOCKS),
+            state: PruningState::default(),
+            in_progress: false,
+        }
+    }
+
+    /// What should remain stored after consolidating the superblock with the given index, or
+    /// `None` if there is nothing new to prune
+    pub fn target(
+        &self,
+        consolidated_superblock_index: u32,
+        superblock_period: u16,
+    ) -> Option<PruningState> {
+        let first_superblock_index =
+            consolidated_superblock_index.saturating_sub(self.keep_superblocks);
+        if first_superblock_index <= self.state.first_superblock_index {
+            return None;
+        }
+
+        Some(PruningState {
+            first_block_epoch: first_superblock_index.saturating_mul(u32::from(superblock_period)),
+            first_superblock_index,
+        })
+    }
+}
+
+/// Read what has already been pruned from storage. Nothing has been pruned if the node never ran
+/// in pruned mode.
+pub fn load(magic: u16) -> impl Future<Item = PruningState, Error = failure::Error> {
+    storage_mngr::get::<_, PruningState>(&storage_keys::pruning_key(magic))
+        .map(Option::unwrap_or_default)
+}
+
+/// Delete the stored blocks with the given epochs and hashes, along with their transactions and
+/// the reports and audit records of the data requests resolved in them, and then the stored
+/// superblocks that are older than the target. Returns how far pruning went.
+///
+/// Blocks are deleted in batches, in the given order, and every batch persists how far pruning
+/// went, so the blocks that were deleted before an interruption are never looked for again.
+/// Pruning stops before the first block that is not stored yet, so that it is deleted in a later
+/// run once it has been written.
+pub fn prune(
+    magic: u16,
+    from: PruningState,
+    blocks: Vec<(Epoch, Hash)>,
+    target: PruningState,
+) -> impl Future<Item = PruningState, Error = failure::Error> {
+    let chunks: Vec<Vec<(Epoch, Hash)>> = blocks
+        .chunks(BLOCKS_PER_BATCH)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+
+    stream::iter_ok::<_, failure::Error>(chunks)
+        .fold((from, false), move |(reached, stopped), chunk| {
+            if stopped {
+                return future::Either::A(future::ok((reached, stopped)));
+            }
+
+            // Chunks are never empty
+            let (last_epoch, _) = chunk[chunk.len() - 1];
+            let blocks = chunk.iter().map(|(_epoch, hash)| get_block(*hash));
+
+            future::Either::B(future::join_all(blocks).and_then(move |blocks| {
+                let mut stored = vec![];
+                let mut first_missing = None;
+                for ((epoch, hash), block) in chunk.into_iter().zip(blocks) {
+                    match block {
+                        Some(block) => stored.push(block),
+                        None => {
+                            log::debug!(
+                                "Block {} of epoch #{} is not stored yet, pruning stops before it",
+                                hash,
+                                epoch
+                            );
+                            first_missing = Some(epoch);
+                            break;
+                        }
+                    }
+                }
+                let state = PruningState {
+                    first_block_epoch: first_missing.unwrap_or(last_epoch + 1),
+                    first_superblock_index: from.first_superblock_index,
+                };
+
+                future::result(blocks_batch(magic, &stored, state))
+                    .and_then(storage_mngr::write)
+                    .map(move |()| (state, first_missing.is_some()))
+            }))
+        })
+        .and_then(move |(reached, stopped)| {
+            if stopped {
+                future::Either::A(future::ok(reached))
+            } else {
+                future::Either::B(
+                    future::result(superblocks_batch(magic, from, target))
+                        .and_then(storage_mngr::write)
+                        .map(move |()| target),
+                )
+            }
+        })
+}
+
+/// Storage batch that deletes some blocks and records how far pruning went
+fn blocks_batch(
+    magic: u16,
+    blocks: &[Block],
+    state: PruningState,
+) -> Result<storage_mngr::WriteBatch, failure::Error> {
+    let mut batch = storage_mngr::WriteBatch::new();
+    for block in blocks {
+        remove_block_from_batch(&mut batch, block)?;
+        for tally in &block.txns.tally_txns {
+            batch.delete(&storage_keys::data_request_report_key(&tally.dr_pointer))?;
+            batch.delete(&storage_keys::data_request_audit_key(&tally.dr_pointer))?;
+        }
+    }
+    batch.put(&storage_keys::pruning_key(magic), &state)?;
+
+    Ok(batch)
+}
+
+/// Storage batch that deletes the superblocks that are older than the target, and records that
+/// pruning reached the target
+fn superblocks_batch(
+    magic: u16,
+    from: PruningState,
+    target: PruningState,
+) -> Result<storage_mngr::WriteBatch, failure::Error> {
+    let mut batch = storage_mngr::WriteBatch::new();
+    for superblock_index in from.first_superblock_index..target.first_superblock_index {
+        remove_superblock_from_batch(&mut batch, superblock_index)?;
+    }
+    batch.put(&storage_keys::pruning_key(magic), &target)?;
+
+    Ok(batch)
+}
+
+/// Get a stored block, or `None` if it is not stored
+fn get_block(hash: Hash) -> impl Future<Item = Option<Block>, Error = failure::Error> {
+    InventoryManager::from_registry()
+        .send(GetItemBlock { hash })
+        .map_err(failure::Error::from)
+        .and_then(|res| match res {
+            Ok(block) => Ok(Some(block)),
+            Err(InventoryManagerError::ItemNotFound) => Ok(None),
+            Err(e) => Err(failure::Error::from(e)),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_util::compat::Compat01As03;
+    use witnet_config::config::{Config, StorageBackend};
+    use witnet_data_structures::{
+        chain::{Hashable, PointerToBlock, SuperBlock},
+        transaction::TallyTransaction,
+    };
+
+    use super::*;
+    use crate::{
+        actors::{
+            inventory_manager::add_items_to_batch,
+            messages::{GetItemSuperblock, StoreInventoryItem, SuperBlockNotify},
+        },
+        config_mngr,
+        utils::test_actix_system,
+    };
+
+    const MAGIC: u16 = 1;
+
+    fn start_storage() {
+        let mut config = Config::default();
+        config.storage.backend = StorageBackend::HashMap;
+        config_mngr::start(Arc::new(config));
+        storage_mngr::start();
+    }
+
+    fn block_with_tally(epoch: Epoch, dr_pointer: Hash) -> Block {
+        let mut block = Block::default();
+        block.block_header.beacon.checkpoint = epoch;
+        block.txns.tally_txns.push(TallyTransaction::new(
+            dr_pointer,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ));
+
+        block
+    }
+
+    fn hash_key(hash: Hash) -> Vec<u8> {
+        match hash {
+            Hash::SHA256(x) => x.to_vec(),
+        }
+    }
+
+    /// Store blocks and superblocks, along with a report and an audit record for every data
+    /// request resolved in the blocks
+    async fn store(blocks: &[Block], superblock_indexes: std::ops::Range<u32>) {
+        let mut items: Vec<_> = blocks
+            .iter()
+            .map(|block| StoreInventoryItem::Block(Box::new(block.clone())))
+            .collect();
+        items.extend(superblock_indexes.map(|index| {
+            StoreInventoryItem::Superblock(SuperBlockNotify {
+                superblock: SuperBlock {
+                    index,
+                    ..SuperBlock::default()
+                },
+                consolidated_block_hashes: vec![],
+            })
+        }));
+        let mut batch = storage_mngr::WriteBatch::new();
+        add_items_to_batch(&mut batch, items).unwrap();
+        for tally in blocks.iter().flat_map(|block| &block.txns.tally_txns) {
+            let report_key = storage_keys::data_request_report_key(&tally.dr_pointer);
+            let audit_key = storage_keys::data_request_audit_key(&tally.dr_pointer);
+            batch.put(&report_key, &"report").unwrap();
+            batch.put(&audit_key, &"audit").unwrap();
+        }
+
+        Compat01As03::new(storage_mngr::write(batch)).await.unwrap();
+    }
+
+    async fn is_block_stored(block: &Block) -> bool {
+        let stored = Compat01As03::new(get_block(block.hash())).await.unwrap();
+        let tally_pointer = Compat01As03::new(storage_mngr::get::<_, PointerToBlock>(&hash_key(
+            block.txns.tally_txns[0].hash(),
+        )))
+        .await
+        .unwrap();
+        assert_eq!(stored.is_some(), tally_pointer.is_some());
+
+        stored.is_some()
+    }
+
+    async fn is_report_stored(dr_pointer: &Hash) -> bool {
+        let report = Compat01As03::new(storage_mngr::get::<_, String>(
+            &storage_keys::data_request_report_key(dr_pointer),
+        ))
+        .await
+        .unwrap();
+        let audit = Compat01As03::new(storage_mngr::get::<_, String>(
+            &storage_keys::data_request_audit_key(dr_pointer),
+        ))
+        .await
+        .unwrap();
+        assert_eq!(report.is_some(), audit.is_some());
+
+        report.is_some()
+    }
+
+    async fn is_superblock_stored(superblock_index: u32) -> bool {
+        let f = InventoryManager::from_registry().send(GetItemSuperblock { superblock_index });
+
+        Compat01As03::new(f).await.unwrap().is_ok()
+    }
+
+    async fn stored_state() -> Option<PruningState> {
+        Compat01As03::new(storage_mngr::get(&storage_keys::pruning_key(MAGIC)))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn blocks_batch_deletes_blocks_and_reports() {
+        test_actix_system(|| async {
+            start_storage();
+            let pruned = block_with_tally(1, Hash::SHA256([1; 32]));
+            let kept = block_with_tally(2, Hash::SHA256([2; 32]));
+            store(&[pruned.clone(), kept.clone()], 0..0).await;
+
+            let state = PruningState {
+                first_block_epoch: 2,
+                first_superblock_index: 0,
+            };
+            let batch = blocks_batch(MAGIC, &[pruned.clone()], state).unwrap();
+            Compat01As03::new(storage_mngr::write(batch)).await.unwrap();
+
+            assert!(!is_block_stored(&pruned).await);
+            assert!(!is_report_stored(&Hash::SHA256([1; 32])).await);
+            assert!(is_block_stored(&kept).await);
+            assert!(is_report_stored(&Hash::SHA256([2; 32])).await);
+            assert_eq!(stored_state().await, Some(state));
+        });
+    }
+
+    #[test]
+    fn superblocks_batch_deletes_older_superblocks() {
+        test_actix_system(|| async {
+            start_storage();
+            store(&[], 0..5).await;
+
+            let from = PruningState {
+                first_block_epoch: 10,
+                first_superblock_index: 1,
+            };
+            let target = PruningState {
+                first_block_epoch: 30,
+                first_superblock_index: 3,
+            };
+            let batch = superblocks_batch(MAGIC, from, target).unwrap();
+            Compat01As03::new(storage_mngr::write(batch)).await.unwrap();
+
+            // Superblocks older than `from` are assumed to be already pruned
+            assert!(is_superblock_stored(0).await);
+            assert!(!is_superblock_stored(1).await);
+            assert!(!is_superblock_stored(2).await);
+            assert!(is_superblock_stored(3).await);
+            assert!(is_superblock_stored(4).await);
+            assert_eq!(stored_state().await, Some(target));
+        });
+    }
+
+    #[test]
+    fn prune_stops_before_missing_block() {
+        test_actix_system(|| async {
+            start_storage();
+            let blocks: Vec<Block> = (0..4)
+                .map(|epoch| block_with_tally(epoch, Hash::SHA256([epoch as u8 + 1; 32])))
+                .collect();
+            // The block of epoch 2 is not stored yet
+            store(
+                &[blocks[0].clone(), blocks[1].clone(), blocks[3].clone()],
+                0..2,
+            )
+            .await;
+
+            let target = PruningState {
+                first_block_epoch: 4,
+                first_superblock_index: 1,
+            };
+            let epochs_and_hashes = blocks
+                .iter()
+                .map(|block| (block.block_header.beacon.checkpoint, block.hash()))
+                .collect();
+            let reached = Compat01As03::new(prune(
+                MAGIC,
+                PruningState::default(),
+                epochs_and_hashes,
+                target,
+            ))
+            .await
+            .unwrap();
+
+            let expected = PruningState {
+                first_block_epoch: 2,
+                first_superblock_index: 0,
+            };
+            assert_eq!(reached, expected);
+            assert_eq!(stored_state().await, Some(expected));
+            assert!(!is_block_stored(&blocks[0]).await);
+            assert!(!is_block_stored(&blocks[1]).await);
+            assert!(is_block_stored(&blocks[3]).await);
+            assert!(is_superblock_stored(0).await);
+        });
+    }
+
+    #[test]
+    fn stored_blocks_start_leaves_out_pruned_blocks() {
+        let state = PruningState {
+            first_block_epoch: 10,
+            first_superblock_index: 1,
+        };
+
+        assert_eq!(
+            state.stored_blocks_start(Bound::Unbounded),
+            Bound::Included(10)
+        );
+        assert_eq!(
+            state.stored_blocks_start(Bound::Included(5)),
+            Bound::Included(10)
+        );
+        assert_eq!(
+            state.stored_blocks_start(Bound::Excluded(8)),
+            Bound::Included(10)
+        );
+        assert_eq!(
+            state.stored_blocks_start(Bound::Excluded(9)),
+            Bound::Excluded(9)
+        );
+        assert_eq!(
+            state.stored_blocks_start(Bound::Included(10)),
+            Bound::Included(10)
+        );
+        assert_eq!(
+            state.stored_blocks_start(Bound::Included(12)),
+            Bound::Included(12)
+        );
+
+        // Nothing is left out if nothing was pruned
+        let state = PruningState::default();
+        assert_eq!(
+            state.stored_blocks_start(Bound::Unbounded),
+            Bound::Included(0)
+        );
+        assert_eq!(
+            state.stored_blocks_start(Bound::Excluded(0)),
+            Bound::Excluded(0)
+        );
+    }
+
+    #[test]
+    fn pruning_target() {
+        let mut pruning = Pruning::new(10);
+
+        assert_eq!(pruning.target(10, 10), None);
+        assert_eq!(
+            pruning.target(15, 10),
+            Some(PruningState {
+                first_block_epoch: 50,
+                first_superblock_index: 5,
+            })
+        );
+
+        pruning.state = pruning.target(15, 10).unwrap();
+        assert_eq!(pruning.target(15, 10), None);
+        assert_eq!(
+            pruning.target(16, 10),
+            Some(PruningState {
+                first_block_epoch: 60,
+                first_superblock_index: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_minimum_window() {
+        let pruning = Pruning::new(0);
+
+        assert_eq!(pruning.target(2, 10), None);
+        assert_eq!(
+            pruning.target(3, 10),
+            Some(PruningState {
+                first_block_epoch: 10,
+                first_superblock_index: 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Enforcement of the retrieval policy
+//!
+//! Before committing to a data request, the node checks that it is willing to contact all of its
+//! sources, as configured in the `[mining.retrieval_policy]` section of the configuration file.
+use std::{collections::HashMap, net::IpAddr};
+
+use failure::Fail;
+use url::{Host, Url};
+
+use witnet_config::config::RetrievalPolicy;
+use witnet_data_structures::chain::{Epoch, Hash, RADRetrieve, RADType};
+use witnet_rad::transport::is_private_ip;
+
+/// Reasons for not resolving a data request because of the retrieval policy
+#[derive(Debug, PartialEq, Eq, Fail)]
+pub enum RetrievalPolicyViolation {
+    /// The URL of a source cannot be parsed
+    #[fail(display = "the URL of a source cannot be parsed: {}", url)]
+    InvalidUrl { url: String },
+    /// The host of a source does not match any of the allowed hosts
+    #[fail(display = "host {} is not in the list of allowed hosts", host)]
+    HostNotAllowed { host: String },
+    /// The host of a source matches one of the denied hosts
+    #[fail(display = "host {} is in the list of denied hosts", host)]
+    HostDenied { host: String },
+    /// The host of a source is a private address
+    #[fail(display = "host {} is a private address", host)]
+    PrivateAddress { host: String },
+    /// Too many retrievals from the same host in this epoch
+    #[fail(
+        display = "host {} has already been contacted {} times in this epoch, which is the limit",
+        host, limit
+    )]
+    HostRateLimit { host: String, limit: u16 },
+    /// Too many retrievals in progress
+    #[fail(
+        display = "{} retrievals are in progress, and {} more would exceed the limit of concurrent retrievals ({})",
+        active, requested, limit
+    )]
+    ConcurrencyLimit {
+        active: usize,
+        requested: usize,
+        limit: u16,
+    },
+}
+
+/// Keeps track of the retrievals performed by the node, in order to enforce the retrieval policy
+#[derive(Debug)]
+pub struct RetrievalPolicyEnforcer {
+    /// Policy to enforce
+    policy: RetrievalPolicy,
+    /// Epoch of the retrievals counted in `retrievals_per_host`
+    epoch: Epoch,
+    /// Number of retrievals from each host in the current epoch
+    retrievals_per_host: HashMap<String, u16>,
+    /// Hosts that are being contacted to resolve each data request
+    in_progress: HashMap<Hash, Vec<String>>,
+}
+
+impl Default for RetrievalPolicyEnforcer {
+    fn default() -> Self {
+        Self::new(RetrievalPolicy {
+            allowed_hosts: vec![],
+            denied_hosts: vec![],
+            deny_private_addresses: false,
+            max_retrievals_per_host_per_epoch: 0,
+            max_concurrent_retrievals: 0,
+        })
+    }
+}
+
+impl RetrievalPolicyEnforcer {
+    /// Create a new enforcer for this policy
+    pub fn new(policy: RetrievalPolicy) -> Self {
+        Self {
+            policy,
+            epoch: 0,
+            retrievals_per_host: HashMap::new(),
+            in_progress: HashMap::new(),
+        }
+    }
+
+    /// Check that the sources of a data request are allowed by the policy and, if so, count their
+    /// hosts as being contacted in this epoch until `finish` or `cancel` are called.
+    pub fn acquire(
+        &mut self,
+        epoch: Epoch,
+        dr_pointer: Hash,
+        retrieve: &[RADRetrieve],
+    ) -> Result<(), RetrievalPolicyViolation> {
+        if epoch != self.epoch {
+            self.epoch = epoch;
+            self.retrievals_per_host.clear();
+        }
+
+        let hosts = self.check_sources(retrieve)?;
+
+        let limit = self.policy.max_concurrent_retrievals;
+        let active = self.in_progress.values().map(Vec::len).sum();
+        if limit != 0 && active + hosts.len() > usize::from(limit) {
+            return Err(RetrievalPolicyViolation::ConcurrencyLimit {
+                active,
+                requested: hosts.len(),
+                limit,
+            });
+        }
+
+        for host in &hosts {
+            let count = self.retrievals_per_host.entry(host.clone()).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+        self.in_progress.insert(dr_pointer, hosts);
+
+        Ok(())
+    }
+
+    /// The sources of a data request have been retrieved, so they no longer count as retrievals
+    /// in progress
+    pub fn finish(&mut self, dr_pointer: &Hash) {
+        self.in_progress.remove(dr_pointer);
+    }
+
+    /// The sources of a data request are not going to be retrieved after all, so they do not
+    /// count towards any limit
+    pub fn cancel(&mut self, dr_pointer: &Hash) {
+        if let Some(hosts) = self.in_progress.remove(dr_pointer) {
+            for host in hosts {
+                if let Some(count) = self.retrievals_per_host.get_mut(&host) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    /// Check every source against the policy, and return the list of hosts that would be
+    /// contacted. Sources that do not involve contacting any host are ignored.
+    fn check_sources(
+        &self,
+        retrieve: &[RADRetrieve],
+    ) -> Result<Vec<String>, RetrievalPolicyViolation> {
+        let mut hosts = vec![];
+
+        for source in retrieve {
+            let host = match source_host(source)? {
+                Some(host) => host,
+                None => continue,
+            };
+            let host_str = host.to_string().to_lowercase();
+
+            if !self.policy.allowed_hosts.is_empty()
+                && !self
+                    .policy
+                    .allowed_hosts
+                    .iter()
+                    .any(|pattern| host_matches(pattern, &host_str))
+            {
+                return Err(RetrievalPolicyViolation::HostNotAllowed { host: host_str });
+            }
+
+            if self
+                .policy
+                .denied_hosts
+                .iter()
+                .any(|pattern| host_matches(pattern, &host_str))
+            {
+                return Err(RetrievalPolicyViolation::HostDenied { host: host_str });
+            }
+
+            if self.policy.deny_private_addresses && is_private_host(&host) {
+                return Err(RetrievalPolicyViolation::PrivateAddress { host: host_str });
+            }
+
+            let limit = self.policy.max_retrievals_per_host_per_epoch;
+            let previous = self
+                .retrievals_per_host
+                .get(&host_str)
+                .copied()
+                .unwrap_or(0);
+            let pending = hosts.iter().filter(|h| **h == host_str).count();
+            if limit != 0 && usize::from(previous) + pending >= usize::from(limit) {
+                return Err(RetrievalPolicyViolation::HostRateLimit {
+                    host: host_str,
+                    limit,
+                });
+            }
+
+            hosts.push(host_str);
+        }
+
+        Ok(hosts)
+    }
+}
+
+/// Host to contact in order to retrieve a source, or `None` if retrieving it does not involve
+/// contacting any host
+pub fn source_host(source: &RADRetrieve) -> Result<Option<Host>, RetrievalPolicyViolation> {
+    if source.kind == RADType::Rng {
+        return Ok(None);
+    }
+
+    Url::parse(&source.url)
+        .ok()
+        .and_then(|url| url.host().map(|host| host.to_owned()))
+        .map(Some)
+        .ok_or_else(|| RetrievalPolicyViolation::InvalidUrl {
+            url: source.url.clone(),
+        })
+}
+
+/// Check whether a host matches a pattern. Patterns starting with `*.` match any subdomain of the
+/// domain that follows, while any other pattern must be equal to the host.
+pub fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+
+    if pattern.starts_with("*.") {
+        host.ends_with(&pattern[1..])
+    } else {
+        pattern == host
+    }
+}
+
+/// Check whether a host refers to the node itself or to its private network. Host names other
+/// than `localhost` are not resolved here: the addresses they resolve to are checked by the
+/// retrieval client when connecting to them.
+fn is_private_host(host: &Host) -> bool {
+    match host {
+        Host::Domain(domain) => {
+            let domain = domain.to_lowercase();
+
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Host::Ipv4(ip) => is_private_ip(&IpAddr::V4(*ip)),
+        Host::Ipv6(ip) => is_private_ip(&IpAddr::V6(*ip)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http_get(url: &str) -> RADRetrieve {
+        RADRetrieve {
+            kind: RADType::HttpGet,
+            url: url.to_string(),
+            ..RADRetrieve::default()
+        }
+    }
+
+    fn policy() -> RetrievalPolicy {
+        RetrievalPolicy {
+            allowed_hosts: vec![],
+            denied_hosts: vec![],
+            deny_private_addresses: false,
+            max_retrievals_per_host_per_epoch: 0,
+            max_concurrent_retrievals: 0,
+        }
+    }
+
+    fn dr_pointer(n: u8) -> Hash {
+        Hash::SHA256([n; 32])
+    }
+
+    #[test]
+    fn default_policy_allows_everything() {
+        let mut enforcer = RetrievalPolicyEnforcer::default();
+        let retrieve = vec![
+            http_get("https://api.example.com/price"),
+            http_get("http://127.0.0.1:8080/"),
+            RADRetrieve {
+                kind: RADType::Rng,
+                ..RADRetrieve::default()
+            },
+        ];
+
+        for i in 0..100 {
+            assert_eq!(enforcer.acquire(1, dr_pointer(i), &retrieve), Ok(()));
+        }
+    }
+
+    #[test]
+    fn allowed_and_denied_hosts() {
+        let mut enforcer = RetrievalPolicyEnforcer::new(RetrievalPolicy {
+            allowed_hosts: vec!["*.example.com".to_string(), "api.test.org".to_string()],
+            denied_hosts: vec!["bad.example.com".to_string()],
+            ..policy()
+        });
+
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(0), &[http_get("https://api.example.com/")]),
+            Ok(())
+        );
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(1), &[http_get("https://API.test.org/")]),
+            Ok(())
+        );
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(2), &[http_get("https://www.test.org/")]),
+            Err(RetrievalPolicyViolation::HostNotAllowed {
+                host: "www.test.org".to_string()
+            })
+        );
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(3), &[http_get("https://notexample.com/")]),
+            Err(RetrievalPolicyViolation::HostNotAllowed {
+                host: "notexample.com".to_string()
+            })
+        );
+        assert_eq!(
+            enforcer.acquire(
+                1,
+                dr_pointer(4),
+                &[
+                    http_get("https://api.example.com/"),
+                    http_get("https://bad.example.com/")
+                ]
+            ),
+            Err(RetrievalPolicyViolation::HostDenied {
+                host: "bad.example.com".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_url() {
+        let mut enforcer = RetrievalPolicyEnforcer::default();
+
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(0), &[http_get("not a url")]),
+            Err(RetrievalPolicyViolation::InvalidUrl {
+                url: "not a url".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn private_addresses() {
+        let mut enforcer = RetrievalPolicyEnforcer::new(RetrievalPolicy {
+            deny_private_addresses: true,
+            ..policy()
+        });
+
+        for url in &[
+            "http://localhost:8080/",
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://192.168.1.1/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0:8080/",
+            "http://100.64.0.1/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[::ffff:10.0.0.1]/",
+        ] {
+            match enforcer.acquire(1, dr_pointer(0), &[http_get(url)]) {
+                Err(RetrievalPolicyViolation::PrivateAddress { .. }) => {}
+                x => panic!("{} should be a private address, got {:?}", url, x),
+            }
+        }
+
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(0), &[http_get("http://8.8.8.8/")]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn rate_limit_per_host() {
+        let mut enforcer = RetrievalPolicyEnforcer::new(RetrievalPolicy {
+            max_retrievals_per_host_per_epoch: 2,
+            ..policy()
+        });
+        let retrieve = vec![http_get("https://api.example.com/")];
+
+        assert_eq!(enforcer.acquire(1, dr_pointer(0), &retrieve), Ok(()));
+        assert_eq!(enforcer.acquire(1, dr_pointer(1), &retrieve), Ok(()));
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(2), &retrieve),
+            Err(RetrievalPolicyViolation::HostRateLimit {
+                host: "api.example.com".to_string(),
+                limit: 2
+            })
+        );
+        // Other hosts are not affected
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(3), &[http_get("https://other.com/")]),
+            Ok(())
+        );
+        // Cancelled retrievals do not count towards the limit
+        enforcer.cancel(&dr_pointer(1));
+        assert_eq!(enforcer.acquire(1, dr_pointer(2), &retrieve), Ok(()));
+        // The counters are reset in every epoch
+        assert_eq!(
+            enforcer.acquire(
+                2,
+                dr_pointer(4),
+                &[retrieve[0].clone(), retrieve[0].clone()]
+            ),
+            Ok(())
+        );
+        // The limit applies to sources of the same data request as well
+        assert_eq!(
+            enforcer.acquire(
+                3,
+                dr_pointer(5),
+                &[
+                    retrieve[0].clone(),
+                    retrieve[0].clone(),
+                    retrieve[0].clone()
+                ]
+            ),
+            Err(RetrievalPolicyViolation::HostRateLimit {
+                host: "api.example.com".to_string(),
+                limit: 2
+            })
+        );
+    }
+
+    #[test]
+    fn concurrency_limit() {
+        let mut enforcer = RetrievalPolicyEnforcer::new(RetrievalPolicy {
+            max_concurrent_retrievals: 3,
+            ..policy()
+        });
+        let retrieve = vec![
+            http_get("https://a.example.com/"),
+            http_get("https://b.example.com/"),
+        ];
+
+        assert_eq!(enforcer.acquire(1, dr_pointer(0), &retrieve), Ok(()));
+        assert_eq!(
+            enforcer.acquire(1, dr_pointer(1), &retrieve),
+            Err(RetrievalPolicyViolation::ConcurrencyLimit {
+                active: 2,
+                requested: 2,
+                limit: 3
+            })
+        );
+        // Finished retrievals are no longer in progress, even in later epochs
+        enforcer.finish(&dr_pointer(0));
+        assert_eq!(enforcer.acquire(2, dr_pointer(1), &retrieve), Ok(()));
+    }
+}
@@ -0,0 +1,232 @@
+//! Accounting of the sources retrieved in each epoch
+//!
+//! The `RadManager` retrieves each distinct source only once per epoch, and shares its response
+//! between all the data requests that use it. The sources that have already been scheduled for
+//! retrieval in the current epoch therefore do not count towards the limit of retrievals per epoch.
+use std::collections::HashMap;
+
+use witnet_data_structures::chain::{Epoch, Hash, RADRetrieve};
+use witnet_rad::cache::RetrievalKey;
+
+/// Sources scheduled for retrieval by a single data request
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScheduledRetrievals {
+    /// Cacheable sources that no other data request had scheduled before in this epoch
+    new_keys: Vec<RetrievalKey>,
+    /// Cacheable sources whose retrieval had already been scheduled by another data request
+    shared_keys: Vec<RetrievalKey>,
+    /// Number of retrievals added to the epoch, including the ones that cannot be cached
+    pub retrievals: u16,
+    /// Number of sources whose response is shared with another data request
+    pub cache_hits: u32,
+    /// Number of cacheable sources that will be retrieved for this data request
+    pub cache_misses: u32,
+}
+
+/// Keeps track of the sources scheduled for retrieval in the current epoch
+#[derive(Debug, Default)]
+pub struct RetrievalSchedule {
+    /// Epoch of the sources in `scheduled`
+    epoch: Epoch,
+    /// Cacheable sources scheduled for retrieval in the current epoch, with the number of data
+    /// requests that use each of them
+    scheduled: HashMap<RetrievalKey, u32>,
+    /// Sources scheduled by each data request that has not started its retrieval yet
+    pending: HashMap<Hash, ScheduledRetrievals>,
+}
+
+impl RetrievalSchedule {
+    /// Number of retrievals that resolving these sources in this epoch would add to the epoch
+    pub fn new_retrievals(&mut self, epoch: Epoch, retrieve: &[RADRetrieve]) -> u16 {
+        self.prepare(epoch, retrieve).retrievals
+    }
+
+    /// Schedule the sources of a data request for retrieval in this epoch, and return the number
+    /// of retrievals that it adds to the epoch.
+    pub fn schedule(&mut self, epoch: Epoch, dr_pointer: Hash, retrieve: &[RADRetrieve]) -> u16 {
+        let scheduled = self.prepare(epoch, retrieve);
+        let retrievals = scheduled.retrievals;
+        for key in scheduled.new_keys.iter().chain(&scheduled.shared_keys) {
+            *self.scheduled.entry(key.clone()).or_insert(0) += 1;
+        }
+        self.pending.insert(dr_pointer, scheduled);
+
+        retrievals
+    }
+
+    /// The retrieval of a scheduled data request has started, so its sources stay in the schedule
+    /// until the end of the epoch.
+    pub fn start(&mut self, dr_pointer: &Hash) -> Option<ScheduledRetrievals> {
+        self.pending.remove(dr_pointer)
+    }
+
+    /// The data request will not be resolved after all. The sources that no other data request
+    /// uses are removed from the schedule, and the number of retrievals that will no longer be
+    /// performed is returned.
+    ///
+    /// The sources that it scheduled first but other data requests also use are still going to
+    /// be retrieved, so they are handed over to one of the pending data requests that use them.
+    pub fn cancel(&mut self, dr_pointer: &Hash) -> u16 {
+        let scheduled = match self.pending.remove(dr_pointer) {
+            Some(scheduled) => scheduled,
+            None => return 0,
+        };
+
+        for key in &scheduled.shared_keys {
+            self.release(key);
+        }
+
+        let mut retrievals = scheduled.retrievals;
+        for key in scheduled.new_keys {
+            if self.release(&key) {
+                continue;
+            }
+            retrievals = retrievals.saturating_sub(1);
+            if let Some(heir) = self
+                .pending
+                .values_mut()
+                .find(|other| other.shared_keys.contains(&key))
+            {
+                heir.shared_keys.retain(|shared_key| *shared_key != key);
+                heir.cache_hits = heir.cache_hits.saturating_sub(1);
+                heir.cache_misses += 1;
+                heir.retrievals = heir.retrievals.saturating_add(1);
+                heir.new_keys.push(key);
+            }
+        }
+
+        retrievals
+    }
+
+    /// A data request no longer uses a scheduled source. Returns `true` if no other data request
+    /// uses it, in which case it is removed from the schedule.
+    fn release(&mut self, key: &RetrievalKey) -> bool {
+        match self.scheduled.get_mut(key) {
+            Some(users) if *users > 1 => {
+                *users -= 1;
+
+                false
+            }
+            _ => {
+                self.scheduled.remove(key);
+
+                true
+            }
+        }
+    }
+
+    fn prepare(&mut self, epoch: Epoch, retrieve: &[RADRetrieve]) -> ScheduledRetrievals {
+        if epoch != self.epoch {
+            self.epoch = epoch;
+            self.scheduled.clear();
+            self.pending.clear();
+        }
+
+        let mut scheduled = ScheduledRetrievals::default();
+        for source in retrieve {
+            match RetrievalKey::from_retrieve(source) {
+                Some(key) => {
+                    if scheduled.new_keys.contains(&key) || scheduled.shared_keys.contains(&key) {
+                        scheduled.cache_hits += 1;
+                    } else if self.scheduled.contains_key(&key) {
+                        scheduled.cache_hits += 1;
+                        scheduled.shared_keys.push(key);
+                    } else {
+                        scheduled.cache_misses += 1;
+                        scheduled.retrievals = scheduled.retrievals.saturating_add(1);
+                        scheduled.new_keys.push(key);
+                    }
+                }
+                None => scheduled.retrievals = scheduled.retrievals.saturating_add(1),
+            }
+        }
+
+        scheduled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use witnet_data_structures::chain::RADType;
+
+    use super::*;
+
+    fn source(kind: RADType, url: &str) -> RADRetrieve {
+        RADRetrieve {
+            kind,
+            url: url.to_string(),
+            script: vec![128],
+            body: vec![],
+            headers: vec![],
+        }
+    }
+
+    #[test]
+    fn shared_sources_do_not_count_twice() {
+        let mut schedule = RetrievalSchedule::default();
+        let dr_1 = Hash::SHA256([1; 32]);
+        let dr_2 = Hash::SHA256([2; 32]);
+        let a = source(RADType::HttpGet, "https://a.com");
+        let b = source(RADType::HttpGet, "https://b.com");
+        let rng = source(RADType::Rng, "");
+
+        // Repeated sources within the same data request are also retrieved once
+        assert_eq!(schedule.new_retrievals(1, &[a.clone(), a.clone()]), 1);
+        assert_eq!(schedule.schedule(1, dr_1, &[a.clone(), rng.clone()]), 2);
+        assert_eq!(schedule.new_retrievals(1, &[a.clone(), b.clone()]), 1);
+        assert_eq!(schedule.schedule(1, dr_2, &[a, b, rng]), 2);
+
+        let started = schedule.start(&dr_2).unwrap();
+        assert_eq!(started.retrievals, 2);
+        assert_eq!(started.cache_hits, 1);
+        assert_eq!(started.cache_misses, 1);
+        assert_eq!(schedule.start(&dr_2), None);
+    }
+
+    #[test]
+    fn cancel_and_new_epoch() {
+        let mut schedule = RetrievalSchedule::default();
+        let dr_1 = Hash::SHA256([1; 32]);
+        let dr_2 = Hash::SHA256([2; 32]);
+        let a = source(RADType::HttpGet, "https://a.com");
+
+        assert_eq!(schedule.schedule(1, dr_1, &[a.clone()]), 1);
+        assert_eq!(schedule.cancel(&dr_1), 1);
+        assert_eq!(schedule.cancel(&dr_1), 0);
+        // Cancelled sources will not be retrieved, so they count again
+        assert_eq!(schedule.schedule(1, dr_2, &[a.clone()]), 1);
+        schedule.start(&dr_2);
+        assert_eq!(schedule.new_retrievals(1, &[a.clone()]), 0);
+
+        // The responses of the previous epoch are not shared
+        assert_eq!(schedule.new_retrievals(2, &[a]), 1);
+    }
+
+    #[test]
+    fn cancel_shared_source() {
+        let mut schedule = RetrievalSchedule::default();
+        let dr_1 = Hash::SHA256([1; 32]);
+        let dr_2 = Hash::SHA256([2; 32]);
+        let dr_3 = Hash::SHA256([3; 32]);
+        let a = source(RADType::HttpGet, "https://a.com");
+        let b = source(RADType::HttpGet, "https://b.com");
+        let rng = source(RADType::Rng, "");
+
+        assert_eq!(schedule.schedule(1, dr_1, &[a.clone(), b.clone(), rng]), 3);
+        assert_eq!(schedule.schedule(1, dr_2, &[a.clone()]), 0);
+        // The other data request still retrieves the shared source
+        assert_eq!(schedule.cancel(&dr_1), 2);
+        assert_eq!(schedule.new_retrievals(1, &[a.clone()]), 0);
+        assert_eq!(schedule.new_retrievals(1, &[b]), 1);
+        assert_eq!(schedule.schedule(1, dr_3, &[a.clone()]), 0);
+
+        let started = schedule.start(&dr_2).unwrap();
+        assert_eq!(started.retrievals, 1);
+        assert_eq!(started.cache_hits, 0);
+        assert_eq!(started.cache_misses, 1);
+
+        // Sources used by started data requests stay in the schedule
+        assert_eq!(schedule.cancel(&dr_3), 0);
+        assert_eq!(schedule.new_retrievals(1, &[a]), 0);
+    }
+}
@@ -18,92 +18,106 @@ fn key_superblock(superblock_index: u32) -> Vec<u8> {
     format!("SUPERBLOCK-{:010}", superblock_index).into()
 }
 
-impl InventoryManager {
-    fn handle_add_items(
-        &mut self,
-        msg: AddItems,
-    ) -> ResponseActFuture<Self, (), InventoryManagerError> {
-        let mut blocks_to_add = vec![];
-        let mut transactions_to_add = vec![];
-        let mut superblocks_to_add = vec![];
-
-        for item in msg.items {
-            match item {
-                StoreInventoryItem::Block(block) => {
-                    let block_hash = block.hash();
-                    let key = match block_hash {
+/// Add the writes needed to store the inventory items to a storage batch: every block is stored
+/// along with pointers to all its transactions.
+pub(crate) fn add_items_to_batch(
+    batch: &mut storage_mngr::WriteBatch,
+    items: Vec<StoreInventoryItem>,
+) -> Result<(), failure::Error> {
+    for item in items {
+        match item {
+            StoreInventoryItem::Block(block) => {
+                let block_hash = block.hash();
+                let key = match block_hash {
+                    Hash::SHA256(h) => h.to_vec(),
+                };
+                // Store the block and all the transactions
+                for (tx_hash, pointer_to_block) in
+                    block.txns.create_pointers_to_transactions(block_hash)
+                {
+                    let key = match tx_hash {
                         Hash::SHA256(h) => h.to_vec(),
                     };
-                    // Store the block and all the transactions
-                    let items_to_add = block.txns.create_pointers_to_transactions(block_hash);
-                    blocks_to_add.push((key, block));
-                    transactions_to_add.extend(items_to_add.into_iter().map(
-                        |(tx_hash, pointer_to_block)| {
-                            let key = match tx_hash {
-                                Hash::SHA256(h) => h.to_vec(),
-                            };
-
-                            (key, pointer_to_block)
-                        },
-                    ));
+                    batch.put(&key, &pointer_to_block)?;
                 }
-                StoreInventoryItem::Transaction(hash, pointer_to_block) => {
-                    let key = match hash {
-                        Hash::SHA256(h) => h.to_vec(),
-                    };
+                batch.put(&key, &block)?;
+            }
+            StoreInventoryItem::Transaction(hash, pointer_to_block) => {
+                let key = match hash {
+                    Hash::SHA256(h) => h.to_vec(),
+                };
 
-                    transactions_to_add.push((key, pointer_to_block));
-                }
-                StoreInventoryItem::Superblock(superblock_notify) => {
-                    let superblock_index = superblock_notify.superblock.index;
-                    let key = key_superblock(superblock_index);
-                    superblocks_to_add.push((key, superblock_notify));
-                }
+                batch.put(&key, &pointer_to_block)?;
+            }
+            StoreInventoryItem::Superblock(superblock_notify) => {
+                let superblock_index = superblock_notify.superblock.index;
+                let key = key_superblock(superblock_index);
+                batch.put(&key, &superblock_notify)?;
             }
         }
+    }
 
-        let block_len = blocks_to_add.len();
-        let tx_len = transactions_to_add.len();
-        let superblock_len = superblocks_to_add.len();
+    Ok(())
+}
 
-        log::trace!("Persisting {} blocks to storage", block_len);
+/// Add the deletes needed to remove a stored block to a storage batch, along with the pointers to
+/// all its transactions.
+pub(crate) fn remove_block_from_batch(
+    batch: &mut storage_mngr::WriteBatch,
+    block: &Block,
+) -> Result<(), failure::Error> {
+    let block_hash = block.hash();
+    for (tx_hash, _pointer_to_block) in block.txns.create_pointers_to_transactions(block_hash) {
+        let key = match tx_hash {
+            Hash::SHA256(h) => h.to_vec(),
+        };
+        batch.delete(&key)?;
+    }
+    let key = match block_hash {
+        Hash::SHA256(h) => h.to_vec(),
+    };
+    batch.delete(&key)?;
 
-        // Store all the blocks, and then store all the transactions
-        Box::new(
-            storage_mngr::put_batch(&blocks_to_add)
-                .into_actor(self)
-                .map_err(|e, _, _| {
-                    log::error!("Error when writing blocks to storage: {}", e);
+    Ok(())
+}
 
-                    InventoryManagerError::MailBoxError(e)
-                })
-                .and_then(move |(), act, _| {
-                    log::trace!("Successfully persisted {} blocks to storage", block_len);
-                    log::trace!("Persisting {} transactions to storage", tx_len);
+/// Add the delete needed to remove a stored superblock to a storage batch
+pub(crate) fn remove_superblock_from_batch(
+    batch: &mut storage_mngr::WriteBatch,
+    superblock_index: u32,
+) -> Result<(), failure::Error> {
+    batch.delete(&key_superblock(superblock_index))
+}
+
+impl InventoryManager {
+    fn handle_add_items(
+        &mut self,
+        msg: AddItems,
+    ) -> ResponseActFuture<Self, (), InventoryManagerError> {
+        let items_len = msg.items.len();
+        let mut batch = storage_mngr::WriteBatch::new();
+        if let Err(e) = add_items_to_batch(&mut batch, msg.items) {
+            log::error!("Error when serializing inventory items: {}", e);
 
-                    storage_mngr::put_batch(&transactions_to_add)
-                        .into_actor(act)
-                        .map_err(|e, _, _| {
-                            log::error!("Error when writing transactions to storage: {}", e);
+            return Box::new(actix::fut::err(InventoryManagerError::MailBoxError(e)));
+        }
 
-                            InventoryManagerError::MailBoxError(e)
-                        })
-                })
-                .and_then(move |(), act, _| {
-                    log::trace!("Successfully persisted {} transactions to storage", tx_len);
+        log::trace!("Persisting {} inventory items to storage", items_len);
 
-                    storage_mngr::put_batch(&superblocks_to_add)
-                        .into_actor(act)
-                        .map_err(|e, _, _| {
-                            log::error!("Error when writing superblocks to storage: {}", e);
+        // Blocks, transactions and superblocks are written in a single batch, so a transaction
+        // pointer is never stored without the block it points to
+        Box::new(
+            storage_mngr::write(batch)
+                .into_actor(self)
+                .map_err(|e, _, _| {
+                    log::error!("Error when writing inventory items to storage: {}", e);
 
-                            InventoryManagerError::MailBoxError(e)
-                        })
+                    InventoryManagerError::MailBoxError(e)
                 })
                 .and_then(move |(), _, _| {
                     log::trace!(
-                        "Successfully persisted {} superblocks to storage",
-                        superblock_len
+                        "Successfully persisted {} inventory items to storage",
+                        items_len
                     );
 
                     actix::fut::ok(())
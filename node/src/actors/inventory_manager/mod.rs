@@ -8,6 +8,10 @@ use witnet_data_structures::chain::PointerToBlock;
 mod actor;
 mod handlers;
 
+pub(crate) use self::handlers::{
+    add_items_to_batch, remove_block_from_batch, remove_superblock_from_batch,
+};
+
 /// InventoryManager actor
 #[derive(Debug, Default)]
 pub struct InventoryManager;
@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use witnet_crypto::key::KeyPath;
 use witnet_data_structures::{
     chain::{Block, Hash, Hashable, PublicKeyHash, StateMachine, SyncStatus},
+    data_request_index::DataRequestQuery,
     transaction::Transaction,
     vrf::VrfMessage,
 };
@@ -28,11 +29,13 @@ use crate::{
         epoch_manager::{EpochManager, EpochManagerError},
         inventory_manager::{InventoryManager, InventoryManagerError},
         messages::{
-            AddCandidates, AddPeers, AddTransaction, BuildDrt, BuildVtt, ClearPeers, GetBalance,
-            GetBlocksEpochRange, GetConsolidatedPeers, GetDataRequestInfo, GetEpoch,
-            GetHighestCheckpointBeacon, GetItemBlock, GetItemSuperblock, GetItemTransaction,
-            GetKnownPeers, GetMemoryTransaction, GetMempool, GetNodeStats, GetReputation, GetState,
-            GetUtxoInfo, InitializePeers, IsConfirmedBlock,
+            AddCandidates, AddPeers, AddTransaction, BuildDrt, BuildVtt, ClearPeers, EstimateDrt,
+            GetAddressTransactions, GetAddressUtxos, GetAvailableRange, GetBalance,
+            GetBlocksEpochRange, GetConsolidatedPeers, GetDataRequestAudit, GetDataRequestInfo,
+            GetEpoch, GetHighestCheckpointBeacon, GetItemBlock, GetItemSuperblock,
+            GetItemTransaction, GetKnownPeers, GetMemoryTransaction, GetMempool, GetNodeStats,
+            GetReputation, GetState, GetUtxoInfo, InitializePeers, IsConfirmedBlock,
+            QueryDataRequests, RebuildAddressIndex,
         },
         peers_manager::PeersManager,
         sessions_manager::SessionsManager,
@@ -85,11 +88,23 @@ pub fn jsonrpc_io_handler(
     io.add_method("getSuperblock", |params: Params| {
         get_superblock(params.parse())
     });
+    io.add_method("getAddressTransactions", |params: Params| {
+        get_address_transactions(params.parse())
+    });
+    io.add_method("getAddressUtxos", |params: Params| {
+        get_address_utxos(params.parse())
+    });
+    io.add_method("queryDataRequests", |params: Params| {
+        query_data_requests(params.parse())
+    });
+    io.add_method("getAvailableRange", |_params: Params| get_available_range());
 
     // Enable methods that assume that JSON-RPC is only accessible by the owner of the node.
     // A method is sensitive if it touches in some way the master key of the node.
     // For example: methods that can be used to create transactions (spending value from this node),
     // sign arbitrary messages with the node master key, and even export the master key.
+    // Methods that are too expensive to be exposed publicly, because they go through the whole
+    // UTXO set or the whole block chain, are also considered sensitive.
     let unauthorized_method = |method_name| {
         Box::new(futures::failed(internal_error_s(unauthorized_message(
             method_name,
@@ -151,6 +166,13 @@ pub fn jsonrpc_io_handler(
             unauthorized_method("masterKeyExport")
         }
     });
+    io.add_method("dataRequestAudit", move |params: Params| {
+        if enable_sensitive_methods {
+            data_request_audit(params.parse())
+        } else {
+            unauthorized_method("dataRequestAudit")
+        }
+    });
     io.add_method("addPeers", move |params: Params| {
         if enable_sensitive_methods {
             add_peers(params.parse())
@@ -174,6 +196,21 @@ pub fn jsonrpc_io_handler(
             unauthorized_method("initializePeers")
         }
     });
+
+    io.add_method("rebuildAddressIndex", move |_params: Params| {
+        if enable_sensitive_methods {
+            rebuild_address_index()
+        } else {
+            unauthorized_method("rebuildAddressIndex")
+        }
+    });
+    io.add_method("estimateRequest", move |params: Params| {
+        if enable_sensitive_methods {
+            estimate_request(params.parse())
+        } else {
+            unauthorized_method("estimateRequest")
+        }
+    });
     // Enable subscriptions
     // We need two Arcs, one for subscribe and one for unsuscribe
     let ss = subscriptions.clone();
@@ -860,6 +897,34 @@ pub fn send_request(params: Result<BuildDrt, jsonrpc_core::Error>) -> JsonRpcRes
     }
 }
 
+/// Estimate the cost of a data request, and check its parameters
+pub fn estimate_request(params: Result<EstimateDrt, jsonrpc_core::Error>) -> JsonRpcResultAsync {
+    match params {
+        Ok(msg) => Box::new(
+            ChainManager::from_registry()
+                .send(msg)
+                .then(|res| match res {
+                    Ok(Ok(estimate)) => match serde_json::to_value(estimate) {
+                        Ok(x) => Box::new(futures::finished(x)),
+                        Err(e) => {
+                            let err = internal_error_s(e);
+                            Box::new(futures::failed(err))
+                        }
+                    },
+                    Ok(Err(e)) => {
+                        let err = internal_error_s(e);
+                        Box::new(futures::failed(err))
+                    }
+                    Err(e) => {
+                        let err = internal_error_s(e);
+                        Box::new(futures::failed(err))
+                    }
+                }),
+        ),
+        Err(err) => Box::new(futures::failed(err)),
+    }
+}
+
 /// Build value transfer transaction
 pub fn send_value(params: Result<BuildVtt, jsonrpc_core::Error>) -> JsonRpcResultAsync {
     log::debug!("Creating value transfer from JSON-RPC.");
@@ -1023,6 +1088,32 @@ pub fn data_request_report(params: Result<(Hash,), jsonrpc_core::Error>) -> Json
     Box::new(fut)
 }
 
+/// Audit record of a data request resolved by this node
+pub fn data_request_audit(params: Result<(Hash,), jsonrpc_core::Error>) -> JsonRpcResultAsync {
+    let dr_pointer = match params {
+        Ok(x) => x.0,
+        Err(e) => return Box::new(futures::failed(e)),
+    };
+
+    let chain_manager_addr = ChainManager::from_registry();
+
+    let fut = chain_manager_addr
+        .send(GetDataRequestAudit { dr_pointer })
+        .map_err(internal_error)
+        .and_then(|audit| match audit {
+            Ok(x) => match serde_json::to_value(&x) {
+                Ok(x) => futures::finished(x),
+                Err(e) => {
+                    let err = internal_error_s(e);
+                    futures::failed(err)
+                }
+            },
+            Err(e) => futures::failed(internal_error_s(e)),
+        });
+
+    Box::new(fut)
+}
+
 /// Get balance
 pub fn get_balance(params: Result<(PublicKeyHash,), jsonrpc_core::Error>) -> JsonRpcResultAsync {
     let pkh = match params {
@@ -1074,6 +1165,107 @@ pub fn get_utxo_info(params: Result<(PublicKeyHash,), jsonrpc_core::Error>) -> J
     Box::new(fut)
 }
 
+/// Get the transactions that touched an address, from the address index
+pub fn get_address_transactions(
+    params: Result<(PublicKeyHash,), jsonrpc_core::Error>,
+) -> JsonRpcResultAsync {
+    let pkh = match params {
+        Ok(x) => x.0,
+        Err(e) => return Box::new(futures::failed(e)),
+    };
+
+    let chain_manager_addr = ChainManager::from_registry();
+
+    let fut = chain_manager_addr
+        .send(GetAddressTransactions { pkh })
+        .map_err(internal_error)
+        .and_then(|res| match res {
+            Ok(x) => match serde_json::to_value(&x) {
+                Ok(x) => futures::finished(x),
+                Err(e) => {
+                    let err = internal_error_s(e);
+                    futures::failed(err)
+                }
+            },
+            Err(e) => futures::failed(internal_error_s(e)),
+        });
+
+    Box::new(fut)
+}
+
+/// Get the unspent outputs of an address, from the address index
+pub fn get_address_utxos(
+    params: Result<(PublicKeyHash,), jsonrpc_core::Error>,
+) -> JsonRpcResultAsync {
+    let pkh = match params {
+        Ok(x) => x.0,
+        Err(e) => return Box::new(futures::failed(e)),
+    };
+
+    let chain_manager_addr = ChainManager::from_registry();
+
+    let fut = chain_manager_addr
+        .send(GetAddressUtxos { pkh })
+        .map_err(internal_error)
+        .and_then(|res| match res {
+            Ok(x) => match serde_json::to_value(&x) {
+                Ok(x) => futures::finished(x),
+                Err(e) => {
+                    let err = internal_error_s(e);
+                    futures::failed(err)
+                }
+            },
+            Err(e) => futures::failed(internal_error_s(e)),
+        });
+
+    Box::new(fut)
+}
+
+/// Query the data request index. All the fields of the query are optional, and the matching data
+/// requests are returned most recent first, along with the total number of matches.
+pub fn query_data_requests(
+    params: Result<DataRequestQuery, jsonrpc_core::Error>,
+) -> JsonRpcResultAsync {
+    let query = match params {
+        Ok(x) => x,
+        Err(e) => return Box::new(futures::failed(e)),
+    };
+
+    let chain_manager_addr = ChainManager::from_registry();
+
+    let fut = chain_manager_addr
+        .send(QueryDataRequests(query))
+        .map_err(internal_error)
+        .and_then(|res| match res {
+            Ok(x) => match serde_json::to_value(&x) {
+                Ok(x) => futures::finished(x),
+                Err(e) => {
+                    let err = internal_error_s(e);
+                    futures::failed(err)
+                }
+            },
+            Err(e) => futures::failed(internal_error_s(e)),
+        });
+
+    Box::new(fut)
+}
+
+/// Rebuild the address index from the stored blocks. The rebuild runs in the background, and this
+/// method returns the number of blocks that will be indexed.
+pub fn rebuild_address_index() -> JsonRpcResultAsync {
+    let chain_manager_addr = ChainManager::from_registry();
+
+    let fut = chain_manager_addr
+        .send(RebuildAddressIndex)
+        .map_err(internal_error)
+        .and_then(|res| match res {
+            Ok(blocks_len) => futures::finished(Value::from(blocks_len)),
+            Err(e) => futures::failed(internal_error_s(e)),
+        });
+
+    Box::new(fut)
+}
+
 /// Get Reputation of one pkh
 pub fn get_reputation(
     params: Result<(PublicKeyHash,), jsonrpc_core::Error>,
@@ -1223,6 +1415,28 @@ pub fn known_peers() -> JsonRpcResultAsync {
     Box::new(fut)
 }
 
+/// Get the range of consolidated blocks and superblocks that the node stores. In pruned mode, the
+/// blocks and superblocks older than that range were deleted.
+pub fn get_available_range() -> JsonRpcResultAsync {
+    let chain_manager_addr = ChainManager::from_registry();
+
+    let fut = chain_manager_addr
+        .send(GetAvailableRange)
+        .map_err(internal_error)
+        .and_then(|res| match res {
+            Ok(x) => match serde_json::to_value(&x) {
+                Ok(x) => futures::finished(x),
+                Err(e) => {
+                    let err = internal_error_s(e);
+                    futures::failed(err)
+                }
+            },
+            Err(e) => futures::failed(internal_error_s(e)),
+        });
+
+    Box::new(fut)
+}
+
 /// Get the node stats
 pub fn node_stats() -> JsonRpcResultAsync {
     let chain_manager_addr = ChainManager::from_registry();
@@ -1619,12 +1833,16 @@ mod tests {
             kind: RADType::HttpGet,
             url: "https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22".to_string(),
             script: vec![0],
+            body: vec![],
+            headers: vec![],
         };
 
         let rad_retrieve_2 = RADRetrieve {
             kind: RADType::HttpGet,
             url: "https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22".to_string(),
             script: vec![0],
+            body: vec![],
+            headers: vec![],
         };
 
         let rad_consensus = RADTally::default();
@@ -1709,7 +1927,12 @@ mod tests {
                 "addPeers",
                 "clearPeers",
                 "createVRF",
+                "dataRequestAudit",
                 "dataRequestReport",
+                "estimateRequest",
+                "getAddressTransactions",
+                "getAddressUtxos",
+                "getAvailableRange",
                 "getBalance",
                 "getBlock",
                 "getBlockChain",
@@ -1728,6 +1951,8 @@ mod tests {
                 "masterKeyExport",
                 "nodeStats",
                 "peers",
+                "queryDataRequests",
+                "rebuildAddressIndex",
                 "sendRequest",
                 "sendValue",
                 "sign",
@@ -1754,11 +1979,14 @@ mod tests {
             "addPeers",
             "clearPeers",
             "createVRF",
+            "dataRequestAudit",
+            "estimateRequest",
             "getPkh",
             "getPublicKey",
             "getUtxoInfo",
             "initializePeers",
             "masterKeyExport",
+            "rebuildAddressIndex",
             "sendRequest",
             "sendValue",
             "sign",
@@ -17,11 +17,14 @@ use serde::{Deserialize, Serialize};
 use tokio::net::TcpStream;
 
 use witnet_data_structures::{
+    address_index::AddressTransaction,
+    audit::{DataRequestAudit, ExecutionTrace},
     chain::{
         Block, CheckpointBeacon, DataRequestInfo, DataRequestOutput, Epoch, EpochConstants, Hash,
         InventoryEntry, InventoryItem, NodeStats, PointerToBlock, PublicKeyHash, RADRequest,
         RADTally, Reputation, StateMachine, SuperBlock, SuperBlockVote, ValueTransferOutput,
     },
+    data_request_index::{DataRequestQuery, DataRequestQueryResult},
     radon_report::RadonReport,
     transaction::{CommitTransaction, RevealTransaction, Transaction},
     types::LastBeacon,
@@ -32,6 +35,7 @@ use witnet_p2p::{
     sessions::{GetConsolidatedPeersResult, SessionStatus, SessionType},
 };
 use witnet_rad::{error::RadError, types::RadonTypes};
+use witnet_validations::estimation::DataRequestEstimate;
 
 use super::{
     chain_manager::{ChainManagerError, MAX_BLOCKS_SYNC},
@@ -218,6 +222,19 @@ impl Message for BuildDrt {
     type Result = Result<Hash, failure::Error>;
 }
 
+/// Estimate the cost of a `DataRequestOutput`, and check whether it is likely to be resolved
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EstimateDrt {
+    /// `DataRequestOutput`
+    pub dro: DataRequestOutput,
+    /// Fee
+    pub fee: u64,
+}
+
+impl Message for EstimateDrt {
+    type Result = Result<DataRequestEstimate, failure::Error>;
+}
+
 /// Get ChainManager State (WaitingConsensus, Synchronizing, AlmostSynced, Synced)
 #[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GetState;
@@ -237,6 +254,17 @@ impl Message for GetDataRequestInfo {
     type Result = Result<DataRequestInfo, failure::Error>;
 }
 
+/// Get the audit record of a data request resolved by this node
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GetDataRequestAudit {
+    /// `DataRequest` transaction hash
+    pub dr_pointer: Hash,
+}
+
+impl Message for GetDataRequestAudit {
+    type Result = Result<DataRequestAudit, failure::Error>;
+}
+
 /// Get Balance
 #[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct GetBalance {
@@ -259,6 +287,69 @@ impl Message for GetUtxoInfo {
     type Result = Result<UtxoInfo, failure::Error>;
 }
 
+/// Get the transactions that touched an address, from the address index
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GetAddressTransactions {
+    /// Public key hash
+    pub pkh: PublicKeyHash,
+}
+
+impl Message for GetAddressTransactions {
+    type Result = Result<Vec<AddressTransaction>, failure::Error>;
+}
+
+/// Get the unspent outputs of an address, from the address index
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GetAddressUtxos {
+    /// Public key hash
+    pub pkh: PublicKeyHash,
+}
+
+impl Message for GetAddressUtxos {
+    type Result = Result<UtxoInfo, failure::Error>;
+}
+
+/// Rebuild the address index from the stored blocks of the chain
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RebuildAddressIndex;
+
+impl Message for RebuildAddressIndex {
+    /// Number of blocks that will be indexed
+    type Result = Result<usize, failure::Error>;
+}
+
+/// Get the range of consolidated blocks and superblocks that this node stores and can serve to
+/// its peers
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GetAvailableRange;
+
+impl Message for GetAvailableRange {
+    type Result = Result<AvailableRange, failure::Error>;
+}
+
+/// Range of consolidated blocks and superblocks stored by this node
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AvailableRange {
+    /// Whether the node runs in pruned mode, deleting old blocks and superblocks
+    pub pruned: bool,
+    /// All the consolidated blocks from this epoch onwards are stored
+    pub first_block_epoch: Epoch,
+    /// Epoch of the last consolidated block
+    pub last_block_epoch: Epoch,
+    /// All the consolidated superblocks from this index onwards are stored
+    pub first_superblock_index: u32,
+    /// Index of the last consolidated superblock
+    pub last_superblock_index: u32,
+}
+
+/// Query the data request index
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct QueryDataRequests(pub DataRequestQuery);
+
+impl Message for QueryDataRequests {
+    type Result = Result<DataRequestQueryResult, failure::Error>;
+}
+
 /// Reputation info
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ReputationStats {
@@ -673,6 +764,20 @@ pub struct ResolveRA {
     pub rad_request: RADRequest,
     /// Timeout: if the execution does not finish before the timeout, it is cancelled.
     pub timeout: Option<Duration>,
+    /// Epoch in which the data request is resolved. The responses of the sources are shared with
+    /// other data requests resolved in the same epoch. If `None`, nothing is shared.
+    pub epoch: Option<Epoch>,
+    /// Collect the raw responses of the sources and the partial results of every script
+    pub trace: bool,
+}
+
+/// Output of the request-aggregate step of a data request
+#[derive(Debug)]
+pub struct ResolveRAOutput {
+    /// Report of the aggregation stage, whose result is the value to commit and reveal
+    pub report: RadonReport<RadonTypes>,
+    /// Trace of the execution, if it was requested and the execution did not time out
+    pub trace: Option<ExecutionTrace>,
 }
 
 /// Message for running the tally step of a data request.
@@ -689,7 +794,7 @@ pub struct RunTally {
 }
 
 impl Message for ResolveRA {
-    type Result = Result<RadonReport<RadonTypes>, RadError>;
+    type Result = Result<ResolveRAOutput, RadError>;
 }
 
 impl Message for RunTally {
@@ -12,6 +12,7 @@ use crate::config_mngr;
 use crate::signature_mngr;
 use crate::storage_mngr;
 use witnet_config::config::Config;
+use witnet_data_structures::chain::Environment;
 
 /// Function to run the main system
 pub fn run(config: Arc<Config>, callback: fn()) -> Result<(), failure::Error> {
@@ -21,6 +22,9 @@ pub fn run(config: Arc<Config>, callback: fn()) -> Result<(), failure::Error> {
     // Call cb function (register interrupt handlers)
     callback();
 
+    // Enable the custom RADON operators before any script can be run
+    register_custom_radon_operators(&config)?;
+
     // Start ConfigManager actor
     config_mngr::start(config.clone());
 
@@ -67,6 +71,25 @@ pub fn run(config: Arc<Config>, callback: fn()) -> Result<(), failure::Error> {
         .map_err(|error| error.into())
 }
 
+/// Register the custom RADON operators enabled in the configuration. Custom operators are never
+/// enabled in mainnet.
+fn register_custom_radon_operators(config: &Config) -> Result<(), failure::Error> {
+    let operators = &config.mining.custom_radon_operators;
+    if config.environment == Environment::Mainnet {
+        if !operators.is_empty() {
+            log::warn!("Custom RADON operators are not allowed in mainnet and will be ignored");
+        }
+
+        return Ok(());
+    }
+
+    for operator in operators {
+        witnet_rad::operators::registry::register_extension(operator.code, &operator.name)?;
+    }
+
+    Ok(())
+}
+
 /// Function to close the main system
 pub fn close() {
     log::info!("Closing node");
@@ -1,5 +1,9 @@
-use super::RadManager;
-use actix::{Actor, Context, Supervised, SystemService};
+use actix::prelude::*;
+
+use witnet_rad::transport::RetrievalClient;
+
+use super::{worker::RadWorker, RadManager};
+use crate::config_mngr;
 
 /// Implement Actor trait for `RadManager`
 impl Actor for RadManager {
@@ -7,8 +11,36 @@ impl Actor for RadManager {
     type Context = Context<Self>;
 
     /// Method to be executed when the actor is started
-    fn started(&mut self, _ctx: &mut Self::Context) {
+    fn started(&mut self, ctx: &mut Self::Context) {
         log::debug!("RadManager actor has been started!");
+
+        // Do not handle any message until the workers are started
+        config_mngr::get()
+            .into_actor(self)
+            .map_err(|err, _act, _ctx| {
+                log::error!("Couldn't get config: {}", err);
+            })
+            .and_then(|config, act, _ctx| {
+                match RetrievalClient::new(&config.retrieval_transport) {
+                    Ok(client) => {
+                        act.retrieval_client = Some(client.deny_private_addresses(
+                            config.mining.retrieval_policy.deny_private_addresses,
+                        ))
+                    }
+                    Err(e) => {
+                        log::error!("Invalid retrieval transport configuration: {}", e);
+                        System::current().stop_with_code(1);
+                        return fut::ok(());
+                    }
+                }
+
+                let workers = usize::from(config.mining.data_request_workers.max(1));
+                log::debug!("Starting {} RadWorker threads", workers);
+                act.workers = Some(SyncArbiter::start(workers, RadWorker::default));
+
+                fut::ok(())
+            })
+            .wait(ctx);
     }
 }
 
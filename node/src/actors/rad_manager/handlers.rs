@@ -1,91 +1,111 @@
 //! Message handlers for `RadManager`
 
+use std::time::Instant;
+
 use actix::{Handler, Message, ResponseFuture};
 use futures::Future;
+use futures03::FutureExt as _;
 use tokio::util::FutureExt;
 
 use witnet_data_structures::radon_report::{RadonReport, ReportContext};
-use witnet_rad::{error::RadError, script::RadonScriptExecutionSettings, types::RadonTypes};
+use witnet_rad::{error::RadError, types::RadonTypes};
 use witnet_validations::validations::{
     construct_report_from_clause_result, evaluate_tally_precondition_clause,
-    TallyPreconditionClauseResult,
 };
 
-use crate::actors::messages::{ResolveRA, RunTally};
+use crate::actors::messages::{ResolveRA, ResolveRAOutput, RunTally};
 
-use super::RadManager;
+use super::{worker::RunRetrievalAndAggregation, RadManager};
 
 impl Handler<ResolveRA> for RadManager {
-    type Result = ResponseFuture<RadonReport<RadonTypes>, RadError>;
+    type Result = ResponseFuture<ResolveRAOutput, RadError>;
 
     fn handle(&mut self, msg: ResolveRA, _ctx: &mut Self::Context) -> Self::Result {
         let timeout = msg.timeout;
-        // The result of the RAD aggregation is computed asynchronously, because the async block
-        // returns a std future. It is called fut03 because it uses the 0.3 version of futures,
-        // while most of our codebase is still on 0.1 futures.
-        let fut03 = async {
-            let sources = msg.rad_request.retrieve;
-            let aggregator = msg.rad_request.aggregate;
+        let trace = msg.trace;
+        // The timeout covers both the retrieval of the sources and the execution of the scripts
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        // The workers and the retrieval client are not created if the configuration could not be
+        // loaded
+        let (workers, client) = match (self.workers.clone(), self.retrieval_client.clone()) {
+            (Some(workers), Some(client)) => (workers, client),
+            _ => return Box::new(futures::future::err(RadError::EngineNotStarted)),
+        };
 
-            let retrieve_responses_fut = sources
+        let sources = msg.rad_request.retrieve;
+        let aggregate = msg.rad_request.aggregate;
+        let input_futures: Vec<_> = match msg.epoch {
+            Some(epoch) => sources
+                .iter()
+                .map(|retrieve| {
+                    self.retrieval_cache
+                        .retrieve_input(epoch, retrieve, &client)
+                })
+                .collect(),
+            None => sources
                 .iter()
-                .map(|retrieve| witnet_rad::run_retrieval(retrieve));
+                .map(|retrieve| {
+                    let retrieve = retrieve.clone();
+                    let client = client.clone();
+                    async move { witnet_rad::retrieve_input(&retrieve, &client).await }.boxed()
+                })
+                .collect(),
+        };
 
+        // The inputs of the retrieval scripts are obtained asynchronously, because the async block
+        // returns a std future. It is called fut03 because it uses the 0.3 version of futures,
+        // while most of our codebase is still on 0.1 futures.
+        let fut03 = async move {
             // Perform retrievals in parallel for the sake of synchronization between sources
             //  (increasing the likeliness of multiple sources returning results that are closer to each
             //  other).
-            let retrieve_responses: Vec<RadonReport<RadonTypes>> =
-                futures03::future::join_all(retrieve_responses_fut)
-                    .await
-                    .into_iter()
-                    .map(|retrieve| RadonReport::from_result(retrieve, &ReportContext::default()))
-                    .collect();
-
-            let clause_result = evaluate_tally_precondition_clause(retrieve_responses, 0.2, 1);
-
-            match clause_result {
-                Ok(TallyPreconditionClauseResult::MajorityOfValues {
-                    values,
-                    liars: _liars,
-                    errors: _errors,
-                }) => {
-                    // Perform aggregation on the values that made it to the output vector after applying the
-                    // source scripts (aka _normalization scripts_ in the original whitepaper) and filtering out
-                    // failures.
-                    witnet_rad::run_aggregation_report(
-                        values,
-                        &aggregator,
-                        RadonScriptExecutionSettings::all_but_partial_results(),
-                    )
-                }
-                Ok(TallyPreconditionClauseResult::MajorityOfErrors { errors_mode }) => {
-                    Ok(RadonReport::from_result(
-                        Ok(RadonTypes::RadonError(errors_mode)),
-                        &ReportContext::default(),
-                    ))
-                }
-                Err(e) => Ok(RadonReport::from_result(Err(e), &ReportContext::default())),
-            }
+            let (inputs, retrieval_times) =
+                futures03::future::join_all(input_futures.into_iter().map(|input| async move {
+                    let start = Instant::now();
+                    let input = input.await;
+
+                    (input, start.elapsed())
+                }))
+                .await
+                .into_iter()
+                .unzip();
+
+            Ok::<_, RadError>(RunRetrievalAndAggregation {
+                retrieve: sources,
+                inputs,
+                retrieval_times,
+                aggregate,
+                deadline,
+                trace,
+            })
         };
 
         // Magic conversion from std::future::Future (futures 0.3) and futures::Future (futures 0.1)
-        let fut = futures_util::compat::Compat::new(Box::pin(fut03));
+        let fut = futures_util::compat::Compat::new(Box::pin(fut03)).and_then(move |msg| {
+            // Running the scripts may block, so it is done in one of the workers
+            workers.send(msg).then(|result| match result {
+                Ok(result) => result,
+                Err(error) => {
+                    log::error!("Couldn't send scripts to RadWorker: {}", error);
+                    Err(RadError::Unknown)
+                }
+            })
+        });
 
         if let Some(timeout) = timeout {
-            // Add timeout, if there is one
-            // TODO: this timeout only works if there are no blocking operations.
-            // Since currently the execution of RADON is blocking this thread, we can only
-            // handle HTTP timeouts.
-            // A simple fix would be to offload computation to another thread, to avoid blocking
-            // the main thread. Then the timeout would apply to the message passing between threads.
+            // Add timeout, if there is one. If the scripts are still running when it expires, the
+            // worker aborts them as well, because they share the same deadline
             Box::new(fut.timeout(timeout).then(|result| match result {
                 Ok(x) => Ok(x),
                 Err(error) => {
                     if error.is_elapsed() {
-                        Ok(RadonReport::from_result(
-                            Err(RadError::RetrieveTimeout),
-                            &ReportContext::default(),
-                        ))
+                        Ok(ResolveRAOutput {
+                            report: RadonReport::from_result(
+                                Err(RadError::RetrieveTimeout),
+                                &ReportContext::default(),
+                            ),
+                            trace: None,
+                        })
                     } else if error.is_inner() {
                         Err(error.into_inner().unwrap())
                     } else {
@@ -6,9 +6,21 @@
 //! [Data Requests]: https://docs.witnet.io/protocol/data-requests/overview/
 //! [RAD Engine]: https://docs.witnet.io/protocol/data-requests/overview/#the-rad-engine
 
+use actix::Addr;
+
+use witnet_rad::{cache::RetrievalCache, transport::RetrievalClient};
+
 mod actor;
 mod handlers;
+mod worker;
 
 /// RadManager actor
 #[derive(Default)]
-pub struct RadManager;
+pub struct RadManager {
+    /// Pool of threads where RADON scripts are executed, started once the configuration is loaded
+    workers: Option<Addr<worker::RadWorker>>,
+    /// HTTP client used for retrievals, configured from the `retrieval_transport` section
+    retrieval_client: Option<RetrievalClient>,
+    /// Responses retrieved in the current epoch, shared between data requests with the same sources
+    retrieval_cache: RetrievalCache,
+}
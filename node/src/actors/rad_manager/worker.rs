@@ -0,0 +1,181 @@
+//! Worker threads for `RadManager`
+//!
+//! Running a RADON script is a CPU bound task, so it is performed by a pool of `RadWorker`s running
+//! on their own threads. This way, a heavy script never blocks the thread of the actor system, and
+//! the timeout of a data request can be enforced on the execution of its scripts.
+
+use std::time::{Duration, Instant};
+
+use actix::{Actor, Handler, Message, SyncContext};
+
+use witnet_data_structures::{
+    audit::{ExecutionTrace, ScriptTrace, SourceTrace},
+    chain::{RADAggregate, RADRetrieve},
+    radon_report::{RadonReport, ReportContext, RetrievalMetadata, Stage},
+};
+use witnet_rad::{
+    error::RadError,
+    script::RadonScriptExecutionSettings,
+    types::{RadonType, RadonTypes},
+};
+use witnet_validations::validations::{
+    evaluate_tally_precondition_clause, TallyPreconditionClauseResult,
+};
+
+use crate::actors::messages::ResolveRAOutput;
+
+/// Actor that executes RADON scripts in a `SyncArbiter`
+#[derive(Default)]
+pub struct RadWorker;
+
+impl Actor for RadWorker {
+    type Context = SyncContext<Self>;
+}
+
+/// Run the retrieval scripts of a data request on the inputs obtained from its sources, and
+/// aggregate their results.
+pub struct RunRetrievalAndAggregation {
+    /// Retrieval sources of the data request
+    pub retrieve: Vec<RADRetrieve>,
+    /// Input for the script of each source, or the error found when retrieving it
+    pub inputs: Vec<Result<RadonTypes, RadError>>,
+    /// Time spent retrieving each of the inputs
+    pub retrieval_times: Vec<Duration>,
+    /// Aggregation stage of the data request
+    pub aggregate: RADAggregate,
+    /// If the execution is not complete at this instant, it is aborted with a timeout error
+    pub deadline: Option<Instant>,
+    /// Collect the partial results of every script, along with the inputs and timings
+    pub trace: bool,
+}
+
+impl Message for RunRetrievalAndAggregation {
+    type Result = Result<ResolveRAOutput, RadError>;
+}
+
+impl Handler<RunRetrievalAndAggregation> for RadWorker {
+    type Result = <RunRetrievalAndAggregation as Message>::Result;
+
+    fn handle(
+        &mut self,
+        msg: RunRetrievalAndAggregation,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        let deadline = msg.deadline;
+
+        // The message may have been waiting in the queue for too long, in which case nobody is
+        // waiting for the result anymore
+        if deadline.map(|deadline| Instant::now() >= deadline) == Some(true) {
+            return Ok(ResolveRAOutput {
+                report: RadonReport::from_result(
+                    Err(RadError::RetrieveTimeout),
+                    &ReportContext::default(),
+                ),
+                trace: None,
+            });
+        }
+
+        let (source_settings, aggregation_settings) = if msg.trace {
+            (
+                RadonScriptExecutionSettings::enable_all(),
+                RadonScriptExecutionSettings::enable_all(),
+            )
+        } else {
+            (
+                RadonScriptExecutionSettings::disable_all(),
+                RadonScriptExecutionSettings::all_but_partial_results(),
+            )
+        };
+        let mut trace = ExecutionTrace::default();
+
+        let retrieve_responses: Vec<RadonReport<RadonTypes>> = msg
+            .retrieve
+            .iter()
+            .zip(msg.inputs)
+            .zip(msg.retrieval_times)
+            .map(|((retrieve, input), retrieval_time)| {
+                let mut context =
+                    ReportContext::from_stage(Stage::Retrieval(RetrievalMetadata::default()));
+                context.deadline = deadline;
+                let (response, error) = match &input {
+                    Ok(input) if msg.trace => (Some(raw_response(input)), None),
+                    Err(e) if msg.trace => (None, Some(e.to_string())),
+                    _ => (None, None),
+                };
+                let report = input.and_then(|input| {
+                    witnet_rad::run_retrieval_script_report(
+                        retrieve,
+                        input,
+                        &mut context,
+                        source_settings,
+                    )
+                });
+
+                if msg.trace {
+                    trace.sources.push(SourceTrace {
+                        kind: retrieve.kind.clone(),
+                        url: retrieve.url.clone(),
+                        response,
+                        error,
+                        retrieval_time,
+                        script: report.as_ref().ok().map(ScriptTrace::from_report),
+                    });
+                }
+
+                RadonReport::from_result(
+                    report.map(RadonReport::into_inner),
+                    &ReportContext::default(),
+                )
+            })
+            .collect();
+
+        let clause_result = evaluate_tally_precondition_clause(retrieve_responses, 0.2, 1);
+
+        let report = match clause_result {
+            Ok(TallyPreconditionClauseResult::MajorityOfValues {
+                values,
+                liars: _liars,
+                errors: _errors,
+            }) => {
+                // Perform aggregation on the values that made it to the output vector after applying the
+                // source scripts (aka _normalization scripts_ in the original whitepaper) and filtering out
+                // failures.
+                let mut context = ReportContext::from_stage(Stage::Aggregation);
+                context.deadline = deadline;
+
+                let report = witnet_rad::run_aggregation_with_context_report(
+                    values,
+                    &msg.aggregate,
+                    &mut context,
+                    aggregation_settings,
+                )?;
+                if msg.trace {
+                    trace.aggregation = Some(ScriptTrace::from_report(&report));
+                }
+
+                report
+            }
+            Ok(TallyPreconditionClauseResult::MajorityOfErrors { errors_mode }) => {
+                RadonReport::from_result(
+                    Ok(RadonTypes::RadonError(errors_mode)),
+                    &ReportContext::default(),
+                )
+            }
+            Err(e) => RadonReport::from_result(Err(e), &ReportContext::default()),
+        };
+
+        Ok(ResolveRAOutput {
+            report,
+            trace: if msg.trace { Some(trace) } else { None },
+        })
+    }
+}
+
+/// Raw response of a source, as it was fed into its script.
+fn raw_response(input: &RadonTypes) -> String {
+    match input {
+        RadonTypes::String(response) => response.value(),
+        RadonTypes::Bytes(random_bytes) => hex::encode(random_bytes.value()),
+        other => other.to_string(),
+    }
+}
@@ -29,11 +29,10 @@ use crate::actors::{
     inventory_manager::InventoryManager,
     messages::{
         AddBlocks, AddCandidates, AddConsolidatedPeer, AddPeers, AddSuperBlock, AddSuperBlockVote,
-        AddTransaction, CloseSession, Consolidate, EpochNotification, GetBlocksEpochRange,
-        GetHighestCheckpointBeacon, GetItem, GetSuperBlockVotes, PeerBeacon,
-        RemoveAddressesFromTried, RequestPeers, SendGetPeers, SendInventoryAnnouncement,
-        SendInventoryItem, SendInventoryRequest, SendLastBeacon, SendSuperBlockVote,
-        SessionUnitResult,
+        AddTransaction, CloseSession, Consolidate, EpochNotification, GetAvailableRange,
+        GetBlocksEpochRange, GetItem, GetSuperBlockVotes, PeerBeacon, RemoveAddressesFromTried,
+        RequestPeers, SendGetPeers, SendInventoryAnnouncement, SendInventoryItem,
+        SendInventoryRequest, SendLastBeacon, SendSuperBlockVote, SessionUnitResult,
     },
     peers_manager::PeersManager,
     sessions_manager::SessionsManager,
@@ -923,27 +922,38 @@ fn session_last_beacon_inbound(
     // TODO: LastBeacon on inbound peers?
     // Get ChainManager address from registry
     let chain_manager_addr = ChainManager::from_registry();
-    // Send GetHighestCheckpointBeacon message to ChainManager
+    // Send GetAvailableRange message to ChainManager
     chain_manager_addr
-        .send(GetHighestCheckpointBeacon)
+        .send(GetAvailableRange)
         .into_actor(session)
         .then(move |res, act, ctx| {
             match res {
-                Ok(Ok(chain_beacon)) => {
-                    match received_checkpoint.cmp(&chain_beacon.checkpoint) {
+                Ok(Ok(available_range)) => {
+                    match received_checkpoint.cmp(&available_range.last_block_epoch) {
                         Ordering::Greater => {
                             log::warn!(
                                 "Received a checkpoint beacon that is ahead of ours ({} > {})",
                                 received_checkpoint,
-                                chain_beacon.checkpoint
+                                available_range.last_block_epoch
                             );
                         }
                         Ordering::Equal => {
                             log::info!("Our chain is on par with our peer's",);
                         }
+                        Ordering::Less
+                            if received_checkpoint + 1 < available_range.first_block_epoch =>
+                        {
+                            // Announcing the blocks that are still stored would be useless, as
+                            // the peer cannot consolidate them without the pruned ones
+                            log::debug!(
+                                "Our peer needs blocks from epoch #{}, but the oldest stored block is from epoch #{}",
+                                received_checkpoint + 1,
+                                available_range.first_block_epoch
+                            );
+                        }
                         Ordering::Less => {
                             let init = received_checkpoint + 1;
-                            let range = init..=chain_beacon.checkpoint;
+                            let range = init..=available_range.last_block_epoch;
 
                             chain_manager_addr
                                 .send(GetBlocksEpochRange::new_with_const_limit(range))
@@ -982,7 +992,7 @@ fn session_last_beacon_inbound(
                     actix::fut::ok(())
                 }
                 _ => {
-                    log::warn!("Failed to get highest checkpoint beacon from ChainManager");
+                    log::warn!("Failed to get available range from ChainManager");
                     ctx.stop();
 
                     actix::fut::err(())
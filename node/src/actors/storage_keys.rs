@@ -1,17 +1,52 @@
+use witnet_data_structures::{chain::Hash, storage_layout::STORAGE_LAYOUT_VERSION};
+
 /// Database key that stores the master secret key
 pub const MASTER_KEY: &[u8] = b"master_key";
 
 /// Database key that stores the BN256 secret key
 pub const BN256_SECRET_KEY: &[u8] = b"bn256_secret_key";
 
-/// Function to create a chain state key for the storage
+/// Function to create a chain state key for the storage. The key includes the version of the
+/// layout of the persisted data structures, so a chain state is never read with a layout other
+/// than the one it was persisted with.
 #[inline]
 pub fn chain_state_key(magic: u16) -> String {
+    format!("chain-{}-v{}-key", magic, STORAGE_LAYOUT_VERSION)
+}
+
+/// Function to create the key of the chain state persisted with the layout version 0
+#[inline]
+pub fn legacy_chain_state_key(magic: u16) -> String {
     format!("chain-{}-key", magic)
 }
 
+/// Function to create the key of the epoch of the last block migrated to the current layout, while
+/// a storage migration is in progress
+#[inline]
+pub fn storage_migration_key(magic: u16) -> String {
+    format!("storage-migration-{}-key", magic)
+}
+
 /// Function to create a peers key for the storage
 #[inline]
 pub fn peers_key(magic: u16) -> String {
     format!("peers-{}-key", magic)
 }
+
+/// Function to create the key of the audit record of a data request for the storage
+#[inline]
+pub fn data_request_audit_key(dr_pointer: &Hash) -> String {
+    format!("DR-AUDIT-{}", dr_pointer)
+}
+
+/// Function to create the key of the report of a finished data request for the storage
+#[inline]
+pub fn data_request_report_key(dr_pointer: &Hash) -> String {
+    format!("DR-REPORT-{}", dr_pointer)
+}
+
+/// Function to create the key of the oldest blocks and superblocks that are stored in pruned mode
+#[inline]
+pub fn pruning_key(magic: u16) -> String {
+    format!("pruning-{}-key", magic)
+}
@@ -0,0 +1,136 @@
+//! # Storage migrations
+//!
+//! Migration of the data persisted by previous versions of the node to the current layout of the
+//! persisted data structures.
+use bincode::{deserialize, serialize};
+
+use witnet_data_structures::{
+    chain::{Block, ChainState, Epoch, Hash},
+    storage_layout::{with_legacy_layout, STORAGE_LAYOUT_VERSION},
+};
+use witnet_storage::storage::{Storage, WriteBatch};
+
+use crate::actors::storage_keys;
+
+/// Maximum number of blocks migrated in a single storage batch
+const BLOCKS_PER_BATCH: usize = 1000;
+
+/// Migrate the chain state and the blocks persisted with the layout version 0 to the current
+/// layout. Nothing is done if there is no chain state persisted with the layout version 0.
+///
+/// Blocks are migrated in batches, in order of epoch, and every batch persists how far the
+/// migration went, so an interrupted migration resumes where it stopped. The chain state is
+/// migrated last, in the same batch that deletes it from its legacy key, so it is only found under
+/// the current key once all the blocks have been migrated.
+pub fn migrate(backend: &mut dyn Storage, magic: u16) -> Result<(), failure::Error> {
+    let legacy_key = serialize(&storage_keys::legacy_chain_state_key(magic))?;
+    let chain_state: ChainState = match backend.get(&legacy_key)? {
+        Some(bytes) => with_legacy_layout(|| deserialize(&bytes))?,
+        None => return Ok(()),
+    };
+
+    let progress_key = serialize(&storage_keys::storage_migration_key(magic))?;
+    let first_epoch = match backend.get(&progress_key)? {
+        Some(bytes) => deserialize::<Epoch>(&bytes)?.saturating_add(1),
+        None => 0,
+    };
+    let blocks: Vec<(Epoch, Hash)> = chain_state
+        .block_chain
+        .range(first_epoch..)
+        .map(|(epoch, hash)| (*epoch, *hash))
+        .collect();
+    log::info!(
+        "Migrating the storage to the layout version {}: {} blocks",
+        STORAGE_LAYOUT_VERSION,
+        blocks.len()
+    );
+
+    let mut migrated = 0;
+    for chunk in blocks.chunks(BLOCKS_PER_BATCH) {
+        let mut batch = WriteBatch::new();
+        for (_epoch, hash) in chunk {
+            let key = match hash {
+                Hash::SHA256(h) => serialize(&h.to_vec())?,
+            };
+            // Blocks that were pruned are not stored
+            if let Some(bytes) = backend.get(&key)? {
+                let block: Block = with_legacy_layout(|| deserialize(&bytes))?;
+                batch.put(key, serialize(&block)?);
+            }
+        }
+        // Chunks are never empty
+        let (last_epoch, _) = chunk[chunk.len() - 1];
+        batch.put(progress_key.clone(), serialize(&last_epoch)?);
+        backend.write(batch)?;
+
+        migrated += chunk.len();
+        log::info!("Migrating blocks: {}/{}", migrated, blocks.len());
+    }
+
+    let mut batch = WriteBatch::new();
+    batch.put(
+        serialize(&storage_keys::chain_state_key(magic))?,
+        serialize(&chain_state)?,
+    );
+    batch.delete(legacy_key);
+    batch.delete(progress_key);
+    backend.write(batch)?;
+    log::info!(
+        "Migrated the storage to the layout version {}",
+        STORAGE_LAYOUT_VERSION
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use witnet_data_structures::chain::Hashable;
+    use witnet_storage::backends::hashmap;
+
+    use super::*;
+
+    fn block_key(block: &Block) -> Vec<u8> {
+        match block.hash() {
+            Hash::SHA256(h) => serialize(&h.to_vec()).unwrap(),
+        }
+    }
+
+    #[test]
+    fn migrate_legacy_chain_state() {
+        let magic = 1;
+        let block = Block::default();
+        let mut chain_state = ChainState::default();
+        chain_state.block_chain.insert(0, block.hash());
+        // The block of epoch 1 was never stored
+        chain_state.block_chain.insert(1, Hash::default());
+
+        let mut backend = hashmap::Backend::new();
+        let legacy_key = serialize(&storage_keys::legacy_chain_state_key(magic)).unwrap();
+        backend
+            .put(legacy_key.clone(), serialize(&chain_state).unwrap())
+            .unwrap();
+        backend
+            .put(block_key(&block), serialize(&block).unwrap())
+            .unwrap();
+
+        migrate(&mut backend, magic).unwrap();
+
+        let key = serialize(&storage_keys::chain_state_key(magic)).unwrap();
+        let migrated: ChainState = deserialize(&backend.get(&key).unwrap().unwrap()).unwrap();
+        assert_eq!(migrated, chain_state);
+        assert_eq!(backend.get(&legacy_key).unwrap(), None);
+        let progress_key = serialize(&storage_keys::storage_migration_key(magic)).unwrap();
+        assert_eq!(backend.get(&progress_key).unwrap(), None);
+        let stored_block: Block =
+            deserialize(&backend.get(&block_key(&block)).unwrap().unwrap()).unwrap();
+        assert_eq!(stored_block, block);
+
+        // Migrating again does nothing
+        migrate(&mut backend, magic).unwrap();
+        assert_eq!(
+            deserialize::<ChainState>(&backend.get(&key).unwrap().unwrap()).unwrap(),
+            chain_state
+        );
+    }
+}
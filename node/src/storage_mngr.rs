@@ -7,7 +7,7 @@ use actix::prelude::*;
 use bincode::{deserialize, serialize};
 use futures::future::{Either, Future};
 
-use crate::config_mngr;
+use crate::{config_mngr, storage_migrations};
 use witnet_config::config;
 use witnet_storage::{backends, storage};
 
@@ -77,6 +77,58 @@ where
     }
 }
 
+/// Set of typed writes to the storage that are applied atomically with `write`
+#[derive(Debug, Default)]
+pub struct WriteBatch(storage::WriteBatch);
+
+impl WriteBatch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a put of a value associated to the key to the batch
+    pub fn put<K, V>(&mut self, key: &K, value: &V) -> Result<(), failure::Error>
+    where
+        K: serde::Serialize,
+        V: serde::Serialize,
+    {
+        let key_bytes = serialize(key).map_err(|e| as_failure!(e))?;
+        let value_bytes = serialize(value).map_err(|e| as_failure!(e))?;
+        self.0.put(key_bytes, value_bytes);
+
+        Ok(())
+    }
+
+    /// Add a delete of the value associated to the key to the batch
+    pub fn delete<K>(&mut self, key: &K) -> Result<(), failure::Error>
+    where
+        K: serde::Serialize,
+    {
+        let key_bytes = serialize(key).map_err(|e| as_failure!(e))?;
+        self.0.delete(key_bytes);
+
+        Ok(())
+    }
+
+    /// Returns `true` if the batch has no writes
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Apply all the writes of a batch atomically: if the node crashes while writing them, either
+/// all of them or none of them will be found in the storage.
+pub fn write(batch: WriteBatch) -> impl Future<Item = (), Error = failure::Error> {
+    if batch.is_empty() {
+        Either::B(futures::future::finished(()))
+    } else {
+        let addr = StorageManagerAdapter::from_registry();
+
+        Either::A(addr.send(Write(batch.0)).flatten())
+    }
+}
+
 /// Delete value associated to key
 pub fn delete<K>(key: &K) -> impl Future<Item = (), Error = failure::Error>
 where
@@ -89,6 +141,73 @@ where
         .and_then(move |key_bytes| addr.send(Delete(key_bytes)).flatten())
 }
 
+/// Get the first `limit` key/value pairs whose serialized key starts with the serialized `prefix`,
+/// in ascending order of serialized keys, from a consistent snapshot of the storage. All the pairs
+/// are kept in memory, so `limit` should be low enough for them to fit.
+///
+/// Keys are serialized with bincode, which simply concatenates the fields of tuples and structs,
+/// so the leading fields of a key are a prefix of it: `(tag, pkh)` is a prefix of
+/// `(tag, pkh, epoch)`. However, the serialization of strings and vectors starts with their
+/// length, so a string is never a prefix of a longer string.
+pub fn get_prefix<P, K, V>(
+    prefix: &P,
+    limit: usize,
+) -> impl Future<Item = Vec<(K, V)>, Error = failure::Error>
+where
+    P: serde::Serialize,
+    K: serde::de::DeserializeOwned,
+    V: serde::de::DeserializeOwned,
+{
+    let addr = StorageManagerAdapter::from_registry();
+
+    futures::future::result(serialize(prefix))
+        .map_err(|e| as_failure!(e))
+        .and_then(move |prefix_bytes| addr.send(GetPrefix(prefix_bytes, limit)).flatten())
+        .and_then(deserialize_pairs)
+}
+
+/// Get the first `limit` key/value pairs whose serialized key is in the range `start..end`, in
+/// ascending order of serialized keys, from a consistent snapshot of the storage. All the pairs
+/// are kept in memory, so `limit` should be low enough for them to fit.
+///
+/// Integers are serialized in little endian, so the order of the serialized keys only matches the
+/// order of the keys when the fields that vary are not integers.
+pub fn get_range<K, V>(
+    start: &K,
+    end: &K,
+    limit: usize,
+) -> impl Future<Item = Vec<(K, V)>, Error = failure::Error>
+where
+    K: serde::Serialize + serde::de::DeserializeOwned,
+    V: serde::de::DeserializeOwned,
+{
+    let addr = StorageManagerAdapter::from_registry();
+
+    futures::future::result(serialize(start))
+        .join(futures::future::result(serialize(end)))
+        .map_err(|e| as_failure!(e))
+        .and_then(move |(start_bytes, end_bytes)| {
+            addr.send(GetRange(start_bytes, end_bytes, limit)).flatten()
+        })
+        .and_then(deserialize_pairs)
+}
+
+fn deserialize_pairs<K, V>(pairs: Vec<(Vec<u8>, Vec<u8>)>) -> Result<Vec<(K, V)>, failure::Error>
+where
+    K: serde::de::DeserializeOwned,
+    V: serde::de::DeserializeOwned,
+{
+    pairs
+        .into_iter()
+        .map(|(key, value)| {
+            Ok((
+                deserialize(key.as_slice()).map_err(|e| as_failure!(e))?,
+                deserialize(value.as_slice()).map_err(|e| as_failure!(e))?,
+            ))
+        })
+        .collect()
+}
+
 struct StorageManager {
     backend: Box<dyn storage::Storage>,
 }
@@ -116,7 +235,9 @@ impl Handler<Configure> for StorageManager {
 
     fn handle(&mut self, Configure(conf): Configure, _ctx: &mut Self::Context) -> Self::Result {
         let storage_conf = &conf.storage;
-        let backend = create_appropriate_backend(storage_conf)?;
+        let magic = conf.consensus_constants.get_magic();
+        let mut backend = create_appropriate_backend(storage_conf)?;
+        storage_migrations::migrate(&mut *backend, magic)?;
 
         self.backend = backend;
         log::info!(
@@ -155,11 +276,26 @@ impl Handler<PutBatch> for StorageManager {
     type Result = <PutBatch as Message>::Result;
 
     fn handle(&mut self, PutBatch(kvs): PutBatch, _ctx: &mut Self::Context) -> Self::Result {
+        let mut batch = storage::WriteBatch::new();
         for (key, value) in kvs {
-            self.backend.put(key, value)?;
+            batch.put(key, value);
         }
 
-        Ok(())
+        self.backend.write(batch)
+    }
+}
+
+struct Write(storage::WriteBatch);
+
+impl Message for Write {
+    type Result = Result<(), failure::Error>;
+}
+
+impl Handler<Write> for StorageManager {
+    type Result = <Write as Message>::Result;
+
+    fn handle(&mut self, Write(batch): Write, _ctx: &mut Self::Context) -> Self::Result {
+        self.backend.write(batch)
     }
 }
 
@@ -191,10 +327,54 @@ impl Handler<Delete> for StorageManager {
     }
 }
 
+struct GetPrefix(Vec<u8>, usize);
+
+impl Message for GetPrefix {
+    type Result = Result<Vec<(Vec<u8>, Vec<u8>)>, failure::Error>;
+}
+
+impl Handler<GetPrefix> for StorageManager {
+    type Result = <GetPrefix as Message>::Result;
+
+    fn handle(
+        &mut self,
+        GetPrefix(prefix, limit): GetPrefix,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        Ok(self
+            .backend
+            .prefix_iterator(prefix.as_ref())?
+            .take(limit)
+            .collect())
+    }
+}
+
+struct GetRange(Vec<u8>, Vec<u8>, usize);
+
+impl Message for GetRange {
+    type Result = Result<Vec<(Vec<u8>, Vec<u8>)>, failure::Error>;
+}
+
+impl Handler<GetRange> for StorageManager {
+    type Result = <GetRange as Message>::Result;
+
+    fn handle(
+        &mut self,
+        GetRange(start, end, limit): GetRange,
+        _ctx: &mut Self::Context,
+    ) -> Self::Result {
+        Ok(self
+            .backend
+            .range_iterator(start.as_ref(), end.as_ref())?
+            .take(limit)
+            .collect())
+    }
+}
+
 macro_rules! encrypted_backend {
     ($backend:expr, $password_opt:expr) => {
         if let Some(password) = $password_opt {
-            Box::new(backends::crypto::Backend::new(password, $backend))
+            Box::new(backends::crypto::Backend::new(password, $backend)?)
                 as Box<dyn storage::Storage>
         } else {
             Box::new($backend) as Box<dyn storage::Storage>
@@ -214,10 +394,10 @@ fn create_appropriate_backend(
         )),
         config::StorageBackend::RocksDB => {
             let path = conf.db_path.as_path();
+            let backend =
+                backends::rocksdb::Backend::open_default(path).map_err(|e| as_failure!(e))?;
 
-            backends::rocksdb::Backend::open_default(path)
-                .map(|backend| encrypted_backend!(backend, passwd))
-                .map_err(|e| as_failure!(e))
+            Ok(encrypted_backend!(backend, passwd))
         }
     }
 }
@@ -279,6 +459,14 @@ impl Handler<PutBatch> for StorageManagerAdapter {
     }
 }
 
+impl Handler<Write> for StorageManagerAdapter {
+    type Result = ResponseFuture<(), failure::Error>;
+
+    fn handle(&mut self, msg: Write, _ctx: &mut Self::Context) -> Self::Result {
+        Box::new(self.storage.send(msg).flatten())
+    }
+}
+
 impl Handler<Delete> for StorageManagerAdapter {
     type Result = ResponseFuture<(), failure::Error>;
 
@@ -286,3 +474,19 @@ impl Handler<Delete> for StorageManagerAdapter {
         Box::new(self.storage.send(msg).flatten())
     }
 }
+
+impl Handler<GetPrefix> for StorageManagerAdapter {
+    type Result = ResponseFuture<Vec<(Vec<u8>, Vec<u8>)>, failure::Error>;
+
+    fn handle(&mut self, msg: GetPrefix, _ctx: &mut Self::Context) -> Self::Result {
+        Box::new(self.storage.send(msg).flatten())
+    }
+}
+
+impl Handler<GetRange> for StorageManagerAdapter {
+    type Result = ResponseFuture<Vec<(Vec<u8>, Vec<u8>)>, failure::Error>;
+
+    fn handle(&mut self, msg: GetRange, _ctx: &mut Self::Context) -> Self::Result {
+        Box::new(self.storage.send(msg).flatten())
+    }
+}
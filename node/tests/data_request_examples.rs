@@ -9,8 +9,12 @@ use serde::{Deserialize, Serialize};
 use witnet_data_structures::chain::DataRequestOutput;
 use witnet_node::actors::messages::BuildDrt;
 use witnet_rad::{
+    analysis::analyze_request,
     script::RadonScriptExecutionSettings,
-    types::{float::RadonFloat, integer::RadonInteger, string::RadonString, RadonTypes},
+    types::{
+        bytes::RadonBytes, float::RadonFloat, integer::RadonInteger, string::RadonString,
+        RadonTypes,
+    },
 };
 
 /// Id. Can be null, a number, or a string
@@ -103,6 +107,15 @@ fn parse_examples() {
                     }
                 };
 
+                // The scripts of all the examples must pass the static analysis
+                let analysis = analyze_request(&file_value.params.dro.data_request);
+                assert_eq!(
+                    analysis.issues,
+                    vec![],
+                    "Issues found in the scripts of data request example {}",
+                    path.display()
+                );
+
                 // Run data request locally
                 let local_result = run_dr_locally_with_data(&file_value.params.dro, example_data);
                 assert_eq!(
@@ -147,6 +160,16 @@ fn existing_examples() -> HashMap<&'static str, (BuildDrt, &'static [&'static st
             &[r#"{"results":[{"dob":{"age":45}}]}"#],
             RadonTypes::Integer(RadonInteger::from(45)),
         ),
+        (
+            "rng_source.json",
+            examples::rng_source(),
+            // The injected data is used as the local entropy of every witness
+            &["Hello, World!"],
+            RadonTypes::Bytes(RadonBytes::from(vec![
+                77, 174, 237, 238, 105, 161, 222, 194, 109, 76, 91, 206, 13, 153, 229, 92, 238, 76,
+                251, 177, 157, 141, 132, 164, 47, 204, 172, 226, 30, 40, 196, 49,
+            ])),
+        ),
         (
             "bitcoin_last_hash.json",
             examples::bitcoin_last_hash(),
@@ -212,11 +235,15 @@ mod examples {
                             kind: RADType::HttpGet,
                             url: url_0.to_string(),
                             script: r0_script,
+                            body: vec![],
+                            headers: vec![],
                         },
                         RADRetrieve {
                             kind: RADType::HttpGet,
                             url: url_1.to_string(),
                             script: r1_script,
+                            body: vec![],
+                            headers: vec![],
                         },
                     ],
                     aggregate: RADAggregate {
@@ -270,6 +297,8 @@ mod examples {
                         kind: RADType::HttpGet,
                         url: url_0.to_string(),
                         script: r0_script,
+                        body: vec![],
+                        headers: vec![],
                     }],
                     aggregate: RADAggregate {
                         filters: vec![],
@@ -293,6 +322,39 @@ mod examples {
         }
     }
 
+    pub fn rng_source() -> BuildDrt {
+        let r0_script = cbor_to_vec(&Value::Array(vec![])).unwrap();
+
+        BuildDrt {
+            dro: DataRequestOutput {
+                data_request: RADRequest {
+                    time_lock: 0,
+                    retrieve: vec![RADRetrieve {
+                        kind: RADType::Rng,
+                        url: String::new(),
+                        script: r0_script,
+                        body: vec![],
+                        headers: vec![],
+                    }],
+                    aggregate: RADAggregate {
+                        filters: vec![],
+                        reducer: RadonReducers::Mode as u32,
+                    },
+                    tally: RADTally {
+                        filters: vec![],
+                        reducer: RadonReducers::HashConcatenate as u32,
+                    },
+                },
+                witness_reward: 1000,
+                witnesses: 4,
+                commit_and_reveal_fee: 10,
+                min_consensus_percentage: 51,
+                collateral: 1_000_000_000,
+            },
+            fee: 0,
+        }
+    }
+
     pub fn error_301_source() -> BuildDrt {
         let url_0 =
             "http://www.skyverge.com/woocommerce–rest–api-docs.html#authentication/over-https";
@@ -317,6 +379,8 @@ mod examples {
                         kind: RADType::HttpGet,
                         url: url_0.to_string(),
                         script: r0_script,
+                        body: vec![],
+                        headers: vec![],
                     }],
                     aggregate: RADAggregate {
                         filters: vec![],
@@ -374,16 +438,22 @@ mod examples {
                             kind: RADType::HttpGet,
                             url: url_0.to_string(),
                             script: r0_script,
+                            body: vec![],
+                            headers: vec![],
                         },
                         RADRetrieve {
                             kind: RADType::HttpGet,
                             url: url_1.to_string(),
                             script: r1_script,
+                            body: vec![],
+                            headers: vec![],
                         },
                         RADRetrieve {
                             kind: RADType::HttpGet,
                             url: url_2.to_string(),
                             script: r2_script,
+                            body: vec![],
+                            headers: vec![],
                         },
                     ],
                     aggregate: RADAggregate {
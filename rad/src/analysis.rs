@@ -0,0 +1,1098 @@
+//! Static analysis of RADON scripts.
+//!
+//! The analyzer walks the scripts of a `RADRequest` without executing them, inferring the type
+//! of the value that flows through every call. This allows detecting type mismatches, unknown
+//! operators and wrong numbers of arguments when a data request is being authored, instead of
+//! when it is resolved by the witnesses.
+//!
+//! Some types cannot be inferred statically, e.g. the items of an array parsed from JSON. Those
+//! are represented as `StaticType::Any`, which is accepted by any operator, so that type
+//! mismatches are only reported when they are certain to happen at runtime.
+
+use std::{convert::TryFrom, fmt};
+
+use failure::Fail;
+use serde::Serialize;
+use serde_cbor::{self as cbor, value::Value};
+
+use witnet_data_structures::chain::{RADFilter, RADRequest, RADType};
+
+use crate::{
+    filters::RadonFilters,
+    hash_functions::RadonHashFunctions,
+    operators::RadonOpCodes,
+    reducers::RadonReducers,
+    script::{create_radon_script_from_filters_and_reducer, unpack_radon_call, RadonCall},
+    types::{
+        array::RadonArray, boolean::RadonBoolean, bytes::RadonBytes, float::RadonFloat,
+        integer::RadonInteger, map::RadonMap, string::RadonString, RadonType,
+    },
+};
+
+/// The type of a value, as inferred without executing the script.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum StaticType {
+    /// The type cannot be inferred statically.
+    Any,
+    /// An array, along with the type of its items.
+    Array(Box<StaticType>),
+    Boolean,
+    Bytes,
+    Float,
+    Integer,
+    Map,
+    String,
+}
+
+impl StaticType {
+    /// An array whose items are of unknown type.
+    pub fn any_array() -> Self {
+        StaticType::Array(Box::new(StaticType::Any))
+    }
+
+    /// The type of the items of an array, or `Any` if this is not an array.
+    pub fn items(&self) -> StaticType {
+        match self {
+            StaticType::Array(items) => (**items).clone(),
+            _ => StaticType::Any,
+        }
+    }
+
+    /// Tells whether a value of type `found` can be used where a value of this type is expected.
+    /// Arrays are compatible with each other regardless of the type of their items.
+    pub fn accepts(&self, found: &StaticType) -> bool {
+        match (self, found) {
+            (StaticType::Any, _) | (_, StaticType::Any) => true,
+            (StaticType::Array(_), StaticType::Array(_)) => true,
+            (expected, found) => expected == found,
+        }
+    }
+
+    /// The type of the input of the script of a retrieval source of the given kind.
+    pub fn retrieval_input(kind: &RADType) -> Self {
+        match kind {
+            RADType::HttpGet | RADType::HttpPost => StaticType::String,
+            RADType::Rng => StaticType::Bytes,
+        }
+    }
+
+    /// The type of a CBOR value given as argument to an operator.
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Array(_) => StaticType::any_array(),
+            Value::Bool(_) => StaticType::Boolean,
+            Value::Bytes(_) => StaticType::Bytes,
+            Value::Float(_) => StaticType::Float,
+            Value::Integer(_) => StaticType::Integer,
+            Value::Map(_) => StaticType::Map,
+            Value::Text(_) => StaticType::String,
+            _ => StaticType::Any,
+        }
+    }
+
+    /// Whether the type is one of the given ones. `Any` is considered to be any of them.
+    fn is_one_of(&self, types: &[StaticType]) -> bool {
+        types.iter().any(|expected| expected.accepts(self))
+    }
+}
+
+impl fmt::Display for StaticType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaticType::Any => write!(f, "any type"),
+            StaticType::Array(items) if **items == StaticType::Any => {
+                write!(f, "{}", RadonArray::radon_type_name())
+            }
+            StaticType::Array(items) => write!(f, "{}<{}>", RadonArray::radon_type_name(), items),
+            StaticType::Boolean => write!(f, "{}", RadonBoolean::radon_type_name()),
+            StaticType::Bytes => write!(f, "{}", RadonBytes::radon_type_name()),
+            StaticType::Float => write!(f, "{}", RadonFloat::radon_type_name()),
+            StaticType::Integer => write!(f, "{}", RadonInteger::radon_type_name()),
+            StaticType::Map => write!(f, "{}", RadonMap::radon_type_name()),
+            StaticType::String => write!(f, "{}", RadonString::radon_type_name()),
+        }
+    }
+}
+
+/// The script of a data request in which an issue was found.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum ScriptStage {
+    /// The script of the retrieval source with the given index.
+    Retrieval(usize),
+    Aggregation,
+    Tally,
+}
+
+impl fmt::Display for ScriptStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptStage::Retrieval(index) => write!(f, "retrieval #{}", index),
+            ScriptStage::Aggregation => write!(f, "aggregation"),
+            ScriptStage::Tally => write!(f, "tally"),
+        }
+    }
+}
+
+/// The kinds of issue that the static analysis can find in a script.
+#[derive(Clone, Debug, Fail, PartialEq, Serialize)]
+pub enum IssueKind {
+    /// The script could not be decoded as an array of calls.
+    #[fail(display = "Malformed script: {}", description)]
+    MalformedScript { description: String },
+    /// A call could not be decoded, e.g. because of an unknown operator code.
+    #[fail(display = "Invalid call: {}", description)]
+    InvalidCall { description: String },
+    /// An operator was applied to a value of a type it does not support.
+    #[fail(
+        display = "Operator `{}` cannot be applied to `{}`, it expects `{}`",
+        operator, found, expected
+    )]
+    TypeMismatch {
+        operator: String,
+        expected: String,
+        found: String,
+    },
+    /// An operator was called with a wrong number of arguments.
+    #[fail(
+        display = "Operator `{}` expects {}, but {} were given",
+        operator, expected, found
+    )]
+    WrongArity {
+        operator: String,
+        expected: String,
+        found: usize,
+    },
+    /// One of the arguments of an operator is not valid.
+    #[fail(
+        display = "Wrong argument for operator `{}`: {}",
+        operator, description
+    )]
+    WrongArgument {
+        operator: String,
+        description: String,
+    },
+    /// The retrieval scripts return values of different types, so they cannot be aggregated.
+    #[fail(display = "Retrieval scripts return mismatching types: {}", types)]
+    MismatchingRetrievals { types: String },
+    /// The filters or the reducer of the aggregation or tally stage are not valid.
+    #[fail(display = "Invalid filters or reducer: {}", description)]
+    InvalidConsensus { description: String },
+}
+
+/// An issue found by the static analysis.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Issue {
+    /// The script in which the issue was found.
+    pub stage: ScriptStage,
+    /// The position of the offending call in the script. Calls inside subscripts are located by
+    /// the position of the call that contains the subscript, followed by their position inside
+    /// the subscript. Empty if the issue is not related to a specific call.
+    pub call: Vec<usize>,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.call.is_empty() {
+            write!(f, "{}: {}", self.stage, self.kind)
+        } else {
+            let call = self
+                .call
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(".");
+            write!(f, "{}, call {}: {}", self.stage, call, self.kind)
+        }
+    }
+}
+
+/// The result of the static analysis of a `RADRequest`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RequestAnalysis {
+    /// The type returned by each of the retrieval scripts.
+    pub retrieve: Vec<StaticType>,
+    /// The type returned by the aggregation stage.
+    pub aggregate: StaticType,
+    /// The type returned by the tally stage.
+    pub tally: StaticType,
+    /// All the issues found, in the order in which the scripts are executed.
+    pub issues: Vec<Issue>,
+}
+
+impl RequestAnalysis {
+    /// Whether the analysis found no issues at all.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Statically analyze all the scripts of a data request.
+pub fn analyze_request(request: &RADRequest) -> RequestAnalysis {
+    let mut issues = vec![];
+
+    let retrieve: Vec<StaticType> = request
+        .retrieve
+        .iter()
+        .enumerate()
+        .map(|(index, retrieve)| {
+            let input = StaticType::retrieval_input(&retrieve.kind);
+            let mut analyzer = Analyzer::new(ScriptStage::Retrieval(index));
+            let output = analyzer.packed_script(&retrieve.script, input);
+            issues.extend(analyzer.issues);
+
+            output
+        })
+        .collect();
+
+    let mut analyzer = Analyzer::new(ScriptStage::Aggregation);
+    let items = analyzer.unify(&retrieve);
+    let aggregate = analyzer.consensus(
+        &request.aggregate.filters,
+        request.aggregate.reducer,
+        StaticType::Array(Box::new(items)),
+    );
+    issues.extend(analyzer.issues);
+
+    let mut analyzer = Analyzer::new(ScriptStage::Tally);
+    let tally = analyzer.consensus(
+        &request.tally.filters,
+        request.tally.reducer,
+        StaticType::Array(Box::new(aggregate.clone())),
+    );
+    issues.extend(analyzer.issues);
+
+    RequestAnalysis {
+        retrieve,
+        aggregate,
+        tally,
+        issues,
+    }
+}
+
+/// Statically analyze a serialized RADON script, given the type of its input. Returns the type of
+/// the output of the script, and all the issues found.
+pub fn analyze_script(
+    script: &[u8],
+    input: StaticType,
+    stage: ScriptStage,
+) -> (StaticType, Vec<Issue>) {
+    let mut analyzer = Analyzer::new(stage);
+    let output = analyzer.packed_script(script, input);
+
+    (output, analyzer.issues)
+}
+
+/// Infer the type returned by a single call, disregarding any issues found in it.
+pub(crate) fn call_output(call: &RadonCall, input: StaticType) -> StaticType {
+    Analyzer::new(ScriptStage::Aggregation).call(call, input, &[])
+}
+
+/// Number of arguments accepted by an operator.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Arity {
+    /// The call must not have an arguments array at all.
+    NoArgs,
+    /// The call must have an arguments array with a length in the given range.
+    Args(usize, usize),
+    /// The call may or may not have an arguments array, with at most the given length.
+    OptionalArgs(usize),
+}
+
+impl Arity {
+    fn allows(self, args: &Option<Vec<Value>>) -> bool {
+        match (self, args) {
+            (Arity::NoArgs, None) | (Arity::OptionalArgs(_), None) => true,
+            (Arity::Args(min, max), Some(args)) => args.len() >= min && args.len() <= max,
+            (Arity::OptionalArgs(max), Some(args)) => args.len() <= max,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "argument" } else { "arguments" };
+        match *self {
+            Arity::NoArgs => write!(f, "no arguments"),
+            Arity::Args(min, max) if min == max => write!(f, "exactly {} {}", min, plural(min)),
+            Arity::Args(min, usize::MAX) => write!(f, "at least {} {}", min, plural(min)),
+            Arity::Args(min, max) => write!(f, "between {} and {} arguments", min, max),
+            Arity::OptionalArgs(max) => write!(f, "at most {} {}", max, plural(max)),
+        }
+    }
+}
+
+pub(crate) fn arity(op_code: RadonOpCodes) -> Arity {
+    match op_code {
+        RadonOpCodes::ArrayFilter | RadonOpCodes::Custom => Arity::Args(1, usize::MAX),
+        RadonOpCodes::ArrayFlatten => Arity::OptionalArgs(1),
+        RadonOpCodes::ArraySort => Arity::Args(0, 1),
+        RadonOpCodes::ArrayTake => Arity::Args(1, 2),
+        RadonOpCodes::BooleanMatch | RadonOpCodes::IntegerMatch | RadonOpCodes::StringMatch => {
+            Arity::Args(2, 2)
+        }
+        RadonOpCodes::ArrayGetArray
+        | RadonOpCodes::ArrayGetBoolean
+        | RadonOpCodes::ArrayGetBytes
+        | RadonOpCodes::ArrayGetFloat
+        | RadonOpCodes::ArrayGetInteger
+        | RadonOpCodes::ArrayGetMap
+        | RadonOpCodes::ArrayGetString
+        | RadonOpCodes::ArrayMap
+        | RadonOpCodes::ArrayReduce
+        | RadonOpCodes::ArraySome
+        | RadonOpCodes::BytesHash
+        | RadonOpCodes::IntegerGreaterThan
+        | RadonOpCodes::IntegerLessThan
+        | RadonOpCodes::IntegerModulo
+        | RadonOpCodes::IntegerMultiply
+        | RadonOpCodes::IntegerPower
+        | RadonOpCodes::IntegerSum
+        | RadonOpCodes::FloatGreaterThan
+        | RadonOpCodes::FloatLessThan
+        | RadonOpCodes::FloatModulo
+        | RadonOpCodes::FloatMultiply
+        | RadonOpCodes::FloatPower
+        | RadonOpCodes::FloatSum
+        | RadonOpCodes::MapGetArray
+        | RadonOpCodes::MapGetBoolean
+        | RadonOpCodes::MapGetBytes
+        | RadonOpCodes::MapGetFloat
+        | RadonOpCodes::MapGetInteger
+        | RadonOpCodes::MapGetMap
+        | RadonOpCodes::MapGetString
+        | RadonOpCodes::MapGetPath => Arity::Args(1, 1),
+        _ => Arity::NoArgs,
+    }
+}
+
+/// The type of the values an operator can be applied to, as given by the range of its code.
+/// `None` means that the operator can be applied to values of any type.
+pub(crate) fn operand_type(op_code: RadonOpCodes) -> Option<StaticType> {
+    match op_code as u8 >> 4 {
+        0x1 => Some(StaticType::any_array()),
+        0x2 => Some(StaticType::Boolean),
+        0x3 => Some(StaticType::Bytes),
+        0x4 => Some(StaticType::Integer),
+        0x5 => Some(StaticType::Float),
+        0x6 => Some(StaticType::Map),
+        0x7 => Some(StaticType::String),
+        _ => None,
+    }
+}
+
+/// The types of the items that a filter can be applied to. `None` means the filter is not
+/// implemented.
+fn filter_operand_types(filter: RadonFilters) -> Option<Vec<StaticType>> {
+    match filter {
+        RadonFilters::GreaterThan | RadonFilters::LessThan | RadonFilters::Equals => Some(vec![
+            StaticType::Integer,
+            StaticType::Float,
+            StaticType::String,
+        ]),
+        RadonFilters::DeviationAbsolute
+        | RadonFilters::DeviationRelative
+        | RadonFilters::DeviationStandard => Some(vec![
+            StaticType::Integer,
+            StaticType::Float,
+            StaticType::any_array(),
+        ]),
+        RadonFilters::Mode => Some(vec![StaticType::Any]),
+        _ => None,
+    }
+}
+
+/// The types of the items that a reducer can be applied to.
+fn reducer_operand_types(reducer: RadonReducers) -> Vec<StaticType> {
+    match reducer {
+        RadonReducers::Min | RadonReducers::Max => vec![
+            StaticType::Integer,
+            StaticType::Float,
+            StaticType::String,
+            StaticType::any_array(),
+        ],
+        RadonReducers::Mode => vec![StaticType::Any],
+        RadonReducers::AverageMeanWeighted | RadonReducers::AverageMedianWeighted => {
+            vec![StaticType::any_array()]
+        }
+        RadonReducers::HashConcatenate => vec![StaticType::Bytes],
+        _ => vec![
+            StaticType::Integer,
+            StaticType::Float,
+            StaticType::any_array(),
+        ],
+    }
+}
+
+/// The type returned by a reducer when applied to items of the given type.
+fn reducer_output(reducer: RadonReducers, items: StaticType) -> StaticType {
+    match reducer {
+        RadonReducers::Min
+        | RadonReducers::Max
+        | RadonReducers::Mode
+        | RadonReducers::AverageMean
+        | RadonReducers::AverageMedian => match items {
+            StaticType::Array(_) => StaticType::any_array(),
+            items => items,
+        },
+        RadonReducers::HashConcatenate => StaticType::Bytes,
+        _ => StaticType::Any,
+    }
+}
+
+struct Analyzer {
+    stage: ScriptStage,
+    issues: Vec<Issue>,
+}
+
+impl Analyzer {
+    fn new(stage: ScriptStage) -> Self {
+        Self {
+            stage,
+            issues: vec![],
+        }
+    }
+
+    fn report(&mut self, call: &[usize], kind: IssueKind) {
+        self.issues.push(Issue {
+            stage: self.stage.clone(),
+            call: call.to_vec(),
+            kind,
+        });
+    }
+
+    /// Analyze a script that is still serialized as CBOR.
+    fn packed_script(&mut self, script: &[u8], input: StaticType) -> StaticType {
+        match cbor::from_slice(script) {
+            Ok(Value::Array(calls)) => self.unpacked_calls(&calls, input, &[]),
+            Ok(_) => {
+                self.report(
+                    &[],
+                    IssueKind::MalformedScript {
+                        description: "the script is not an array of calls".to_string(),
+                    },
+                );
+
+                StaticType::Any
+            }
+            Err(error) => {
+                self.report(
+                    &[],
+                    IssueKind::MalformedScript {
+                        description: error.to_string(),
+                    },
+                );
+
+                StaticType::Any
+            }
+        }
+    }
+
+    /// Analyze a sequence of calls that are still CBOR values, so that an invalid call does not
+    /// prevent the rest of them from being analyzed.
+    fn unpacked_calls(&mut self, calls: &[Value], input: StaticType, path: &[usize]) -> StaticType {
+        calls
+            .iter()
+            .enumerate()
+            .fold(input, |current, (index, packed_call)| {
+                let call_path = [path, &[index]].concat();
+                match unpack_radon_call(packed_call) {
+                    Ok(call) => self.call(&call, current, &call_path),
+                    Err(error) => {
+                        self.report(
+                            &call_path,
+                            IssueKind::InvalidCall {
+                                description: error.to_string(),
+                            },
+                        );
+
+                        StaticType::Any
+                    }
+                }
+            })
+    }
+
+    /// Analyze the filters and reducer of the aggregation or tally stages.
+    fn consensus(&mut self, filters: &[RADFilter], reducer: u32, input: StaticType) -> StaticType {
+        match create_radon_script_from_filters_and_reducer(filters, reducer) {
+            Ok(calls) => calls
+                .iter()
+                .enumerate()
+                .fold(input, |current, (index, call)| {
+                    self.call(call, current, &[index])
+                }),
+            Err(error) => {
+                self.report(
+                    &[],
+                    IssueKind::InvalidConsensus {
+                        description: error.to_string(),
+                    },
+                );
+
+                StaticType::Any
+            }
+        }
+    }
+
+    /// Find the type of the items of the array that is fed into the aggregation stage.
+    fn unify(&mut self, types: &[StaticType]) -> StaticType {
+        let known: Vec<&StaticType> = types.iter().filter(|t| **t != StaticType::Any).collect();
+
+        match known.split_first() {
+            None => StaticType::Any,
+            Some((first, rest)) if rest.iter().all(|t| first.accepts(t)) => (*first).clone(),
+            Some(_) => {
+                let types = types
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.report(&[], IssueKind::MismatchingRetrievals { types });
+
+                StaticType::Any
+            }
+        }
+    }
+
+    /// Analyze a single call, returning the type of its output.
+    fn call(&mut self, call: &RadonCall, input: StaticType, path: &[usize]) -> StaticType {
+        let (op_code, args) = call;
+        let operator = op_code.to_string();
+
+        if *op_code == RadonOpCodes::Fail {
+            self.report(
+                path,
+                IssueKind::InvalidCall {
+                    description: format!("operator `{}` always fails", operator),
+                },
+            );
+
+            return StaticType::Any;
+        }
+
+        // In case of a type mismatch, keep on analyzing as if the input had the expected type, so
+        // that a single mistake does not result in a cascade of issues
+        let input = match operand_type(*op_code) {
+            Some(expected) if !expected.accepts(&input) => {
+                self.report(
+                    path,
+                    IssueKind::TypeMismatch {
+                        operator: operator.clone(),
+                        expected: expected.to_string(),
+                        found: input.to_string(),
+                    },
+                );
+
+                expected
+            }
+            _ => input,
+        };
+
+        let arity = arity(*op_code);
+        if !arity.allows(args) {
+            self.report(
+                path,
+                IssueKind::WrongArity {
+                    operator,
+                    expected: arity.to_string(),
+                    found: args.as_ref().map(Vec::len).unwrap_or_default(),
+                },
+            );
+
+            return StaticType::Any;
+        }
+
+        let args = args.as_deref().unwrap_or_default();
+        self.output(*op_code, input, args, path)
+    }
+
+    /// Infer the type returned by an operator, and check its arguments where possible.
+    fn output(
+        &mut self,
+        op_code: RadonOpCodes,
+        input: StaticType,
+        args: &[Value],
+        path: &[usize],
+    ) -> StaticType {
+        match op_code {
+            // The types accepted and returned by custom operators are unknown
+            RadonOpCodes::Fail | RadonOpCodes::Custom => StaticType::Any,
+            RadonOpCodes::Identity => input,
+            RadonOpCodes::ArrayCount => StaticType::Integer,
+            RadonOpCodes::ArrayFilter => {
+                self.filter(op_code, &input, args, path);
+
+                input
+            }
+            RadonOpCodes::ArrayFlatten => match (input.items(), args.is_empty()) {
+                (StaticType::Array(inner), true) => StaticType::Array(inner),
+                _ => StaticType::any_array(),
+            },
+            RadonOpCodes::ArrayGetArray | RadonOpCodes::MapGetArray => StaticType::any_array(),
+            RadonOpCodes::ArrayGetBoolean | RadonOpCodes::MapGetBoolean => StaticType::Boolean,
+            RadonOpCodes::ArrayGetBytes | RadonOpCodes::MapGetBytes => StaticType::Bytes,
+            RadonOpCodes::ArrayGetFloat | RadonOpCodes::MapGetFloat => StaticType::Float,
+            RadonOpCodes::ArrayGetInteger | RadonOpCodes::MapGetInteger => StaticType::Integer,
+            RadonOpCodes::ArrayGetMap | RadonOpCodes::MapGetMap => StaticType::Map,
+            RadonOpCodes::ArrayGetString | RadonOpCodes::MapGetString => StaticType::String,
+            RadonOpCodes::ArrayMap => {
+                let items = self.subscript(op_code, &args[0], input.items(), path);
+
+                StaticType::Array(Box::new(items))
+            }
+            RadonOpCodes::ArrayReduce => self.reduce(op_code, &input, &args[0], path),
+            RadonOpCodes::ArraySome => {
+                let output = self.subscript(op_code, &args[0], input.items(), path);
+                self.check_subscript_output(op_code, &[StaticType::Boolean], &output, path);
+
+                StaticType::Boolean
+            }
+            RadonOpCodes::ArraySort => {
+                if let Some(arg) = args.first() {
+                    let output = self.subscript(op_code, arg, input.items(), path);
+                    self.check_subscript_output(
+                        op_code,
+                        &[StaticType::Integer, StaticType::String],
+                        &output,
+                        path,
+                    );
+                } else {
+                    self.check_subscript_output(
+                        op_code,
+                        &[StaticType::Integer, StaticType::String],
+                        &input.items(),
+                        path,
+                    );
+                }
+
+                input
+            }
+            RadonOpCodes::ArrayTake => input,
+            RadonOpCodes::BooleanAsString
+            | RadonOpCodes::BytesAsString
+            | RadonOpCodes::FloatAsString
+            | RadonOpCodes::IntegerAsString
+            | RadonOpCodes::StringToLowerCase
+            | RadonOpCodes::StringToUpperCase => StaticType::String,
+            RadonOpCodes::BooleanMatch | RadonOpCodes::IntegerMatch | RadonOpCodes::StringMatch => {
+                match args[0] {
+                    Value::Map(_) => {}
+                    _ => self.report(
+                        path,
+                        IssueKind::WrongArgument {
+                            operator: op_code.to_string(),
+                            description: "the first argument must be a map".to_string(),
+                        },
+                    ),
+                }
+
+                StaticType::from_value(&args[1])
+            }
+            RadonOpCodes::BooleanNegate
+            | RadonOpCodes::FloatGreaterThan
+            | RadonOpCodes::FloatLessThan
+            | RadonOpCodes::IntegerGreaterThan
+            | RadonOpCodes::IntegerLessThan
+            | RadonOpCodes::StringAsBoolean => StaticType::Boolean,
+            RadonOpCodes::BytesHash => {
+                let valid = cbor::value::from_value::<u8>(args[0].clone())
+                    .ok()
+                    .and_then(|code| RadonHashFunctions::try_from(code).ok())
+                    .filter(|function| *function != RadonHashFunctions::Fail)
+                    .is_some();
+                if !valid {
+                    self.report(
+                        path,
+                        IssueKind::WrongArgument {
+                            operator: op_code.to_string(),
+                            description: format!("unknown hash function {:?}", args[0]),
+                        },
+                    );
+                }
+
+                StaticType::Bytes
+            }
+            RadonOpCodes::IntegerAbsolute
+            | RadonOpCodes::IntegerModulo
+            | RadonOpCodes::IntegerMultiply
+            | RadonOpCodes::IntegerNegate
+            | RadonOpCodes::IntegerPower
+            | RadonOpCodes::IntegerSum
+            | RadonOpCodes::FloatCeiling
+            | RadonOpCodes::FloatFloor
+            | RadonOpCodes::FloatRound
+            | RadonOpCodes::FloatTruncate
+            | RadonOpCodes::StringAsInteger
+            | RadonOpCodes::StringLength => StaticType::Integer,
+            RadonOpCodes::IntegerAsFloat
+            | RadonOpCodes::IntegerReciprocal
+            | RadonOpCodes::FloatAbsolute
+            | RadonOpCodes::FloatModulo
+            | RadonOpCodes::FloatMultiply
+            | RadonOpCodes::FloatNegate
+            | RadonOpCodes::FloatPower
+            | RadonOpCodes::FloatReciprocal
+            | RadonOpCodes::FloatSum
+            | RadonOpCodes::StringAsFloat => StaticType::Float,
+            RadonOpCodes::MapEntries => StaticType::Array(Box::new(StaticType::any_array())),
+            RadonOpCodes::MapGetPath => StaticType::Any,
+            RadonOpCodes::MapKeys => StaticType::Array(Box::new(StaticType::String)),
+            RadonOpCodes::MapValues | RadonOpCodes::StringParseJSONArray => StaticType::any_array(),
+            RadonOpCodes::StringAsBytes => StaticType::Bytes,
+            RadonOpCodes::StringParseJSONMap | RadonOpCodes::StringParseXML => StaticType::Map,
+        }
+    }
+
+    /// Analyze the subscript given as argument to an array operator, returning its output type.
+    fn subscript(
+        &mut self,
+        op_code: RadonOpCodes,
+        arg: &Value,
+        items: StaticType,
+        path: &[usize],
+    ) -> StaticType {
+        if let Value::Array(calls) = arg {
+            self.unpacked_calls(calls, items, path)
+        } else {
+            self.report(
+                path,
+                IssueKind::WrongArgument {
+                    operator: op_code.to_string(),
+                    description: format!("expected a subscript, found {:?}", arg),
+                },
+            );
+
+            StaticType::Any
+        }
+    }
+
+    fn check_subscript_output(
+        &mut self,
+        op_code: RadonOpCodes,
+        expected: &[StaticType],
+        found: &StaticType,
+        path: &[usize],
+    ) {
+        if !found.is_one_of(expected) {
+            let expected = expected
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" or ");
+            self.report(
+                path,
+                IssueKind::WrongArgument {
+                    operator: op_code.to_string(),
+                    description: format!(
+                        "the subscript should return `{}`, but it returns `{}`",
+                        expected, found
+                    ),
+                },
+            );
+        }
+    }
+
+    fn filter(
+        &mut self,
+        op_code: RadonOpCodes,
+        input: &StaticType,
+        args: &[Value],
+        path: &[usize],
+    ) {
+        match &args[0] {
+            arg @ Value::Array(_) => {
+                let output = self.subscript(op_code, arg, input.items(), path);
+                self.check_subscript_output(op_code, &[StaticType::Boolean], &output, path);
+            }
+            Value::Integer(code) => {
+                let filter = u8::try_from(*code)
+                    .ok()
+                    .and_then(|code| RadonFilters::try_from(code).ok());
+                let description = match filter {
+                    None => format!("unknown filter code {}", code),
+                    Some(filter) => match filter_operand_types(filter) {
+                        None => format!("filter `{}` is not implemented", filter),
+                        Some(types) if !input.items().is_one_of(&types) => format!(
+                            "filter `{}` cannot be applied to items of type `{}`",
+                            filter,
+                            input.items()
+                        ),
+                        Some(_) => return,
+                    },
+                };
+                self.report(
+                    path,
+                    IssueKind::WrongArgument {
+                        operator: op_code.to_string(),
+                        description,
+                    },
+                );
+            }
+            arg => self.report(
+                path,
+                IssueKind::WrongArgument {
+                    operator: op_code.to_string(),
+                    description: format!("expected a filter code or a subscript, found {:?}", arg),
+                },
+            ),
+        }
+    }
+
+    fn reduce(
+        &mut self,
+        op_code: RadonOpCodes,
+        input: &StaticType,
+        arg: &Value,
+        path: &[usize],
+    ) -> StaticType {
+        let reducer = cbor::value::from_value::<u8>(arg.clone())
+            .ok()
+            .and_then(|code| RadonReducers::try_from(code).ok());
+        let description = match reducer {
+            None => format!("unknown reducer {:?}", arg),
+            Some(reducer) if !input.items().is_one_of(&reducer_operand_types(reducer)) => format!(
+                "reducer `{}` cannot be applied to items of type `{}`",
+                reducer,
+                input.items()
+            ),
+            Some(reducer) => return reducer_output(reducer, input.items()),
+        };
+        self.report(
+            path,
+            IssueKind::WrongArgument {
+                operator: op_code.to_string(),
+                description,
+            },
+        );
+
+        StaticType::Any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use witnet_data_structures::chain::{RADAggregate, RADRetrieve, RADTally};
+
+    use super::*;
+
+    fn retrieval(script: Value) -> RADRetrieve {
+        RADRetrieve {
+            kind: RADType::HttpGet,
+            url: "https://example.com".to_string(),
+            script: cbor::to_vec(&script).unwrap(),
+            body: vec![],
+            headers: vec![],
+        }
+    }
+
+    fn data_request(retrieve: Vec<RADRetrieve>, reducer: RadonReducers) -> RADRequest {
+        RADRequest {
+            time_lock: 0,
+            retrieve,
+            aggregate: RADAggregate {
+                filters: vec![],
+                reducer: reducer as u32,
+            },
+            tally: RADTally {
+                filters: vec![],
+                reducer: reducer as u32,
+            },
+        }
+    }
+
+    fn call(op_code: RadonOpCodes, args: Vec<Value>) -> Value {
+        let mut call = vec![Value::Integer(op_code as i128)];
+        call.extend(args);
+
+        Value::Array(call)
+    }
+
+    fn op(op_code: RadonOpCodes) -> Value {
+        Value::Integer(op_code as i128)
+    }
+
+    #[test]
+    fn test_analyze_valid_request() {
+        let script = Value::Array(vec![
+            op(RadonOpCodes::StringParseJSONMap),
+            call(RadonOpCodes::MapGetMap, vec![Value::Text("main".into())]),
+            call(RadonOpCodes::MapGetFloat, vec![Value::Text("temp".into())]),
+        ]);
+        let request = data_request(vec![retrieval(script)], RadonReducers::AverageMean);
+
+        let analysis = analyze_request(&request);
+
+        assert_eq!(analysis.issues, vec![]);
+        assert_eq!(analysis.retrieve, vec![StaticType::Float]);
+        assert_eq!(analysis.aggregate, StaticType::Float);
+        assert_eq!(analysis.tally, StaticType::Float);
+    }
+
+    #[test]
+    fn test_analyze_type_mismatch() {
+        let script = Value::Array(vec![
+            op(RadonOpCodes::StringParseJSONArray),
+            call(RadonOpCodes::MapGetFloat, vec![Value::Text("temp".into())]),
+        ]);
+        let request = data_request(vec![retrieval(script)], RadonReducers::AverageMean);
+
+        let analysis = analyze_request(&request);
+
+        assert_eq!(
+            analysis.issues,
+            vec![Issue {
+                stage: ScriptStage::Retrieval(0),
+                call: vec![1],
+                kind: IssueKind::TypeMismatch {
+                    operator: "MapGetFloat".to_string(),
+                    expected: "RadonMap".to_string(),
+                    found: "RadonArray".to_string(),
+                },
+            }]
+        );
+        // The analysis goes on as if the input was right
+        assert_eq!(analysis.retrieve, vec![StaticType::Float]);
+    }
+
+    #[test]
+    fn test_analyze_wrong_arity() {
+        let script = Value::Array(vec![
+            call(RadonOpCodes::StringParseJSONMap, vec![]),
+            op(RadonOpCodes::MapGetFloat),
+        ]);
+        let (_, issues) = analyze_script(
+            &cbor::to_vec(&script).unwrap(),
+            StaticType::String,
+            ScriptStage::Retrieval(0),
+        );
+
+        let kinds: Vec<IssueKind> = issues.into_iter().map(|issue| issue.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::WrongArity {
+                    operator: "StringParseJSONMap".to_string(),
+                    expected: "no arguments".to_string(),
+                    found: 0,
+                },
+                IssueKind::WrongArity {
+                    operator: "MapGetFloat".to_string(),
+                    expected: "exactly 1 argument".to_string(),
+                    found: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analyze_unknown_operator() {
+        let script = Value::Array(vec![
+            Value::Integer(0xF0),
+            op(RadonOpCodes::StringParseJSONMap),
+        ]);
+        let (output, issues) = analyze_script(
+            &cbor::to_vec(&script).unwrap(),
+            StaticType::String,
+            ScriptStage::Retrieval(0),
+        );
+
+        assert_eq!(output, StaticType::Map);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].call, vec![0]);
+        match issues[0].kind {
+            IssueKind::InvalidCall { .. } => {}
+            ref kind => panic!("Expected an invalid call issue, found {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_analyze_subscript() {
+        // Map every item to a float, then filter by a subscript that does not return a boolean
+        let script = Value::Array(vec![
+            op(RadonOpCodes::StringParseJSONArray),
+            call(
+                RadonOpCodes::ArrayMap,
+                vec![Value::Array(vec![call(
+                    RadonOpCodes::MapGetFloat,
+                    vec![Value::Text("price".into())],
+                )])],
+            ),
+            call(
+                RadonOpCodes::ArrayFilter,
+                vec![Value::Array(vec![call(
+                    RadonOpCodes::FloatMultiply,
+                    vec![Value::Float(2.0)],
+                )])],
+            ),
+        ]);
+        let (output, issues) = analyze_script(
+            &cbor::to_vec(&script).unwrap(),
+            StaticType::String,
+            ScriptStage::Retrieval(0),
+        );
+
+        assert_eq!(output, StaticType::Array(Box::new(StaticType::Float)));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].call, vec![2]);
+        assert_eq!(
+            issues[0].to_string(),
+            "retrieval #0, call 2: Wrong argument for operator `ArrayFilter`: the subscript \
+             should return `RadonBoolean`, but it returns `RadonFloat`"
+        );
+    }
+
+    #[test]
+    fn test_analyze_consensus() {
+        let script = Value::Array(vec![op(RadonOpCodes::StringAsBoolean)]);
+        let request = data_request(vec![retrieval(script)], RadonReducers::AverageMean);
+
+        let analysis = analyze_request(&request);
+
+        // The tally stage is not affected, as the type returned by the aggregation is unknown
+        assert_eq!(analysis.issues.len(), 1);
+        assert_eq!(analysis.issues[0].stage, ScriptStage::Aggregation);
+        assert_eq!(
+            analysis.issues[0].to_string(),
+            "aggregation, call 0: Wrong argument for operator `ArrayReduce`: reducer \
+             `RadonReducers::AverageMean` cannot be applied to items of type `RadonBoolean`"
+        );
+
+        let script = Value::Array(vec![op(RadonOpCodes::StringAsFloat)]);
+        let mut request = data_request(vec![retrieval(script)], RadonReducers::AverageMean);
+        request.tally.reducer = RadonReducers::DeviationStandard as u32;
+
+        let analysis = analyze_request(&request);
+
+        assert_eq!(analysis.issues.len(), 1);
+        assert_eq!(analysis.issues[0].stage, ScriptStage::Tally);
+        match analysis.issues[0].kind {
+            IssueKind::InvalidConsensus { .. } => {}
+            ref kind => panic!("Expected an invalid consensus issue, found {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn test_analyze_mismatching_retrievals() {
+        let request = data_request(
+            vec![
+                retrieval(Value::Array(vec![op(RadonOpCodes::StringAsFloat)])),
+                retrieval(Value::Array(vec![op(RadonOpCodes::StringParseJSONMap)])),
+            ],
+            RadonReducers::Mode,
+        );
+
+        let analysis = analyze_request(&request);
+
+        assert_eq!(
+            analysis.issues,
+            vec![Issue {
+                stage: ScriptStage::Aggregation,
+                call: vec![],
+                kind: IssueKind::MismatchingRetrievals {
+                    types: "RadonFloat, RadonMap".to_string(),
+                },
+            }]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Epoch-scoped cache of the responses of HTTP sources
+//!
+//! Several data requests resolved during the same epoch often share some of their sources. The
+//! responses of those sources are retrieved only once, and the same response is then fed into the
+//! script of each of the retrievals.
+
+use std::collections::HashMap;
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+
+use witnet_data_structures::chain::{Epoch, RADRetrieve, RADType};
+
+use crate::{
+    http_response, retrieve_input,
+    transport::RetrievalClient,
+    types::{string::RadonString, RadonTypes},
+    Result,
+};
+
+/// Everything that identifies the HTTP request performed by a retrieval. The script is not part
+/// of the key, because it is applied to the response afterwards.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RetrievalKey {
+    kind: RADType,
+    url: String,
+    body: Vec<u8>,
+    headers: Vec<(String, String)>,
+}
+
+impl RetrievalKey {
+    /// Key of the request performed by a retrieval, or `None` if its response cannot be shared
+    /// with other retrievals. This is the case of RNG retrievals, which must always return fresh
+    /// random bytes.
+    pub fn from_retrieve(retrieve: &RADRetrieve) -> Option<Self> {
+        match retrieve.kind {
+            RADType::HttpGet | RADType::HttpPost => Some(Self {
+                kind: retrieve.kind.clone(),
+                url: retrieve.url.clone(),
+                body: retrieve.body.clone(),
+                headers: retrieve.headers.clone(),
+            }),
+            RADType::Rng => None,
+        }
+    }
+}
+
+type SharedResponse = Shared<BoxFuture<'static, Result<String>>>;
+
+/// Responses of the HTTP sources retrieved during the current epoch.
+///
+/// Retrievals are shared as soon as they are started, so concurrent data requests with the same
+/// source wait for the same response instead of retrieving it again. Failed retrievals are only
+/// shared while they are in progress: once they fail, the next retrieval of the same source is
+/// performed again, because errors such as timeouts are often transient.
+#[derive(Default)]
+pub struct RetrievalCache {
+    epoch: Option<Epoch>,
+    responses: HashMap<RetrievalKey, SharedResponse>,
+}
+
+impl RetrievalCache {
+    /// Obtain the input of the script of a retrieval, reusing the response retrieved for any
+    /// other retrieval with the same `RetrievalKey` in this epoch.
+    ///
+    /// Responses retrieved in a previous epoch are discarded.
+    pub fn retrieve_input(
+        &mut self,
+        epoch: Epoch,
+        retrieve: &RADRetrieve,
+        client: &RetrievalClient,
+    ) -> BoxFuture<'static, Result<RadonTypes>> {
+        let retrieve = retrieve.clone();
+        let client = client.clone();
+
+        let key = match RetrievalKey::from_retrieve(&retrieve) {
+            Some(key) => key,
+            None => return async move { retrieve_input(&retrieve, &client).await }.boxed(),
+        };
+
+        if self.epoch != Some(epoch) {
+            self.epoch = Some(epoch);
+            self.responses.clear();
+        }
+        if let Some(Err(_)) = self
+            .responses
+            .get(&key)
+            .and_then(|response| response.peek())
+        {
+            self.responses.remove(&key);
+        }
+
+        let response = self
+            .responses
+            .entry(key)
+            .or_insert_with(|| {
+                async move { http_response(&retrieve, &client).await }
+                    .boxed()
+                    .shared()
+            })
+            .clone();
+
+        async move {
+            response
+                .await
+                .map(|response| RadonTypes::from(RadonString::from(response)))
+        }
+        .boxed()
+    }
+
+    /// Number of responses cached in this epoch.
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns `true` if no responses are cached in this epoch.
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn retrieve(kind: RADType, url: &str) -> RADRetrieve {
+        RADRetrieve {
+            kind,
+            url: url.to_string(),
+            script: vec![128],
+            body: vec![],
+            headers: vec![],
+        }
+    }
+
+    #[test]
+    fn key_ignores_script() {
+        let a = retrieve(RADType::HttpGet, "https://example.com");
+        let mut b = a.clone();
+        b.script = vec![130];
+        assert_eq!(
+            RetrievalKey::from_retrieve(&a),
+            RetrievalKey::from_retrieve(&b)
+        );
+
+        let mut c = a.clone();
+        c.headers = vec![("X-Api-Key".to_string(), "secret".to_string())];
+        assert_ne!(
+            RetrievalKey::from_retrieve(&a),
+            RetrievalKey::from_retrieve(&c)
+        );
+
+        let d = RADRetrieve {
+            kind: RADType::HttpPost,
+            ..a.clone()
+        };
+        assert_ne!(
+            RetrievalKey::from_retrieve(&a),
+            RetrievalKey::from_retrieve(&d)
+        );
+
+        assert_eq!(
+            RetrievalKey::from_retrieve(&retrieve(RADType::Rng, "")),
+            None
+        );
+    }
+
+    #[test]
+    fn responses_are_shared_within_an_epoch() {
+        let client = RetrievalClient::with_defaults().unwrap();
+        let mut cache = RetrievalCache::default();
+        let a = retrieve(RADType::HttpGet, "not a url");
+
+        // The response is not retrieved until the futures are polled, and the error is shared
+        let first = cache.retrieve_input(1, &a, &client);
+        let second = cache.retrieve_input(1, &a, &client);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(block_on(first), block_on(second));
+
+        // Once the retrieval has failed, the source is retrieved again
+        let key = RetrievalKey::from_retrieve(&a).unwrap();
+        assert!(cache.responses[&key].peek().unwrap().is_err());
+        let third = cache.retrieve_input(1, &a, &client);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.responses[&key].peek().is_none());
+        assert!(block_on(third).is_err());
+
+        // Responses from previous epochs are discarded
+        let _ = cache.retrieve_input(2, &retrieve(RADType::HttpGet, "other url"), &client);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn rng_is_never_cached() {
+        let client = RetrievalClient::with_defaults().unwrap();
+        let mut cache = RetrievalCache::default();
+        let rng = retrieve(RADType::Rng, "");
+
+        let first = block_on(cache.retrieve_input(1, &rng, &client)).unwrap();
+        let second = block_on(cache.retrieve_input(1, &rng, &client)).unwrap();
+        assert!(cache.is_empty());
+        assert_ne!(first, second);
+    }
+}
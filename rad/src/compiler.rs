@@ -0,0 +1,932 @@
+//! Textual representation of RADON scripts.
+//!
+//! Instead of authoring scripts as raw arrays of operator codes, they can be written as a chain of
+//! method calls, e.g. `.parseJSONMap().getFloat("price").multiply(1000).round()`, which compiles
+//! into the CBOR bytes expected in the `script` field of a `RADRetrieve`. Scripts can also be
+//! decompiled back into this form.
+//!
+//! Methods are named after their operator codes without the type prefix, e.g. `getFloat` for
+//! `MapGetFloat`. As the same name can refer to operators for different types, the compiler
+//! resolves each name using the type of the value that flows into the call, as inferred by the
+//! static analysis. The full name of the operator (e.g. `mapGetFloat`) can always be used instead.
+//!
+//! Arguments are written as JSON-like literals. Subscripts are written as chains of calls, e.g.
+//! `.map(.getFloat("price"))`, and the arguments of `filter`, `reduce` and `hash` can be given as
+//! the name of a filter, reducer or hash function, e.g. `.reduce(AverageMean)`.
+
+use std::{collections::BTreeMap, convert::TryFrom, fmt};
+
+use failure::Fail;
+use serde_cbor::{self as cbor, value::Value};
+
+use crate::{
+    analysis::{arity, call_output, operand_type, Arity, StaticType},
+    filters::RadonFilters,
+    hash_functions::RadonHashFunctions,
+    operators::RadonOpCodes,
+    reducers::RadonReducers,
+    script::unpack_radon_call,
+};
+
+/// Prefixes of the names of the operators that can only be applied to one type.
+const TYPE_PREFIXES: [&str; 7] = [
+    "Array", "Boolean", "Bytes", "Integer", "Float", "Map", "String",
+];
+
+/// Errors found when compiling or decompiling a RADON script. Positions are byte offsets into the
+/// source text.
+#[derive(Clone, Debug, Fail, PartialEq)]
+pub enum CompileError {
+    /// A character that cannot appear at this point of the script.
+    #[fail(
+        display = "Unexpected character `{}` at position {}, expected {}",
+        character, position, expected
+    )]
+    UnexpectedCharacter {
+        character: char,
+        position: usize,
+        expected: String,
+    },
+    /// The script ended in the middle of a call or an argument.
+    #[fail(display = "Unexpected end of script, expected {}", expected)]
+    UnexpectedEnd { expected: String },
+    /// There is no operator with the given name.
+    #[fail(display = "Unknown method `{}` at position {}", name, position)]
+    UnknownMethod { name: String, position: usize },
+    /// The name refers to operators for different types, and the type of the input does not tell
+    /// which one is meant.
+    #[fail(
+        display = "Method `{}` at position {} cannot be applied to `{}`, use one of: {}",
+        name, position, input, candidates
+    )]
+    AmbiguousMethod {
+        name: String,
+        position: usize,
+        input: String,
+        candidates: String,
+    },
+    /// The name is neither a literal nor a filter, reducer or hash function accepted by the method.
+    #[fail(display = "Unknown constant `{}` at position {}", name, position)]
+    UnknownConstant { name: String, position: usize },
+    /// A numeric literal that cannot be parsed.
+    #[fail(display = "Invalid number `{}` at position {}", literal, position)]
+    InvalidNumber { literal: String, position: usize },
+    /// The script to decompile is not a valid array of calls.
+    #[fail(display = "Malformed script: {}", description)]
+    MalformedScript { description: String },
+    /// The script to decompile contains a value that has no textual representation.
+    #[fail(display = "Value {:?} cannot be represented as text", value)]
+    UnsupportedValue { value: Value },
+}
+
+/// Compile the textual representation of a script into its CBOR serialization, given the type of
+/// the input of the script.
+pub fn compile(text: &str, input: StaticType) -> Result<Vec<u8>, CompileError> {
+    let calls = compile_calls(text, input)?;
+
+    Ok(cbor::to_vec(&Value::Array(calls)).expect("Serializing CBOR values should never fail"))
+}
+
+/// Compile the textual representation of a script into an array of packed calls.
+pub fn compile_calls(text: &str, input: StaticType) -> Result<Vec<Value>, CompileError> {
+    let mut parser = Parser::new(text);
+    let (calls, _output) = parser.script(input)?;
+
+    match parser.peek() {
+        None => Ok(calls),
+        Some((position, character)) => Err(CompileError::UnexpectedCharacter {
+            character,
+            position,
+            expected: "`.`".to_string(),
+        }),
+    }
+}
+
+/// Decompile a CBOR serialized script into its textual representation, given the type of the
+/// input of the script.
+pub fn decompile(script: &[u8], input: StaticType) -> Result<String, CompileError> {
+    match cbor::from_slice(script) {
+        Ok(Value::Array(calls)) => decompile_calls(&calls, input),
+        Ok(_) => Err(CompileError::MalformedScript {
+            description: "the script is not an array of calls".to_string(),
+        }),
+        Err(error) => Err(CompileError::MalformedScript {
+            description: error.to_string(),
+        }),
+    }
+}
+
+/// Decompile an array of packed calls into its textual representation.
+pub fn decompile_calls(calls: &[Value], input: StaticType) -> Result<String, CompileError> {
+    let mut current = input;
+    let mut text = String::new();
+
+    for packed_call in calls {
+        let call =
+            unpack_radon_call(packed_call).map_err(|error| CompileError::MalformedScript {
+                description: error.to_string(),
+            })?;
+        let (op_code, args) = &call;
+        let args = args
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| decompile_argument(*op_code, index, arg, &current))
+            .collect::<Result<Vec<String>, CompileError>>()?;
+
+        text.push_str(&format!(
+            ".{}({})",
+            method_name(*op_code, &current),
+            args.join(", ")
+        ));
+        current = call_output(&call, current);
+    }
+
+    Ok(text)
+}
+
+/// The full name of an operator, e.g. `mapGetFloat`.
+fn full_name(op_code: RadonOpCodes) -> String {
+    lower_camel_case(&format!("{:?}", op_code))
+}
+
+/// The name of an operator without its type prefix, e.g. `getFloat`.
+fn short_name(op_code: RadonOpCodes) -> String {
+    let name = format!("{:?}", op_code);
+    let stripped = TYPE_PREFIXES
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .filter(|_| operand_type(op_code).is_some())
+        .unwrap_or(&name);
+
+    lower_camel_case(stripped)
+}
+
+fn lower_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// The shortest name that resolves to the operator when applied to the given type.
+fn method_name(op_code: RadonOpCodes, input: &StaticType) -> String {
+    let short = short_name(op_code);
+    match resolve(&short, input) {
+        Ok(resolved) if resolved == op_code => short,
+        _ => full_name(op_code),
+    }
+}
+
+/// Find the operator a method name refers to when applied to the given type. If it cannot be
+/// found, returns the operators that the name could refer to, if any.
+fn resolve(name: &str, input: &StaticType) -> Result<RadonOpCodes, Vec<RadonOpCodes>> {
+    let op_codes: Vec<RadonOpCodes> = (0..=u8::MAX)
+        .filter_map(|code| RadonOpCodes::try_from(code).ok())
+        .collect();
+
+    if let Some(op_code) = op_codes.iter().find(|op_code| full_name(**op_code) == name) {
+        return Ok(*op_code);
+    }
+
+    let candidates: Vec<RadonOpCodes> = op_codes
+        .into_iter()
+        .filter(|op_code| short_name(*op_code) == name)
+        .collect();
+    let applicable: Vec<RadonOpCodes> = candidates
+        .iter()
+        .copied()
+        .filter(|op_code| match operand_type(*op_code) {
+            Some(expected) => expected.accepts(input),
+            None => true,
+        })
+        .collect();
+
+    match (candidates.as_slice(), applicable.as_slice()) {
+        ([op_code], _) | (_, [op_code]) => Ok(*op_code),
+        _ => Err(candidates),
+    }
+}
+
+/// The kind of named constants accepted as an argument of an operator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Constants {
+    None,
+    Filters,
+    HashFunctions,
+    Reducers,
+}
+
+impl Constants {
+    fn of_argument(op_code: RadonOpCodes, index: usize) -> Self {
+        match (op_code, index) {
+            (RadonOpCodes::ArrayFilter, 0) => Constants::Filters,
+            (RadonOpCodes::ArrayReduce, 0) => Constants::Reducers,
+            (RadonOpCodes::BytesHash, 0) => Constants::HashFunctions,
+            _ => Constants::None,
+        }
+    }
+
+    fn code(self, name: &str) -> Option<u8> {
+        (0..=u8::MAX).find(|code| self.name(*code).as_deref() == Some(name))
+    }
+
+    fn name(self, code: u8) -> Option<String> {
+        match self {
+            Constants::None => None,
+            Constants::Filters => constant_name::<RadonFilters>(code),
+            Constants::HashFunctions => constant_name::<RadonHashFunctions>(code),
+            Constants::Reducers => constant_name::<RadonReducers>(code),
+        }
+    }
+}
+
+fn constant_name<T: TryFrom<u8> + fmt::Debug>(code: u8) -> Option<String> {
+    T::try_from(code)
+        .ok()
+        .map(|constant| format!("{:?}", constant))
+}
+
+/// Whether the argument of an operator at the given position can be a subscript.
+fn takes_subscript(op_code: RadonOpCodes, index: usize) -> bool {
+    index == 0
+        && matches!(
+            op_code,
+            RadonOpCodes::ArrayFilter
+                | RadonOpCodes::ArrayMap
+                | RadonOpCodes::ArraySome
+                | RadonOpCodes::ArraySort
+        )
+}
+
+fn decompile_argument(
+    op_code: RadonOpCodes,
+    index: usize,
+    arg: &Value,
+    input: &StaticType,
+) -> Result<String, CompileError> {
+    match arg {
+        Value::Integer(code) => {
+            let name = u8::try_from(*code)
+                .ok()
+                .and_then(|code| Constants::of_argument(op_code, index).name(code));
+
+            Ok(name.unwrap_or_else(|| code.to_string()))
+        }
+        Value::Array(calls) if !calls.is_empty() && takes_subscript(op_code, index) => {
+            // Arrays that do not look like subscripts are shown as plain literals
+            decompile_calls(calls, input.items()).or_else(|_| decompile_value(arg))
+        }
+        arg => decompile_value(arg),
+    }
+}
+
+fn decompile_value(value: &Value) -> Result<String, CompileError> {
+    let unsupported = || CompileError::UnsupportedValue {
+        value: value.clone(),
+    };
+
+    match value {
+        Value::Null => Ok("null".to_string()),
+        Value::Bool(boolean) => Ok(boolean.to_string()),
+        Value::Integer(integer) => Ok(integer.to_string()),
+        // The debug representation of a finite float always contains a dot or an exponent
+        Value::Float(float) if float.is_finite() => Ok(format!("{:?}", float)),
+        Value::Text(text) => Ok(quote(text)),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(decompile_value)
+                .collect::<Result<Vec<String>, CompileError>>()?;
+
+            Ok(format!("[{}]", items.join(", ")))
+        }
+        Value::Map(entries) => {
+            let entries = entries
+                .iter()
+                .map(|(key, value)| {
+                    Ok(format!(
+                        "{}: {}",
+                        decompile_value(key)?,
+                        decompile_value(value)?
+                    ))
+                })
+                .collect::<Result<Vec<String>, CompileError>>()?;
+
+            Ok(format!("{{{}}}", entries.join(", ")))
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+struct Parser {
+    chars: Vec<(usize, char)>,
+    index: usize,
+    length: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self {
+            chars: text.char_indices().collect(),
+            index: 0,
+            length: text.len(),
+        }
+    }
+
+    /// The next character that is not whitespace, along with its position.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        while let Some((_, character)) = self.chars.get(self.index) {
+            if character.is_whitespace() {
+                self.index += 1;
+            } else {
+                break;
+            }
+        }
+
+        self.chars.get(self.index).copied()
+    }
+
+    /// Like `peek`, but without skipping whitespace.
+    fn peek_raw(&self) -> Option<(usize, char)> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn advance(&mut self) {
+        self.index += 1;
+    }
+
+    fn position(&self) -> usize {
+        self.peek_raw()
+            .map(|(position, _)| position)
+            .unwrap_or(self.length)
+    }
+
+    fn unexpected(&mut self, expected: &str) -> CompileError {
+        match self.peek() {
+            Some((position, character)) => CompileError::UnexpectedCharacter {
+                character,
+                position,
+                expected: expected.to_string(),
+            },
+            None => CompileError::UnexpectedEnd {
+                expected: expected.to_string(),
+            },
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), CompileError> {
+        match self.peek() {
+            Some((_, character)) if character == expected => {
+                self.advance();
+
+                Ok(())
+            }
+            _ => Err(self.unexpected(&format!("`{}`", expected))),
+        }
+    }
+
+    /// Parse a chain of calls, returning the packed calls and the type of their output.
+    fn script(&mut self, input: StaticType) -> Result<(Vec<Value>, StaticType), CompileError> {
+        let mut calls = vec![];
+        let mut current = input;
+
+        while let Some((_, '.')) = self.peek() {
+            self.advance();
+            let (call, output) = self.call(current)?;
+            calls.push(call);
+            current = output;
+        }
+
+        Ok((calls, current))
+    }
+
+    fn call(&mut self, input: StaticType) -> Result<(Value, StaticType), CompileError> {
+        let position = self.position();
+        let name = self.identifier();
+        if name.is_empty() {
+            return Err(self.unexpected("a method name"));
+        }
+
+        let op_code = resolve(&name, &input).map_err(|candidates| {
+            if candidates.is_empty() {
+                CompileError::UnknownMethod {
+                    name: name.clone(),
+                    position,
+                }
+            } else {
+                CompileError::AmbiguousMethod {
+                    name: name.clone(),
+                    position,
+                    input: input.to_string(),
+                    candidates: candidates
+                        .into_iter()
+                        .map(full_name)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                }
+            }
+        })?;
+
+        self.expect('(')?;
+        let mut index = 0;
+        let args = self.list(')', |parser| {
+            let arg = parser.argument(Constants::of_argument(op_code, index), input.items());
+            index += 1;
+
+            arg
+        })?;
+
+        // Calls without arguments are packed as a bare operator code, unless the operator
+        // requires an arguments array
+        let args = match (args.is_empty(), arity(op_code)) {
+            (true, Arity::NoArgs) | (true, Arity::OptionalArgs(_)) => None,
+            _ => Some(args),
+        };
+        let packed = match &args {
+            // Custom operators are called by their own code, which is the first argument
+            Some(args) if op_code == RadonOpCodes::Custom => match args.as_slice() {
+                [code] => code.clone(),
+                args => Value::Array(args.to_vec()),
+            },
+            None => Value::Integer(op_code as i128),
+            Some(args) => Value::Array(
+                std::iter::once(Value::Integer(op_code as i128))
+                    .chain(args.iter().cloned())
+                    .collect(),
+            ),
+        };
+        let output = call_output(&(op_code, args), input);
+
+        Ok((packed, output))
+    }
+
+    /// Parse a comma separated list of items up to the closing character.
+    fn list<F>(&mut self, close: char, mut item: F) -> Result<Vec<Value>, CompileError>
+    where
+        F: FnMut(&mut Self) -> Result<Value, CompileError>,
+    {
+        let mut items = vec![];
+        if let Some((_, character)) = self.peek() {
+            if character == close {
+                self.advance();
+
+                return Ok(items);
+            }
+        }
+
+        loop {
+            items.push(item(self)?);
+            match self.peek() {
+                Some((_, ',')) => self.advance(),
+                Some((_, character)) if character == close => {
+                    self.advance();
+
+                    return Ok(items);
+                }
+                _ => return Err(self.unexpected(&format!("`,` or `{}`", close))),
+            }
+        }
+    }
+
+    /// Parse an argument of a call. `items` is the type fed into a subscript found here.
+    fn argument(&mut self, constants: Constants, items: StaticType) -> Result<Value, CompileError> {
+        match self.peek() {
+            Some((_, '.')) => Ok(Value::Array(self.script(items)?.0)),
+            Some((_, '"')) => self.string().map(Value::Text),
+            Some((_, '[')) => {
+                self.advance();
+                let items = self.list(']', |parser| {
+                    parser.argument(Constants::None, StaticType::Any)
+                })?;
+
+                Ok(Value::Array(items))
+            }
+            Some((_, '{')) => {
+                self.advance();
+                let mut entries = BTreeMap::new();
+                self.list('}', |parser| {
+                    let key = parser.argument(Constants::None, StaticType::Any)?;
+                    parser.expect(':')?;
+                    let value = parser.argument(Constants::None, StaticType::Any)?;
+                    entries.insert(key, value);
+
+                    // Entries are collected into the map, so the list itself is discarded
+                    Ok(Value::Null)
+                })?;
+
+                Ok(Value::Map(entries))
+            }
+            Some((_, character)) if character == '-' || character.is_ascii_digit() => self.number(),
+            Some((position, character)) if character.is_alphabetic() => {
+                let name = self.identifier();
+                match name.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    _ => constants
+                        .code(&name)
+                        .map(|code| Value::Integer(i128::from(code)))
+                        .ok_or(CompileError::UnknownConstant { name, position }),
+                }
+            }
+            _ => Err(self.unexpected("an argument")),
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let mut identifier = String::new();
+        while let Some((_, character)) = self.peek_raw() {
+            if character.is_alphanumeric() || character == '_' {
+                identifier.push(character);
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        identifier
+    }
+
+    fn number(&mut self) -> Result<Value, CompileError> {
+        let position = self.position();
+        let mut literal = String::new();
+        while let Some((_, character)) = self.peek_raw() {
+            let exponent_sign =
+                (character == '-' || character == '+') && literal.ends_with(&['e', 'E'][..]);
+            if character.is_ascii_alphanumeric()
+                || character == '.'
+                || exponent_sign
+                || (character == '-' && literal.is_empty())
+            {
+                literal.push(character);
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        let invalid = || CompileError::InvalidNumber {
+            literal: literal.clone(),
+            position,
+        };
+        if literal.contains(&['.', 'e', 'E'][..]) {
+            literal
+                .parse::<f64>()
+                .ok()
+                .filter(|float| float.is_finite())
+                .map(Value::Float)
+                .ok_or_else(invalid)
+        } else {
+            literal
+                .parse::<i128>()
+                .map(Value::Integer)
+                .map_err(|_| invalid())
+        }
+    }
+
+    fn string(&mut self) -> Result<String, CompileError> {
+        self.expect('"')?;
+        let mut string = String::new();
+
+        loop {
+            let (position, character) =
+                self.peek_raw().ok_or_else(|| CompileError::UnexpectedEnd {
+                    expected: "`\"`".to_string(),
+                })?;
+            self.advance();
+
+            match character {
+                '"' => return Ok(string),
+                '\\' => {
+                    let (_, escaped) =
+                        self.peek_raw().ok_or_else(|| CompileError::UnexpectedEnd {
+                            expected: "an escape sequence".to_string(),
+                        })?;
+                    self.advance();
+                    match escaped {
+                        '"' | '\\' | '/' => string.push(escaped),
+                        'n' => string.push('\n'),
+                        'r' => string.push('\r'),
+                        't' => string.push('\t'),
+                        'u' => string.push(self.unicode_escape(position)?),
+                        _ => {
+                            return Err(CompileError::UnexpectedCharacter {
+                                character: escaped,
+                                position: position + 1,
+                                expected: "an escape sequence".to_string(),
+                            })
+                        }
+                    }
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    /// Parse the `{hex}` part of an escape sequence like `\u{1f600}`.
+    fn unicode_escape(&mut self, position: usize) -> Result<char, CompileError> {
+        self.expect('{')?;
+        let mut digits = String::new();
+        while let Some((_, character)) = self.peek_raw() {
+            if character.is_ascii_hexdigit() {
+                digits.push(character);
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        self.expect('}')?;
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(std::char::from_u32)
+            .ok_or(CompileError::InvalidNumber {
+                literal: digits,
+                position,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(op_code: RadonOpCodes, args: Vec<Value>) -> Value {
+        let mut call = vec![Value::Integer(op_code as i128)];
+        call.extend(args);
+
+        Value::Array(call)
+    }
+
+    fn op(op_code: RadonOpCodes) -> Value {
+        Value::Integer(op_code as i128)
+    }
+
+    #[test]
+    fn test_compile_script() {
+        let text = r#".asString().parseJSONMap().getFloat("price").multiply(1000).round()"#;
+        let expected = vec![
+            op(RadonOpCodes::BytesAsString),
+            op(RadonOpCodes::StringParseJSONMap),
+            call(RadonOpCodes::MapGetFloat, vec![Value::Text("price".into())]),
+            call(RadonOpCodes::FloatMultiply, vec![Value::Integer(1000)]),
+            op(RadonOpCodes::FloatRound),
+        ];
+
+        assert_eq!(compile_calls(text, StaticType::Bytes), Ok(expected.clone()));
+        assert_eq!(
+            compile(text, StaticType::Bytes),
+            Ok(cbor::to_vec(&Value::Array(expected)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_compile_empty_script() {
+        assert_eq!(compile_calls("", StaticType::String), Ok(vec![]));
+        assert_eq!(compile_calls("  ", StaticType::String), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_compile_resolves_names_by_input_type() {
+        let output = compile_calls(".parseJSONArray().getFloat(0)", StaticType::String);
+        let expected = vec![
+            op(RadonOpCodes::StringParseJSONArray),
+            call(RadonOpCodes::ArrayGetFloat, vec![Value::Integer(0)]),
+        ];
+        assert_eq!(output, Ok(expected));
+
+        let output = compile_calls(".parseJSONMap().getFloat(\"a\")", StaticType::String);
+        let expected = vec![
+            op(RadonOpCodes::StringParseJSONMap),
+            call(RadonOpCodes::MapGetFloat, vec![Value::Text("a".into())]),
+        ];
+        assert_eq!(output, Ok(expected));
+    }
+
+    #[test]
+    fn test_compile_ambiguous_method() {
+        let output = compile_calls(".getPath(\"a\").getFloat(0)", StaticType::Map);
+        let expected = CompileError::AmbiguousMethod {
+            name: "getFloat".to_string(),
+            position: 14,
+            input: "any type".to_string(),
+            candidates: "arrayGetFloat, mapGetFloat".to_string(),
+        };
+        assert_eq!(output, Err(expected));
+
+        // The full name of the operator is never ambiguous
+        let output = compile_calls(".getPath(\"a\").arrayGetFloat(0)", StaticType::Map);
+        let expected = vec![
+            call(RadonOpCodes::MapGetPath, vec![Value::Text("a".into())]),
+            call(RadonOpCodes::ArrayGetFloat, vec![Value::Integer(0)]),
+        ];
+        assert_eq!(output, Ok(expected));
+    }
+
+    #[test]
+    fn test_compile_subscripts_and_constants() {
+        let text = ".parseJSONArray().map(.mapGetFloat(\"price\")).filter(DeviationStandard, 1.5)\
+                    .sort().reduce(AverageMedian)";
+        let expected = vec![
+            op(RadonOpCodes::StringParseJSONArray),
+            call(
+                RadonOpCodes::ArrayMap,
+                vec![Value::Array(vec![call(
+                    RadonOpCodes::MapGetFloat,
+                    vec![Value::Text("price".into())],
+                )])],
+            ),
+            call(
+                RadonOpCodes::ArrayFilter,
+                vec![
+                    Value::Integer(RadonFilters::DeviationStandard as i128),
+                    Value::Float(1.5),
+                ],
+            ),
+            call(RadonOpCodes::ArraySort, vec![]),
+            call(
+                RadonOpCodes::ArrayReduce,
+                vec![Value::Integer(RadonReducers::AverageMedian as i128)],
+            ),
+        ];
+
+        assert_eq!(compile_calls(text, StaticType::String), Ok(expected));
+
+        let expected = vec![call(
+            RadonOpCodes::BytesHash,
+            vec![Value::Integer(RadonHashFunctions::SHA2_256 as i128)],
+        )];
+        assert_eq!(
+            compile_calls(".hash(SHA2_256)", StaticType::Bytes),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_compile_literals() {
+        let text = r#".match({"yes": true, "no\"\u{21}": false}, null)"#;
+        let mut map = BTreeMap::new();
+        map.insert(Value::Text("yes".into()), Value::Bool(true));
+        map.insert(Value::Text("no\"!".into()), Value::Bool(false));
+        let expected = vec![call(
+            RadonOpCodes::StringMatch,
+            vec![Value::Map(map), Value::Null],
+        )];
+        assert_eq!(compile_calls(text, StaticType::String), Ok(expected));
+
+        let text = ".take(-1, 2e3).flatten([1, [2.5, \"x\"]])";
+        let expected = vec![
+            call(
+                RadonOpCodes::ArrayTake,
+                vec![Value::Integer(-1), Value::Float(2000.0)],
+            ),
+            call(
+                RadonOpCodes::ArrayFlatten,
+                vec![Value::Array(vec![
+                    Value::Integer(1),
+                    Value::Array(vec![Value::Float(2.5), Value::Text("x".into())]),
+                ])],
+            ),
+        ];
+        assert_eq!(compile_calls(text, StaticType::any_array()), Ok(expected));
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        assert_eq!(
+            compile_calls(".parseJSONMap().foo()", StaticType::String),
+            Err(CompileError::UnknownMethod {
+                name: "foo".to_string(),
+                position: 16,
+            })
+        );
+        assert_eq!(
+            compile_calls(".parseJSONArray().reduce(Average)", StaticType::String),
+            Err(CompileError::UnknownConstant {
+                name: "Average".to_string(),
+                position: 25,
+            })
+        );
+        assert_eq!(
+            compile_calls(".parseJSONMap(", StaticType::String),
+            Err(CompileError::UnexpectedEnd {
+                expected: "an argument".to_string(),
+            })
+        );
+        assert_eq!(
+            compile_calls(".parseJSONMap() x", StaticType::String),
+            Err(CompileError::UnexpectedCharacter {
+                character: 'x',
+                position: 16,
+                expected: "`.`".to_string(),
+            })
+        );
+        assert_eq!(
+            compile_calls(".parseJSONMap().getFloat(\"a\" \"b\")", StaticType::String),
+            Err(CompileError::UnexpectedCharacter {
+                character: '"',
+                position: 29,
+                expected: "`,` or `)`".to_string(),
+            })
+        );
+        assert_eq!(
+            compile_calls(".parseJSONArray().getFloat(1.2.3)", StaticType::String),
+            Err(CompileError::InvalidNumber {
+                literal: "1.2.3".to_string(),
+                position: 27,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decompile_script() {
+        let script = cbor::to_vec(&Value::Array(vec![
+            op(RadonOpCodes::StringParseJSONMap),
+            call(RadonOpCodes::MapGetFloat, vec![Value::Text("price".into())]),
+            call(RadonOpCodes::FloatMultiply, vec![Value::Float(1000.0)]),
+            op(RadonOpCodes::FloatRound),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            decompile(&script, StaticType::String),
+            Ok(r#".parseJSONMap().getFloat("price").multiply(1000.0).round()"#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_decompile_uses_full_names_when_needed() {
+        let script = cbor::to_vec(&Value::Array(vec![
+            call(RadonOpCodes::MapGetPath, vec![Value::Text("a".into())]),
+            call(RadonOpCodes::ArrayGetFloat, vec![Value::Integer(0)]),
+            op(RadonOpCodes::FloatRound),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            decompile(&script, StaticType::Map),
+            Ok(r#".getPath("a").arrayGetFloat(0).round()"#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_decompile_compile_round_trip() {
+        let texts = [
+            r#".parseJSONArray().map(.mapGetFloat("price")).filter(DeviationStandard, 1.5).sort().reduce(AverageMedian)"#,
+            r#".parseJSONArray().filter(.arrayGetBoolean(0)).some(.mapGetBoolean("ok").negate()).match({"a\n": 1}, 0)"#,
+            r#".asString().toUpperCase().asBytes().hash(Blake256)"#,
+            r#".parseJSONArray().flatten().take(1).map([]).count()"#,
+        ];
+
+        for text in texts.iter() {
+            let script = compile(text, StaticType::Bytes).unwrap();
+            assert_eq!(decompile(&script, StaticType::Bytes).as_deref(), Ok(*text));
+        }
+    }
+
+    #[test]
+    fn test_decompile_errors() {
+        assert_eq!(
+            decompile(
+                &cbor::to_vec(&Value::Integer(1)).unwrap(),
+                StaticType::String
+            ),
+            Err(CompileError::MalformedScript {
+                description: "the script is not an array of calls".to_string(),
+            })
+        );
+
+        let bytes = Value::Bytes(vec![1, 2, 3]);
+        let script = cbor::to_vec(&Value::Array(vec![call(
+            RadonOpCodes::StringMatch,
+            vec![Value::Map(BTreeMap::new()), bytes.clone()],
+        )]))
+        .unwrap();
+        assert_eq!(
+            decompile(&script, StaticType::String),
+            Err(CompileError::UnsupportedValue { value: bytes })
+        );
+    }
+}
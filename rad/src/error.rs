@@ -38,18 +38,36 @@ pub enum RadError {
         description
     )]
     JsonParse { description: String },
+    /// Failed to parse an object from a XML buffer
+    #[fail(
+        display = "Failed to parse an object from a XML buffer: {:?}",
+        description
+    )]
+    XmlParse { description: String },
     /// The given index is not present in a RadonArray
     #[fail(display = "Failed to get item at index `{}` from RadonArray", index)]
     ArrayIndexOutOfBounds { index: i32 },
     /// The given key is not present in a RadonMap
     #[fail(display = "Failed to get key `{}` from RadonMap", key)]
     MapKeyNotFound { key: String },
+    /// The given path cannot be followed through a RadonMap
+    #[fail(
+        display = "Failed to follow path `{}` from RadonMap at segment `{}`",
+        path, segment
+    )]
+    PathNotFound { path: String, segment: String },
     /// The given subscript does not return RadonBoolean in an ArrayFilter
     #[fail(
         display = "ArrayFilter subscript output was not RadonBoolean (was `{}`)",
         value
     )]
     ArrayFilterWrongSubscript { value: String },
+    /// The given subscript does not return RadonBoolean in an ArraySome
+    #[fail(
+        display = "ArraySome subscript output was not RadonBoolean (was `{}`)",
+        value
+    )]
+    ArraySomeWrongSubscript { value: String },
     /// Failed to parse a Value from a buffer
     #[fail(
         display = "Failed to parse a Value from a buffer. Error message: {}",
@@ -138,6 +156,12 @@ pub enum RadError {
     /// Tried to apply mod reducer on an empty array
     #[fail(display = "Tried to apply mode reducer on an empty array")]
     ModeEmpty,
+    /// The items of an array reduced with a weighted reducer must be `[value, weight]` pairs
+    #[fail(
+        display = "Reducer `{}` expects `[value, weight]` pairs with a non-negative weight, found `{:?}`",
+        reducer, item
+    )]
+    MalformedWeightedItem { reducer: String, item: RadonTypes },
     /// The given arguments are not valid for the given operator
     #[fail(
         display = "Wrong `{}::{}()` arguments: `{:?}`",
@@ -149,14 +173,26 @@ pub enum RadError {
         args: Vec<SerdeCborValue>,
     },
     /// The HTTP response was an error code
-    #[fail(display = "HTTP GET response was an HTTP error code: {}", status_code)]
+    #[fail(display = "HTTP response was an HTTP error code: {}", status_code)]
     HttpStatus { status_code: u16 },
     /// Failed to execute HTTP request
     #[fail(
-        display = "Failed to execute HTTP GET request with error message: {}",
+        display = "Failed to execute HTTP request with error message: {}",
         message
     )]
     HttpOther { message: String },
+    /// Invalid HTTP header
+    #[fail(display = "Invalid HTTP header. Name: {:?}, value: {:?}", name, value)]
+    InvalidHttpHeader { name: String, value: String },
+    /// Invalid configuration of the transport used for retrievals
+    #[fail(display = "Invalid retrieval transport configuration: {}", message)]
+    InvalidRetrievalTransport { message: String },
+    /// The host of a retrieval is or resolves to a private address, which are denied
+    #[fail(display = "Host {} is or resolves to a private address", host)]
+    PrivateAddress { host: String },
+    /// The kind of retrieval cannot be performed by the function it was passed to
+    #[fail(display = "Unknown retrieval kind: {}", kind)]
+    UnknownRetrieval { kind: String },
     /// Failed to convert string to float
     #[fail(
         display = "Failed to convert string to float with error message: {}",
@@ -221,6 +257,9 @@ pub enum RadError {
     /// Timeout during retrieval phase
     #[fail(display = "Timeout during retrieval phase")]
     RetrieveTimeout,
+    /// The RAD engine cannot resolve requests because it has not been started
+    #[fail(display = "The RAD engine has not been started")]
+    EngineNotStarted,
     /// Invalid script
     #[fail(
         display = "CBOR value cannot be translated into a proper RADON script: {:?}",
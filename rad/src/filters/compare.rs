@@ -0,0 +1,247 @@
+use std::cmp::Ordering;
+
+use serde_cbor::Value;
+
+use crate::{
+    error::RadError,
+    filters::{keep_items, RadonFilters},
+    types::{array::RadonArray, RadonType, RadonTypes},
+};
+use witnet_data_structures::radon_report::ReportContext;
+
+/// Keeps the values that are greater than the given argument.
+pub fn greater_than_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+) -> Result<RadonTypes, RadError> {
+    compare_filter(input, extra_args, context, RadonFilters::GreaterThan)
+}
+
+/// Keeps the values that are less than the given argument.
+pub fn less_than_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+) -> Result<RadonTypes, RadError> {
+    compare_filter(input, extra_args, context, RadonFilters::LessThan)
+}
+
+/// Keeps the values that are equal to the given argument.
+pub fn equals_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+) -> Result<RadonTypes, RadError> {
+    compare_filter(input, extra_args, context, RadonFilters::Equals)
+}
+
+/// Compares every value in the input against the argument of the filter. Integers and floats are
+/// compared numerically against an integer or float argument, while strings are compared
+/// lexicographically against a text argument.
+// FIXME: Allow for now, since there is no safe cast function from an i128 to float yet
+#[allow(clippy::cast_precision_loss)]
+fn compare_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+    filter: RadonFilters,
+) -> Result<RadonTypes, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonArray::radon_type_name(),
+        operator: filter.to_string(),
+        args: extra_args.to_vec(),
+    };
+
+    if extra_args.len() != 1 {
+        return Err(wrong_args());
+    }
+
+    let arg = &extra_args[0];
+    let wanted = match filter {
+        RadonFilters::GreaterThan => Ordering::Greater,
+        RadonFilters::LessThan => Ordering::Less,
+        _ => Ordering::Equal,
+    };
+
+    let keep = input
+        .value()
+        .iter()
+        .map(|item| {
+            let ordering = match (item, arg) {
+                (RadonTypes::Integer(i), Value::Integer(a)) => Some(i.value().cmp(a)),
+                (RadonTypes::Integer(i), Value::Float(a)) => (i.value() as f64).partial_cmp(a),
+                (RadonTypes::Float(f), Value::Integer(a)) => f.value().partial_cmp(&(*a as f64)),
+                (RadonTypes::Float(f), Value::Float(a)) => f.value().partial_cmp(a),
+                (RadonTypes::String(s), Value::Text(a)) => Some(s.value().as_str().cmp(a)),
+                (RadonTypes::Integer(_), _)
+                | (RadonTypes::Float(_), _)
+                | (RadonTypes::String(_), _) => return Err(wrong_args()),
+                _ => {
+                    return Err(RadError::UnsupportedFilter {
+                        array: input.clone(),
+                        filter: filter.to_string(),
+                    })
+                }
+            };
+
+            // Comparisons involving NaN have no ordering, so those values are never kept
+            Ok(ordering == Some(wanted))
+        })
+        .collect::<Result<Vec<bool>, RadError>>()?;
+
+    Ok(keep_items(input, &keep, context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        boolean::RadonBoolean, float::RadonFloat, integer::RadonInteger, string::RadonString,
+    };
+    use witnet_data_structures::radon_report::{Stage, TallyMetaData};
+
+    // Helper function which works with Rust integers, to remove RadonTypes from tests
+    fn icompare(
+        input_i128: &[i128],
+        arg: Value,
+        filter: RadonFilters,
+    ) -> Result<Vec<i128>, RadError> {
+        let input_vec: Vec<RadonTypes> = input_i128
+            .iter()
+            .map(|i| RadonTypes::Integer(RadonInteger::from(*i)))
+            .collect();
+        let input = RadonArray::from(input_vec);
+
+        let output = compare_filter(&input, &[arg], &mut ReportContext::default(), filter)?;
+
+        let output_vec = match output {
+            RadonTypes::Array(x) => x.value(),
+            _ => panic!("Filter method should return a RadonArray"),
+        };
+        let output_i128 = output_vec
+            .into_iter()
+            .map(|r| match r {
+                RadonTypes::Integer(x) => x.value(),
+                _ => panic!("Filter method should return an array of integers"),
+            })
+            .collect();
+
+        Ok(output_i128)
+    }
+
+    #[test]
+    fn test_filter_greater_than_integer() {
+        let output = icompare(&[1, 5, 3, 7], Value::Integer(3), RadonFilters::GreaterThan);
+        assert_eq!(output, Ok(vec![5, 7]));
+
+        let output = icompare(&[1, 5, 3, 7], Value::Float(2.5), RadonFilters::GreaterThan);
+        assert_eq!(output, Ok(vec![5, 3, 7]));
+    }
+
+    #[test]
+    fn test_filter_less_than_integer() {
+        let output = icompare(&[1, 5, 3, 7], Value::Integer(3), RadonFilters::LessThan);
+        assert_eq!(output, Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_filter_equals_integer() {
+        let output = icompare(&[1, 5, 3, 7, 3], Value::Integer(3), RadonFilters::Equals);
+        assert_eq!(output, Ok(vec![3, 3]));
+    }
+
+    #[test]
+    fn test_filter_less_than_float() {
+        let input = RadonArray::from(vec![
+            RadonFloat::from(0.5).into(),
+            RadonFloat::from(std::f64::NAN).into(),
+            RadonFloat::from(2.0).into(),
+        ]);
+        let extra_args = vec![Value::Integer(1)];
+
+        let output = less_than_filter(&input, &extra_args, &mut ReportContext::default());
+        let expected = RadonTypes::from(RadonArray::from(vec![RadonFloat::from(0.5).into()]));
+
+        assert_eq!(output, Ok(expected));
+    }
+
+    #[test]
+    fn test_filter_equals_string_tally() {
+        let input = RadonArray::from(vec![
+            RadonString::from("foo").into(),
+            RadonString::from("bar").into(),
+            RadonString::from("foo").into(),
+        ]);
+        let extra_args = vec![Value::Text("foo".to_string())];
+
+        let mut context = ReportContext::default();
+        context.stage = Stage::Tally(TallyMetaData::default());
+
+        let output = equals_filter(&input, &extra_args, &mut context).unwrap();
+        let expected = RadonTypes::from(RadonArray::from(vec![
+            RadonString::from("foo").into(),
+            RadonString::from("foo").into(),
+        ]));
+
+        assert_eq!(output, expected);
+
+        if let Stage::Tally(metadata) = context.stage {
+            assert_eq!(metadata.liars, vec![false, true, false]);
+        } else {
+            panic!("Not tally stage");
+        }
+    }
+
+    #[test]
+    fn test_filter_greater_than_string() {
+        let input = RadonArray::from(vec![
+            RadonString::from("apple").into(),
+            RadonString::from("pear").into(),
+        ]);
+        let extra_args = vec![Value::Text("banana".to_string())];
+
+        let output = greater_than_filter(&input, &extra_args, &mut ReportContext::default());
+        let expected = RadonTypes::from(RadonArray::from(vec![RadonString::from("pear").into()]));
+
+        assert_eq!(output, Ok(expected));
+    }
+
+    #[test]
+    fn test_filter_compare_wrong_arg() {
+        let input = RadonArray::from(vec![RadonString::from("foo").into()]);
+        let extra_args = vec![Value::Integer(1)];
+
+        let expected = RadError::WrongArguments {
+            input_type: RadonArray::radon_type_name(),
+            operator: RadonFilters::Equals.to_string(),
+            args: extra_args.clone(),
+        };
+
+        let result = equals_filter(&input, &extra_args, &mut ReportContext::default());
+
+        assert_eq!(result.unwrap_err(), expected);
+
+        let result = equals_filter(&input, &[], &mut ReportContext::default());
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_filter_compare_unsupported_type() {
+        let input = RadonArray::from(vec![
+            RadonBoolean::from(true).into(),
+            RadonBoolean::from(false).into(),
+        ]);
+        let extra_args = vec![Value::Bool(true)];
+
+        let expected = RadError::UnsupportedFilter {
+            array: input.clone(),
+            filter: RadonFilters::Equals.to_string(),
+        };
+
+        let result = equals_filter(&input, &extra_args, &mut ReportContext::default());
+
+        assert_eq!(result.unwrap_err(), expected);
+    }
+}
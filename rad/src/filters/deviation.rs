@@ -1,6 +1,6 @@
 use crate::{
     error::RadError,
-    filters::RadonFilters,
+    filters::{keep_items, RadonFilters},
     operators::array::transpose,
     reducers,
     types::{array::RadonArray, boolean::RadonBoolean, float::RadonFloat, RadonType, RadonTypes},
@@ -208,6 +208,168 @@ fn boolean_standard_filter(input: &RadonArray, sigmas_float: f64) -> Result<Rado
     }
 }
 
+/// Keeps the values whose distance to the median of the input is at most the given absolute
+/// deviation.
+pub fn absolute_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+) -> Result<RadonTypes, RadError> {
+    median_deviation_filter(input, extra_args, context, RadonFilters::DeviationAbsolute)
+}
+
+/// Keeps the values whose distance to the median of the input is at most the given ratio of the
+/// median itself, e.g. `0.05` keeps values within a 5% margin of the median.
+pub fn relative_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+) -> Result<RadonTypes, RadError> {
+    median_deviation_filter(input, extra_args, context, RadonFilters::DeviationRelative)
+}
+
+// Unlike the standard deviation filter, deviations are measured from the median rather than the
+// mean, so that a single outlier cannot drag the center away when there are few values.
+// FIXME: Allow for now, wait for https://github.com/rust-lang/rust/issues/67058 to reach stable
+#[allow(clippy::cast_precision_loss)]
+fn median_deviation_filter(
+    input: &RadonArray,
+    extra_args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+    filter: RadonFilters,
+) -> Result<RadonTypes, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonArray::radon_type_name(),
+        operator: filter.to_string(),
+        args: extra_args.to_vec(),
+    };
+
+    if extra_args.len() != 1 {
+        return Err(wrong_args());
+    }
+
+    let max_deviation = match &extra_args[0] {
+        Value::Integer(i) => *i as f64,
+        Value::Float(f) => *f,
+        _ => {
+            return Err(wrong_args());
+        }
+    };
+    if max_deviation.is_nan() || max_deviation < 0f64 {
+        return Err(wrong_args());
+    }
+
+    let value = input.value();
+
+    match value.first() {
+        None => Ok(RadonTypes::from(input.clone())),
+        Some(RadonTypes::Array(arr2)) => {
+            // 2D array
+            if let Some(RadonTypes::Array(_arr3)) = arr2.value().first() {
+                // 3D array
+                return Err(RadError::UnsupportedFilter {
+                    array: input.clone(),
+                    filter: filter.to_string(),
+                });
+            }
+            let bool_matrix = boolean_median_deviation_filter(&input, filter, max_deviation)?;
+
+            keep_rows(input, &bool_matrix, context)
+        }
+        Some(_rad_types) => {
+            // 1D array
+            let bool_array = boolean_median_deviation_filter(&input, filter, max_deviation)?;
+
+            let keep: Vec<bool> = bool_array
+                .value()
+                .iter()
+                .map(|b| match b {
+                    RadonTypes::Boolean(rad_bool) => rad_bool.value(),
+                    _ => panic!("Expected RadonArray of RadonBoolean"),
+                })
+                .collect();
+
+            Ok(keep_items(input, &keep, context))
+        }
+    }
+}
+
+// Return an array with the same dimensions as the input, with a boolean indicating
+// whether to keep a value or not
+// FIXME: Allow for now, since there is no safe cast function from an i128 to float yet
+#[allow(clippy::cast_precision_loss)]
+fn boolean_median_deviation_filter(
+    input: &RadonArray,
+    filter: RadonFilters,
+    max_deviation: f64,
+) -> Result<RadonArray, RadError> {
+    // if input is empty, return the array
+    if input.value().is_empty() {
+        return Ok(input.clone());
+    }
+
+    if !input.is_homogeneous() {
+        return Err(RadError::UnsupportedOpNonHomogeneous {
+            operator: filter.to_string(),
+        });
+    }
+
+    let value = input.value();
+
+    match value.first() {
+        None => Ok(input.clone()),
+        Some(RadonTypes::Integer(_)) | Some(RadonTypes::Float(_)) => {
+            let median =
+                reducers::average::median(input, reducers::average::MeanReturnPolicy::ReturnFloat)?;
+            let median = RadonFloat::try_from(median)?.value();
+
+            let max_distance = if filter == RadonFilters::DeviationRelative {
+                max_deviation * median.abs()
+            } else {
+                max_deviation
+            };
+
+            let mut result = vec![];
+            for item in input.value() {
+                let xv = match item {
+                    RadonTypes::Integer(i) => i.value() as f64,
+                    RadonTypes::Float(f) => f.value(),
+                    _ => unreachable!(),
+                };
+                let keep = (xv - median).abs() <= max_distance;
+                result.push(RadonTypes::Boolean(RadonBoolean::from(keep)));
+            }
+
+            Ok(RadonArray::from(result))
+        }
+        Some(RadonTypes::Array(_)) => {
+            let v = transpose(input)?;
+
+            let mut deviation_v = vec![];
+            for v2dev in v.value() {
+                if let RadonTypes::Array(v2dev) = v2dev {
+                    deviation_v.push(RadonTypes::from(boolean_median_deviation_filter(
+                        &v2dev,
+                        filter,
+                        max_deviation,
+                    )?));
+                } else {
+                    unreachable!()
+                }
+            }
+
+            let o = RadonArray::from(deviation_v);
+            let ot = transpose(&o)?;
+
+            Ok(ot)
+        }
+        Some(_rad_types) => Err(RadError::UnsupportedFilter {
+            array: input.clone(),
+            filter: filter.to_string(),
+        }),
+    }
+}
+
 fn standard_limits(mean: f64, std_dev: f64, sigmas: f64) -> (f64, f64) {
     // Keep values between
     // [mean - sigmas * std_dev, mean + sigmas * std_dev] (inclusive)
@@ -611,4 +773,174 @@ mod tests {
 
         assert_eq!(output_i128, expected);
     }
+
+    // Helper function which works with Rust floats, to remove RadonTypes from tests
+    fn fdev(
+        input_f64: &[f64],
+        max_deviation: f64,
+        filter: RadonFilters,
+    ) -> Result<Vec<f64>, RadError> {
+        let input = rfa(input_f64);
+        let extra_args = vec![Value::Float(max_deviation)];
+
+        let output =
+            median_deviation_filter(&input, &extra_args, &mut ReportContext::default(), filter)?;
+
+        let output_vec = match output {
+            RadonTypes::Array(x) => x.value(),
+            _ => panic!("Filter method should return a RadonArray"),
+        };
+        let output_f64 = output_vec
+            .into_iter()
+            .map(|r| match r {
+                RadonTypes::Float(x) => x.value(),
+                _ => panic!("Filter method should return an array of floats"),
+            })
+            .collect();
+
+        Ok(output_f64)
+    }
+
+    #[test]
+    fn test_filter_deviation_absolute_float() {
+        let input = vec![9.0, 10.0, 11.0, 30.0];
+        let expected = vec![9.0, 10.0, 11.0];
+
+        assert_eq!(
+            fdev(&input, 1.5, RadonFilters::DeviationAbsolute),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_filter_deviation_absolute_empty() {
+        let input = vec![];
+        let expected = vec![];
+
+        assert_eq!(
+            fdev(&input, 1.0, RadonFilters::DeviationAbsolute),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_filter_deviation_relative_float() {
+        let input = vec![100.0, 101.0, 200.0];
+        let expected = vec![100.0, 101.0];
+
+        assert_eq!(
+            fdev(&input, 0.05, RadonFilters::DeviationRelative),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_filter_deviation_relative_integer_tally() {
+        let input = RadonArray::from(vec![
+            RadonTypes::Integer(RadonInteger::from(1000)),
+            RadonTypes::Integer(RadonInteger::from(20)),
+            RadonTypes::Integer(RadonInteger::from(1010)),
+            RadonTypes::Integer(RadonInteger::from(990)),
+        ]);
+        let extra_args = vec![Value::Integer(0)];
+
+        let mut context = ReportContext::default();
+        context.stage = Stage::Tally(TallyMetaData::default());
+
+        // The median is 995, so no value is within a 0% margin
+        let output = relative_filter(&input, &extra_args, &mut context).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonArray::from(vec![])));
+
+        let extra_args = vec![Value::Float(0.02)];
+
+        let mut context = ReportContext::default();
+        context.stage = Stage::Tally(TallyMetaData::default());
+
+        let output = relative_filter(&input, &extra_args, &mut context).unwrap();
+        let expected = RadonTypes::from(RadonArray::from(vec![
+            RadonTypes::Integer(RadonInteger::from(1000)),
+            RadonTypes::Integer(RadonInteger::from(1010)),
+            RadonTypes::Integer(RadonInteger::from(990)),
+        ]));
+        assert_eq!(output, expected);
+
+        if let Stage::Tally(metadata) = context.stage {
+            assert_eq!(metadata.liars, vec![false, true, false, false]);
+        } else {
+            panic!("Not tally stage");
+        }
+    }
+
+    #[test]
+    fn test_filter_deviation_relative_array_of_floats() {
+        let array1 = rfa(&[100.0, 10.0]);
+        let array2 = rfa(&[101.0, 20.0]);
+        let array3 = rfa(&[102.0, 10.5]);
+        let input_vec: Vec<RadonTypes> = vec![
+            RadonTypes::Array(array1),
+            RadonTypes::Array(array2),
+            RadonTypes::Array(array3),
+        ];
+        let input = RadonArray::from(input_vec);
+        let extra_args = vec![Value::Float(0.1)];
+
+        let expected_vec: Vec<RadonTypes> = vec![
+            RadonTypes::Array(rfa(&[100.0, 10.0])),
+            RadonTypes::Array(rfa(&[102.0, 10.5])),
+        ];
+        let expected = RadonTypes::Array(RadonArray::from(expected_vec));
+
+        let mut context = ReportContext::default();
+        context.stage = Stage::Tally(TallyMetaData::default());
+
+        let output = relative_filter(&input, &extra_args, &mut context).unwrap();
+
+        assert_eq!(output, expected);
+
+        if let Stage::Tally(metadata) = context.stage {
+            assert_eq!(metadata.liars, vec![false, true, false]);
+        } else {
+            panic!("Not tally stage");
+        }
+    }
+
+    #[test]
+    fn test_filter_deviation_absolute_wrong_arg() {
+        let input = rfa(&[1.0, 2.0]);
+
+        for extra_args in vec![
+            vec![],
+            vec![Value::Text("1.0".to_string())],
+            vec![Value::Float(-1.0)],
+        ] {
+            let expected = RadError::WrongArguments {
+                input_type: RadonArray::radon_type_name(),
+                operator: RadonFilters::DeviationAbsolute.to_string(),
+                args: extra_args.clone(),
+            };
+
+            let result = absolute_filter(&input, &extra_args, &mut ReportContext::default());
+
+            assert_eq!(result.unwrap_err(), expected);
+        }
+    }
+
+    #[test]
+    fn test_filter_deviation_relative_unsupported_type() {
+        let input_vec: Vec<RadonTypes> = vec![
+            RadonTypes::String(RadonString::from("foo")),
+            RadonTypes::String(RadonString::from("bar")),
+        ];
+        let input = RadonArray::from(input_vec);
+        let extra_args = vec![Value::Float(1.0)];
+
+        let expected = RadError::UnsupportedFilter {
+            array: input.clone(),
+            filter: RadonFilters::DeviationRelative.to_string(),
+        };
+
+        let result = relative_filter(&input, &extra_args, &mut ReportContext::default());
+
+        assert_eq!(result.unwrap_err(), expected);
+    }
 }
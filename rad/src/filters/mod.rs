@@ -5,26 +5,26 @@ use serde_cbor::Value;
 
 use crate::error::RadError;
 use crate::types::{array::RadonArray, RadonType, RadonTypes};
-use witnet_data_structures::radon_report::ReportContext;
+use witnet_data_structures::radon_report::{ReportContext, Stage};
 
+pub mod compare;
 pub mod deviation;
 pub mod mode;
 
-#[derive(Debug, PartialEq, TryFromPrimitive)]
+/// Filters that can be applied by the `ArrayFilter` operator and in the aggregation and tally
+/// stages. The codes that are not listed in `filter` are reserved and not implemented yet.
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum RadonFilters {
-    // Implemented
-    DeviationStandard = 0x05,
-    Mode = 0x08,
-
-    // Not implemented
     GreaterThan = 0x00,
     LessThan = 0x01,
     Equals = 0x02,
     DeviationAbsolute = 0x03,
     DeviationRelative = 0x04,
+    DeviationStandard = 0x05,
     Top = 0x06,
     Bottom = 0x07,
+    Mode = 0x08,
     LessOrEqualThan = 0x80,
     GreaterOrEqualThan = 0x81,
     NotEquals = 0x82,
@@ -57,6 +57,15 @@ pub fn filter(
 
     if input.is_homogeneous() || input.value().is_empty() {
         match filter_code {
+            RadonFilters::GreaterThan => compare::greater_than_filter(input, extra_args, context),
+            RadonFilters::LessThan => compare::less_than_filter(input, extra_args, context),
+            RadonFilters::Equals => compare::equals_filter(input, extra_args, context),
+            RadonFilters::DeviationAbsolute => {
+                deviation::absolute_filter(input, extra_args, context)
+            }
+            RadonFilters::DeviationRelative => {
+                deviation::relative_filter(input, extra_args, context)
+            }
             RadonFilters::DeviationStandard => {
                 deviation::standard_filter(input, extra_args, context)
             }
@@ -70,3 +79,25 @@ pub fn filter(
         })
     }
 }
+
+/// Keeps the items of `input` that are flagged in `keep`. In tally stage, the items that are not
+/// kept are marked as liars.
+pub(crate) fn keep_items(
+    input: &RadonArray,
+    keep: &[bool],
+    context: &mut ReportContext<RadonTypes>,
+) -> RadonTypes {
+    let result: Vec<RadonTypes> = input
+        .value()
+        .into_iter()
+        .zip(keep.iter())
+        .filter(|(_, &keep)| keep)
+        .map(|(item, _)| item)
+        .collect();
+
+    if let Stage::Tally(ref mut metadata) = context.stage {
+        metadata.update_liars(keep.iter().map(|keep| !keep).collect());
+    }
+
+    RadonArray::from(result).into()
+}
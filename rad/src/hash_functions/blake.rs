@@ -0,0 +1,63 @@
+use blake2::{Blake2b, Blake2s};
+use blake_hash::{Blake256, Blake512};
+use digest::Digest;
+
+pub fn blake_256(input: &[u8]) -> Vec<u8> {
+    Blake256::digest(input).to_vec()
+}
+
+pub fn blake_512(input: &[u8]) -> Vec<u8> {
+    Blake512::digest(input).to_vec()
+}
+
+pub fn blake2s_256(input: &[u8]) -> Vec<u8> {
+    Blake2s::digest(input).to_vec()
+}
+
+pub fn blake2b_512(input: &[u8]) -> Vec<u8> {
+    Blake2b::digest(input).to_vec()
+}
+
+#[test]
+fn test_blake_256() {
+    // One block message test vector from the BLAKE specification
+    let output = blake_256(&[0]);
+    let expected =
+        hex::decode("0ce8d4ef4dd7cd8d62dfded9d4edb0a774ae6a41929a74da23109e8f11139c87").unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_blake_512() {
+    // One block message test vector from the BLAKE specification
+    let output = blake_512(&[0]);
+    let expected = hex::decode(
+        "97961587f6d970faba6d2478045de6d1fabd09b61ae50932054d52bc29d31be4\
+         ff9102b9f69e2bbdb83be13d4b9c06091e5fa0b48bd081b634058be0ec49beb3",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_blake2s_256() {
+    let output = blake2s_256(b"abc");
+    let expected =
+        hex::decode("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982").unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_blake2b_512() {
+    let output = blake2b_512(b"abc");
+    let expected = hex::decode(
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+         7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
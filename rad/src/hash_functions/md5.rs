@@ -0,0 +1,14 @@
+use ::md5::Md5;
+use digest::Digest;
+
+pub fn md5_128(input: &[u8]) -> Vec<u8> {
+    Md5::digest(input).to_vec()
+}
+
+#[test]
+fn test_md5_128() {
+    let output = md5_128(b"abc");
+    let expected = hex::decode("900150983cd24fb0d6963f7d28e17f72").unwrap();
+
+    assert_eq!(output, expected);
+}
@@ -3,11 +3,16 @@ use std::fmt;
 use num_enum::TryFromPrimitive;
 
 use crate::error::RadError;
-use crate::hash_functions::sha2::sha2_256;
 
+mod blake;
+mod md5;
+mod ripemd;
+mod sha1;
 mod sha2;
+mod sha3;
+mod whirlpool;
 
-#[derive(Debug, PartialEq, TryFromPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum RadonHashFunctions {
     Fail = 0xFF,
@@ -39,8 +44,25 @@ impl fmt::Display for RadonHashFunctions {
 
 pub fn hash(input: &[u8], hash_function_code: RadonHashFunctions) -> Result<Vec<u8>, RadError> {
     match hash_function_code {
-        RadonHashFunctions::SHA2_256 => Ok(sha2_256(input)),
-        _ => Err(RadError::UnsupportedHashFunction {
+        RadonHashFunctions::Blake256 => Ok(blake::blake_256(input)),
+        RadonHashFunctions::Blake512 => Ok(blake::blake_512(input)),
+        RadonHashFunctions::Blake2s256 => Ok(blake::blake2s_256(input)),
+        RadonHashFunctions::Blake2b512 => Ok(blake::blake2b_512(input)),
+        RadonHashFunctions::MD5_128 => Ok(md5::md5_128(input)),
+        RadonHashFunctions::Ripemd128 => Ok(ripemd::ripemd128(input)),
+        RadonHashFunctions::Ripemd160 => Ok(ripemd::ripemd160(input)),
+        RadonHashFunctions::Ripemd320 => Ok(ripemd::ripemd320(input)),
+        RadonHashFunctions::SHA1_160 => Ok(sha1::sha1_160(input)),
+        RadonHashFunctions::SHA2_224 => Ok(sha2::sha2_224(input)),
+        RadonHashFunctions::SHA2_256 => Ok(sha2::sha2_256(input)),
+        RadonHashFunctions::SHA2_384 => Ok(sha2::sha2_384(input)),
+        RadonHashFunctions::SHA2_512 => Ok(sha2::sha2_512(input)),
+        RadonHashFunctions::SHA3_224 => Ok(sha3::sha3_224(input)),
+        RadonHashFunctions::SHA3_256 => Ok(sha3::sha3_256(input)),
+        RadonHashFunctions::SHA3_384 => Ok(sha3::sha3_384(input)),
+        RadonHashFunctions::SHA3_512 => Ok(sha3::sha3_512(input)),
+        RadonHashFunctions::Whirlpool512 => Ok(whirlpool::whirlpool_512(input)),
+        RadonHashFunctions::Fail => Err(RadError::UnsupportedHashFunction {
             function: hash_function_code.to_string(),
         }),
     }
@@ -58,3 +80,44 @@ fn test_hash() {
 
     assert_eq!(output_slice, expected);
 }
+
+#[test]
+fn test_hash_output_lengths() {
+    let input = b"Hello, World!";
+    let expected_lengths = [
+        (RadonHashFunctions::Blake256, 32),
+        (RadonHashFunctions::Blake512, 64),
+        (RadonHashFunctions::Blake2s256, 32),
+        (RadonHashFunctions::Blake2b512, 64),
+        (RadonHashFunctions::MD5_128, 16),
+        (RadonHashFunctions::Ripemd128, 16),
+        (RadonHashFunctions::Ripemd160, 20),
+        (RadonHashFunctions::Ripemd320, 40),
+        (RadonHashFunctions::SHA1_160, 20),
+        (RadonHashFunctions::SHA2_224, 28),
+        (RadonHashFunctions::SHA2_256, 32),
+        (RadonHashFunctions::SHA2_384, 48),
+        (RadonHashFunctions::SHA2_512, 64),
+        (RadonHashFunctions::SHA3_224, 28),
+        (RadonHashFunctions::SHA3_256, 32),
+        (RadonHashFunctions::SHA3_384, 48),
+        (RadonHashFunctions::SHA3_512, 64),
+        (RadonHashFunctions::Whirlpool512, 64),
+    ];
+
+    for (hash_function_code, length) in expected_lengths.iter() {
+        let output = hash(input, *hash_function_code).unwrap();
+
+        assert_eq!(output.len(), *length, "{}", hash_function_code);
+    }
+}
+
+#[test]
+fn test_hash_fail() {
+    let output = hash(&[], RadonHashFunctions::Fail).unwrap_err();
+    let expected = RadError::UnsupportedHashFunction {
+        function: RadonHashFunctions::Fail.to_string(),
+    };
+
+    assert_eq!(output, expected);
+}
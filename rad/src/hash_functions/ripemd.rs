@@ -0,0 +1,247 @@
+use ::ripemd160::Ripemd160;
+use digest::Digest;
+
+// RIPEMD-128 and RIPEMD-320 are not available in the RustCrypto family of crates, so they are
+// implemented here following "RIPEMD-160: A Strengthened Version of RIPEMD", by Dobbertin,
+// Bosselaers and Preneel. Both share the message schedule and rotation amounts of RIPEMD-160.
+
+/// Message word selection for the left line
+const R_LEFT: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5,
+    2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8, 12, 4,
+    13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+/// Message word selection for the right line
+const R_RIGHT: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12,
+    4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11, 15, 0, 5,
+    12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// Rotation amounts for the left line
+const S_LEFT: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15,
+    9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14, 15, 14,
+    15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+/// Rotation amounts for the right line
+const S_RIGHT: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12,
+    7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11, 14, 14,
+    6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+const K_LEFT: [u32; 5] = [
+    0x0000_0000,
+    0x5A82_7999,
+    0x6ED9_EBA1,
+    0x8F1B_BCDC,
+    0xA953_FD4E,
+];
+const K_RIGHT_128: [u32; 4] = [0x50A2_8BE6, 0x5C4D_D124, 0x6D70_3EF3, 0x0000_0000];
+const K_RIGHT_320: [u32; 5] = [
+    0x50A2_8BE6,
+    0x5C4D_D124,
+    0x6D70_3EF3,
+    0x7A6D_76E9,
+    0x0000_0000,
+];
+
+pub fn ripemd160(input: &[u8]) -> Vec<u8> {
+    Ripemd160::digest(input).to_vec()
+}
+
+pub fn ripemd128(input: &[u8]) -> Vec<u8> {
+    let mut state: [u32; 4] = [0x6745_2301, 0xEFCD_AB89, 0x98BA_DCFE, 0x1032_5476];
+
+    for words in blocks(input) {
+        let [mut al, mut bl, mut cl, mut dl] = state;
+        let [mut ar, mut br, mut cr, mut dr] = state;
+
+        for step in 0..64 {
+            let round = step / 16;
+
+            let t = al
+                .wrapping_add(f(round, bl, cl, dl))
+                .wrapping_add(words[R_LEFT[step]])
+                .wrapping_add(K_LEFT[round])
+                .rotate_left(S_LEFT[step]);
+            al = dl;
+            dl = cl;
+            cl = bl;
+            bl = t;
+
+            // The right line uses the boolean functions in reverse order
+            let t = ar
+                .wrapping_add(f(3 - round, br, cr, dr))
+                .wrapping_add(words[R_RIGHT[step]])
+                .wrapping_add(K_RIGHT_128[round])
+                .rotate_left(S_RIGHT[step]);
+            ar = dr;
+            dr = cr;
+            cr = br;
+            br = t;
+        }
+
+        let t = state[1].wrapping_add(cl).wrapping_add(dr);
+        state[1] = state[2].wrapping_add(dl).wrapping_add(ar);
+        state[2] = state[3].wrapping_add(al).wrapping_add(br);
+        state[3] = state[0].wrapping_add(bl).wrapping_add(cr);
+        state[0] = t;
+    }
+
+    to_bytes(&state)
+}
+
+pub fn ripemd320(input: &[u8]) -> Vec<u8> {
+    let mut state: [u32; 10] = [
+        0x6745_2301,
+        0xEFCD_AB89,
+        0x98BA_DCFE,
+        0x1032_5476,
+        0xC3D2_E1F0,
+        0x7654_3210,
+        0xFEDC_BA98,
+        0x89AB_CDEF,
+        0x0123_4567,
+        0x3C2D_1E0F,
+    ];
+
+    for words in blocks(input) {
+        let mut left = [state[0], state[1], state[2], state[3], state[4]];
+        let mut right = [state[5], state[6], state[7], state[8], state[9]];
+
+        for step in 0..80 {
+            let round = step / 16;
+
+            let f_left = f(round, left[1], left[2], left[3]);
+            step_320(
+                &mut left,
+                f_left,
+                words[R_LEFT[step]],
+                K_LEFT[round],
+                S_LEFT[step],
+            );
+            // The right line uses the boolean functions in reverse order
+            let f_right = f(4 - round, right[1], right[2], right[3]);
+            step_320(
+                &mut right,
+                f_right,
+                words[R_RIGHT[step]],
+                K_RIGHT_320[round],
+                S_RIGHT[step],
+            );
+
+            // At the end of every round, one of the chaining variables is exchanged between the
+            // left and right lines, in the order B, D, A, C, E
+            if step % 16 == 15 {
+                let swapped = [1, 3, 0, 2, 4][round];
+                std::mem::swap(&mut left[swapped], &mut right[swapped]);
+            }
+        }
+
+        for (i, word) in left.iter().chain(right.iter()).enumerate() {
+            state[i] = state[i].wrapping_add(*word);
+        }
+    }
+
+    to_bytes(&state)
+}
+
+/// A single step of RIPEMD-320 over the `[A, B, C, D, E]` variables of one of the lines.
+fn step_320(vars: &mut [u32; 5], f: u32, word: u32, k: u32, s: u32) {
+    let t = vars[0]
+        .wrapping_add(f)
+        .wrapping_add(word)
+        .wrapping_add(k)
+        .rotate_left(s)
+        .wrapping_add(vars[4]);
+    vars[0] = vars[4];
+    vars[4] = vars[3];
+    vars[3] = vars[2].rotate_left(10);
+    vars[2] = vars[1];
+    vars[1] = t;
+}
+
+/// Boolean functions used in every round, from the first to the fifth.
+fn f(round: usize, x: u32, y: u32, z: u32) -> u32 {
+    match round {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+/// Pads the input following the MD4 scheme, and splits it into blocks of 16 little endian words.
+fn blocks(input: &[u8]) -> Vec<[u32; 16]> {
+    let bit_length = (input.len() as u64).wrapping_mul(8);
+
+    let mut message = input.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&bit_length.to_le_bytes());
+
+    message
+        .chunks(64)
+        .map(|block| {
+            let mut words = [0u32; 16];
+            for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+                *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            }
+            words
+        })
+        .collect()
+}
+
+fn to_bytes(state: &[u32]) -> Vec<u8> {
+    state
+        .iter()
+        .flat_map(|word| word.to_le_bytes().to_vec())
+        .collect()
+}
+
+#[test]
+fn test_ripemd128() {
+    let output = ripemd128(b"");
+    let expected = hex::decode("cdf26213a150dc3ecb610f18f6b38b46").unwrap();
+    assert_eq!(output, expected);
+
+    let output = ripemd128(b"abc");
+    let expected = hex::decode("c14a12199c66e4ba84636b0f69144c77").unwrap();
+    assert_eq!(output, expected);
+
+    let output = ripemd128(b"message digest");
+    let expected = hex::decode("9e327b3d6e523062afc1132d7df9d1b8").unwrap();
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_ripemd160() {
+    let output = ripemd160(b"abc");
+    let expected = hex::decode("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc").unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_ripemd320() {
+    let output = ripemd320(b"");
+    let expected = hex::decode(
+        "22d65d5661536cdc75c1fdf5c6de7b41b9f27325ebc61e8557177d705a0ec880151c3a32a00899b8",
+    )
+    .unwrap();
+    assert_eq!(output, expected);
+
+    let output = ripemd320(b"abc");
+    let expected = hex::decode(
+        "de4c01b3054f8930a79d09ae738e92301e5a17085beffdc1b8d116713e74f82fa942d64cdbc4682d",
+    )
+    .unwrap();
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,14 @@
+use ::sha1::Sha1;
+use digest::Digest;
+
+pub fn sha1_160(input: &[u8]) -> Vec<u8> {
+    Sha1::digest(input).to_vec()
+}
+
+#[test]
+fn test_sha1_160() {
+    let output = sha1_160(b"abc");
+    let expected = hex::decode("a9993e364706816aba3e25717850c26c9cd0d89d").unwrap();
+
+    assert_eq!(output, expected);
+}
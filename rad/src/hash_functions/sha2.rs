@@ -1,9 +1,31 @@
+use ::sha2::{Sha224, Sha384, Sha512};
+use digest::Digest;
 use witnet_crypto::hash::calculate_sha256;
 
+pub fn sha2_224(input: &[u8]) -> Vec<u8> {
+    Sha224::digest(input).to_vec()
+}
+
 pub fn sha2_256(input: &[u8]) -> Vec<u8> {
     calculate_sha256(input).as_ref().to_vec()
 }
 
+pub fn sha2_384(input: &[u8]) -> Vec<u8> {
+    Sha384::digest(input).to_vec()
+}
+
+pub fn sha2_512(input: &[u8]) -> Vec<u8> {
+    Sha512::digest(input).to_vec()
+}
+
+#[test]
+fn test_sha2_224() {
+    let output = sha2_224(b"abc");
+    let expected = hex::decode("23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7").unwrap();
+
+    assert_eq!(output, expected);
+}
+
 #[test]
 fn test_sha2_256() {
     let input = [72, 101, 108, 108, 111, 44, 32, 87, 111, 114, 108, 100, 33];
@@ -16,3 +38,27 @@ fn test_sha2_256() {
 
     assert_eq!(output_slice, expected);
 }
+
+#[test]
+fn test_sha2_384() {
+    let output = sha2_384(b"abc");
+    let expected = hex::decode(
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+         8086072ba1e7cc2358baeca134c825a7",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sha2_512() {
+    let output = sha2_512(b"abc");
+    let expected = hex::decode(
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,59 @@
+use ::sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+use digest::Digest;
+
+pub fn sha3_224(input: &[u8]) -> Vec<u8> {
+    Sha3_224::digest(input).to_vec()
+}
+
+pub fn sha3_256(input: &[u8]) -> Vec<u8> {
+    Sha3_256::digest(input).to_vec()
+}
+
+pub fn sha3_384(input: &[u8]) -> Vec<u8> {
+    Sha3_384::digest(input).to_vec()
+}
+
+pub fn sha3_512(input: &[u8]) -> Vec<u8> {
+    Sha3_512::digest(input).to_vec()
+}
+
+#[test]
+fn test_sha3_224() {
+    let output = sha3_224(b"abc");
+    let expected = hex::decode("e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf").unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sha3_256() {
+    let output = sha3_256(b"abc");
+    let expected =
+        hex::decode("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532").unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sha3_384() {
+    let output = sha3_384(b"abc");
+    let expected = hex::decode(
+        "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2\
+         98d88cea927ac7f539f1edf228376d25",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sha3_512() {
+    let output = sha3_512(b"abc");
+    let expected = hex::decode(
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+         10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,18 @@
+use ::whirlpool::Whirlpool;
+use digest::Digest;
+
+pub fn whirlpool_512(input: &[u8]) -> Vec<u8> {
+    Whirlpool::digest(input).to_vec()
+}
+
+#[test]
+fn test_whirlpool_512() {
+    let output = whirlpool_512(b"abc");
+    let expected = hex::decode(
+        "4e2448a4c6f486bb16b6562c73b4020bf3043e3a731bce721ae1b303d97e6d4c\
+         7181eebdb6c57e277d0e34957114cbd6c797fc9d95d8b582d225292076d4eef5",
+    )
+    .unwrap();
+
+    assert_eq!(output, expected);
+}
@@ -1,6 +1,13 @@
 //! # RAD Engine
 
 use futures::{executor::block_on, future::join_all};
+use isahc::{
+    http::{
+        header::{HeaderName, HeaderValue, USER_AGENT},
+        Request,
+    },
+    ResponseExt,
+};
 use serde::Serialize;
 pub use serde_cbor::to_vec as cbor_to_vec;
 pub use serde_cbor::Value as CborValue;
@@ -16,21 +23,29 @@ use crate::{
         create_radon_script_from_filters_and_reducer, execute_radon_script, unpack_radon_script,
         RadonScriptExecutionSettings,
     },
-    types::{array::RadonArray, string::RadonString, RadonTypes},
+    transport::RetrievalClient,
+    types::{array::RadonArray, bytes::RadonBytes, string::RadonString, RadonTypes},
     user_agents::UserAgent,
 };
 
+pub mod analysis;
+pub mod cache;
+pub mod compiler;
 pub mod error;
 pub mod filters;
 pub mod hash_functions;
 pub mod operators;
 pub mod reducers;
 pub mod script;
+pub mod transport;
 pub mod types;
 pub mod user_agents;
 
 pub type Result<T> = std::result::Result<T, RadError>;
 
+/// Number of random bytes contributed by each witness in a RNG retrieval.
+pub const RNG_BYTES_LENGTH: usize = 32;
+
 /// The return type of any method executing the entire life cycle of a data request.
 #[derive(Debug, Serialize)]
 pub struct RADRequestExecutionReport {
@@ -42,7 +57,8 @@ pub struct RADRequestExecutionReport {
     pub tally: RadonReport<RadonTypes>,
 }
 
-/// Executes a data request locally.
+/// Executes a data request locally, retrieving the data sources with a client that uses the
+/// default transport settings.
 /// The `inputs_injection` allows for disabling the actual retrieval of the data sources and
 /// the provided strings will be fed to the retrieval scripts instead. It is therefore expected that
 /// the length of `sources_injection` matches that of `request.retrieve`.
@@ -51,29 +67,104 @@ pub fn try_data_request(
     settings: RadonScriptExecutionSettings,
     inputs_injection: Option<&[&str]>,
 ) -> RADRequestExecutionReport {
-    let mut retrieval_context =
-        ReportContext::from_stage(Stage::Retrieval(RetrievalMetadata::default()));
-    let retrieve_responses = if let Some(inputs) = inputs_injection {
-        assert_eq!(inputs.len(), request.retrieve.len(), "Tried to locally run a data request with a number of injected sources different than the number of retrieval paths ({} != {})", inputs.len(), request.retrieve.len());
+    if let Some(inputs) = inputs_injection {
+        return try_data_request_with_inputs(request, settings, inputs);
+    }
 
-        request
-            .retrieve
-            .iter()
-            .zip(inputs.iter())
-            .map(|(retrieve, input)| {
-                run_retrieval_with_data_report(retrieve, input, &mut retrieval_context, settings)
-            })
-            .collect()
+    match RetrievalClient::with_defaults() {
+        Ok(client) => try_data_request_with_client(request, settings, None, &client),
+        Err(error) => {
+            // No data source can be retrieved without a client
+            let retrieve_responses = request
+                .retrieve
+                .iter()
+                .map(|_| Err(error.clone()))
+                .collect();
+
+            run_aggregation_and_tally_report(request, settings, retrieve_responses)
+        }
+    }
+}
+
+/// Executes a data request locally, retrieving the data sources with the given client.
+/// The `inputs_injection` allows for disabling the actual retrieval of the data sources and
+/// the provided strings will be fed to the retrieval scripts instead. It is therefore expected that
+/// the length of `sources_injection` matches that of `request.retrieve`.
+pub fn try_data_request_with_client(
+    request: &RADRequest,
+    settings: RadonScriptExecutionSettings,
+    inputs_injection: Option<&[&str]>,
+    client: &RetrievalClient,
+) -> RADRequestExecutionReport {
+    if let Some(inputs) = inputs_injection {
+        try_data_request_with_inputs(request, settings, inputs)
     } else {
-        block_on(join_all(
+        let retrieve_responses = block_on(join_all(
             request
                 .retrieve
                 .iter()
-                .map(|retrieve| run_retrieval_report(retrieve, settings))
+                .map(|retrieve| run_retrieval_report(retrieve, settings, client))
                 .collect::<Vec<_>>(),
-        ))
-    };
+        ));
+
+        run_aggregation_and_tally_report(request, settings, retrieve_responses)
+    }
+}
+
+/// Executes a data request feeding the given strings to the retrieval scripts instead of
+/// retrieving the data sources.
+fn try_data_request_with_inputs(
+    request: &RADRequest,
+    settings: RadonScriptExecutionSettings,
+    inputs: &[&str],
+) -> RADRequestExecutionReport {
+    assert_eq!(inputs.len(), request.retrieve.len(), "Tried to locally run a data request with a number of injected sources different than the number of retrieval paths ({} != {})", inputs.len(), request.retrieve.len());
+
+    let inputs = request
+        .retrieve
+        .iter()
+        .zip(inputs.iter())
+        .map(|(retrieve, input)| injected_input(retrieve, input))
+        .collect();
+
+    replay_data_request(request, settings, inputs)
+}
+
+/// Executes a data request fully offline, feeding the given inputs to the retrieval scripts as if
+/// they had been obtained from the sources. Replaying the recorded inputs of a data request always
+/// produces the same reports.
+///
+/// The length of `inputs` must match that of `request.retrieve`.
+pub fn replay_data_request(
+    request: &RADRequest,
+    settings: RadonScriptExecutionSettings,
+    inputs: Vec<RadonTypes>,
+) -> RADRequestExecutionReport {
+    assert_eq!(inputs.len(), request.retrieve.len(), "Tried to replay a data request with a number of inputs different than the number of retrieval paths ({} != {})", inputs.len(), request.retrieve.len());
 
+    let retrieve_responses = request
+        .retrieve
+        .iter()
+        .zip(inputs)
+        .map(|(retrieve, input)| {
+            let mut context =
+                ReportContext::from_stage(Stage::Retrieval(RetrievalMetadata::default()));
+            run_retrieval_script_report(retrieve, input, &mut context, settings)
+        })
+        .collect();
+
+    run_aggregation_and_tally_report(request, settings, retrieve_responses)
+}
+
+/// Aggregate the results of the retrievals of a data request, and run the tally on the result of
+/// the aggregation as if it had been revealed by a single witness.
+fn run_aggregation_and_tally_report(
+    request: &RADRequest,
+    settings: RadonScriptExecutionSettings,
+    retrieve_responses: Vec<Result<RadonReport<RadonTypes>>>,
+) -> RADRequestExecutionReport {
+    let retrieval_context =
+        ReportContext::from_stage(Stage::Retrieval(RetrievalMetadata::default()));
     let retrieval_reports: Vec<RadonReport<RadonTypes>> = retrieve_responses
         .into_iter()
         .map(|retrieve| {
@@ -112,6 +203,15 @@ pub fn try_data_request(
     }
 }
 
+/// Input of the script of a retrieval, built from a response that was not obtained from its
+/// source.
+pub fn injected_input(retrieve: &RADRetrieve, response: &str) -> RadonTypes {
+    match retrieve.kind {
+        RADType::HttpGet | RADType::HttpPost => RadonTypes::from(RadonString::from(response)),
+        RADType::Rng => RadonTypes::from(RadonBytes::from(response.as_bytes().to_vec())),
+    }
+}
+
 /// Run retrieval without performing any external network requests, return `RadonReport`.
 pub fn run_retrieval_with_data_report(
     retrieve: &RADRetrieve,
@@ -119,14 +219,24 @@ pub fn run_retrieval_with_data_report(
     context: &mut ReportContext<RadonTypes>,
     settings: RadonScriptExecutionSettings,
 ) -> Result<RadonReport<RadonTypes>> {
-    match retrieve.kind {
-        RADType::HttpGet => {
-            let input = RadonTypes::from(RadonString::from(response));
-            let radon_script = unpack_radon_script(&retrieve.script)?;
+    run_retrieval_script_report(
+        retrieve,
+        injected_input(retrieve, response),
+        context,
+        settings,
+    )
+}
 
-            execute_radon_script(input, &radon_script, context, settings)
-        }
-    }
+/// Run the script of a retrieval on the input obtained from its source, return `RadonReport`.
+pub fn run_retrieval_script_report(
+    retrieve: &RADRetrieve,
+    input: RadonTypes,
+    context: &mut ReportContext<RadonTypes>,
+    settings: RadonScriptExecutionSettings,
+) -> Result<RadonReport<RadonTypes>> {
+    let radon_script = unpack_radon_script(&retrieve.script)?;
+
+    execute_radon_script(input, &radon_script, context, settings)
 }
 
 /// Run retrieval without performing any external network requests, return `RadonTypes`.
@@ -140,70 +250,143 @@ pub fn run_retrieval_with_data(
         .map(RadonReport::into_inner)
 }
 
+/// Parse and validate the extra headers of a retrieval, so that they can be safely attached to
+/// an HTTP request.
+pub fn parse_headers(headers: &[(String, String)]) -> Result<Vec<(HeaderName, HeaderValue)>> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let invalid_header = || RadError::InvalidHttpHeader {
+                name: name.clone(),
+                value: value.clone(),
+            };
+            let header_name =
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid_header())?;
+            let header_value = HeaderValue::from_str(value).map_err(|_| invalid_header())?;
+
+            Ok((header_name, header_value))
+        })
+        .collect()
+}
+
+/// Perform the HTTP request described by a `RADRetrieve` and return the body of the response.
+async fn http_response(retrieve: &RADRetrieve, client: &RetrievalClient) -> Result<String> {
+    let url = url::Url::parse(&retrieve.url).map_err(|err| RadError::UrlParseError {
+        inner: err,
+        url: retrieve.url.clone(),
+    })?;
+    let headers = parse_headers(&retrieve.headers)?;
+
+    let (builder, body) = match retrieve.kind {
+        RADType::HttpGet => (Request::get(url.as_str()), vec![]),
+        RADType::HttpPost => (Request::post(url.as_str()), retrieve.body.clone()),
+        RADType::Rng => {
+            return Err(RadError::UnknownRetrieval {
+                kind: format!("{:?}", retrieve.kind),
+            })
+        }
+    };
+    let mut request = client
+        .configure_request(builder, &url)
+        .await?
+        .body(body)
+        .map_err(|x| RadError::HttpOther {
+            message: x.to_string(),
+        })?;
+
+    // Set a random user-agent from the list. The extra headers are applied afterwards, so a data
+    // request is allowed to override the user-agent as well
+    let request_headers = request.headers_mut();
+    request_headers.insert(USER_AGENT, HeaderValue::from_static(UserAgent::random()));
+    for (name, value) in headers {
+        request_headers.insert(name, value);
+    }
+
+    let mut response = client
+        .http_client()
+        .send_async(request)
+        .await
+        .map_err(|x| match x {
+            isahc::Error::Timeout => RadError::RetrieveTimeout,
+            x => RadError::HttpOther {
+                message: x.to_string(),
+            },
+        })?;
+
+    if !response.status().is_success() {
+        return Err(RadError::HttpStatus {
+            status_code: response.status().into(),
+        });
+    }
+
+    response
+        // TODO: replace with .bytes() and let RADON handle the encoding?
+        .text_async()
+        .await
+        .map_err(|x| RadError::HttpOther {
+            message: x.to_string(),
+        })
+}
+
+/// Obtain the input of the script of a retrieval from its source, without running the script.
+///
+/// This is the only part of the retrieval stage that performs I/O, so that the script can later be
+/// executed elsewhere, e.g. in a thread that is allowed to block.
+pub async fn retrieve_input(
+    retrieve: &RADRetrieve,
+    client: &RetrievalClient,
+) -> Result<RadonTypes> {
+    match retrieve.kind {
+        RADType::HttpGet | RADType::HttpPost => {
+            let response_string = http_response(retrieve, client).await?;
+
+            Ok(RadonTypes::from(RadonString::from(response_string)))
+        }
+        RADType::Rng => {
+            // Every witness contributes its own local entropy, which is later combined with that
+            // of the other witnesses in the tally stage
+            let random_bytes: [u8; RNG_BYTES_LENGTH] = rand::random();
+
+            Ok(RadonTypes::from(RadonBytes::from(random_bytes.to_vec())))
+        }
+    }
+}
+
 /// Run retrieval stage of a data request, return `RadonReport`.
 pub async fn run_retrieval_report(
     retrieve: &RADRetrieve,
     settings: RadonScriptExecutionSettings,
+    client: &RetrievalClient,
 ) -> Result<RadonReport<RadonTypes>> {
     let context = &mut ReportContext::from_stage(Stage::Retrieval(RetrievalMetadata::default()));
+    let input = retrieve_input(retrieve, client).await?;
 
-    match retrieve.kind {
-        RADType::HttpGet => {
-            // Validate URL because surf::get panics on invalid URL
-            // It could still panic if surf gets updated and changes their URL parsing library
-            let _valid_url =
-                url::Url::parse(&retrieve.url).map_err(|err| RadError::UrlParseError {
-                    inner: err,
-                    url: retrieve.url.clone(),
-                })?;
-
-            // Set a random user-agent from the list
-            let mut response = surf::get(&retrieve.url)
-                .set_header("User-Agent", UserAgent::random())
-                .await
-                .map_err(|x| RadError::HttpOther {
-                    message: x.to_string(),
-                })?;
-
-            if !response.status().is_success() {
-                return Err(RadError::HttpStatus {
-                    status_code: response.status().into(),
-                });
-            }
-
-            let response_string = response
-                // TODO: replace with .body_bytes() and let RADON handle the encoding?
-                .body_string()
-                .await
-                .map_err(|x| RadError::HttpOther {
-                    message: x.to_string(),
-                })?;
-
-            let result =
-                run_retrieval_with_data_report(retrieve, &response_string, context, settings);
-
-            match &result {
-                Ok(report) => {
-                    log::debug!(
-                        "Successful result for source {}: {:?}",
-                        retrieve.url,
-                        report.result
-                    );
-                }
-                Err(e) => log::debug!("Failed result for source {}: {:?}", retrieve.url, e),
-            }
+    let result = run_retrieval_script_report(retrieve, input, context, settings);
 
-            result
+    match &result {
+        Ok(report) => {
+            log::debug!(
+                "Successful result for source {}: {:?}",
+                retrieve.url,
+                report.result
+            );
         }
+        Err(e) => log::debug!("Failed result for source {}: {:?}", retrieve.url, e),
     }
+
+    result
 }
 
 /// Run retrieval stage of a data request, return `RadonTypes`.
-pub async fn run_retrieval(retrieve: &RADRetrieve) -> Result<RadonTypes> {
+pub async fn run_retrieval(retrieve: &RADRetrieve, client: &RetrievalClient) -> Result<RadonTypes> {
     // Disable all execution tracing features, as this is the best-effort version of this method
-    run_retrieval_report(retrieve, RadonScriptExecutionSettings::disable_all())
-        .await
-        .map(RadonReport::into_inner)
+    run_retrieval_report(
+        retrieve,
+        RadonScriptExecutionSettings::disable_all(),
+        client,
+    )
+    .await
+    .map(RadonReport::into_inner)
 }
 
 /// Run aggregate stage of a data request, return `RadonReport`.
@@ -316,7 +499,7 @@ mod tests {
         filters::RadonFilters,
         operators::RadonOpCodes,
         reducers::RadonReducers,
-        types::{float::RadonFloat, integer::RadonInteger},
+        types::{float::RadonFloat, integer::RadonInteger, RadonType},
     };
 
     use super::*;
@@ -340,6 +523,8 @@ mod tests {
             kind: RADType::HttpGet,
             url: "https://openweathermap.org/data/2.5/weather?id=2950159&appid=b6907d289e10d714a6e88b30761fae22".to_string(),
             script: packed_script_r,
+            body: vec![],
+            headers: vec![],
         };
         let response = r#"{"coord":{"lon":13.41,"lat":52.52},"weather":[{"id":500,"main":"Rain","description":"light rain","icon":"10d"}],"base":"stations","main":{"temp":17.59,"pressure":1022,"humidity":67,"temp_min":15,"temp_max":20},"visibility":10000,"wind":{"speed":3.6,"deg":260},"rain":{"1h":0.51},"clouds":{"all":20},"dt":1567501321,"sys":{"type":1,"id":1275,"message":0.0089,"country":"DE","sunrise":1567484402,"sunset":1567533129},"timezone":7200,"id":2950159,"name":"Berlin","cod":200}"#;
 
@@ -356,6 +541,170 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_retrieval_xml() {
+        let script_r = Value::Array(vec![
+            Value::Integer(RadonOpCodes::StringParseXML as i128),
+            Value::Array(vec![
+                Value::Integer(RadonOpCodes::MapGetPath as i128),
+                Value::Text("current/temperature/@value".to_string()),
+            ]),
+            Value::Integer(RadonOpCodes::StringAsFloat as i128),
+        ]);
+        let packed_script_r = serde_cbor::to_vec(&script_r).unwrap();
+
+        let retrieve = RADRetrieve {
+            kind: RADType::HttpGet,
+            url: "https://api.openweathermap.org/data/2.5/weather?q=Berlin&mode=xml".to_string(),
+            script: packed_script_r,
+            body: vec![],
+            headers: vec![],
+        };
+        let response = r#"<?xml version="1.0" encoding="UTF-8"?><current><city id="2950159" name="Berlin"><country>DE</country></city><temperature value="17.59" min="15" max="20" unit="celsius"></temperature><humidity value="67" unit="%"></humidity></current>"#;
+
+        let result = run_retrieval_with_data(
+            &retrieve,
+            response,
+            RadonScriptExecutionSettings::disable_all(),
+        )
+        .unwrap();
+
+        assert_eq!(result, RadonTypes::Float(RadonFloat::from(17.59)));
+    }
+
+    #[test]
+    fn test_run_retrieval_http_post() {
+        let script_r = Value::Array(vec![
+            Value::Integer(RadonOpCodes::StringParseJSONMap as i128),
+            Value::Array(vec![
+                Value::Integer(RadonOpCodes::MapGetMap as i128),
+                Value::Text("data".to_string()),
+            ]),
+            Value::Array(vec![
+                Value::Integer(RadonOpCodes::MapGetFloat as i128),
+                Value::Text("price".to_string()),
+            ]),
+        ]);
+        let packed_script_r = serde_cbor::to_vec(&script_r).unwrap();
+
+        let retrieve = RADRetrieve {
+            kind: RADType::HttpPost,
+            url: "https://api.example.com/graphql".to_string(),
+            script: packed_script_r,
+            body: br#"{"query":"{ price }"}"#.to_vec(),
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+        };
+        let response = r#"{"data":{"price":9724.8354}}"#;
+
+        let result = run_retrieval_with_data(
+            &retrieve,
+            response,
+            RadonScriptExecutionSettings::disable_all(),
+        )
+        .unwrap();
+
+        assert_eq!(result, RadonTypes::Float(RadonFloat::from(9724.8354)));
+    }
+
+    #[test]
+    fn test_run_retrieval_rng() {
+        let retrieve = RADRetrieve {
+            kind: RADType::Rng,
+            url: String::new(),
+            script: vec![128],
+            body: vec![],
+            headers: vec![],
+        };
+
+        let client = RetrievalClient::with_defaults().unwrap();
+        let first = block_on(run_retrieval(&retrieve, &client)).unwrap();
+        let second = block_on(run_retrieval(&retrieve, &client)).unwrap();
+
+        match (&first, &second) {
+            (RadonTypes::Bytes(first_bytes), RadonTypes::Bytes(second_bytes)) => {
+                assert_eq!(first_bytes.value().len(), RNG_BYTES_LENGTH);
+                assert_eq!(second_bytes.value().len(), RNG_BYTES_LENGTH);
+            }
+            _ => panic!(
+                "RNG retrieval should return RadonBytes: {:?}",
+                (first, second)
+            ),
+        }
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_run_all_rng() {
+        let retrieve = RADRetrieve {
+            kind: RADType::Rng,
+            url: String::new(),
+            script: vec![128],
+            body: vec![],
+            headers: vec![],
+        };
+        let aggregate = RADAggregate {
+            filters: vec![],
+            reducer: RadonReducers::Mode as u32,
+        };
+        let tally = RADTally {
+            filters: vec![],
+            reducer: RadonReducers::HashConcatenate as u32,
+        };
+
+        // Every witness retrieves and aggregates its own entropy
+        let reveals = ["Hello,", " World!"]
+            .iter()
+            .map(|entropy| {
+                let retrieved = run_retrieval_with_data(
+                    &retrieve,
+                    entropy,
+                    RadonScriptExecutionSettings::disable_all(),
+                )
+                .unwrap();
+
+                run_aggregation(vec![retrieved], &aggregate).unwrap()
+            })
+            .collect();
+        let tallied = run_tally(reveals, &tally).unwrap();
+
+        // SHA2-256 of "Hello, World!"
+        let expected = RadonTypes::from(RadonBytes::from(vec![
+            223, 253, 96, 33, 187, 43, 213, 176, 175, 103, 98, 144, 128, 158, 195, 165, 49, 145,
+            221, 129, 199, 247, 10, 75, 40, 104, 138, 54, 33, 130, 152, 111,
+        ]));
+        assert_eq!(tallied, expected);
+    }
+
+    #[test]
+    fn test_parse_headers() {
+        let headers = vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("X-Api-Key".to_string(), "secret".to_string()),
+        ];
+        let parsed = parse_headers(&headers).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].0, "content-type");
+        assert_eq!(parsed[1].1, "secret");
+
+        let invalid_name = vec![("Content Type".to_string(), "text".to_string())];
+        assert_eq!(
+            parse_headers(&invalid_name).unwrap_err(),
+            RadError::InvalidHttpHeader {
+                name: "Content Type".to_string(),
+                value: "text".to_string(),
+            }
+        );
+
+        let invalid_value = vec![("X-Api-Key".to_string(), "line\nbreak".to_string())];
+        assert_eq!(
+            parse_headers(&invalid_value).unwrap_err(),
+            RadError::InvalidHttpHeader {
+                name: "X-Api-Key".to_string(),
+                value: "line\nbreak".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn test_run_consensus_and_aggregation() {
         let f_1 = RadonTypes::Float(RadonFloat::from(1f64));
@@ -394,6 +743,8 @@ mod tests {
             kind: RADType::HttpGet,
             url: "https://wrapapi.com/use/aesedepece/ffzz/prima/0.0.3?wrapAPIKey=ql4DVWylABdXCpt1NUTLNEDwPH57aHGm".to_string(),
             script: packed_script_r,
+            body: vec![],
+            headers: vec![],
         };
         let response = "84";
         let expected = RadonTypes::Float(RadonFloat::from(84));
@@ -428,6 +779,8 @@ mod tests {
             kind: RADType::HttpGet,
             url: "https://wrapapi.com/use/aesedepece/ffzz/murders/0.0.2?wrapAPIKey=ql4DVWylABdXCpt1NUTLNEDwPH57aHGm".to_string(),
             script: packed_script_r,
+            body: vec![],
+            headers: vec![],
         };
         let response = "307";
         let expected = RadonTypes::Float(RadonFloat::from(307));
@@ -453,6 +806,65 @@ mod tests {
         assert_eq!(tallied, expected);
     }
 
+    #[test]
+    fn test_replay_data_request() {
+        let script_r = Value::Array(vec![Value::Integer(RadonOpCodes::StringAsFloat as i128)]);
+        let retrieve = RADRetrieve {
+            kind: RADType::HttpGet,
+            url: "https://example.com/murders".to_string(),
+            script: serde_cbor::to_vec(&script_r).unwrap(),
+            body: vec![],
+            headers: vec![],
+        };
+        let request = RADRequest {
+            time_lock: 0,
+            retrieve: vec![retrieve.clone(), retrieve],
+            aggregate: RADAggregate {
+                filters: vec![],
+                reducer: RadonReducers::AverageMean as u32,
+            },
+            tally: RADTally {
+                filters: vec![],
+                reducer: RadonReducers::AverageMean as u32,
+            },
+        };
+        let inputs = || {
+            vec![
+                RadonTypes::from(RadonString::from("300")),
+                RadonTypes::from(RadonString::from("310")),
+            ]
+        };
+
+        let report = replay_data_request(
+            &request,
+            RadonScriptExecutionSettings::enable_all(),
+            inputs(),
+        );
+        assert_eq!(
+            report.retrieve[1].partial_results,
+            Some(vec![
+                RadonTypes::from(RadonString::from("310")),
+                RadonTypes::Float(RadonFloat::from(310)),
+            ])
+        );
+        assert_eq!(
+            report.aggregate.result,
+            RadonTypes::Float(RadonFloat::from(305))
+        );
+        assert_eq!(
+            report.tally.result,
+            RadonTypes::Float(RadonFloat::from(305))
+        );
+
+        // Replaying the same inputs always yields the same results
+        let replayed = replay_data_request(
+            &request,
+            RadonScriptExecutionSettings::enable_all(),
+            inputs(),
+        );
+        assert_eq!(replayed.tally.result, report.tally.result);
+    }
+
     #[test]
     fn test_run_all_air_quality() {
         let script_r = Value::Array(vec![
@@ -476,6 +888,8 @@ mod tests {
             kind: RADType::HttpGet,
             url: "http://airemadrid.herokuapp.com/api/estacion".to_string(),
             script: packed_script_r,
+            body: vec![],
+            headers: vec![],
         };
         // This response was modified because the original was about 100KB.
         let response = r#"[{"estacion_nombre":"Pza. de España","estacion_numero":4,"fecha":"03092019","hora0":{"estado":"Pasado","valor":"00008"}}]"#;
@@ -517,6 +931,8 @@ mod tests {
             kind: RADType::HttpGet,
             url: "https://wrapapi.com/use/aesedepece/ffzz/generales/0.0.3?wrapAPIKey=ql4DVWylABdXCpt1NUTLNEDwPH57aHGm".to_string(),
             script: packed_script_r,
+            body: vec![],
+            headers: vec![],
         };
         let response = r#"{"PSOE":123,"PP":66,"Cs":57,"UP":42,"VOX":24,"ERC-SOBIRANISTES":15,"JxCAT-JUNTS":7,"PNV":6,"EH Bildu":4,"CCa-PNC":2,"NA+":2,"COMPROMÍS 2019":1,"PRC":1,"PACMA":0,"FRONT REPUBLICÀ":0,"BNG":0,"RECORTES CERO-GV":0,"NCa":0,"PACT":0,"ARA-MES-ESQUERRA":0,"GBAI":0,"PUM+J":0,"EN MAREA":0,"PCTE":0,"EL PI":0,"AxSI":0,"PCOE":0,"PCPE":0,"AVANT ADELANTE LOS VERDES":0,"EB":0,"CpM":0,"SOMOS REGIÓN":0,"PCPA":0,"PH":0,"UIG-SOM-CUIDES":0,"ERPV":0,"IZQP":0,"PCPC":0,"AHORA CANARIAS":0,"CxG":0,"PPSO":0,"CNV":0,"PREPAL":0,"C.Ex-C.R.Ex-P.R.Ex":0,"PR+":0,"P-LIB":0,"CILU-LINARES":0,"ANDECHA ASTUR":0,"JF":0,"PYLN":0,"FIA":0,"FE de las JONS":0,"SOLIDARIA":0,"F8":0,"DPL":0,"UNIÓN REGIONALISTA":0,"centrados":0,"DP":0,"VOU":0,"PDSJE-UDEC":0,"IZAR":0,"RISA":0,"C 21":0,"+MAS+":0,"UDT":0}"#;
         let expected = RadonTypes::Float(RadonFloat::from(123));
@@ -568,6 +984,8 @@ mod tests {
             kind: RADType::HttpGet,
             url: "https://www.sofascore.com/event/8397714/json".to_string(),
             script: packed_script_r,
+            body: vec![],
+            headers: vec![],
         };
         let response = r#"{"event":{"homeTeam":{"name":"Ryazan-VDV","slug":"ryazan-vdv","gender":"F","national":false,"id":171120,"shortName":"Ryazan-VDV","subTeams":[]},"awayTeam":{"name":"Olympique Lyonnais","slug":"olympique-lyonnais","gender":"F","national":false,"id":26245,"shortName":"Lyon","subTeams":[]},"homeScore":{"current":0,"display":0,"period1":0,"normaltime":0},"awayScore":{"current":9,"display":9,"period1":5,"normaltime":9}}}"#;
         let retrieved = run_retrieval_with_data(
@@ -755,6 +1173,45 @@ mod tests {
         assert_eq!(tally_metadata.liars, expected_liars);
     }
 
+    #[test]
+    fn test_run_consensus_with_liar_deviation_relative() {
+        let f_1 = RadonTypes::Float(RadonFloat::from(100f64));
+        let f_2 = RadonTypes::Float(RadonFloat::from(101f64));
+        let f_3 = RadonTypes::Float(RadonFloat::from(102f64));
+        let f_out = RadonTypes::Float(RadonFloat::from(300f64));
+
+        let radon_types_vec = vec![f_1, f_2, f_3, f_out];
+
+        let expected = RadonTypes::Float(RadonFloat::from(101f64));
+
+        let report = run_tally_report(
+            radon_types_vec,
+            &RADTally {
+                filters: vec![RADFilter {
+                    op: RadonFilters::DeviationRelative as u32,
+                    // 0.1 encoded as a CBOR float
+                    args: vec![251, 63, 185, 153, 153, 153, 153, 153, 154],
+                }],
+                reducer: RadonReducers::AverageMedian as u32,
+            },
+            None,
+            None,
+            RadonScriptExecutionSettings::disable_all(),
+        )
+        .unwrap();
+
+        let output_tally = report.clone().into_inner();
+        assert_eq!(output_tally, expected);
+
+        let expected_liars = vec![false, false, false, true];
+        let tally_metadata = if let Stage::Tally(tm) = report.context.stage {
+            tm
+        } else {
+            panic!("No tally stage");
+        };
+        assert_eq!(tally_metadata.liars, expected_liars);
+    }
+
     #[test]
     fn test_mode_reducer_not_affecting_liars() {
         let f_1 = RadonTypes::Float(RadonFloat::from(1f64));
@@ -952,7 +1409,11 @@ mod tests {
     #[test]
     fn test_header_correctly_set() {
         let test_header = UserAgent::random();
-        let req = surf::get("https://httpbin.org/get?page=2").set_header("User-Agent", test_header);
-        assert_eq!(req.header("User-Agent"), Some(test_header));
+        let mut req = Request::get("https://httpbin.org/get?page=2")
+            .body(())
+            .unwrap();
+        req.headers_mut()
+            .insert(USER_AGENT, HeaderValue::from_static(test_header));
+        assert_eq!(req.headers()[USER_AGENT], test_header);
     }
 }
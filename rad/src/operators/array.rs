@@ -97,6 +97,130 @@ pub fn get_string(input: &RadonArray, args: &[Value]) -> Result<RadonString, Rad
     item.try_into()
 }
 
+/// Flattens nested arrays into their parent array, as many levels deep as the optional `depth`
+/// argument says (1 by default). Items that are not arrays are left untouched.
+pub fn flatten(input: &RadonArray, args: &[Value]) -> Result<RadonArray, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonArray::radon_type_name(),
+        operator: "Flatten".to_string(),
+        args: args.to_vec(),
+    };
+
+    let depth = match args {
+        [] => 1,
+        [arg] => from_value::<u32>(arg.to_owned()).map_err(|_| wrong_args())?,
+        _ => return Err(wrong_args()),
+    };
+
+    Ok(flatten_depth(input.value(), depth))
+}
+
+fn flatten_depth(items: Vec<RadonTypes>, depth: u32) -> RadonArray {
+    if depth == 0 {
+        return RadonArray::from(items);
+    }
+
+    let flattened = items
+        .into_iter()
+        .flat_map(|item| match item {
+            RadonTypes::Array(array) => flatten_depth(array.value(), depth - 1).value(),
+            item => vec![item],
+        })
+        .collect::<Vec<RadonTypes>>();
+
+    RadonArray::from(flattened)
+}
+
+/// Takes a slice of the input. With a single `count` argument, it takes the first `count` items.
+/// With `start` and `count` arguments, it takes `count` items beginning at `start`, which counts
+/// from the end of the array if negative. Slices going past the end of the array are truncated.
+pub fn take(input: &RadonArray, args: &[Value]) -> Result<RadonArray, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonArray::radon_type_name(),
+        operator: "Take".to_string(),
+        args: args.to_vec(),
+    };
+
+    let (start, count) = match args {
+        [count] => (0, count),
+        [start, count] => (
+            from_value::<i64>(start.to_owned()).map_err(|_| wrong_args())?,
+            count,
+        ),
+        _ => return Err(wrong_args()),
+    };
+    let count = from_value::<u32>(count.to_owned()).map_err(|_| wrong_args())?;
+
+    let value = input.value();
+    let len = i64::try_from(value.len()).map_err(|_| wrong_args())?;
+    let start = if start < 0 {
+        (len + start).max(0)
+    } else {
+        start.min(len)
+    };
+    // `start` is now within `0..=len`, so it always fits into an `usize`
+    let start = usize::try_from(start).unwrap();
+
+    Ok(RadonArray::from(
+        value
+            .into_iter()
+            .skip(start)
+            .take(usize::try_from(count).unwrap_or(usize::MAX))
+            .collect::<Vec<RadonTypes>>(),
+    ))
+}
+
+/// Tells whether the subscript given as argument returns `true` for at least one of the items.
+pub fn some(
+    input: &RadonArray,
+    args: &[Value],
+    context: &mut ReportContext<RadonTypes>,
+) -> Result<RadonTypes, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonArray::radon_type_name(),
+        operator: "Some".to_string(),
+        args: args.to_vec(),
+    };
+
+    if args.len() != 1 {
+        return Err(wrong_args());
+    }
+
+    let subscript_err = |e| RadError::Subscript {
+        input_type: "RadonArray".to_string(),
+        operator: "Some".to_string(),
+        inner: Box::new(e),
+    };
+    let subscript = unpack_subscript(&args[0]).map_err(subscript_err)?;
+
+    let mut reports = vec![];
+    let mut result = false;
+
+    let settings = RadonScriptExecutionSettings::tailored_to_stage(&context.stage);
+    for item in input.value() {
+        let report = execute_radon_script(item, subscript.as_slice(), context, settings)?;
+
+        // If there is an error while evaluating the items, short-circuit and bubble up the error
+        // as it comes from the radon script execution
+        match &report.result {
+            RadonTypes::RadonError(error) => return Err(error.clone().into_inner()),
+            RadonTypes::Boolean(boolean) => result |= boolean.value(),
+            other => {
+                return Err(RadError::ArraySomeWrongSubscript {
+                    value: other.to_string(),
+                })
+            }
+        }
+
+        reports.push(report);
+    }
+
+    // Extract the partial results from the reports and put them in the execution context if needed
+    partial_results_extract(&subscript, &reports, context);
+
+    Ok(RadonBoolean::from(result).into())
+}
+
 pub fn map(
     input: &RadonArray,
     args: &[Value],
@@ -832,6 +956,179 @@ mod tests {
         assert_eq!(output, expected)
     }
 
+    #[test]
+    fn test_flatten() {
+        let inner = RadonArray::from(vec![
+            RadonInteger::from(3).into(),
+            RadonArray::from(vec![RadonInteger::from(4).into()]).into(),
+        ]);
+        let input = RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonArray::from(vec![RadonInteger::from(2).into(), inner.into()]).into(),
+        ]);
+
+        let output = flatten(&input, &[]).unwrap();
+        let expected = RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonInteger::from(2).into(),
+            RadonInteger::from(3).into(),
+            RadonArray::from(vec![RadonInteger::from(4).into()]).into(),
+        ]);
+        assert_eq!(output, expected);
+
+        let output = flatten(&input, &[Value::Integer(5)]).unwrap();
+        let expected = RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonInteger::from(2).into(),
+            RadonInteger::from(3).into(),
+            RadonInteger::from(4).into(),
+        ]);
+        assert_eq!(output, expected);
+
+        let output = flatten(&input, &[Value::Integer(0)]).unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_flatten_wrong_args() {
+        let input = RadonArray::from(vec![]);
+        let args = vec![Value::Integer(-1)];
+
+        let result = flatten(&input, &args);
+
+        assert_eq!(
+            &result.unwrap_err().to_string(),
+            "Wrong `RadonArray::Flatten()` arguments: `[Integer(-1)]`"
+        );
+    }
+
+    #[test]
+    fn test_take() {
+        let input = RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonInteger::from(2).into(),
+            RadonInteger::from(3).into(),
+            RadonInteger::from(4).into(),
+        ]);
+        let ints = |v: &[i128]| {
+            RadonArray::from(
+                v.iter()
+                    .map(|i| RadonInteger::from(*i).into())
+                    .collect::<Vec<RadonTypes>>(),
+            )
+        };
+
+        assert_eq!(take(&input, &[Value::Integer(2)]), Ok(ints(&[1, 2])));
+        assert_eq!(take(&input, &[Value::Integer(10)]), Ok(ints(&[1, 2, 3, 4])));
+        assert_eq!(
+            take(&input, &[Value::Integer(1), Value::Integer(2)]),
+            Ok(ints(&[2, 3]))
+        );
+        assert_eq!(
+            take(&input, &[Value::Integer(-2), Value::Integer(5)]),
+            Ok(ints(&[3, 4]))
+        );
+        assert_eq!(
+            take(&input, &[Value::Integer(-10), Value::Integer(1)]),
+            Ok(ints(&[1]))
+        );
+        assert_eq!(
+            take(&input, &[Value::Integer(7), Value::Integer(1)]),
+            Ok(ints(&[]))
+        );
+    }
+
+    #[test]
+    fn test_take_wrong_args() {
+        let input = RadonArray::from(vec![]);
+
+        for args in vec![
+            vec![],
+            vec![Value::Integer(-1)],
+            vec![Value::Text("1".to_string())],
+            vec![Value::Integer(0), Value::Integer(1), Value::Integer(2)],
+        ] {
+            match take(&input, &args) {
+                Err(RadError::WrongArguments { .. }) => {}
+                other => panic!("Expected wrong arguments error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_some_integer_greater_than() {
+        let input = RadonArray::from(vec![
+            RadonInteger::from(2).into(),
+            RadonInteger::from(6).into(),
+        ]);
+        let script = |threshold| {
+            vec![Value::Array(vec![Value::Array(vec![
+                Value::Integer(IntegerGreaterThan as i128),
+                Value::Integer(threshold),
+            ])])]
+        };
+
+        let output = some(&input, &script(4), &mut ReportContext::default()).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonBoolean::from(true)));
+
+        let output = some(&input, &script(6), &mut ReportContext::default()).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonBoolean::from(false)));
+
+        let empty = RadonArray::from(vec![]);
+        let output = some(&empty, &script(6), &mut ReportContext::default()).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonBoolean::from(false)));
+    }
+
+    #[test]
+    fn test_some_with_partial_results() {
+        let input = RadonArray::from(vec![
+            RadonInteger::from(2).into(),
+            RadonInteger::from(3).into(),
+        ]);
+        let script = vec![Value::Array(vec![
+            Value::Array(vec![
+                Value::Integer(IntegerMultiply as i128),
+                Value::Integer(2),
+            ]),
+            Value::Array(vec![
+                Value::Integer(IntegerGreaterThan as i128),
+                Value::Integer(5),
+            ]),
+        ])];
+        let mut context = ReportContext::from_stage(Stage::Retrieval(RetrievalMetadata::default()));
+        some(&input, &script, &mut context).unwrap();
+
+        if let Stage::Retrieval(metadata) = context.stage {
+            let expected_partial_results = vec![vec![
+                vec![RadonInteger::from(2).into(), RadonInteger::from(3).into()],
+                vec![RadonInteger::from(4).into(), RadonInteger::from(6).into()],
+                vec![
+                    RadonBoolean::from(false).into(),
+                    RadonBoolean::from(true).into(),
+                ],
+            ]];
+            assert_eq!(metadata.subscript_partial_results, expected_partial_results);
+        }
+    }
+
+    #[test]
+    fn test_some_negative() {
+        let input = RadonArray::from(vec![
+            RadonInteger::from(2).into(),
+            RadonInteger::from(6).into(),
+        ]);
+        let script = vec![Value::Array(vec![Value::Array(vec![
+            Value::Integer(IntegerMultiply as i128),
+            Value::Integer(4),
+        ])])];
+        let result = some(&input, &script, &mut ReportContext::default());
+
+        assert_eq!(
+            &result.unwrap_err().to_string(),
+            "ArraySome subscript output was not RadonBoolean (was `RadonTypes::RadonInteger(8)`)"
+        );
+    }
+
     #[test]
     fn test_sort_map_string_values() {
         let mut map1 = HashMap::new();
@@ -4,9 +4,19 @@ use serde_cbor::Value;
 
 use crate::{
     error::RadError,
-    types::{boolean::RadonBoolean, string::RadonString, RadonType},
+    operators::string,
+    types::{boolean::RadonBoolean, string::RadonString, RadonType, RadonTypes},
 };
 
+pub fn boolean_match(input: &RadonBoolean, args: &[Value]) -> Result<RadonTypes, RadError> {
+    string::match_key(
+        &input.value().to_string(),
+        args,
+        RadonBoolean::radon_type_name(),
+        "BooleanMatch",
+    )
+}
+
 pub fn negate(input: &RadonBoolean) -> RadonBoolean {
     RadonBoolean::from(!input.value())
 }
@@ -31,3 +41,35 @@ fn test_boolean_to_string() {
 
     assert_eq!(to_string(rad_int).unwrap(), rad_string);
 }
+
+#[test]
+fn test_boolean_match() {
+    use crate::types::integer::RadonInteger;
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    map.insert(Value::Text("true".to_string()), Value::Integer(1));
+    map.insert(Value::Text("false".to_string()), Value::Integer(0));
+    let args = vec![Value::Map(map), Value::Integer(-1)];
+
+    let result = boolean_match(&RadonBoolean::from(true), &args).unwrap();
+    assert_eq!(result, RadonTypes::from(RadonInteger::from(1)));
+
+    let result = boolean_match(&RadonBoolean::from(false), &args).unwrap();
+    assert_eq!(result, RadonTypes::from(RadonInteger::from(0)));
+}
+
+#[test]
+fn test_boolean_match_wrong_args() {
+    let args = vec![Value::Integer(1)];
+    let result = boolean_match(&RadonBoolean::from(true), &args);
+
+    assert_eq!(
+        result.unwrap_err(),
+        RadError::WrongArguments {
+            input_type: RadonBoolean::radon_type_name(),
+            operator: "BooleanMatch".to_string(),
+            args,
+        }
+    );
+}
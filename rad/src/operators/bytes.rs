@@ -64,4 +64,23 @@ mod tests {
             "Hash function `RadonHashFunctions::Fail` is not implemented"
         );
     }
+
+    #[test]
+    fn test_bytes_hash_other_functions() {
+        let input = RadonBytes::from(vec![0x01, 0x02, 0x03]);
+
+        // 0x0E is RadonHashFunctions::SHA3_256
+        let output = hash(&input, &[Value::from(0x0E)]).unwrap();
+        assert_eq!(
+            hex::encode(output.value()),
+            "fd1780a6fc9ee0dab26ceb4b3941ab03e66ccd970d1db91612c66df4515b0a0a"
+        );
+
+        // 0x06 is RadonHashFunctions::Ripemd160
+        let output = hash(&input, &[Value::from(0x06)]).unwrap();
+        assert_eq!(
+            hex::encode(output.value()),
+            "79f901da2609f020adadbf2e5f68a16c8c3f7d57"
+        );
+    }
 }
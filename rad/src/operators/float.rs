@@ -76,6 +76,16 @@ pub fn negate(input: &RadonFloat) -> RadonFloat {
     RadonFloat::from(-input.value())
 }
 
+pub fn reciprocal(input: &RadonFloat) -> Result<RadonFloat, RadError> {
+    let value = input.value();
+
+    if value != 0f64 {
+        Ok(RadonFloat::from(1f64 / value))
+    } else {
+        Err(RadError::DivisionByZero)
+    }
+}
+
 pub fn power(input: &RadonFloat, args: &[Value]) -> Result<RadonFloat, RadError> {
     let wrong_args = || RadError::WrongArguments {
         input_type: RadonFloat::radon_type_name(),
@@ -107,6 +117,25 @@ pub fn truncate(input: &RadonFloat) -> RadonInteger {
     RadonInteger::from(input.value().trunc() as i128)
 }
 
+pub fn sum(input: &RadonFloat, args: &[Value]) -> Result<RadonFloat, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonFloat::radon_type_name(),
+        operator: "Sum".to_string(),
+        args: args.to_vec(),
+    };
+
+    let arg = args.first().ok_or_else(wrong_args)?.to_owned();
+    let addend = from_value::<f64>(arg).map_err(|_| wrong_args())?;
+    let result = input.value() + addend;
+
+    // Adding two finite floats should never result in an infinite one
+    if result.is_infinite() && input.value().is_finite() && addend.is_finite() {
+        Err(RadError::Overflow)
+    } else {
+        Ok(RadonFloat::from(result))
+    }
+}
+
 #[test]
 fn test_float_absolute() {
     let positive_integer = RadonFloat::from(10.0);
@@ -257,3 +286,32 @@ fn test_float_trunc() {
     assert_eq!(truncate(&float2), RadonInteger::from(10));
     assert_eq!(truncate(&float3), RadonInteger::from(-10));
 }
+
+#[test]
+fn test_float_sum() {
+    let rad_float = RadonFloat::from(1.5);
+    let args = vec![Value::Float(-0.25)];
+
+    assert_eq!(sum(&rad_float, &args).unwrap(), RadonFloat::from(1.25));
+
+    let max_float = RadonFloat::from(std::f64::MAX);
+    let args = vec![Value::Float(std::f64::MAX)];
+
+    assert_eq!(sum(&max_float, &args).unwrap_err(), RadError::Overflow);
+
+    let args = vec![Value::Text("1".to_string())];
+    let result = sum(&rad_float, &args);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_float_reciprocal() {
+    let rad_float = RadonFloat::from(0.5);
+
+    assert_eq!(reciprocal(&rad_float).unwrap(), RadonFloat::from(2.0));
+
+    let zero = RadonFloat::from(0.0);
+
+    assert_eq!(reciprocal(&zero).unwrap_err(), RadError::DivisionByZero);
+}
@@ -4,9 +4,10 @@ use serde_cbor::value::{from_value, Value};
 
 use crate::{
     error::RadError,
+    operators::string,
     types::{
         boolean::RadonBoolean, float::RadonFloat, integer::RadonInteger, string::RadonString,
-        RadonType,
+        RadonType, RadonTypes,
     },
 };
 
@@ -70,6 +71,15 @@ pub fn less_than(input: &RadonInteger, args: &[Value]) -> Result<RadonBoolean, R
     Ok(RadonBoolean::from(input.value() < other))
 }
 
+pub fn integer_match(input: &RadonInteger, args: &[Value]) -> Result<RadonTypes, RadError> {
+    string::match_key(
+        &input.value().to_string(),
+        args,
+        RadonInteger::radon_type_name(),
+        "IntegerMatch",
+    )
+}
+
 pub fn modulo(input: &RadonInteger, args: &[Value]) -> Result<RadonInteger, RadError> {
     let wrong_args = || RadError::WrongArguments {
         input_type: RadonInteger::radon_type_name(),
@@ -81,8 +91,9 @@ pub fn modulo(input: &RadonInteger, args: &[Value]) -> Result<RadonInteger, RadE
     let modulo = from_value::<i128>(arg).map_err(|_| wrong_args())?;
 
     // TODO: Modify by checked_rem_euclid in rust 1.38
-    if modulo != 0 {
-        Ok(RadonInteger::from(input.value() % modulo))
+    // A zero modulo is reported as an overflow, like it always has been
+    if let Some(result) = input.value().checked_rem(modulo) {
+        Ok(RadonInteger::from(result))
     } else {
         Err(RadError::Overflow)
     }
@@ -116,6 +127,36 @@ pub fn power(input: &RadonInteger, args: &[Value]) -> Result<RadonInteger, RadEr
     }
 }
 
+// FIXME: Allow for now, since there is no safe cast function from an i128 to float yet
+#[allow(clippy::cast_precision_loss)]
+pub fn reciprocal(input: &RadonInteger) -> Result<RadonFloat, RadError> {
+    let value = input.value();
+
+    if value != 0 {
+        Ok(RadonFloat::from(1f64 / value as f64))
+    } else {
+        Err(RadError::DivisionByZero)
+    }
+}
+
+pub fn sum(input: &RadonInteger, args: &[Value]) -> Result<RadonInteger, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonInteger::radon_type_name(),
+        operator: "Sum".to_string(),
+        args: args.to_vec(),
+    };
+
+    let arg = args.first().ok_or_else(wrong_args)?.to_owned();
+    let addend = from_value::<i128>(arg).map_err(|_| wrong_args())?;
+    let result = input.value().checked_add(addend);
+
+    if let Some(result) = result {
+        Ok(RadonInteger::from(result))
+    } else {
+        Err(RadError::Overflow)
+    }
+}
+
 #[test]
 fn test_integer_absolute() {
     let positive_integer = RadonInteger::from(10);
@@ -243,6 +284,14 @@ fn test_integer_modulo() {
         modulo(&RadonInteger::from(-5), &[Value::Integer(-3)]).unwrap(),
         RadonInteger::from(-2)
     );
+    assert_eq!(
+        modulo(&RadonInteger::from(5), &[Value::Integer(0)]).unwrap_err(),
+        RadError::Overflow
+    );
+    assert_eq!(
+        modulo(&RadonInteger::from(i128::MIN), &[Value::Integer(-1)]).unwrap_err(),
+        RadError::Overflow
+    );
 }
 
 #[test]
@@ -259,3 +308,48 @@ fn test_integer_power() {
         "Overflow error".to_string(),
     );
 }
+
+#[test]
+fn test_integer_sum() {
+    let rad_int = RadonInteger::from(10);
+    let args = vec![Value::Integer(-3)];
+
+    assert_eq!(sum(&rad_int, &args).unwrap(), RadonInteger::from(7));
+
+    let max_int = RadonInteger::from(i128::MAX);
+    let args = vec![Value::Integer(1)];
+
+    assert_eq!(sum(&max_int, &args).unwrap_err(), RadError::Overflow);
+
+    let args = vec![Value::Float(1.5)];
+    let result = sum(&rad_int, &args);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_integer_reciprocal() {
+    let rad_int = RadonInteger::from(4);
+
+    assert_eq!(reciprocal(&rad_int).unwrap(), RadonFloat::from(0.25));
+
+    let zero = RadonInteger::from(0);
+
+    assert_eq!(reciprocal(&zero).unwrap_err(), RadError::DivisionByZero);
+}
+
+#[test]
+fn test_integer_match() {
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    map.insert(Value::Text("1".to_string()), Value::Text("one".to_string()));
+    map.insert(Value::Text("2".to_string()), Value::Text("two".to_string()));
+    let args = vec![Value::Map(map), Value::Text("many".to_string())];
+
+    let result = integer_match(&RadonInteger::from(2), &args).unwrap();
+    assert_eq!(result, RadonTypes::from(RadonString::from("two")));
+
+    let result = integer_match(&RadonInteger::from(3), &args).unwrap();
+    assert_eq!(result, RadonTypes::from(RadonString::from("many")));
+}
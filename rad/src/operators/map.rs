@@ -64,11 +64,74 @@ pub fn keys(input: &RadonMap) -> RadonArray {
     RadonArray::from(v)
 }
 
+/// Returns an array of `[key, value]` pairs, sorted by key so that the result does not depend on
+/// the order in which the map was built.
+pub fn entries(input: &RadonMap) -> RadonArray {
+    let mut entries: Vec<(String, RadonTypes)> = input.value().into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let v: Vec<RadonTypes> = entries
+        .into_iter()
+        .map(|(key, value)| {
+            RadonTypes::from(RadonArray::from(vec![
+                RadonTypes::from(RadonString::from(key)),
+                value,
+            ]))
+        })
+        .collect();
+    RadonArray::from(v)
+}
+
 pub fn values(input: &RadonMap) -> RadonArray {
     let v: Vec<RadonTypes> = input.value().values().cloned().collect();
     RadonArray::from(v)
 }
 
+/// Selects the item found at a `/`-separated path, e.g. `channel/item/0/title` or
+/// `response/@status`.
+///
+/// Every segment is looked up as a key when the current item is a `RadonMap`, or as an index when
+/// it is a `RadonArray`. An index of `0` on any other item selects the item itself, so that paths
+/// into elements that may appear once or many times in a XML document work in both cases.
+pub fn get_path(input: &RadonMap, args: &[Value]) -> Result<RadonTypes, RadError> {
+    let wrong_args = || RadError::WrongArguments {
+        input_type: RadonMap::radon_type_name(),
+        operator: "GetPath".to_string(),
+        args: args.to_vec(),
+    };
+
+    if args.len() != 1 {
+        return Err(wrong_args());
+    }
+
+    let arg = args[0].to_owned();
+    let path = from_value::<String>(arg).map_err(|_| wrong_args())?;
+    let not_found = |segment: &str| RadError::PathNotFound {
+        path: path.clone(),
+        segment: segment.to_string(),
+    };
+
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(
+            RadonTypes::from(input.clone()),
+            |item, segment| match item {
+                RadonTypes::Map(map) => match map.value().get(segment) {
+                    Some(value) => Ok(value.clone()),
+                    None if segment == "0" => Ok(RadonTypes::from(map)),
+                    None => Err(not_found(segment)),
+                },
+                RadonTypes::Array(array) => segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| array.value().get(index).cloned())
+                    .ok_or_else(|| not_found(segment)),
+                item if segment == "0" => Ok(item),
+                _ => Err(not_found(segment)),
+            },
+        )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +141,28 @@ mod tests {
         convert::TryFrom,
     };
 
+    #[test]
+    fn test_map_entries() {
+        let mut map = HashMap::new();
+        map.insert("b".to_string(), RadonTypes::from(RadonInteger::from(2)));
+        map.insert("a".to_string(), RadonTypes::from(RadonInteger::from(1)));
+        let input = RadonMap::from(map);
+
+        let output = entries(&input);
+        let expected = RadonArray::from(vec![
+            RadonTypes::from(RadonArray::from(vec![
+                RadonString::from("a").into(),
+                RadonInteger::from(1).into(),
+            ])),
+            RadonTypes::from(RadonArray::from(vec![
+                RadonString::from("b").into(),
+                RadonInteger::from(2).into(),
+            ])),
+        ]);
+
+        assert_eq!(output, expected);
+    }
+
     #[test]
     fn test_map_get() {
         let key = "Zero";
@@ -165,6 +250,79 @@ mod tests {
         assert_eq!(hs.len(), values.value().len());
     }
 
+    #[test]
+    fn test_map_get_path() {
+        let mut item = HashMap::new();
+        item.insert(
+            "@id".to_string(),
+            RadonTypes::from(RadonString::from("first")),
+        );
+        item.insert(
+            "price".to_string(),
+            RadonTypes::from(RadonString::from("1.5")),
+        );
+        let items = RadonArray::from(vec![
+            RadonTypes::from(RadonMap::from(item)),
+            RadonTypes::from(RadonString::from("second")),
+        ]);
+        let mut map = HashMap::new();
+        map.insert("items".to_string(), RadonTypes::from(items));
+        map.insert(
+            "single".to_string(),
+            RadonTypes::from(RadonString::from("only")),
+        );
+        let input = RadonMap::from(map);
+
+        let get = |path: &str| get_path(&input, &[Value::Text(path.to_string())]);
+
+        assert_eq!(
+            get("items/0/@id"),
+            Ok(RadonTypes::from(RadonString::from("first")))
+        );
+        assert_eq!(
+            get("/items/0/price/"),
+            Ok(RadonTypes::from(RadonString::from("1.5")))
+        );
+        assert_eq!(
+            get("items/1"),
+            Ok(RadonTypes::from(RadonString::from("second")))
+        );
+        assert_eq!(
+            get("single/0"),
+            Ok(RadonTypes::from(RadonString::from("only")))
+        );
+        assert_eq!(get(""), Ok(RadonTypes::from(input.clone())));
+    }
+
+    #[test]
+    fn test_map_get_path_error() {
+        let mut map = HashMap::new();
+        map.insert(
+            "items".to_string(),
+            RadonTypes::from(RadonArray::from(vec![RadonTypes::from(
+                RadonInteger::from(1),
+            )])),
+        );
+        let input = RadonMap::from(map);
+
+        let output = get_path(&input, &[Value::Text("items/2".to_string())]);
+        let expected = RadError::PathNotFound {
+            path: "items/2".to_string(),
+            segment: "2".to_string(),
+        };
+        assert_eq!(output, Err(expected));
+
+        let output = get_path(&input, &[Value::Text("items/0/foo".to_string())]);
+        let expected = RadError::PathNotFound {
+            path: "items/0/foo".to_string(),
+            segment: "foo".to_string(),
+        };
+        assert_eq!(output, Err(expected));
+
+        let output = get_path(&input, &[Value::Integer(0)]);
+        assert!(output.is_err());
+    }
+
     // Auxiliar functions
 
     fn radon_map_of_arrays() -> (RadonMap, String, RadonArray) {
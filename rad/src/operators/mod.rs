@@ -13,6 +13,7 @@ pub mod bytes;
 pub mod float;
 pub mod integer;
 pub mod map;
+pub mod registry;
 pub mod string;
 
 /// List of RADON operators.
@@ -30,7 +31,7 @@ pub enum RadonOpCodes {
     // Array operator codes (start at 0x10)
     ArrayCount = 0x10,
     ArrayFilter = 0x11,
-    //    ArrayFlatten = 0x12,
+    ArrayFlatten = 0x12,
     ArrayGetArray = 0x13,
     ArrayGetBoolean = 0x14,
     ArrayGetBytes = 0x15,
@@ -40,13 +41,13 @@ pub enum RadonOpCodes {
     ArrayGetString = 0x19,
     ArrayMap = 0x1A,
     ArrayReduce = 0x1B,
-    //    ArraySome = 0x1C,
+    ArraySome = 0x1C,
     ArraySort = 0x1D,
-    //    ArrayTake = 0x1E,
+    ArrayTake = 0x1E,
     ///////////////////////////////////////////////////////////////////////
     // Boolean operator codes (start at 0x20)
     BooleanAsString = 0x20,
-    //    BooleanMatch = 0x21,
+    BooleanMatch = 0x21,
     BooleanNegate = 0x22,
     ///////////////////////////////////////////////////////////////////////
     // Bytes operator codes (start at 0x30)
@@ -59,13 +60,13 @@ pub enum RadonOpCodes {
     IntegerAsString = 0x42,
     IntegerGreaterThan = 0x43,
     IntegerLessThan = 0x44,
-    //    IntegerMatch = 0x45,
+    IntegerMatch = 0x45,
     IntegerModulo = 0x46,
     IntegerMultiply = 0x47,
     IntegerNegate = 0x48,
     IntegerPower = 0x49,
-    //    IntegerReciprocal = 0x4A,
-    //    IntegerSum = 0x4B,
+    IntegerReciprocal = 0x4A,
+    IntegerSum = 0x4B,
     ///////////////////////////////////////////////////////////////////////
     // Float operator codes (start at 0x50)
     FloatAbsolute = 0x50,
@@ -78,13 +79,13 @@ pub enum RadonOpCodes {
     FloatMultiply = 0x57,
     FloatNegate = 0x58,
     FloatPower = 0x59,
-    //    FloatReciprocal = 0x5A,
+    FloatReciprocal = 0x5A,
     FloatRound = 0x5B,
-    //    FloatSum = 0x5C,
+    FloatSum = 0x5C,
     FloatTruncate = 0x5D,
     ///////////////////////////////////////////////////////////////////////
     // Map operator codes (start at 0x60)
-    //    MapEntries = 0x60,
+    MapEntries = 0x60,
     MapGetArray = 0x61,
     MapGetBoolean = 0x62,
     MapGetBytes = 0x63,
@@ -94,19 +95,25 @@ pub enum RadonOpCodes {
     MapGetString = 0x67,
     MapKeys = 0x68,
     MapValues = 0x69,
+    MapGetPath = 0x6A,
     ///////////////////////////////////////////////////////////////////////
     // String operator codes (start at 0x70)
     StringAsBoolean = 0x70,
-    //    StringAsBytes = 0x71,
+    StringAsBytes = 0x71,
     StringAsFloat = 0x72,
     StringAsInteger = 0x73,
     StringLength = 0x74,
     StringMatch = 0x75,
     StringParseJSONArray = 0x76,
     StringParseJSONMap = 0x77,
-    //    StringParseXML = 0x78,
+    StringParseXML = 0x78,
     StringToLowerCase = 0x79,
     StringToUpperCase = 0x7A,
+    ///////////////////////////////////////////////////////////////////////
+    // Codes from 0x80 to 0xFE are reserved for custom operators, which are only available outside
+    // of mainnet (see the `registry` module). Calls to those operators are unpacked into a
+    // `Custom` call whose first argument is the actual operator code.
+    Custom = 0x80,
 }
 
 impl fmt::Display for RadonOpCodes {
@@ -126,7 +133,10 @@ pub trait Operable {
 }
 
 pub fn operate(input: RadonTypes, call: &RadonCall) -> Result<RadonTypes, RadError> {
-    input.as_operable().operate(call)
+    match call {
+        (RadonOpCodes::Custom, args) => registry::operate(input, args),
+        call => input.as_operable().operate(call),
+    }
 }
 
 /// This is bound to be a replacement for the original `operate` method.
@@ -137,7 +147,10 @@ pub fn operate_in_context(
     call: &RadonCall,
     context: &mut ReportContext<RadonTypes>,
 ) -> Result<RadonTypes, RadError> {
-    input.as_operable().operate_in_context(call, context)
+    match call {
+        (RadonOpCodes::Custom, args) => registry::operate(input, args),
+        call => input.as_operable().operate_in_context(call, context),
+    }
 }
 
 pub fn identity(input: RadonTypes) -> Result<RadonTypes, RadError> {
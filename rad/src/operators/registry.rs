@@ -0,0 +1,311 @@
+//! Registry of custom RADON operators
+//!
+//! Private networks and research deployments can extend the RAD engine with node-local operators
+//! without forking it. Each custom operator is registered under an operator code from the range
+//! reserved for extensions, and it is applied to inputs of any type.
+//!
+//! Custom operators are never available in mainnet: when running in mainnet, scripts that call
+//! them are rejected as if their operator codes were unknown, so consensus only accepts the
+//! built-in set of operators. In other environments, scripts that call them are valid whether the
+//! operators are registered or not, so that the validity of data requests does not depend on the
+//! configuration of each node. Only executing a call to an operator that is not registered fails.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt,
+    ops::RangeInclusive,
+    sync::{Arc, RwLock},
+};
+
+use failure::Fail;
+use lazy_static::lazy_static;
+use serde_cbor::value::Value;
+
+use witnet_data_structures::{chain::Environment, get_environment};
+
+use crate::{
+    error::RadError,
+    types::{string::RadonString, RadonType, RadonTypes},
+};
+
+/// Operator codes reserved for custom operators. No built-in operator will ever use them.
+pub const CUSTOM_OP_CODES: RangeInclusive<u8> = 0x80..=0xFE;
+
+/// Implementation of a custom operator, which receives the input and the arguments of the call.
+pub type CustomOperatorFn =
+    dyn Fn(RadonTypes, &[Value]) -> Result<RadonTypes, RadError> + Send + Sync;
+
+/// A custom operator, along with the name used to refer to it in logs and errors.
+#[derive(Clone)]
+pub struct CustomOperator {
+    /// Name of the operator
+    pub name: String,
+    /// Implementation of the operator
+    pub function: Arc<CustomOperatorFn>,
+}
+
+impl fmt::Debug for CustomOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomOperator")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// Errors found when registering a custom operator.
+#[derive(Clone, Debug, Fail, PartialEq)]
+pub enum RegistryError {
+    /// The operator code is not in the range reserved for custom operators.
+    #[fail(
+        display = "Operator code `{:#04x}` is not in the range reserved for custom operators ({:#04x}..={:#04x})",
+        code, 0x80, 0xFE
+    )]
+    CodeNotReserved { code: u8 },
+    /// There is already an operator registered with that code.
+    #[fail(
+        display = "Operator code `{:#04x}` is already registered for `{}`",
+        code, name
+    )]
+    AlreadyRegistered { code: u8, name: String },
+    /// There is no custom operator with that name among the ones that ship with the RAD engine.
+    #[fail(display = "There is no custom operator named `{}`", name)]
+    UnknownExtension { name: String },
+}
+
+/// Mapping from operator codes to custom operators.
+#[derive(Debug, Default)]
+pub struct OperatorRegistry {
+    operators: BTreeMap<u8, CustomOperator>,
+}
+
+impl OperatorRegistry {
+    /// Register a custom operator under the given code.
+    pub fn register(&mut self, code: u8, operator: CustomOperator) -> Result<(), RegistryError> {
+        if !CUSTOM_OP_CODES.contains(&code) {
+            return Err(RegistryError::CodeNotReserved { code });
+        }
+        if let Some(registered) = self.operators.get(&code) {
+            return Err(RegistryError::AlreadyRegistered {
+                code,
+                name: registered.name.clone(),
+            });
+        }
+        self.operators.insert(code, operator);
+
+        Ok(())
+    }
+
+    /// Get the custom operator registered under the given code, as long as custom operators are
+    /// allowed in the given environment.
+    pub fn get(&self, code: u8, environment: Environment) -> Option<&CustomOperator> {
+        if environment == Environment::Mainnet {
+            None
+        } else {
+            self.operators.get(&code)
+        }
+    }
+
+    /// Codes and names of all the registered operators.
+    pub fn list(&self) -> Vec<(u8, String)> {
+        self.operators
+            .iter()
+            .map(|(code, operator)| (*code, operator.name.clone()))
+            .collect()
+    }
+}
+
+lazy_static! {
+    /// Custom operators registered in this process.
+    static ref REGISTRY: RwLock<OperatorRegistry> = RwLock::new(OperatorRegistry::default());
+}
+
+/// Register a custom operator in the global registry, so that it can be called from RADON scripts
+/// executed in this process. This should be done during initialization, before any script is
+/// executed, so that all the scripts are executed with the same set of operators.
+pub fn register_operator<F>(code: u8, name: &str, function: F) -> Result<(), RegistryError>
+where
+    F: Fn(RadonTypes, &[Value]) -> Result<RadonTypes, RadError> + Send + Sync + 'static,
+{
+    register(
+        code,
+        CustomOperator {
+            name: name.to_string(),
+            function: Arc::new(function),
+        },
+    )
+}
+
+/// Register one of the custom operators that ship with the RAD engine in the global registry,
+/// under the given code.
+pub fn register_extension(code: u8, name: &str) -> Result<(), RegistryError> {
+    let operator = extension(name).ok_or_else(|| RegistryError::UnknownExtension {
+        name: name.to_string(),
+    })?;
+
+    register(code, operator)
+}
+
+fn register(code: u8, operator: CustomOperator) -> Result<(), RegistryError> {
+    let name = operator.name.clone();
+    // The lock can only become poisoned if a writer panics, and `register` never panics
+    REGISTRY.write().unwrap().register(code, operator)?;
+    log::info!("Registered custom RADON operator {} ({:#04x})", name, code);
+
+    Ok(())
+}
+
+/// Custom operators that ship with the RAD engine, so that they can be enabled from the
+/// configuration of a node without writing any code.
+pub fn extension(name: &str) -> Option<CustomOperator> {
+    let function: Arc<CustomOperatorFn> = match name {
+        "StringTrim" => Arc::new(|input: RadonTypes, _args: &[Value]| {
+            let input = string_input(input, "StringTrim", &[])?;
+
+            Ok(RadonString::from(input.value().trim().to_string()).into())
+        }),
+        "StringReplace" => Arc::new(|input: RadonTypes, args: &[Value]| {
+            let input = string_input(input, "StringReplace", args)?;
+            match args {
+                [Value::Text(from), Value::Text(to)] => {
+                    Ok(RadonString::from(input.value().replace(from, to)).into())
+                }
+                _ => Err(RadError::WrongArguments {
+                    input_type: RadonString::radon_type_name(),
+                    operator: "StringReplace".to_string(),
+                    args: args.to_vec(),
+                }),
+            }
+        }),
+        _ => return None,
+    };
+
+    Some(CustomOperator {
+        name: name.to_string(),
+        function,
+    })
+}
+
+fn string_input(
+    input: RadonTypes,
+    operator: &str,
+    args: &[Value],
+) -> Result<RadonString, RadError> {
+    match input {
+        RadonTypes::String(input) => Ok(input),
+        input => Err(RadError::UnsupportedOperator {
+            input_type: input.radon_type_name().to_string(),
+            operator: operator.to_string(),
+            args: Some(args.to_vec()),
+        }),
+    }
+}
+
+/// Get the custom operator registered under the given code in the global registry. Always
+/// returns `None` in mainnet.
+pub fn custom_operator(code: u8) -> Option<CustomOperator> {
+    custom_operator_in_environment(code, get_environment())
+}
+
+/// Get the custom operator registered under the given code in the global registry, as long as
+/// custom operators are allowed in the given environment.
+pub fn custom_operator_in_environment(
+    code: u8,
+    environment: Environment,
+) -> Option<CustomOperator> {
+    REGISTRY.read().unwrap().get(code, environment).cloned()
+}
+
+/// Codes and names of all the operators in the global registry.
+pub fn registered_operators() -> Vec<(u8, String)> {
+    REGISTRY.read().unwrap().list()
+}
+
+/// Apply the custom operator called by a `RadonOpCodes::Custom` call, whose first argument is the
+/// code of the operator.
+pub fn operate(input: RadonTypes, args: &Option<Vec<Value>>) -> Result<RadonTypes, RadError> {
+    let (code, args) = match args.as_deref() {
+        Some([Value::Integer(code), args @ ..]) => (*code, args),
+        _ => return Err(RadError::NotIntegerOperator),
+    };
+    let operator = u8::try_from(code)
+        .ok()
+        .and_then(custom_operator)
+        .ok_or(RadError::UnknownOperator { code })?;
+
+    (operator.function)(input, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::integer::RadonInteger;
+
+    use super::*;
+
+    fn double() -> CustomOperator {
+        CustomOperator {
+            name: "double".to_string(),
+            function: Arc::new(|input: RadonTypes, _args: &[Value]| match input {
+                RadonTypes::Integer(integer) => Ok(RadonInteger::from(integer.value() * 2).into()),
+                _ => Err(RadError::Unknown),
+            }),
+        }
+    }
+
+    #[test]
+    fn register_and_call() {
+        let mut registry = OperatorRegistry::default();
+        registry.register(0x80, double()).unwrap();
+
+        let operator = registry.get(0x80, Environment::Testnet).unwrap();
+        let output = (operator.function)(RadonInteger::from(21).into(), &[]).unwrap();
+        assert_eq!(output, RadonInteger::from(42).into());
+        assert_eq!(registry.list(), vec![(0x80, "double".to_string())]);
+    }
+
+    #[test]
+    fn not_available_in_mainnet() {
+        let mut registry = OperatorRegistry::default();
+        registry.register(0x80, double()).unwrap();
+
+        assert!(registry.get(0x80, Environment::Mainnet).is_none());
+    }
+
+    #[test]
+    fn extensions() {
+        let trim = extension("StringTrim").unwrap();
+        let output = (trim.function)(RadonString::from(" 42\n").into(), &[]).unwrap();
+        assert_eq!(output, RadonString::from("42").into());
+        assert!((trim.function)(RadonInteger::from(42).into(), &[]).is_err());
+
+        let replace = extension("StringReplace").unwrap();
+        let args = [Value::Text(",".to_string()), Value::Text("".to_string())];
+        let output = (replace.function)(RadonString::from("1,234.5").into(), &args).unwrap();
+        assert_eq!(output, RadonString::from("1234.5").into());
+        assert!((replace.function)(RadonString::from("1,234.5").into(), &args[..1]).is_err());
+
+        assert!(extension("Unknown").is_none());
+    }
+
+    #[test]
+    fn invalid_registrations() {
+        let mut registry = OperatorRegistry::default();
+
+        assert_eq!(
+            registry.register(0x40, double()).unwrap_err(),
+            RegistryError::CodeNotReserved { code: 0x40 }
+        );
+        assert_eq!(
+            registry.register(0xFF, double()).unwrap_err(),
+            RegistryError::CodeNotReserved { code: 0xFF }
+        );
+        registry.register(0x81, double()).unwrap();
+        assert_eq!(
+            registry.register(0x81, double()).unwrap_err(),
+            RegistryError::AlreadyRegistered {
+                code: 0x81,
+                name: "double".to_string()
+            }
+        );
+    }
+}
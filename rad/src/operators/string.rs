@@ -1,5 +1,6 @@
 use serde_cbor::value::{from_value, Value};
 use std::{
+    collections::HashMap,
     convert::{TryFrom, TryInto},
     str::FromStr,
 };
@@ -35,6 +36,25 @@ pub fn parse_json_array(input: &RadonString) -> Result<RadonArray, RadError> {
     item.try_into()
 }
 
+/// Parses a XML document into a `RadonMap` whose only key is the name of the root element.
+///
+/// Elements containing only text become `RadonString`s. Otherwise they become `RadonMap`s in
+/// which attributes are prefixed with `@`, any text content is found under `#text`, and child
+/// elements are found under their own name. Child elements that appear more than once with the
+/// same name are grouped into a `RadonArray` in document order.
+pub fn parse_xml_map(input: &RadonString) -> Result<RadonMap, RadError> {
+    let value = input.value();
+    let document = roxmltree::Document::parse(&value).map_err(|xml_error| RadError::XmlParse {
+        description: xml_error.to_string(),
+    })?;
+    let root = document.root_element();
+
+    let mut map = HashMap::new();
+    map.insert(root.tag_name().name().to_string(), xml_to_radon(root));
+
+    Ok(RadonMap::from(map))
+}
+
 pub fn radon_trim(input: &RadonString) -> String {
     if input.value().ends_with('\n') {
         input.value()[..input.value().len() - 1].to_string()
@@ -97,10 +117,46 @@ pub fn hash(input: &RadonString, args: &[Value]) -> Result<RadonString, RadError
     Ok(RadonString::from(hex_string))
 }
 
+pub fn to_bytes(input: &RadonString) -> Result<RadonBytes, RadError> {
+    let str_value = radon_trim(input);
+    let hex_str = if str_value.starts_with("0x") {
+        &str_value[2..]
+    } else {
+        &str_value
+    };
+
+    hex::decode(hex_str)
+        .map(RadonBytes::from)
+        .map_err(|_| RadError::Decode {
+            from: RadonString::radon_type_name(),
+            to: RadonBytes::radon_type_name(),
+        })
+}
+
 pub fn string_match(input: &RadonString, args: &[Value]) -> Result<RadonTypes, RadError> {
+    match_key(
+        &input.value(),
+        args,
+        RadonString::radon_type_name(),
+        "String match",
+    )
+}
+
+/// Looks up `key` in the map given as the first argument, and returns the value found there or
+/// the default value given as the second argument. The value is returned with the same type as
+/// the default value.
+///
+/// This is shared by all the `Match` operators, which use the string representation of their
+/// input as the key.
+pub fn match_key(
+    key: &str,
+    args: &[Value],
+    input_type: &'static str,
+    operator: &str,
+) -> Result<RadonTypes, RadError> {
     let wrong_args = || RadError::WrongArguments {
-        input_type: RadonString::radon_type_name(),
-        operator: "String match".to_string(),
+        input_type,
+        operator: operator.to_string(),
         args: args.to_vec(),
     };
 
@@ -112,7 +168,7 @@ pub fn string_match(input: &RadonString, args: &[Value]) -> Result<RadonTypes, R
     let map_value = map.value();
 
     map_value
-        .get(&input.value())
+        .get(key)
         .map(|res| match default {
             RadonTypes::Array(_) => Ok(RadonTypes::from(RadonArray::try_from(res.clone())?)),
             RadonTypes::Boolean(_) => Ok(RadonTypes::from(RadonBoolean::try_from(res.clone())?)),
@@ -158,6 +214,55 @@ fn json_to_cbor(value: &json::JsonValue) -> Value {
     }
 }
 
+/// Converts a XML element (`roxmltree::Node`) into a `RadonTypes` value as described in
+/// `parse_xml_map`.
+fn xml_to_radon(node: roxmltree::Node) -> RadonTypes {
+    let mut map: HashMap<String, RadonTypes> = node
+        .attributes()
+        .iter()
+        .map(|attribute| {
+            (
+                format!("@{}", attribute.name()),
+                RadonTypes::from(RadonString::from(attribute.value())),
+            )
+        })
+        .collect();
+    let mut text = String::new();
+
+    for child in node.children() {
+        if child.is_element() {
+            let key = child.tag_name().name().to_string();
+            let value = xml_to_radon(child);
+            // Elements never become arrays by themselves, so an array means a repeated element
+            let grouped = match map.remove(&key) {
+                None => value,
+                Some(RadonTypes::Array(siblings)) => {
+                    let mut siblings = siblings.value();
+                    siblings.push(value);
+                    RadonTypes::from(RadonArray::from(siblings))
+                }
+                Some(sibling) => RadonTypes::from(RadonArray::from(vec![sibling, value])),
+            };
+            map.insert(key, grouped);
+        } else if let Some(child_text) = child.text() {
+            text.push_str(child_text);
+        }
+    }
+
+    let text = text.trim();
+    if map.is_empty() {
+        RadonTypes::from(RadonString::from(text))
+    } else {
+        if !text.is_empty() {
+            map.insert(
+                "#text".to_string(),
+                RadonTypes::from(RadonString::from(text)),
+            );
+        }
+        RadonTypes::from(RadonMap::from(map))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,6 +335,55 @@ mod tests {
         assert_eq!(output, expected_err);
     }
 
+    #[test]
+    fn test_parse_xml_map() {
+        let xml = RadonString::from(
+            r#"<?xml version="1.0"?>
+            <weather station="LEMD">
+                <temperature unit="C">21.5</temperature>
+                <wind>12</wind>
+                <reading>1</reading>
+                <reading>2</reading>
+            </weather>"#,
+        );
+        let output = parse_xml_map(&xml).unwrap();
+
+        let mut temperature = HashMap::new();
+        temperature.insert("@unit".to_string(), RadonString::from("C").into());
+        temperature.insert("#text".to_string(), RadonString::from("21.5").into());
+        let mut weather = HashMap::new();
+        weather.insert("@station".to_string(), RadonString::from("LEMD").into());
+        weather.insert(
+            "temperature".to_string(),
+            RadonMap::from(temperature).into(),
+        );
+        weather.insert("wind".to_string(), RadonString::from("12").into());
+        weather.insert(
+            "reading".to_string(),
+            RadonArray::from(vec![
+                RadonString::from("1").into(),
+                RadonString::from("2").into(),
+            ])
+            .into(),
+        );
+        let mut map = HashMap::new();
+        map.insert("weather".to_string(), RadonMap::from(weather).into());
+        let expected_output = RadonMap::from(map);
+
+        assert_eq!(output, expected_output);
+    }
+
+    #[test]
+    fn test_parse_xml_map_fail() {
+        let invalid_xml = RadonString::from(r#"<a><b></a>"#);
+        let output = parse_xml_map(&invalid_xml).unwrap_err();
+
+        match output {
+            RadError::XmlParse { .. } => {}
+            _ => panic!("Expected XmlParse error, got {:?}", output),
+        }
+    }
+
     #[test]
     fn test_hash() {
         let input = RadonString::from("Hello, World!");
@@ -300,6 +454,27 @@ mod tests {
         assert_eq!(to_uppercase(&rad_string), RadonString::from("HELLO"));
     }
 
+    #[test]
+    fn test_string_to_bytes() {
+        let input = RadonString::from("0x00ff10");
+        let output = to_bytes(&input).unwrap();
+        assert_eq!(output, RadonBytes::from(vec![0x00, 0xff, 0x10]));
+
+        let input = RadonString::from("deadbeef");
+        let output = to_bytes(&input).unwrap();
+        assert_eq!(output, RadonBytes::from(vec![0xde, 0xad, 0xbe, 0xef]));
+
+        let input = RadonString::from("not hex");
+        let output = to_bytes(&input).unwrap_err();
+        assert_eq!(
+            output,
+            RadError::Decode {
+                from: RadonString::radon_type_name(),
+                to: RadonBytes::radon_type_name(),
+            }
+        );
+    }
+
     #[test]
     fn test_string_match_booleans() {
         let mut map: BTreeMap<Value, Value> = BTreeMap::new();
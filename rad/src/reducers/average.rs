@@ -1,9 +1,9 @@
-use std::ops::Div;
+use std::{cmp::Ordering, ops::Div};
 
 use crate::{
     error::RadError,
     operators::{array as array_operators, float as float_operators},
-    reducers::RadonReducers,
+    reducers::{min_max::float_total_cmp, RadonReducers},
     types::{array::RadonArray, float::RadonFloat, integer::RadonInteger, RadonType, RadonTypes},
 };
 
@@ -125,6 +125,217 @@ pub fn mean(input: &RadonArray, return_policy: MeanReturnPolicy) -> Result<Radon
     }
 }
 
+/// Computes the median of the values found in a `RadonArray`.
+///
+/// For an even number of values, the median is the average mean of the two central values, so
+/// `return_policy` applies the same as in [`mean`](fn.mean.html). Arrays of arrays are reduced
+/// element-wise.
+pub fn median(input: &RadonArray, return_policy: MeanReturnPolicy) -> Result<RadonTypes, RadError> {
+    let value = input.value();
+
+    match value.first() {
+        None => Ok(RadonTypes::from(RadonFloat::from(std::f64::NAN))),
+        Some(RadonTypes::Integer(_)) | Some(RadonTypes::Float(_)) => {
+            let sorted = sort_numeric(input.value(), RadonReducers::AverageMedian)?;
+            let len = sorted.len();
+
+            if len % 2 == 1 {
+                middle_value(&sorted[len / 2..=len / 2], return_policy)
+            } else {
+                middle_value(&sorted[len / 2 - 1..=len / 2], return_policy)
+            }
+        }
+        Some(RadonTypes::Array(_)) => {
+            let v = array_operators::transpose(input)?;
+
+            let mut median_v = vec![];
+            for v2median in v.value() {
+                if let RadonTypes::Array(v2median) = v2median {
+                    median_v.push(median(&v2median, return_policy)?);
+                } else {
+                    unreachable!()
+                }
+            }
+
+            Ok(RadonTypes::from(RadonArray::from(median_v)))
+        }
+        Some(_rad_types) => Err(RadError::UnsupportedReducer {
+            array: input.clone(),
+            reducer: RadonReducers::AverageMedian.to_string(),
+        }),
+    }
+}
+
+/// Computes the weighted average mean of a `RadonArray` of `[value, weight]` pairs.
+///
+/// Values must be all `Integer` or all `Float`, while weights can be any non-negative `Integer`
+/// or `Float`. The same `return_policy` as in [`mean`](fn.mean.html) applies.
+pub fn mean_weighted(
+    input: &RadonArray,
+    return_policy: MeanReturnPolicy,
+) -> Result<RadonTypes, RadError> {
+    let pairs = weighted_pairs(input, RadonReducers::AverageMeanWeighted)?;
+    let total_weight: f64 = pairs.iter().map(|(_, weight)| weight).sum();
+
+    if pairs.is_empty() || total_weight == 0f64 {
+        return Ok(RadonTypes::from(RadonFloat::from(std::f64::NAN)));
+    }
+
+    let weighted_sum: f64 = pairs
+        .iter()
+        .map(|(value, weight)| numeric_value(value) * weight)
+        .sum();
+    let float_mean = RadonFloat::from(weighted_sum.div(total_weight));
+
+    match (&pairs[0].0, return_policy) {
+        (RadonTypes::Integer(_), MeanReturnPolicy::RoundToInteger) => {
+            Ok(RadonTypes::from(float_operators::round(&float_mean)))
+        }
+        _ => Ok(RadonTypes::from(float_mean)),
+    }
+}
+
+/// Computes the weighted median of a `RadonArray` of `[value, weight]` pairs.
+///
+/// The weighted median is the value at which the accumulated weight of the sorted values reaches
+/// half of the total weight. If it reaches exactly one half, the average mean of that value and
+/// the next one is returned, which makes it match [`median`](fn.median.html) for equal weights.
+pub fn median_weighted(
+    input: &RadonArray,
+    return_policy: MeanReturnPolicy,
+) -> Result<RadonTypes, RadError> {
+    let mut pairs = weighted_pairs(input, RadonReducers::AverageMedianWeighted)?;
+    // Values without weight do not take part in the median at all
+    pairs.retain(|(_, weight)| *weight > 0f64);
+    pairs.sort_by(|(a, _), (b, _)| numeric_cmp(a, b));
+
+    let total_weight: f64 = pairs.iter().map(|(_, weight)| weight).sum();
+    let half_weight = total_weight / 2f64;
+
+    let mut accumulated_weight = 0f64;
+    for (i, (value, weight)) in pairs.iter().enumerate() {
+        accumulated_weight += weight;
+        match float_total_cmp(accumulated_weight, half_weight) {
+            Ordering::Less => continue,
+            Ordering::Greater => return middle_value(&[value.clone()], return_policy),
+            Ordering::Equal => {
+                let middle = match pairs.get(i + 1) {
+                    Some((next_value, _)) => vec![value.clone(), next_value.clone()],
+                    None => vec![value.clone()],
+                };
+
+                return middle_value(&middle, return_policy);
+            }
+        }
+    }
+
+    Ok(RadonTypes::from(RadonFloat::from(std::f64::NAN)))
+}
+
+/// Computes the average mean of the central value(s) picked by a median, keeping single integer
+/// values untouched when the reducer contract is enforced.
+fn middle_value(
+    middle: &[RadonTypes],
+    return_policy: MeanReturnPolicy,
+) -> Result<RadonTypes, RadError> {
+    match middle {
+        [single @ RadonTypes::Integer(_)] if return_policy == MeanReturnPolicy::RoundToInteger => {
+            Ok(single.clone())
+        }
+        _ => mean(&RadonArray::from(middle.to_vec()), return_policy),
+    }
+}
+
+/// Checks that all the values are numbers of the same type as the first one, and sorts them.
+fn sort_numeric(
+    mut value: Vec<RadonTypes>,
+    reducer: RadonReducers,
+) -> Result<Vec<RadonTypes>, RadError> {
+    check_numeric(&value, reducer)?;
+    value.sort_by(numeric_cmp);
+
+    Ok(value)
+}
+
+/// Checks that all the values are numbers of the same type as the first one.
+fn check_numeric(value: &[RadonTypes], reducer: RadonReducers) -> Result<(), RadError> {
+    let expected = match value.first() {
+        Some(RadonTypes::Float(_)) => RadonFloat::radon_type_name(),
+        _ => RadonInteger::radon_type_name(),
+    };
+
+    value.iter().try_for_each(|item| {
+        let found = item.radon_type_name();
+        if found == expected {
+            Ok(())
+        } else {
+            Err(RadError::MismatchingTypes {
+                method: reducer.to_string(),
+                expected,
+                found,
+            })
+        }
+    })
+}
+
+/// Compares two numbers of the same type. Values that are not numbers are considered equal.
+fn numeric_cmp(a: &RadonTypes, b: &RadonTypes) -> Ordering {
+    match (a, b) {
+        (RadonTypes::Integer(a), RadonTypes::Integer(b)) => a.value().cmp(&b.value()),
+        (RadonTypes::Float(a), RadonTypes::Float(b)) => float_total_cmp(a.value(), b.value()),
+        _ => Ordering::Equal,
+    }
+}
+
+// FIXME: Allow for now, since there is no safe cast function from an i128 to float yet
+#[allow(clippy::cast_precision_loss)]
+fn numeric_value(value: &RadonTypes) -> f64 {
+    match value {
+        RadonTypes::Integer(i) => i.value() as f64,
+        RadonTypes::Float(f) => f.value(),
+        _ => std::f64::NAN,
+    }
+}
+
+/// Splits the `[value, weight]` pairs of the input of a weighted reducer.
+fn weighted_pairs(
+    input: &RadonArray,
+    reducer: RadonReducers,
+) -> Result<Vec<(RadonTypes, f64)>, RadError> {
+    let malformed = |item: &RadonTypes| RadError::MalformedWeightedItem {
+        reducer: reducer.to_string(),
+        item: item.clone(),
+    };
+
+    let pairs = input
+        .value()
+        .iter()
+        .map(|item| match item {
+            RadonTypes::Array(pair) => match pair.value().as_slice() {
+                [value @ RadonTypes::Integer(_), weight]
+                | [value @ RadonTypes::Float(_), weight] => match weight {
+                    RadonTypes::Integer(_) | RadonTypes::Float(_) => {
+                        let weight = numeric_value(weight);
+                        if weight >= 0f64 && weight.is_finite() {
+                            Ok((value.clone(), weight))
+                        } else {
+                            Err(malformed(item))
+                        }
+                    }
+                    _ => Err(malformed(item)),
+                },
+                _ => Err(malformed(item)),
+            },
+            _ => Err(malformed(item)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let values: Vec<RadonTypes> = pairs.iter().map(|(value, _)| value.clone()).collect();
+    check_numeric(&values, reducer)?;
+
+    Ok(pairs)
+}
+
 #[cfg(test)]
 mod tests {
     use serde_cbor::Value;
@@ -333,4 +544,219 @@ mod tests {
 
         assert_eq!(output, expected);
     }
+
+    fn pair(value: RadonTypes, weight: RadonTypes) -> RadonTypes {
+        RadonTypes::from(RadonArray::from(vec![value, weight]))
+    }
+
+    #[test]
+    fn test_reduce_average_median_integer_odd() {
+        let input = &RadonArray::from(vec![
+            RadonInteger::from(7).into(),
+            RadonInteger::from(1).into(),
+            RadonInteger::from(1000).into(),
+        ]);
+        let args = &[Value::Integer(RadonReducers::AverageMedian as i128)];
+        let expected = RadonTypes::from(RadonInteger::from(7));
+
+        let output = reduce(input, args).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_integer_even() {
+        let input = &RadonArray::from(vec![
+            RadonInteger::from(4).into(),
+            RadonInteger::from(1).into(),
+            RadonInteger::from(1000).into(),
+            RadonInteger::from(1).into(),
+        ]);
+        let args = &[Value::Integer(RadonReducers::AverageMedian as i128)];
+        // Mean of 1 and 4 is 2.5, which is rounded just like in `AverageMean`
+        let expected = RadonTypes::from(RadonInteger::from(3));
+
+        let output = reduce(input, args).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_float() {
+        let input = RadonArray::from(vec![
+            RadonFloat::from(3f64).into(),
+            RadonFloat::from(1f64).into(),
+            RadonFloat::from(2f64).into(),
+            RadonFloat::from(10f64).into(),
+        ]);
+        let expected = RadonTypes::from(RadonFloat::from(2.5f64));
+
+        let output = median(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_integer_arrays() {
+        let array_1 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonInteger::from(30).into(),
+        ]));
+        let array_2 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(5).into(),
+            RadonInteger::from(10).into(),
+        ]));
+        let array_3 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(2).into(),
+            RadonInteger::from(20).into(),
+        ]));
+        let input = RadonArray::from(vec![array_1, array_2, array_3]);
+
+        let expected = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(2).into(),
+            RadonInteger::from(20).into(),
+        ]));
+
+        let output = median(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_empty() {
+        let input = RadonArray::from(vec![]);
+        let output = median(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonFloat::from(std::f64::NAN)));
+    }
+
+    #[test]
+    fn test_reduce_average_median_string_unsupported() {
+        let input = &RadonArray::from(vec![
+            RadonString::from("Hello").into(),
+            RadonString::from("world").into(),
+        ]);
+        let args = &[Value::Integer(RadonReducers::AverageMedian as i128)];
+        let output = reduce(input, args).unwrap_err();
+
+        let expected = RadError::UnsupportedReducer {
+            array: input.clone(),
+            reducer: "RadonReducers::AverageMedian".to_string(),
+        };
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_mean_weighted_float() {
+        let input = RadonArray::from(vec![
+            pair(RadonFloat::from(1f64).into(), RadonInteger::from(3).into()),
+            pair(RadonFloat::from(5f64).into(), RadonFloat::from(1f64).into()),
+        ]);
+        let args = &[Value::Integer(RadonReducers::AverageMeanWeighted as i128)];
+        let expected = RadonTypes::from(RadonFloat::from(2f64));
+
+        let output = reduce(&input, args).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_mean_weighted_integer() {
+        let input = RadonArray::from(vec![
+            pair(RadonInteger::from(1).into(), RadonInteger::from(1).into()),
+            pair(RadonInteger::from(2).into(), RadonInteger::from(2).into()),
+        ]);
+
+        let output = mean_weighted(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonInteger::from(2)));
+
+        let output = mean_weighted(&input, MeanReturnPolicy::ReturnFloat).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonFloat::from(5f64 / 3f64)));
+    }
+
+    #[test]
+    fn test_reduce_average_mean_weighted_zero_weight() {
+        let input = RadonArray::from(vec![pair(
+            RadonInteger::from(1).into(),
+            RadonInteger::from(0).into(),
+        )]);
+
+        let output = mean_weighted(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+
+        assert_eq!(output, RadonTypes::from(RadonFloat::from(std::f64::NAN)));
+    }
+
+    #[test]
+    fn test_reduce_average_mean_weighted_malformed() {
+        let not_a_pair = RadonTypes::from(RadonArray::from(vec![RadonInteger::from(1).into()]));
+        let negative_weight = pair(RadonInteger::from(1).into(), RadonInteger::from(-1).into());
+        let string_value = pair(RadonString::from("1").into(), RadonInteger::from(1).into());
+
+        for item in vec![not_a_pair, negative_weight, string_value] {
+            let input = RadonArray::from(vec![item.clone()]);
+            let output = mean_weighted(&input, MeanReturnPolicy::RoundToInteger).unwrap_err();
+            let expected = RadError::MalformedWeightedItem {
+                reducer: RadonReducers::AverageMeanWeighted.to_string(),
+                item,
+            };
+
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn test_reduce_average_mean_weighted_float_int_values() {
+        let input = RadonArray::from(vec![
+            pair(RadonFloat::from(1f64).into(), RadonInteger::from(1).into()),
+            pair(RadonInteger::from(1).into(), RadonInteger::from(1).into()),
+        ]);
+
+        let output = mean_weighted(&input, MeanReturnPolicy::RoundToInteger).unwrap_err();
+        let expected = RadError::MismatchingTypes {
+            method: RadonReducers::AverageMeanWeighted.to_string(),
+            expected: RadonFloat::radon_type_name(),
+            found: RadonInteger::radon_type_name(),
+        };
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_weighted() {
+        let input = RadonArray::from(vec![
+            pair(RadonInteger::from(100).into(), RadonInteger::from(1).into()),
+            pair(RadonInteger::from(1).into(), RadonInteger::from(1).into()),
+            pair(RadonInteger::from(5).into(), RadonInteger::from(3).into()),
+        ]);
+        let args = &[Value::Integer(RadonReducers::AverageMedianWeighted as i128)];
+        let expected = RadonTypes::from(RadonInteger::from(5));
+
+        let output = reduce(&input, args).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_weighted_equal_weights() {
+        let input = RadonArray::from(vec![
+            pair(RadonFloat::from(4f64).into(), RadonInteger::from(2).into()),
+            pair(RadonFloat::from(1f64).into(), RadonInteger::from(2).into()),
+            pair(
+                RadonFloat::from(1000f64).into(),
+                RadonInteger::from(0).into(),
+            ),
+        ]);
+        let expected = RadonTypes::from(RadonFloat::from(2.5f64));
+
+        let output = median_weighted(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_average_median_weighted_empty() {
+        let input = RadonArray::from(vec![]);
+        let output = median_weighted(&input, MeanReturnPolicy::RoundToInteger).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonFloat::from(std::f64::NAN)));
+    }
 }
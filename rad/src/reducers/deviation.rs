@@ -2,8 +2,8 @@ use crate::{
     error::RadError,
     operators::array::transpose,
     reducers::{
-        average::{mean, MeanReturnPolicy},
-        RadonReducers,
+        average::{mean, median, MeanReturnPolicy},
+        min_max, RadonReducers,
     },
     types::{array::RadonArray, float::RadonFloat, RadonType, RadonTypes},
 };
@@ -95,6 +95,87 @@ pub fn standard(input: &RadonArray) -> Result<RadonTypes, RadError> {
     }
 }
 
+/// Average absolute deviation around the mean
+pub fn average_absolute(input: &RadonArray) -> Result<RadonTypes, RadError> {
+    absolute(input, RadonReducers::DeviationAverageAbsolute)
+}
+
+/// Median absolute deviation around the median
+pub fn median_absolute(input: &RadonArray) -> Result<RadonTypes, RadError> {
+    absolute(input, RadonReducers::DeviationMedianAbsolute)
+}
+
+/// Maximum absolute deviation around the mean
+pub fn maximum_absolute(input: &RadonArray) -> Result<RadonTypes, RadError> {
+    absolute(input, RadonReducers::DeviationMaximumAbsolute)
+}
+
+/// Computes the absolute deviations of every value from the central point that corresponds to the
+/// `reducer`, and then reduces them into a single `Float`.
+// FIXME: Allow for now, since there is no safe cast function from an i128 to float yet
+#[allow(clippy::cast_precision_loss)]
+fn absolute(input: &RadonArray, reducer: RadonReducers) -> Result<RadonTypes, RadError> {
+    let value = input.value();
+
+    match value.first() {
+        None => Ok(RadonTypes::from(RadonFloat::from(std::f64::NAN))),
+        Some(RadonTypes::Integer(_)) | Some(RadonTypes::Float(_)) => {
+            let center = match reducer {
+                RadonReducers::DeviationMedianAbsolute => {
+                    median(input, MeanReturnPolicy::ReturnFloat)?
+                }
+                _ => mean(input, MeanReturnPolicy::ReturnFloat)?,
+            };
+            let center = if let RadonTypes::Float(f) = center {
+                f.value()
+            } else {
+                unreachable!()
+            };
+
+            let deviations = value
+                .iter()
+                .map(|item| match item {
+                    RadonTypes::Integer(i) => Ok((i.value() as f64 - center).abs()),
+                    RadonTypes::Float(f) => Ok((f.value() - center).abs()),
+                    _ => Err(RadError::MismatchingTypes {
+                        method: reducer.to_string(),
+                        expected: RadonFloat::radon_type_name(),
+                        found: item.radon_type_name(),
+                    }),
+                })
+                .map(|deviation| Ok(RadonTypes::from(RadonFloat::from(deviation?))))
+                .collect::<Result<Vec<_>, RadError>>()?;
+            let deviations = RadonArray::from(deviations);
+
+            match reducer {
+                RadonReducers::DeviationMedianAbsolute => {
+                    median(&deviations, MeanReturnPolicy::ReturnFloat)
+                }
+                RadonReducers::DeviationMaximumAbsolute => min_max::max(&deviations),
+                _ => mean(&deviations, MeanReturnPolicy::ReturnFloat),
+            }
+        }
+        Some(RadonTypes::Array(_)) => {
+            let v = transpose(input)?;
+
+            let mut deviation_v = vec![];
+            for v2dev in v.value() {
+                if let RadonTypes::Array(v2dev) = v2dev {
+                    deviation_v.push(absolute(&v2dev, reducer)?);
+                } else {
+                    unreachable!()
+                }
+            }
+
+            Ok(RadonTypes::from(RadonArray::from(deviation_v)))
+        }
+        Some(_rad_types) => Err(RadError::UnsupportedReducer {
+            array: input.clone(),
+            reducer: reducer.to_string(),
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -318,4 +399,95 @@ mod tests {
 
         assert_eq!(result.unwrap_err(), expected);
     }
+
+    #[test]
+    fn test_reduce_deviation_average_absolute() {
+        let input = RadonArray::from(vec![
+            RadonInteger::from(1i128).into(),
+            RadonInteger::from(2i128).into(),
+            RadonInteger::from(6i128).into(),
+        ]);
+        // Mean is 3, so deviations are 2, 1 and 3
+        let expected = RadonTypes::from(RadonFloat::from(2f64));
+
+        let output = average_absolute(&input).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_deviation_median_absolute() {
+        let input = RadonArray::from(vec![
+            RadonFloat::from(1f64).into(),
+            RadonFloat::from(2f64).into(),
+            RadonFloat::from(3f64).into(),
+            RadonFloat::from(4f64).into(),
+            RadonFloat::from(100f64).into(),
+        ]);
+        // Median is 3, so deviations are 2, 1, 0, 1 and 97
+        let expected = RadonTypes::from(RadonFloat::from(1f64));
+
+        let output = median_absolute(&input).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_deviation_maximum_absolute() {
+        let input = RadonArray::from(vec![
+            RadonFloat::from(1f64).into(),
+            RadonFloat::from(2f64).into(),
+            RadonFloat::from(6f64).into(),
+        ]);
+        let expected = RadonTypes::from(RadonFloat::from(3f64));
+
+        let output = maximum_absolute(&input).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_deviation_absolute_integer_arrays() {
+        let array_1 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(1i128).into(),
+            RadonInteger::from(6i128).into(),
+        ]));
+        let array_2 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(2i128).into(),
+            RadonInteger::from(8i128).into(),
+        ]));
+        let input = RadonArray::from(vec![array_1, array_2]);
+
+        let expected = RadonTypes::from(RadonArray::from(vec![
+            RadonFloat::from(0.5).into(),
+            RadonFloat::from(1.0).into(),
+        ]));
+
+        assert_eq!(average_absolute(&input).unwrap(), expected);
+        assert_eq!(median_absolute(&input).unwrap(), expected);
+        assert_eq!(maximum_absolute(&input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_reduce_deviation_absolute_empty() {
+        let input = RadonArray::from(vec![]);
+        let output = median_absolute(&input).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonFloat::from(std::f64::NAN)));
+    }
+
+    #[test]
+    fn test_reduce_deviation_absolute_string_unsupported() {
+        let input = &RadonArray::from(vec![
+            RadonString::from("Hello").into(),
+            RadonString::from("world").into(),
+        ]);
+        let result = maximum_absolute(&input);
+
+        let expected = RadError::UnsupportedReducer {
+            array: input.clone(),
+            reducer: "RadonReducers::DeviationMaximumAbsolute".to_string(),
+        };
+
+        assert_eq!(result.unwrap_err(), expected);
+    }
 }
@@ -0,0 +1,86 @@
+use crate::{
+    error::RadError,
+    hash_functions::{self, RadonHashFunctions},
+    reducers::RadonReducers,
+    types::{array::RadonArray, bytes::RadonBytes, RadonType, RadonTypes},
+};
+
+/// Concatenates all the `RadonBytes` found in a `RadonArray` and computes the SHA2-256 digest of
+/// the result.
+///
+/// The items are concatenated in the same order in which they appear in the input array. In the
+/// tally stage, that order is the one given by the public key hashes of the revealers, so every
+/// node will derive the same value.
+pub fn hash_concatenate(input: &RadonArray) -> Result<RadonTypes, RadError> {
+    let concatenated = input
+        .value()
+        .iter()
+        .try_fold(vec![], |mut acc, item| match item {
+            RadonTypes::Bytes(bytes) => {
+                acc.extend(bytes.value());
+
+                Ok(acc)
+            }
+            _ => Err(RadError::MismatchingTypes {
+                method: RadonReducers::HashConcatenate.to_string(),
+                expected: RadonBytes::radon_type_name(),
+                found: item.clone().radon_type_name(),
+            }),
+        })?;
+
+    let digest = hash_functions::hash(&concatenated, RadonHashFunctions::SHA2_256)?;
+
+    Ok(RadonTypes::from(RadonBytes::from(digest)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::types::integer::RadonInteger;
+
+    #[test]
+    fn test_hash_concatenate() {
+        let input = RadonArray::from(vec![
+            RadonBytes::from(vec![72, 101, 108, 108, 111, 44]).into(),
+            RadonBytes::from(vec![32, 87, 111, 114, 108, 100, 33]).into(),
+        ]);
+        // SHA2-256 of "Hello, World!"
+        let expected = RadonTypes::from(RadonBytes::from(vec![
+            223, 253, 96, 33, 187, 43, 213, 176, 175, 103, 98, 144, 128, 158, 195, 165, 49, 145,
+            221, 129, 199, 247, 10, 75, 40, 104, 138, 54, 33, 130, 152, 111,
+        ]));
+        let output = hash_concatenate(&input).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_hash_concatenate_order_matters() {
+        let a = RadonTypes::from(RadonBytes::from(vec![1, 2, 3]));
+        let b = RadonTypes::from(RadonBytes::from(vec![4, 5, 6]));
+
+        let output_ab = hash_concatenate(&RadonArray::from(vec![a.clone(), b.clone()])).unwrap();
+        let output_ba = hash_concatenate(&RadonArray::from(vec![b, a])).unwrap();
+
+        assert_ne!(output_ab, output_ba);
+    }
+
+    #[test]
+    fn test_hash_concatenate_wrong_type() {
+        let input = RadonArray::from(vec![
+            RadonBytes::from(vec![1, 2, 3]).into(),
+            RadonInteger::from(4).into(),
+        ]);
+        let output = hash_concatenate(&input).unwrap_err();
+
+        assert_eq!(
+            output,
+            RadError::MismatchingTypes {
+                method: "RadonReducers::HashConcatenate".to_string(),
+                expected: "RadonBytes",
+                found: "RadonInteger",
+            }
+        );
+    }
+}
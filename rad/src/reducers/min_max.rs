@@ -0,0 +1,267 @@
+use std::cmp::Ordering;
+
+use crate::{
+    error::RadError,
+    operators::array::transpose,
+    reducers::RadonReducers,
+    types::{
+        array::RadonArray, float::RadonFloat, integer::RadonInteger, string::RadonString,
+        RadonType, RadonTypes,
+    },
+};
+
+/// Returns the smallest of the values found in a `RadonArray`.
+///
+/// Integers and floats are compared numerically, and strings are compared lexicographically.
+/// Arrays of arrays are reduced element-wise.
+pub fn min(input: &RadonArray) -> Result<RadonTypes, RadError> {
+    extreme(input, RadonReducers::Min, Ordering::Less)
+}
+
+/// Returns the greatest of the values found in a `RadonArray`.
+///
+/// Integers and floats are compared numerically, and strings are compared lexicographically.
+/// Arrays of arrays are reduced element-wise.
+pub fn max(input: &RadonArray) -> Result<RadonTypes, RadError> {
+    extreme(input, RadonReducers::Max, Ordering::Greater)
+}
+
+/// Total order for floats in which `NaN` is greater than any other value, so that reducing is
+/// deterministic regardless of the order of the input.
+pub fn float_total_cmp(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+/// Picks the item that compares as `wanted` against every other item.
+fn extreme(
+    input: &RadonArray,
+    reducer: RadonReducers,
+    wanted: Ordering,
+) -> Result<RadonTypes, RadError> {
+    let value = input.value();
+    let mismatching = |expected: &'static str, item: &RadonTypes| RadError::MismatchingTypes {
+        method: reducer.to_string(),
+        expected,
+        found: item.radon_type_name(),
+    };
+
+    match value.first() {
+        None => Ok(RadonTypes::from(RadonFloat::from(std::f64::NAN))),
+        Some(RadonTypes::Integer(first)) => {
+            let result = value
+                .iter()
+                .try_fold(first.value(), |acc, item| match item {
+                    RadonTypes::Integer(i) if i.value().cmp(&acc) == wanted => Ok(i.value()),
+                    RadonTypes::Integer(_) => Ok(acc),
+                    _ => Err(mismatching(RadonInteger::radon_type_name(), item)),
+                })?;
+
+            Ok(RadonTypes::from(RadonInteger::from(result)))
+        }
+        Some(RadonTypes::Float(first)) => {
+            let result = value
+                .iter()
+                .try_fold(first.value(), |acc, item| match item {
+                    RadonTypes::Float(f) if float_total_cmp(f.value(), acc) == wanted => {
+                        Ok(f.value())
+                    }
+                    RadonTypes::Float(_) => Ok(acc),
+                    _ => Err(mismatching(RadonFloat::radon_type_name(), item)),
+                })?;
+
+            Ok(RadonTypes::from(RadonFloat::from(result)))
+        }
+        Some(RadonTypes::String(first)) => {
+            let result = value
+                .iter()
+                .try_fold(first.value(), |acc, item| match item {
+                    RadonTypes::String(s) if s.value().cmp(&acc) == wanted => Ok(s.value()),
+                    RadonTypes::String(_) => Ok(acc),
+                    _ => Err(mismatching(RadonString::radon_type_name(), item)),
+                })?;
+
+            Ok(RadonTypes::from(RadonString::from(result)))
+        }
+        Some(RadonTypes::Array(_)) => {
+            let v = transpose(input)?;
+
+            let mut extreme_v = vec![];
+            for v2reduce in v.value() {
+                if let RadonTypes::Array(v2reduce) = v2reduce {
+                    extreme_v.push(extreme(&v2reduce, reducer, wanted)?);
+                } else {
+                    unreachable!()
+                }
+            }
+
+            Ok(RadonTypes::from(RadonArray::from(extreme_v)))
+        }
+        Some(_rad_types) => Err(RadError::UnsupportedReducer {
+            array: input.clone(),
+            reducer: reducer.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_cbor::Value;
+
+    use crate::{operators::array::reduce, types::boolean::RadonBoolean};
+
+    use super::*;
+
+    #[test]
+    fn test_reduce_min_integer() {
+        let input = &RadonArray::from(vec![
+            RadonInteger::from(3).into(),
+            RadonInteger::from(-1).into(),
+            RadonInteger::from(2).into(),
+        ]);
+        let args = &[Value::Integer(RadonReducers::Min as i128)];
+        let expected = RadonTypes::from(RadonInteger::from(-1));
+
+        let output = reduce(input, args).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_max_integer() {
+        let input = &RadonArray::from(vec![
+            RadonInteger::from(3).into(),
+            RadonInteger::from(-1).into(),
+            RadonInteger::from(2).into(),
+        ]);
+        let args = &[Value::Integer(RadonReducers::Max as i128)];
+        let expected = RadonTypes::from(RadonInteger::from(3));
+
+        let output = reduce(input, args).unwrap();
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_min_max_float() {
+        let input = RadonArray::from(vec![
+            RadonFloat::from(1.5f64).into(),
+            RadonFloat::from(-0.5f64).into(),
+            RadonFloat::from(7.25f64).into(),
+        ]);
+
+        assert_eq!(
+            min(&input).unwrap(),
+            RadonTypes::from(RadonFloat::from(-0.5f64))
+        );
+        assert_eq!(
+            max(&input).unwrap(),
+            RadonTypes::from(RadonFloat::from(7.25f64))
+        );
+    }
+
+    #[test]
+    fn test_reduce_min_max_float_nan_is_greatest() {
+        let input = RadonArray::from(vec![
+            RadonFloat::from(std::f64::NAN).into(),
+            RadonFloat::from(1f64).into(),
+        ]);
+
+        assert_eq!(
+            min(&input).unwrap(),
+            RadonTypes::from(RadonFloat::from(1f64))
+        );
+        assert_eq!(
+            max(&input).unwrap(),
+            RadonTypes::from(RadonFloat::from(std::f64::NAN))
+        );
+    }
+
+    #[test]
+    fn test_reduce_min_max_string() {
+        let input = RadonArray::from(vec![
+            RadonString::from("world").into(),
+            RadonString::from("Hello").into(),
+            RadonString::from("hello").into(),
+        ]);
+
+        assert_eq!(
+            min(&input).unwrap(),
+            RadonTypes::from(RadonString::from("Hello"))
+        );
+        assert_eq!(
+            max(&input).unwrap(),
+            RadonTypes::from(RadonString::from("world"))
+        );
+    }
+
+    #[test]
+    fn test_reduce_min_max_integer_arrays() {
+        let array_1 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonInteger::from(20).into(),
+        ]));
+        let array_2 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(6).into(),
+            RadonInteger::from(10).into(),
+        ]));
+        let input = RadonArray::from(vec![array_1, array_2]);
+
+        let expected_min = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(1).into(),
+            RadonInteger::from(10).into(),
+        ]));
+        let expected_max = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(6).into(),
+            RadonInteger::from(20).into(),
+        ]));
+
+        assert_eq!(min(&input).unwrap(), expected_min);
+        assert_eq!(max(&input).unwrap(), expected_max);
+    }
+
+    #[test]
+    fn test_reduce_min_empty() {
+        let input = RadonArray::from(vec![]);
+        let output = min(&input).unwrap();
+        assert_eq!(output, RadonTypes::from(RadonFloat::from(std::f64::NAN)));
+    }
+
+    #[test]
+    fn test_reduce_max_boolean_unsupported() {
+        let input = &RadonArray::from(vec![
+            RadonBoolean::from(true).into(),
+            RadonBoolean::from(false).into(),
+        ]);
+        let args = &[Value::Integer(RadonReducers::Max as i128)];
+        let output = reduce(input, args).unwrap_err();
+
+        let expected = RadError::UnsupportedReducer {
+            array: input.clone(),
+            reducer: "RadonReducers::Max".to_string(),
+        };
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_reduce_min_float_int_arrays() {
+        let array_1 = RadonTypes::from(RadonArray::from(vec![
+            RadonFloat::from(1f64).into(),
+            RadonFloat::from(2f64).into(),
+        ]));
+        let array_2 = RadonTypes::from(RadonArray::from(vec![
+            RadonInteger::from(6i128).into(),
+            RadonInteger::from(10i128).into(),
+        ]));
+        let input = RadonArray::from(vec![array_1, array_2]);
+
+        let expected = RadError::MismatchingTypes {
+            method: RadonReducers::Min.to_string(),
+            expected: RadonFloat::radon_type_name(),
+            found: RadonInteger::radon_type_name(),
+        };
+
+        assert_eq!(min(&input).unwrap_err(), expected);
+    }
+}
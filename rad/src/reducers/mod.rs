@@ -9,24 +9,24 @@ use crate::{
 
 pub mod average;
 pub mod deviation;
+pub mod hash_concatenate;
+pub mod min_max;
 pub mod mode;
 
-#[derive(Debug, PartialEq, TryFromPrimitive)]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum RadonReducers {
-    // Implemented
-    Mode = 0x02,
-    AverageMean = 0x03,
-    DeviationStandard = 0x07,
-
-    // Not implemented
     Min = 0x00,
     Max = 0x01,
+    Mode = 0x02,
+    AverageMean = 0x03,
     AverageMeanWeighted = 0x04,
     AverageMedian = 0x05,
     AverageMedianWeighted = 0x06,
+    DeviationStandard = 0x07,
     DeviationAverageAbsolute = 0x08,
     DeviationMedianAbsolute = 0x09,
+    HashConcatenate = 0x0B,
     DeviationMaximumAbsolute = 0x10,
 }
 
@@ -37,21 +37,28 @@ impl fmt::Display for RadonReducers {
 }
 
 pub fn reduce(input: &RadonArray, reducer_code: RadonReducers) -> Result<RadonTypes, RadError> {
-    let error = || {
-        Err(RadError::UnsupportedReducer {
-            array: input.clone(),
-            reducer: reducer_code.to_string(),
-        })
-    };
-
     if input.is_homogeneous() || input.value().is_empty() {
         match reducer_code {
+            RadonReducers::Min => min_max::min(input),
+            RadonReducers::Max => min_max::max(input),
+            RadonReducers::Mode => mode::mode(input),
             RadonReducers::AverageMean => {
                 average::mean(input, average::MeanReturnPolicy::RoundToInteger)
             }
-            RadonReducers::Mode => mode::mode(input),
+            RadonReducers::AverageMeanWeighted => {
+                average::mean_weighted(input, average::MeanReturnPolicy::RoundToInteger)
+            }
+            RadonReducers::AverageMedian => {
+                average::median(input, average::MeanReturnPolicy::RoundToInteger)
+            }
+            RadonReducers::AverageMedianWeighted => {
+                average::median_weighted(input, average::MeanReturnPolicy::RoundToInteger)
+            }
             RadonReducers::DeviationStandard => deviation::standard(input),
-            _ => error(),
+            RadonReducers::DeviationAverageAbsolute => deviation::average_absolute(input),
+            RadonReducers::DeviationMedianAbsolute => deviation::median_absolute(input),
+            RadonReducers::DeviationMaximumAbsolute => deviation::maximum_absolute(input),
+            RadonReducers::HashConcatenate => hash_concatenate::hash_concatenate(input),
         }
     } else {
         Err(RadError::UnsupportedOpNonHomogeneous {
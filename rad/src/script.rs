@@ -6,14 +6,15 @@ use serde_cbor::{
 };
 
 use witnet_data_structures::{
-    chain::RADFilter,
+    chain::{Environment, RADFilter},
+    get_environment,
     radon_report::{RadonReport, ReportContext, Stage},
 };
 
 use crate::{
     error::RadError,
     filters::RadonFilters,
-    operators::{operate, operate_in_context, RadonOpCodes},
+    operators::{operate, operate_in_context, registry::CUSTOM_OP_CODES, RadonOpCodes},
     reducers::RadonReducers,
     types::RadonTypes,
 };
@@ -110,8 +111,14 @@ pub fn execute_radon_script(
                 context.call_index = Some(u8::try_from(i).unwrap());
             }
 
-            // Apply the call
-            let partial_result = operate_in_context(input, call, context);
+            // Apply the call, unless the execution has run out of time. This is checked before
+            // every call, including those in subscripts, so that long running scripts are aborted
+            // as soon as possible
+            let partial_result = if context.deadline_elapsed() {
+                Err(RadError::RetrieveTimeout)
+            } else {
+                operate_in_context(input, call, context)
+            };
 
             // Keep partial result, if enabled by `partial_results` setting
             if let Some(partial_results) = partial_results.as_mut() {
@@ -145,10 +152,17 @@ pub fn execute_contextfree_radon_script(
 }
 
 pub fn unpack_radon_script(packed: &[u8]) -> Result<RadonScript, RadError> {
+    unpack_radon_script_in_environment(packed, get_environment())
+}
+
+fn unpack_radon_script_in_environment(
+    packed: &[u8],
+    environment: Environment,
+) -> Result<RadonScript, RadError> {
     match cbor::from_slice(packed) {
         Ok(Value::Array(array)) => array
             .iter()
-            .map(unpack_radon_call)
+            .map(|packed_call| unpack_radon_call_in_environment(packed_call, environment))
             .collect::<Result<RadonScript, RadError>>(),
         Ok(_) => Err(errorify(RadError::ScriptNotArray {
             input_type: String::from("different thing"),
@@ -160,12 +174,23 @@ pub fn unpack_radon_script(packed: &[u8]) -> Result<RadonScript, RadError> {
 }
 
 pub fn unpack_radon_call(packed_call: &Value) -> Result<RadonCall, RadError> {
+    unpack_radon_call_in_environment(packed_call, get_environment())
+}
+
+fn unpack_radon_call_in_environment(
+    packed_call: &Value,
+    environment: Environment,
+) -> Result<RadonCall, RadError> {
     match packed_call {
-        Value::Array(array) => unpack_compound_call(array),
+        Value::Array(array) => unpack_compound_call(array, environment),
         Value::Integer(integer) => {
             if *integer >= 0i128 {
                 let [raw_op_code, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _] =
                     integer.to_le_bytes();
+                if CUSTOM_OP_CODES.contains(&raw_op_code) {
+                    return unpack_custom_call(raw_op_code, &[], environment)
+                        .map_err(|_| errorify(RadError::UnknownOperator { code: *integer }));
+                }
                 RadonOpCodes::try_from(raw_op_code)
                     .map(|op_code| (op_code, None))
                     .map_err(|_| errorify(RadError::UnknownOperator { code: *integer }))
@@ -177,18 +202,50 @@ pub fn unpack_radon_call(packed_call: &Value) -> Result<RadonCall, RadError> {
     }
 }
 
-fn unpack_compound_call(array: &[Value]) -> Result<RadonCall, RadError> {
+fn unpack_compound_call(array: &[Value], environment: Environment) -> Result<RadonCall, RadError> {
     let (head, tail) = array
         .split_first()
         .ok_or_else(|| errorify(RadError::NoOperatorInCompoundCall))?;
     let op_code =
         from_value::<u8>(head.to_owned()).map_err(|_| errorify(RadError::NotIntegerOperator))?;
+    if CUSTOM_OP_CODES.contains(&op_code) {
+        return unpack_custom_call(op_code, tail, environment)
+            .map_err(|_| errorify(RadError::NotIntegerOperator));
+    }
     let op_code =
         RadonOpCodes::try_from(op_code).map_err(|_| errorify(RadError::NotIntegerOperator))?;
 
     Ok((op_code, Some(tail.to_vec())))
 }
 
+/// Unpack a call to a custom operator into a `Custom` call, as long as custom operators are
+/// allowed in the given environment.
+///
+/// The call is unpacked whether the operator is registered in this process or not, because
+/// unpacking is part of the validation of data requests, which must not depend on the
+/// configuration of the node. Calling an operator that is not registered fails when the script is
+/// executed instead.
+fn unpack_custom_call(
+    op_code: u8,
+    args: &[Value],
+    environment: Environment,
+) -> Result<RadonCall, RadError> {
+    if environment == Environment::Mainnet {
+        return Err(RadError::UnknownOperator {
+            code: i128::from(op_code),
+        });
+    }
+
+    Ok((
+        RadonOpCodes::Custom,
+        Some(
+            std::iter::once(Value::Integer(i128::from(op_code)))
+                .chain(args.iter().cloned())
+                .collect(),
+        ),
+    ))
+}
+
 pub fn unpack_subscript(value: &Value) -> Result<Vec<RadonCall>, RadError> {
     let mut subscript = vec![];
     let subscript_arg = match value {
@@ -224,7 +281,13 @@ pub fn create_radon_script_from_filters_and_reducer(
 
         // TODO: Update with more filters
         match rad_filter {
-            RadonFilters::DeviationStandard | RadonFilters::Mode => {}
+            RadonFilters::GreaterThan
+            | RadonFilters::LessThan
+            | RadonFilters::Equals
+            | RadonFilters::DeviationAbsolute
+            | RadonFilters::DeviationRelative
+            | RadonFilters::DeviationStandard
+            | RadonFilters::Mode => {}
             _ => {
                 return Err(RadError::UnsupportedFilterInAT {
                     operator: rad_filter as u8,
@@ -252,7 +315,14 @@ pub fn create_radon_script_from_filters_and_reducer(
     )
     .map_err(|_| unknown_reducer(i128::from(reducer)))?;
     match rad_reducer {
-        RadonReducers::AverageMean | RadonReducers::Mode => {}
+        RadonReducers::Min
+        | RadonReducers::Max
+        | RadonReducers::Mode
+        | RadonReducers::AverageMean
+        | RadonReducers::AverageMeanWeighted
+        | RadonReducers::AverageMedian
+        | RadonReducers::AverageMedianWeighted
+        | RadonReducers::HashConcatenate => {}
         _ => {
             return Err(RadError::UnsupportedReducerInAT {
                 operator: rad_reducer as u8,
@@ -534,6 +604,39 @@ fn test_floats_as_integers() {
     );
 }
 
+#[test]
+fn test_execute_radon_script_past_deadline() {
+    use crate::types::{array::RadonArray, integer::RadonInteger};
+    use witnet_data_structures::radon_error::RadonError;
+
+    let input = RadonTypes::from(RadonArray::from(vec![
+        RadonInteger::from(1).into(),
+        RadonInteger::from(2).into(),
+    ]));
+    let script = vec![(RadonOpCodes::ArrayCount, None)];
+
+    let mut context = ReportContext::default();
+    let output = execute_radon_script(
+        input.clone(),
+        &script,
+        &mut context,
+        RadonScriptExecutionSettings::disable_all(),
+    )
+    .unwrap();
+    assert_eq!(output.result, RadonTypes::from(RadonInteger::from(2)));
+
+    context.deadline = Some(std::time::Instant::now());
+    let output = execute_radon_script(
+        input,
+        &script,
+        &mut context,
+        RadonScriptExecutionSettings::disable_all(),
+    )
+    .unwrap();
+    let expected = RadonTypes::RadonError(RadonError::try_from(RadError::RetrieveTimeout).unwrap());
+    assert_eq!(output.result, expected);
+}
+
 #[test]
 fn test_unpack_radon_script() {
     let cbor_vec = Value::Array(vec![
@@ -596,7 +699,7 @@ fn test_create_radon_script() {
 #[test]
 fn test_create_radon_script_invalid_filter() {
     let filters = vec![RADFilter {
-        op: RadonFilters::DeviationAbsolute as u32,
+        op: RadonFilters::Top as u32,
         args: vec![249, 60, 0],
     }];
     let reducer = RadonReducers::AverageMean as u32;
@@ -604,7 +707,7 @@ fn test_create_radon_script_invalid_filter() {
         create_radon_script_from_filters_and_reducer(filters.as_slice(), reducer).unwrap_err();
 
     let expected = RadError::UnsupportedFilterInAT {
-        operator: RadonFilters::DeviationAbsolute as u8,
+        operator: RadonFilters::Top as u8,
     };
     assert_eq!(output, expected);
 
@@ -625,12 +728,12 @@ fn test_create_radon_script_invalid_reducer() {
         op: RadonFilters::DeviationStandard as u32,
         args: vec![249, 60, 0],
     }];
-    let reducer = RadonReducers::Min as u32;
+    let reducer = RadonReducers::DeviationStandard as u32;
     let output =
         create_radon_script_from_filters_and_reducer(filters.as_slice(), reducer).unwrap_err();
 
     let expected = RadError::UnsupportedReducerInAT {
-        operator: RadonReducers::Min as u8,
+        operator: RadonReducers::DeviationStandard as u8,
     };
     assert_eq!(output, expected);
 
@@ -639,3 +742,57 @@ fn test_create_radon_script_invalid_reducer() {
     let expected = RadError::UnknownReducer { code: 99 };
     assert_eq!(output, expected);
 }
+
+#[test]
+fn test_custom_operators_environment() {
+    use crate::{
+        operators::registry::{custom_operator, custom_operator_in_environment, register_operator},
+        types::string::RadonString,
+    };
+
+    // The global registry is shared by all the tests, so this code must not be used by any other
+    register_operator(0xFD, "identity", |input: RadonTypes, _args: &[Value]| {
+        Ok(input)
+    })
+    .unwrap();
+    assert_eq!(
+        custom_operator_in_environment(0xFD, Environment::Testnet)
+            .unwrap()
+            .name,
+        "identity"
+    );
+
+    // Tests run in mainnet, where registered custom operators are unknown
+    assert!(custom_operator(0xFD).is_none());
+    let packed = cbor::to_vec(&Value::Array(vec![Value::Integer(0xFD)])).unwrap();
+    assert_eq!(
+        unpack_radon_script(&packed),
+        Err(RadError::UnknownOperator { code: 0xFD })
+    );
+    let custom_call = (RadonOpCodes::Custom, Some(vec![Value::Integer(0xFD)]));
+    let input = RadonTypes::from(RadonString::from("42"));
+    assert_eq!(
+        execute_contextfree_radon_script(input.clone(), &[custom_call.clone()]),
+        Err(RadError::UnknownOperator { code: 0xFD })
+    );
+
+    // In other environments, calls to custom operators are unpacked whether they are registered
+    // or not, so the validity of a script does not depend on the configuration of the node
+    assert_eq!(
+        unpack_radon_script_in_environment(&packed, Environment::Testnet),
+        Ok(vec![custom_call])
+    );
+    let packed = cbor::to_vec(&Value::Array(vec![Value::Array(vec![
+        Value::Integer(0xFC),
+        Value::Text("arg".to_string()),
+    ])]))
+    .unwrap();
+    assert!(custom_operator_in_environment(0xFC, Environment::Testnet).is_none());
+    assert_eq!(
+        unpack_radon_script_in_environment(&packed, Environment::Testnet),
+        Ok(vec![(
+            RadonOpCodes::Custom,
+            Some(vec![Value::Integer(0xFC), Value::Text("arg".to_string())])
+        )])
+    );
+}
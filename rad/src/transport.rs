@@ -0,0 +1,417 @@
+//! HTTP transport used for the retrievals of data requests
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
+    thread,
+    time::Duration,
+};
+
+use futures::channel::oneshot;
+use isahc::{
+    config::{CaCertificate, Configurable, Dialer},
+    http::{request::Builder, Uri},
+    HttpClient,
+};
+
+use witnet_config::config::RetrievalTransport;
+
+use crate::{error::RadError, Result};
+
+/// HTTP client used for performing retrievals, configured according to the `RetrievalTransport`
+/// section of the configuration.
+///
+/// Cloning a `RetrievalClient` is cheap, as all the clones share the same connection pool.
+#[derive(Clone, Debug)]
+pub struct RetrievalClient {
+    client: HttpClient,
+    http_proxy: Option<Uri>,
+    https_proxy: Option<Uri>,
+    socks5_proxy: Option<Uri>,
+    deny_private_addresses: bool,
+}
+
+impl RetrievalClient {
+    /// Create a client without proxies and with the default timeouts and certificate
+    /// authorities.
+    pub fn with_defaults() -> Result<Self> {
+        let client = HttpClient::new().map_err(|e| RadError::InvalidRetrievalTransport {
+            message: e.to_string(),
+        })?;
+
+        Ok(Self {
+            client,
+            http_proxy: None,
+            https_proxy: None,
+            socks5_proxy: None,
+            deny_private_addresses: false,
+        })
+    }
+
+    /// Create a client from the transport configuration, validating the proxy addresses.
+    pub fn new(config: &RetrievalTransport) -> Result<Self> {
+        let http_proxy = config
+            .http_proxy
+            .as_ref()
+            .map(|proxy| parse_proxy(proxy, "http", &["http", "https"]))
+            .transpose()?;
+        let https_proxy = config
+            .https_proxy
+            .as_ref()
+            .map(|proxy| parse_proxy(proxy, "http", &["http", "https"]))
+            .transpose()?;
+        // Unless told otherwise, let the SOCKS5 proxy resolve host names so that no DNS queries
+        // leak when routing retrievals over Tor
+        let socks5_proxy = config
+            .socks5_proxy
+            .as_ref()
+            .map(|proxy| parse_proxy(proxy, "socks5h", &["socks5", "socks5h"]))
+            .transpose()?;
+
+        let mut builder = HttpClient::builder();
+        if config.connect_timeout != Duration::from_secs(0) {
+            builder = builder.connect_timeout(config.connect_timeout);
+        }
+        if config.timeout != Duration::from_secs(0) {
+            builder = builder.timeout(config.timeout);
+        }
+        if let Some(ca_bundle) = &config.ca_bundle {
+            if !ca_bundle.is_file() {
+                return Err(RadError::InvalidRetrievalTransport {
+                    message: format!("CA bundle {:?} is not a file", ca_bundle),
+                });
+            }
+            builder = builder.ssl_ca_certificate(CaCertificate::file(ca_bundle));
+        }
+        let client = builder
+            .build()
+            .map_err(|e| RadError::InvalidRetrievalTransport {
+                message: e.to_string(),
+            })?;
+
+        Ok(Self {
+            client,
+            http_proxy,
+            https_proxy,
+            socks5_proxy,
+            deny_private_addresses: false,
+        })
+    }
+
+    /// Refuse to connect to loopback, private and link-local addresses, checking the addresses
+    /// that host names resolve to.
+    ///
+    /// When a proxy is used, the proxy is the one that resolves host names and connects to them,
+    /// so the check only applies to retrievals that are not proxied.
+    pub fn deny_private_addresses(mut self, deny: bool) -> Self {
+        self.deny_private_addresses = deny;
+
+        self
+    }
+
+    /// Proxy that should be used for retrieving the given URL, if any.
+    pub fn proxy_for(&self, url: &url::Url) -> Option<&Uri> {
+        self.socks5_proxy.as_ref().or_else(|| match url.scheme() {
+            "http" => self.http_proxy.as_ref(),
+            "https" => self.https_proxy.as_ref(),
+            _ => None,
+        })
+    }
+
+    /// Apply the per-request configuration needed for retrieving the given URL.
+    ///
+    /// If private addresses are denied, the host of the URL is resolved and checked here, and the
+    /// request is pinned to the resolved address, so that it cannot resolve to a different one
+    /// when connecting.
+    pub(crate) async fn configure_request(
+        &self,
+        request: Builder,
+        url: &url::Url,
+    ) -> Result<Builder> {
+        if let Some(proxy) = self.proxy_for(url) {
+            return Ok(request.proxy(proxy.clone()));
+        }
+        if !self.deny_private_addresses {
+            return Ok(request);
+        }
+
+        let addr = resolve_public_address(url).await?;
+
+        Ok(request.dial(Dialer::ip_socket(addr)))
+    }
+
+    /// Underlying HTTP client.
+    pub(crate) fn http_client(&self) -> &HttpClient {
+        &self.client
+    }
+}
+
+/// Resolve the host of a URL, failing if any of its addresses is a private address.
+///
+/// Host names are resolved in a separate thread, because the system resolver blocks.
+async fn resolve_public_address(url: &url::Url) -> Result<SocketAddr> {
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let resolve_error = |message: String| RadError::HttpOther {
+        message: format!("Failed to resolve host {}: {}", host, message),
+    };
+
+    let addrs: Vec<SocketAddr> = match url.host() {
+        Some(url::Host::Ipv4(ip)) => vec![SocketAddr::new(IpAddr::V4(ip), port)],
+        Some(url::Host::Ipv6(ip)) => vec![SocketAddr::new(IpAddr::V6(ip), port)],
+        Some(url::Host::Domain(domain)) => {
+            let domain = domain.to_string();
+            let (sender, receiver) = oneshot::channel();
+            thread::spawn(move || {
+                let addrs: std::io::Result<Vec<SocketAddr>> = (domain.as_str(), port)
+                    .to_socket_addrs()
+                    .map(Iterator::collect);
+                // The retrieval may have been dropped in the meantime
+                let _ = sender.send(addrs);
+            });
+
+            receiver
+                .await
+                .map_err(|e| resolve_error(e.to_string()))?
+                .map_err(|e| resolve_error(e.to_string()))?
+        }
+        None => vec![],
+    };
+
+    if addrs.iter().any(|addr| is_private_ip(&addr.ip())) {
+        return Err(RadError::PrivateAddress { host: host.clone() });
+    }
+
+    addrs
+        .into_iter()
+        .next()
+        .ok_or_else(|| resolve_error("no addresses found".to_string()))
+}
+
+/// Check whether an IP address refers to the node itself, to its private network, or to a
+/// network of its provider, including IPv4 addresses mapped to IPv6.
+pub fn is_private_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => is_private_ipv6(ip),
+    }
+}
+
+fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    // "This network" (0.0.0.0/8) and shared address space for carrier-grade NAT (100.64.0.0/10)
+    let this_network = octets[0] == 0;
+    let shared = octets[0] == 100 && octets[1] & 0xc0 == 64;
+
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || this_network
+        || shared
+}
+
+fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    // Unique local addresses (fc00::/7) and link-local unicast addresses (fe80::/10)
+    let unique_local = segments[0] & 0xfe00 == 0xfc00;
+    let link_local = segments[0] & 0xffc0 == 0xfe80;
+    // IPv4-mapped addresses (::ffff:0:0/96)
+    let ipv4_mapped = segments[..5].iter().all(|s| *s == 0) && segments[5] == 0xffff;
+
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || unique_local
+        || link_local
+        || (ipv4_mapped && is_private_ipv4(&ip.to_ipv4().unwrap()))
+}
+
+/// Parse the address of a proxy, using `default_scheme` if none was given.
+fn parse_proxy(proxy: &str, default_scheme: &str, allowed_schemes: &[&str]) -> Result<Uri> {
+    let invalid_proxy = |reason: &str| RadError::InvalidRetrievalTransport {
+        message: format!("Invalid proxy {:?}: {}", proxy, reason),
+    };
+
+    let proxy_with_scheme = if proxy.contains("://") {
+        proxy.to_string()
+    } else {
+        format!("{}://{}", default_scheme, proxy)
+    };
+    let uri: Uri = proxy_with_scheme
+        .parse()
+        .map_err(|e: isahc::http::uri::InvalidUri| invalid_proxy(&e.to_string()))?;
+
+    match uri.scheme_str() {
+        Some(scheme) if allowed_schemes.contains(&scheme) => {}
+        _ => {
+            return Err(invalid_proxy(&format!(
+                "scheme must be one of {:?}",
+                allowed_schemes
+            )))
+        }
+    }
+    if uri.host().is_none() {
+        return Err(invalid_proxy("missing host"));
+    }
+
+    Ok(uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use isahc::http::Request;
+    use witnet_config::{config::PartialRetrievalTransport, defaults::Testnet};
+
+    use super::*;
+
+    fn default_transport() -> RetrievalTransport {
+        RetrievalTransport::from_partial(&PartialRetrievalTransport::default(), &Testnet)
+    }
+
+    fn url(s: &str) -> url::Url {
+        url::Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn no_proxy_by_default() {
+        let client = RetrievalClient::new(&default_transport()).unwrap();
+
+        assert_eq!(client.proxy_for(&url("http://example.com")), None);
+        assert_eq!(client.proxy_for(&url("https://example.com")), None);
+    }
+
+    #[test]
+    fn proxy_by_scheme() {
+        let config = RetrievalTransport {
+            http_proxy: Some("127.0.0.1:3128".to_string()),
+            https_proxy: Some("https://proxy.example.com:443".to_string()),
+            ..default_transport()
+        };
+        let client = RetrievalClient::new(&config).unwrap();
+
+        assert_eq!(
+            client.proxy_for(&url("http://example.com")).unwrap(),
+            "http://127.0.0.1:3128"
+        );
+        assert_eq!(
+            client.proxy_for(&url("https://example.com")).unwrap(),
+            "https://proxy.example.com:443"
+        );
+    }
+
+    #[test]
+    fn socks5_proxy_takes_precedence() {
+        let config = RetrievalTransport {
+            http_proxy: Some("127.0.0.1:3128".to_string()),
+            socks5_proxy: Some("127.0.0.1:9050".to_string()),
+            ..default_transport()
+        };
+        let client = RetrievalClient::new(&config).unwrap();
+
+        // Host names are resolved by the proxy unless the scheme is given explicitly
+        assert_eq!(
+            client.proxy_for(&url("http://example.com")).unwrap(),
+            "socks5h://127.0.0.1:9050"
+        );
+        assert_eq!(
+            client.proxy_for(&url("https://example.com")).unwrap(),
+            "socks5h://127.0.0.1:9050"
+        );
+
+        let config = RetrievalTransport {
+            socks5_proxy: Some("socks5://127.0.0.1:9050".to_string()),
+            ..default_transport()
+        };
+        let client = RetrievalClient::new(&config).unwrap();
+        assert_eq!(
+            client.proxy_for(&url("https://example.com")).unwrap(),
+            "socks5://127.0.0.1:9050"
+        );
+    }
+
+    #[test]
+    fn private_ips() {
+        for ip in &[
+            "0.0.0.0",
+            "0.1.2.3",
+            "10.1.2.3",
+            "100.64.0.1",
+            "100.127.255.255",
+            "127.0.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.168.1.1",
+            "255.255.255.255",
+            "::",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+            "::ffff:127.0.0.1",
+            "::ffff:100.64.0.1",
+        ] {
+            assert!(is_private_ip(&ip.parse().unwrap()), "{}", ip);
+        }
+
+        for ip in &[
+            "8.8.8.8",
+            "100.63.255.255",
+            "100.128.0.1",
+            "2001:4860:4860::8888",
+            "::ffff:8.8.8.8",
+        ] {
+            assert!(!is_private_ip(&ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn private_addresses_are_checked_after_resolving() {
+        let client = RetrievalClient::new(&default_transport())
+            .unwrap()
+            .deny_private_addresses(true);
+
+        for s in &[
+            "http://localhost:8080/",
+            "http://127.0.0.1/",
+            "http://[::1]/",
+        ] {
+            let request = block_on(client.configure_request(Request::get(*s), &url(s)));
+            match request {
+                Err(RadError::PrivateAddress { .. }) => {}
+                x => panic!("{} should be a private address, got {:?}", s, x.map(|_| ())),
+            }
+        }
+
+        // Proxies resolve host names themselves
+        let config = RetrievalTransport {
+            http_proxy: Some("127.0.0.1:3128".to_string()),
+            ..default_transport()
+        };
+        let client = RetrievalClient::new(&config)
+            .unwrap()
+            .deny_private_addresses(true);
+        let s = "http://localhost:8080/";
+        assert!(block_on(client.configure_request(Request::get(s), &url(s))).is_ok());
+    }
+
+    #[test]
+    fn invalid_transport() {
+        let wrong_scheme = RetrievalTransport {
+            http_proxy: Some("socks5://127.0.0.1:9050".to_string()),
+            ..default_transport()
+        };
+        assert!(RetrievalClient::new(&wrong_scheme).is_err());
+
+        let invalid_uri = RetrievalTransport {
+            socks5_proxy: Some("127.0.0.1:9050 ".to_string()),
+            ..default_transport()
+        };
+        assert!(RetrievalClient::new(&invalid_uri).is_err());
+
+        let missing_ca_bundle = RetrievalTransport {
+            ca_bundle: Some("/nonexistent/ca.pem".into()),
+            ..default_transport()
+        };
+        assert!(RetrievalClient::new(&missing_ca_bundle).is_err());
+    }
+}
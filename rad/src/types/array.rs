@@ -145,16 +145,28 @@ impl Operable for RadonArray {
             (RadonOpCodes::ArrayFilter, Some(args)) => {
                 array_operators::filter(self, args.as_slice(), &mut ReportContext::default())
             }
+            (RadonOpCodes::ArrayFlatten, None) => {
+                array_operators::flatten(self, &[]).map(RadonTypes::from)
+            }
+            (RadonOpCodes::ArrayFlatten, Some(args)) => {
+                array_operators::flatten(self, args.as_slice()).map(RadonTypes::from)
+            }
             (RadonOpCodes::ArrayMap, Some(args)) => {
                 array_operators::map(self, args.as_slice(), &mut ReportContext::default())
             }
             (RadonOpCodes::ArrayReduce, Some(args)) => {
                 array_operators::reduce(self, args.as_slice())
             }
+            (RadonOpCodes::ArraySome, Some(args)) => {
+                array_operators::some(self, args.as_slice(), &mut ReportContext::default())
+            }
             (RadonOpCodes::ArraySort, Some(args)) => {
                 array_operators::sort(self, args.as_slice(), &mut ReportContext::default())
                     .map(RadonTypes::from)
             }
+            (RadonOpCodes::ArrayTake, Some(args)) => {
+                array_operators::take(self, args.as_slice()).map(RadonTypes::from)
+            }
             (op_code, args) => Err(RadError::UnsupportedOperator {
                 input_type: RADON_ARRAY_TYPE_NAME.to_string(),
                 operator: op_code.to_string(),
@@ -175,6 +187,9 @@ impl Operable for RadonArray {
             (RadonOpCodes::ArrayMap, Some(args)) => {
                 array_operators::map(self, args.as_slice(), context)
             }
+            (RadonOpCodes::ArraySome, Some(args)) => {
+                array_operators::some(self, args.as_slice(), context)
+            }
             (RadonOpCodes::ArraySort, Some(args)) => {
                 array_operators::sort(self, args.as_slice(), context)
             }
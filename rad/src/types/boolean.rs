@@ -79,6 +79,9 @@ impl Operable for RadonBoolean {
     fn operate(&self, call: &RadonCall) -> Result<RadonTypes, RadError> {
         match call {
             (RadonOpCodes::Identity, None) => identity(RadonTypes::from(self.clone())),
+            (RadonOpCodes::BooleanMatch, Some(args)) => {
+                boolean_operators::boolean_match(self, args)
+            }
             (RadonOpCodes::BooleanNegate, None) => Ok(boolean_operators::negate(self).into()),
             (RadonOpCodes::BooleanAsString, None) => boolean_operators::to_string(self.clone())
                 .map(RadonTypes::from)
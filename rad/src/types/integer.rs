@@ -104,6 +104,9 @@ impl<'a> Operable for RadonInteger {
             (RadonOpCodes::IntegerLessThan, Some(args)) => {
                 integer_operators::less_than(self, args).map(Into::into)
             }
+            (RadonOpCodes::IntegerMatch, Some(args)) => {
+                integer_operators::integer_match(self, args.as_slice())
+            }
             (RadonOpCodes::IntegerModulo, Some(args)) => {
                 integer_operators::modulo(self, args.as_slice()).map(Into::into)
             }
@@ -116,6 +119,12 @@ impl<'a> Operable for RadonInteger {
             (RadonOpCodes::IntegerPower, Some(args)) => {
                 integer_operators::power(self, args.as_slice()).map(Into::into)
             }
+            (RadonOpCodes::IntegerReciprocal, None) => {
+                integer_operators::reciprocal(self).map(Into::into)
+            }
+            (RadonOpCodes::IntegerSum, Some(args)) => {
+                integer_operators::sum(self, args.as_slice()).map(Into::into)
+            }
             // Unsupported / unimplemented
             (op_code, args) => Err(RadError::UnsupportedOperator {
                 input_type: RADON_INTEGER_TYPE_NAME.to_string(),
@@ -124,6 +124,7 @@ impl Operable for RadonMap {
     fn operate(&self, call: &RadonCall) -> Result<RadonTypes, RadError> {
         match call {
             (RadonOpCodes::Identity, None) => identity(RadonTypes::from(self.clone())),
+            (RadonOpCodes::MapEntries, None) => Ok(RadonTypes::from(map_operators::entries(self))),
             (RadonOpCodes::MapGetArray, Some(args)) => {
                 map_operators::get_array(self, args.as_slice()).map(RadonTypes::from)
             }
@@ -145,6 +146,9 @@ impl Operable for RadonMap {
             (RadonOpCodes::MapGetString, Some(args)) => {
                 map_operators::get_string(self, args.as_slice()).map(RadonTypes::from)
             }
+            (RadonOpCodes::MapGetPath, Some(args)) => {
+                map_operators::get_path(self, args.as_slice())
+            }
             (RadonOpCodes::MapKeys, None) => Ok(RadonTypes::from(map_operators::keys(self))),
             (RadonOpCodes::MapValues, None) => Ok(RadonTypes::from(map_operators::values(self))),
             (op_code, args) => Err(RadError::UnsupportedOperator {
@@ -88,6 +88,9 @@ impl Operable for RadonString {
             (RadonOpCodes::StringAsInteger, None) => string_operators::to_int(self)
                 .map(RadonTypes::from)
                 .map_err(Into::into),
+            (RadonOpCodes::StringAsBytes, None) => {
+                string_operators::to_bytes(self).map(RadonTypes::from)
+            }
             (RadonOpCodes::StringAsBoolean, None) => string_operators::to_bool(self)
                 .map(RadonTypes::from)
                 .map_err(Into::into),
@@ -97,6 +100,9 @@ impl Operable for RadonString {
             (RadonOpCodes::StringParseJSONMap, None) => string_operators::parse_json_map(self)
                 .map(RadonTypes::from)
                 .map_err(Into::into),
+            (RadonOpCodes::StringParseXML, None) => {
+                string_operators::parse_xml_map(self).map(RadonTypes::from)
+            }
             (RadonOpCodes::StringMatch, Some(args)) => {
                 string_operators::string_match(self, args.as_slice()).map(RadonTypes::from)
             }
@@ -8,24 +8,29 @@ use std::{
     collections::HashMap,
     convert::{TryFrom, TryInto},
     fmt,
-    fs::File,
+    fs::{self, File},
     io::{self, BufRead, BufReader, Read, Write},
     net::{SocketAddr, TcpStream},
-    path::Path,
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
+use witnet_config::config::RetrievalTransport;
 use witnet_crypto::{
     hash::calculate_sha256,
     key::{CryptoEngine, ExtendedPK, ExtendedSK},
 };
 use witnet_data_structures::{
+    audit::{DataRequestAudit, ScriptTrace},
     chain::{
-        Block, ConsensusConstants, DataRequestInfo, DataRequestOutput, Environment, KeyedSignature,
-        NodeStats, OutputPointer, PublicKey, PublicKeyHash, StateMachine, SyncStatus,
-        ValueTransferOutput,
+        Block, ConsensusConstants, DataRequestInfo, DataRequestOutput, Environment, Hash,
+        KeyedSignature, NodeStats, OutputPointer, PublicKey, PublicKeyHash, RADRequest,
+        RADRetrieve, RADType, StateMachine, SyncStatus, ValueTransferOutput,
     },
+    data_request_index::{DataRequestLifecycleStage, DataRequestQuery, DataRequestQueryResult},
     proto::ProtobufConvert,
+    radon_error::RadonErrors,
+    radon_report::RadonReport,
     transaction::Transaction,
     utxo_pool::{UtxoInfo, UtxoSelectionStrategy},
 };
@@ -33,11 +38,21 @@ use witnet_node::actors::{
     json_rpc::json_rpc_methods::{
         AddrType, GetBlockChainParams, GetTransactionOutput, PeersResult,
     },
-    messages::{BuildVtt, GetReputationResult},
+    messages::{AvailableRange, BuildVtt, GetReputationResult},
+};
+use witnet_rad::{
+    analysis::{analyze_request, RequestAnalysis, StaticType},
+    compiler::{compile, decompile},
+    error::RadError,
+    script::RadonScriptExecutionSettings,
+    transport::RetrievalClient,
+    types::{bytes::RadonBytes, RadonTypes},
 };
-use witnet_rad::types::RadonTypes;
 use witnet_util::{credentials::create_credentials_file, timestamp::pretty_print};
-use witnet_validations::validations::{validate_data_request_output, validate_rad_request, Wit};
+use witnet_validations::{
+    estimation::DataRequestEstimate,
+    validations::{validate_data_request_output, validate_rad_request, Wit},
+};
 
 pub fn raw(addr: SocketAddr) -> Result<(), failure::Error> {
     let mut stream = start_client(addr)?;
@@ -483,10 +498,14 @@ pub fn send_vtt(
     Ok(())
 }
 
-fn run_dr_locally(dr: &DataRequestOutput) -> Result<RadonTypes, failure::Error> {
+fn run_dr_locally(
+    dr: &DataRequestOutput,
+    retrieval_transport: &RetrievalTransport,
+) -> Result<RadonTypes, failure::Error> {
     // Block on data request retrieval because the CLI application blocks everywhere anyway
+    let client = RetrievalClient::new(retrieval_transport)?;
     let run_retrieval_blocking =
-        |retrieve| futures03::executor::block_on(witnet_rad::run_retrieval(retrieve));
+        |retrieve| futures03::executor::block_on(witnet_rad::run_retrieval(retrieve, &client));
 
     let mut retrieval_results = vec![];
     for r in &dr.data_request.retrieve {
@@ -541,15 +560,97 @@ fn deserialize_and_validate_hex_dr(hex_bytes: String) -> Result<DataRequestOutpu
     Ok(dr)
 }
 
+fn print_analysis_issues(analysis: &RequestAnalysis) {
+    for issue in &analysis.issues {
+        println!("{} {}", Red.bold().paint("[Issue]"), issue);
+    }
+}
+
+/// The textual representation of the scripts of all the retrieval sources of a data request.
+fn decompile_retrieval_scripts(request: &RADRequest) -> Vec<String> {
+    request
+        .retrieve
+        .iter()
+        .map(|retrieve| {
+            decompile(
+                &retrieve.script,
+                StaticType::retrieval_input(&retrieve.kind),
+            )
+            .unwrap_or_else(|error| format!("<{}>", error))
+        })
+        .collect()
+}
+
+pub fn lint_dr(hex_bytes: String, json: bool) -> Result<(), failure::Error> {
+    let dr_bytes = hex::decode(hex_bytes)?;
+    // Do not validate the data request, so that the issues in its scripts are reported in detail
+    let dr: DataRequestOutput = ProtobufConvert::from_pb_bytes(&dr_bytes)?;
+    let analysis = analyze_request(&dr.data_request);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&analysis)?);
+    } else {
+        let scripts = decompile_retrieval_scripts(&dr.data_request);
+        for (i, (script, output)) in scripts.iter().zip(&analysis.retrieve).enumerate() {
+            println!("Retrieval #{} script: {}", i, script);
+            println!("Retrieval #{} returns: {}", i, output);
+        }
+        println!("Aggregation returns: {}", analysis.aggregate);
+        println!("Tally returns: {}", analysis.tally);
+        print_analysis_issues(&analysis);
+    }
+
+    if !analysis.is_ok() {
+        bail!(
+            "Found {} issue(s) in the RADON scripts of the data request",
+            analysis.issues.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn retrieval_input(rng: bool) -> StaticType {
+    let kind = if rng { RADType::Rng } else { RADType::HttpGet };
+
+    StaticType::retrieval_input(&kind)
+}
+
+pub fn compile_script(script: String, rng: bool) -> Result<(), failure::Error> {
+    let script_bytes = compile(&script, retrieval_input(rng))?;
+    println!("{}", hex::encode(script_bytes));
+
+    Ok(())
+}
+
+pub fn decompile_script(hex_bytes: String, rng: bool) -> Result<(), failure::Error> {
+    let script_bytes = hex::decode(hex_bytes)?;
+    println!("{}", decompile(&script_bytes, retrieval_input(rng))?);
+
+    Ok(())
+}
+
 pub fn send_dr(
     addr: SocketAddr,
     hex_bytes: String,
     fee: u64,
     run: bool,
+    retrieval_transport: &RetrievalTransport,
 ) -> Result<(), failure::Error> {
     let dr_output = deserialize_and_validate_hex_dr(hex_bytes)?;
+
+    // Refuse to send data requests whose scripts are known to fail
+    let analysis = analyze_request(&dr_output.data_request);
+    if !analysis.is_ok() {
+        print_analysis_issues(&analysis);
+        bail!(
+            "Found {} issue(s) in the RADON scripts of the data request",
+            analysis.issues.len()
+        );
+    }
+
     if run {
-        run_dr_locally(&dr_output)?;
+        run_dr_locally(&dr_output, retrieval_transport)?;
     }
 
     let bdr_params = json!({"dro": dr_output, "fee": fee});
@@ -565,6 +666,308 @@ pub fn send_dr(
     Ok(())
 }
 
+pub fn estimate_dr(
+    addr: SocketAddr,
+    hex_bytes: String,
+    fee: u64,
+    json: bool,
+) -> Result<(), failure::Error> {
+    let dr_bytes = hex::decode(hex_bytes)?;
+    // Do not validate the data request, the node reports the invalid parameters as warnings
+    let dr_output: DataRequestOutput = ProtobufConvert::from_pb_bytes(&dr_bytes)?;
+
+    let edr_params = json!({"dro": dr_output, "fee": fee});
+    let request = format!(
+        r#"{{"jsonrpc": "2.0","method": "estimateRequest", "params": {}, "id": "1"}}"#,
+        serde_json::to_string(&edr_params)?
+    );
+    let mut stream = start_client(addr)?;
+    let response = send_request(&mut stream, &request)?;
+    let estimate: DataRequestEstimate = parse_response(&response)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&estimate)?);
+    } else {
+        println!("Weight: {}", estimate.weight);
+        println!(
+            "Rewards of {} witnesses: {} wits",
+            dr_output.witnesses,
+            Wit::from_nanowits(estimate.witness_rewards)
+        );
+        println!(
+            "Commit and reveal fees: {} wits",
+            Wit::from_nanowits(estimate.commit_and_reveal_fees)
+        );
+        println!("Fee: {} wits", Wit::from_nanowits(estimate.fee));
+        println!(
+            "Total cost: {} wits",
+            Wit::from_nanowits(estimate.total_cost)
+        );
+        println!(
+            "Maximum change returned by the tally: {} wits",
+            Wit::from_nanowits(estimate.max_tally_change)
+        );
+        println!(
+            "Collateral per witness: {} wits",
+            Wit::from_nanowits(estimate.collateral)
+        );
+        for warning in &estimate.warnings {
+            println!("{} {}", Yellow.bold().paint("[Warning]"), warning);
+        }
+    }
+
+    if estimate.is_rejected() {
+        bail!("The data request would be rejected by the network");
+    }
+
+    Ok(())
+}
+
+/// Read the recorded responses of the sources of a data request from a directory.
+///
+/// The response of the source with index `i` is read from the file named `i` in that directory,
+/// with any extension (e.g. `0.json`, `1.xml`). The responses of RNG sources are expected to be
+/// hex-encoded random bytes, as they are stored in the audit log of the node.
+fn read_recorded_payloads(
+    dir: &Path,
+    retrieve: &[RADRetrieve],
+) -> Result<Vec<RadonTypes>, failure::Error> {
+    let mut files = HashMap::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            if let Some(previous) = files.insert(stem.to_string(), path.clone()) {
+                bail!(
+                    "Found more than one payload for the same source: {:?} and {:?}",
+                    previous,
+                    path
+                );
+            }
+        }
+    }
+
+    retrieve
+        .iter()
+        .enumerate()
+        .map(|(i, retrieve)| -> Result<RadonTypes, failure::Error> {
+            let path = match files.get(&i.to_string()) {
+                Some(path) => path,
+                None => bail!("Missing payload for retrieval #{} ({})", i, retrieve.url),
+            };
+            let payload = fs::read_to_string(path)?;
+
+            Ok(match retrieve.kind {
+                RADType::Rng => RadonTypes::from(RadonBytes::from(hex::decode(payload.trim())?)),
+                _ => witnet_rad::injected_input(retrieve, &payload),
+            })
+        })
+        .collect()
+}
+
+/// Print the result of every call of a script, starting with its input.
+fn print_report_trace(report: &RadonReport<RadonTypes>) {
+    for (i, partial_result) in report.partial_results.iter().flatten().enumerate() {
+        println!("  [{}] {}", i, partial_result);
+    }
+    println!("  Result: {}", report.result);
+}
+
+/// Get a data request output, either from a data request transaction known by the node, or from
+/// a JSON file.
+fn get_dr_output(
+    addr: SocketAddr,
+    dr_tx_hash: Option<String>,
+    file: Option<PathBuf>,
+) -> Result<DataRequestOutput, failure::Error> {
+    Ok(match (dr_tx_hash, file) {
+        (Some(hash), None) => {
+            let mut stream = start_client(addr)?;
+            let request = format!(
+                r#"{{"jsonrpc": "2.0","method": "getTransaction", "params": [{:?}], "id": "1"}}"#,
+                hash,
+            );
+            let response = send_request(&mut stream, &request)?;
+            let transaction: GetTransactionOutput = parse_response(&response)?;
+            match transaction.transaction {
+                Transaction::DataRequest(dr_tx) => dr_tx.body.dr_output,
+                _ => bail!("This is not a data request transaction"),
+            }
+        }
+        (None, Some(file)) => serde_json::from_reader(BufReader::new(File::open(file)?))?,
+        _ => bail!("Exactly one of --dr-tx-hash and --file must be provided"),
+    })
+}
+
+pub fn replay_dr(
+    addr: SocketAddr,
+    dr_tx_hash: Option<String>,
+    file: Option<PathBuf>,
+    payloads: &Path,
+    json: bool,
+) -> Result<(), failure::Error> {
+    let dr_output = get_dr_output(addr, dr_tx_hash, file)?;
+    let request = &dr_output.data_request;
+    let inputs = read_recorded_payloads(payloads, &request.retrieve)?;
+
+    let report = witnet_rad::replay_data_request(
+        request,
+        RadonScriptExecutionSettings::enable_all(),
+        inputs,
+    );
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        let scripts = decompile_retrieval_scripts(request);
+        for (i, ((retrieve, script), retrieve_report)) in request
+            .retrieve
+            .iter()
+            .zip(&scripts)
+            .zip(&report.retrieve)
+            .enumerate()
+        {
+            println!("Retrieval #{}: {:?} {}", i, retrieve.kind, retrieve.url);
+            println!("  Script: {}", script);
+            print_report_trace(retrieve_report);
+        }
+        println!("Aggregation:");
+        print_report_trace(&report.aggregate);
+        println!("Tally:");
+        print_report_trace(&report.tally);
+    }
+
+    Ok(())
+}
+
+/// Parse a synthetic reveal: `null` if the witness did not reveal, `{"value": <JSON value>}` if it
+/// revealed a value, or `{"error": <name or code of the error>, "args": [...]}` if it revealed an
+/// error.
+fn parse_simulated_reveal(reveal: serde_json::Value) -> Result<Option<RadonTypes>, failure::Error> {
+    let reveal = match reveal {
+        serde_json::Value::Null => return Ok(None),
+        serde_json::Value::Object(reveal) => reveal,
+        other => bail!(
+            "Invalid reveal {}: expected null, {{\"value\": ...}} or {{\"error\": ...}}",
+            other
+        ),
+    };
+
+    if let Some(value) = reveal.get("value") {
+        let value_bytes = witnet_rad::cbor_to_vec(value)?;
+
+        return Ok(Some(RadonTypes::try_from(value_bytes.as_slice())?));
+    }
+
+    let kind = match reveal.get("error") {
+        Some(serde_json::Value::Number(code)) => code
+            .as_u64()
+            .and_then(|code| u8::try_from(code).ok())
+            .and_then(|code| RadonErrors::try_from(code).ok()),
+        Some(serde_json::Value::String(name)) => (0..=u8::MAX)
+            .filter_map(|code| RadonErrors::try_from(code).ok())
+            .find(|kind| format!("{:?}", kind) == *name),
+        _ => bail!(
+            "Invalid reveal {:?}: expected null, {{\"value\": ...}} or {{\"error\": ...}}",
+            reveal
+        ),
+    };
+    let kind = match kind {
+        Some(kind) => kind,
+        None => bail!("Unknown RADON error {}", reveal["error"]),
+    };
+    let args = match reveal.get("args") {
+        Some(serde_json::Value::Array(args)) => Some(
+            args.iter()
+                .map(serde_cbor::value::to_value)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Some(other) => bail!("Invalid RADON error arguments {}: expected an array", other),
+        None => None,
+    };
+    let error = RadError::try_from_kind_and_cbor_args(kind, args)?;
+
+    Ok(Some(RadonTypes::RadonError(error)))
+}
+
+pub fn simulate_tally(
+    addr: SocketAddr,
+    dr_tx_hash: Option<String>,
+    file: Option<PathBuf>,
+    reveals: &Path,
+    json: bool,
+) -> Result<(), failure::Error> {
+    let mut dr_output = get_dr_output(addr, dr_tx_hash, file)?;
+
+    // When collateral is set to 0, it is actually the default collateral
+    // Get the consensus constants from to node to find out what is the default collateral
+    if dr_output.collateral == 0 {
+        let mut stream = start_client(addr)?;
+        let request = r#"{"jsonrpc": "2.0","method": "getConsensusConstants", "id": "1"}"#;
+        let response = send_request(&mut stream, request)?;
+        let consensus_constants: ConsensusConstants = parse_response(&response)?;
+        dr_output.collateral = consensus_constants.collateral_minimum;
+    }
+
+    let reveals: Vec<serde_json::Value> =
+        serde_json::from_reader(BufReader::new(File::open(reveals)?))?;
+    let reveals = reveals
+        .into_iter()
+        .map(parse_simulated_reveal)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let simulation =
+        witnet_validations::simulation::simulate_tally(&dr_output, reveals, dr_output.collateral)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&simulation)?);
+    } else {
+        let mut witnesses_table = Table::new();
+        witnesses_table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        witnesses_table.set_titles(row!["Witness", "Reveal", "Outcome", "Paid", "Profit"]);
+        for (i, witness) in simulation.witnesses.iter().enumerate() {
+            let profit = if witness.value >= simulation.collateral {
+                format!(
+                    "+{}",
+                    Wit::from_nanowits(witness.value - simulation.collateral)
+                )
+            } else {
+                format!(
+                    "-{}",
+                    Wit::from_nanowits(simulation.collateral - witness.value)
+                )
+            };
+            witnesses_table.add_row(row![
+                i,
+                witness
+                    .reveal
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                format!("{:?}", witness.outcome),
+                Wit::from_nanowits(witness.value),
+                profit,
+            ]);
+        }
+        witnesses_table.printstd();
+        println!();
+        println!("Tally result: {}", simulation.tally);
+        println!("Consensus: {:.2}%", simulation.consensus * 100.0);
+        println!(
+            "Collateral per witness: {} wits",
+            Wit::from_nanowits(simulation.collateral)
+        );
+        println!(
+            "Change returned to the requester: {} wits",
+            Wit::from_nanowits(simulation.change)
+        );
+    }
+
+    Ok(())
+}
+
 pub fn master_key_export(
     addr: SocketAddr,
     write_to_path: Option<&Path>,
@@ -640,6 +1043,10 @@ impl fmt::Display for DataRequestTransactionInfo {
                 "data_request_output: {}",
                 serde_json::to_string_pretty(&self.data_request_output).unwrap()
             )?;
+            let scripts = decompile_retrieval_scripts(&self.data_request_output.data_request);
+            for (i, script) in scripts.iter().enumerate() {
+                writeln!(f, "Retrieval #{} script: {}", i, script)?;
+            }
         }
 
         if self.block_hash_data_request_tx == "pending" {
@@ -968,6 +1375,202 @@ pub fn get_known_peers(addr: SocketAddr) -> Result<(), failure::Error> {
     Ok(())
 }
 
+pub fn data_request_audit(
+    addr: SocketAddr,
+    hash: String,
+    json: bool,
+) -> Result<(), failure::Error> {
+    let mut stream = start_client(addr)?;
+    let request = format!(
+        r#"{{"jsonrpc": "2.0","method": "dataRequestAudit", "params": [{:?}], "id": "1"}}"#,
+        hash,
+    );
+    let response = send_request(&mut stream, &request)?;
+    let audit: DataRequestAudit = parse_response(&response)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&audit)?);
+        return Ok(());
+    }
+
+    let print_script = |trace: &ScriptTrace| {
+        for (i, partial_result) in trace.partial_results.iter().enumerate() {
+            println!("    [{}] {}", i, partial_result);
+        }
+        println!(
+            "    Result: {} ({} ms)",
+            trace.result,
+            trace.running_time.as_millis()
+        );
+    };
+
+    println!("Data request: {}", audit.dr_pointer);
+    println!("Resolved in epoch: {}", audit.epoch);
+    for (i, source) in audit.trace.sources.iter().enumerate() {
+        println!("Retrieval #{}: {:?} {}", i, source.kind, source.url);
+        println!("  Retrieval time: {} ms", source.retrieval_time.as_millis());
+        if let Some(response) = &source.response {
+            println!("  Response: {}", response);
+        }
+        if let Some(error) = &source.error {
+            println!("  Error: {}", Red.paint(error));
+        }
+        if let Some(script) = &source.script {
+            println!("  Script:");
+            print_script(script);
+        }
+    }
+    if let Some(aggregation) = &audit.trace.aggregation {
+        println!("Aggregation:");
+        print_script(aggregation);
+    }
+    println!("Result: {}", audit.result);
+    println!("Revealed value: {}", hex::encode(&audit.reveal_value));
+    println!(
+        "Commit transaction: {}",
+        audit
+            .commit_transaction
+            .map(|x| x.to_string())
+            .unwrap_or_default()
+    );
+    println!(
+        "Reveal transaction: {}",
+        audit
+            .reveal_transaction
+            .map(|x| x.to_string())
+            .unwrap_or_default()
+    );
+
+    Ok(())
+}
+
+/// Criteria of the `queryDataRequests` command, before resolving the stage and the epoch range
+#[derive(Debug)]
+pub struct DataRequestQueryArgs {
+    pub requester: Option<PublicKeyHash>,
+    pub rad_hash: Option<Hash>,
+    pub stage: Option<String>,
+    pub from_epoch: Option<u32>,
+    pub to_epoch: Option<u32>,
+    pub last_epochs: Option<u32>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+pub fn query_data_requests(
+    addr: SocketAddr,
+    args: DataRequestQueryArgs,
+    json: bool,
+) -> Result<(), failure::Error> {
+    let mut stream = start_client(addr)?;
+
+    let stage: Option<DataRequestLifecycleStage> = match args.stage {
+        Some(stage) => Some(
+            serde_json::from_value(json!(stage.to_uppercase())).map_err(|_| {
+                failure::format_err!(
+                    "Invalid stage `{}`, expected one of: commit, reveal, tally, finished",
+                    stage
+                )
+            })?,
+        ),
+        None => None,
+    };
+
+    let from_epoch = match args.last_epochs {
+        Some(last_epochs) => {
+            let request = r#"{"jsonrpc": "2.0","method": "syncStatus", "id": "1"}"#;
+            let response = send_request(&mut stream, &request)?;
+            let sync_status: SyncStatus = parse_response(&response)?;
+            let current_epoch = sync_status.current_epoch.unwrap_or_default();
+
+            Some(current_epoch.saturating_sub(last_epochs))
+        }
+        None => args.from_epoch,
+    };
+
+    let query = DataRequestQuery {
+        requester: args.requester,
+        rad_hash: args.rad_hash,
+        stage,
+        from_epoch,
+        to_epoch: args.to_epoch,
+        offset: args.offset,
+        limit: args.limit,
+    };
+    let request = format!(
+        r#"{{"jsonrpc": "2.0","method": "queryDataRequests", "params": {}, "id": "1"}}"#,
+        serde_json::to_string(&query)?,
+    );
+    let response = send_request(&mut stream, &request)?;
+    let result: DataRequestQueryResult = parse_response(&response)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(*prettytable::format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![
+        "Data request",
+        "Epoch",
+        "Requester",
+        "Stage",
+        "Result"
+    ]);
+    for record in &result.data_requests {
+        let tally_result = match &record.tally {
+            Some(tally) if tally.success => tally.result.clone(),
+            Some(tally) => Red.paint(&tally.result).to_string(),
+            None => String::new(),
+        };
+        table.add_row(row![
+            record.dr_pointer.to_string(),
+            record.epoch,
+            record.requester.to_string(),
+            format!("{:?}", record.stage),
+            tally_result
+        ]);
+    }
+    table.printstd();
+    println!(
+        "Showing {} of {} matching data requests",
+        result.data_requests.len(),
+        result.total
+    );
+
+    Ok(())
+}
+
+pub fn get_available_range(addr: SocketAddr, json: bool) -> Result<(), failure::Error> {
+    let mut stream = start_client(addr)?;
+    let request = r#"{"jsonrpc": "2.0","method": "getAvailableRange", "id": "1"}"#;
+    let response = send_request(&mut stream, &request)?;
+    let available_range: AvailableRange = parse_response(&response)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&available_range)?);
+        return Ok(());
+    }
+
+    println!(
+        "Pruned mode: {}\n\
+         Blocks: from epoch #{} to epoch #{}\n\
+         Superblocks: from #{} to #{}",
+        if available_range.pruned {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        available_range.first_block_epoch,
+        available_range.last_block_epoch,
+        available_range.first_superblock_index,
+        available_range.last_superblock_index
+    );
+
+    Ok(())
+}
+
 pub fn get_node_stats(addr: SocketAddr) -> Result<(), failure::Error> {
     let mut stream = start_client(addr)?;
     let request = r#"{"jsonrpc": "2.0","method": "nodeStats", "id": "1"}"#;
@@ -980,12 +1583,20 @@ pub fn get_node_stats(addr: SocketAddr) -> Result<(), failure::Error> {
      - Blocks included in the block chain: {}\n\
     Data Request mining stats:\n\
      - Times with eligibility to mine a data request: {}\n\
+     - Data requests skipped by the retrieval policy: {}\n\
+     - Data requests skipped by the selection criteria: {}\n\
+     - Sources shared with other data requests (cache hits): {}\n\
+     - Sources retrieved (cache misses): {}\n\
      - Proposed commits: {}\n\
      - Accepted commits: {}\n\
      - Slashed commits: {}",
         node_stats.block_proposed_count,
         node_stats.block_mined_count,
         node_stats.dr_eligibility_count,
+        node_stats.dr_skipped_by_policy_count,
+        node_stats.dr_skipped_by_selection_count,
+        node_stats.retrieval_cache_hits,
+        node_stats.retrieval_cache_misses,
         node_stats.commits_proposed_count,
         node_stats.commits_count,
         node_stats.slashed_count
@@ -1291,6 +1902,8 @@ fn parse_response<'a, T: Deserialize<'a>>(response: &'a str) -> Result<T, failur
 
 #[cfg(test)]
 mod tests {
+    use witnet_rad::{types::string::RadonString, CborValue};
+
     use super::*;
 
     #[test]
@@ -1329,6 +1942,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn recorded_payloads() {
+        let retrieve = |kind, url: &str| RADRetrieve {
+            kind,
+            url: url.to_string(),
+            script: vec![128],
+            body: vec![],
+            headers: vec![],
+        };
+        let sources = vec![
+            retrieve(RADType::HttpGet, "https://example.com/0"),
+            retrieve(RADType::Rng, ""),
+        ];
+
+        let dir = std::env::temp_dir().join(format!("witnet-replay-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("0.json"), r#"{"price": 1}"#).unwrap();
+
+        // The payload of the RNG source is missing
+        assert!(read_recorded_payloads(&dir, &sources).is_err());
+
+        fs::write(dir.join("1"), "00ff\n").unwrap();
+        let payloads = read_recorded_payloads(&dir, &sources).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            payloads,
+            vec![
+                RadonTypes::from(RadonString::from(r#"{"price": 1}"#)),
+                RadonTypes::from(RadonBytes::from(vec![0x00, 0xff])),
+            ]
+        );
+    }
+
+    #[test]
+    fn simulated_reveals() {
+        let parse = |reveal: &str| parse_simulated_reveal(serde_json::from_str(reveal).unwrap());
+
+        assert_eq!(parse("null").unwrap(), None);
+        assert_eq!(
+            parse(r#"{"value": "hello"}"#).unwrap(),
+            Some(RadonTypes::from(RadonString::from("hello")))
+        );
+        assert_eq!(
+            parse(r#"{"error": "HTTPError", "args": [404]}"#).unwrap(),
+            parse(r#"{"error": 48, "args": [404]}"#).unwrap()
+        );
+        assert_eq!(
+            parse(r#"{"error": "HTTPError", "args": [404]}"#).unwrap(),
+            Some(RadonTypes::RadonError(
+                RadError::try_from_kind_and_cbor_args(
+                    RadonErrors::HTTPError,
+                    Some(vec![CborValue::Integer(404)])
+                )
+                .unwrap()
+            ))
+        );
+        // Missing arguments
+        assert!(parse(r#"{"error": "HTTPError"}"#).is_err());
+        assert!(parse(r#"{"error": "NotAnError"}"#).is_err());
+        assert!(parse("1").is_err());
+    }
+
     #[test]
     fn verify_claim_output() {
         use witnet_crypto::{
@@ -144,7 +144,53 @@ pub fn exec_cmd(
             hex,
             fee,
             run,
-        } => rpc::send_dr(node.unwrap_or(config.jsonrpc.server_address), hex, fee, run),
+        } => rpc::send_dr(
+            node.unwrap_or(config.jsonrpc.server_address),
+            hex,
+            fee,
+            run,
+            &config.retrieval_transport,
+        ),
+        Command::EstimateRequest {
+            node,
+            hex,
+            fee,
+            json,
+        } => rpc::estimate_dr(
+            node.unwrap_or(config.jsonrpc.server_address),
+            hex,
+            fee,
+            json,
+        ),
+        Command::LintRequest { hex, json } => rpc::lint_dr(hex, json),
+        Command::SimulateTally {
+            node,
+            dr_tx_hash,
+            file,
+            reveals,
+            json,
+        } => rpc::simulate_tally(
+            node.unwrap_or(config.jsonrpc.server_address),
+            dr_tx_hash,
+            file,
+            &reveals,
+            json,
+        ),
+        Command::ReplayRequest {
+            node,
+            dr_tx_hash,
+            file,
+            payloads,
+            json,
+        } => rpc::replay_dr(
+            node.unwrap_or(config.jsonrpc.server_address),
+            dr_tx_hash,
+            file,
+            &payloads,
+            json,
+        ),
+        Command::CompileScript { script, rng } => rpc::compile_script(script, rng),
+        Command::DecompileScript { hex, rng } => rpc::decompile_script(hex, rng),
         Command::Raw { node } => rpc::raw(node.unwrap_or(config.jsonrpc.server_address)),
         Command::ShowConfig => {
             let serialized = toml::to_string(&config.to_partial()).unwrap();
@@ -216,6 +262,39 @@ pub fn exec_cmd(
             json,
             print_data_request,
         ),
+        Command::DataRequestAudit {
+            node,
+            dr_tx_hash,
+            json,
+        } => rpc::data_request_audit(
+            node.unwrap_or(config.jsonrpc.server_address),
+            dr_tx_hash,
+            json,
+        ),
+        Command::QueryDataRequests {
+            node,
+            requester,
+            rad_hash,
+            stage,
+            from_epoch,
+            to_epoch,
+            last_epochs,
+            offset,
+            limit,
+            json,
+        } => {
+            let query = rpc::DataRequestQueryArgs {
+                requester: requester.map(|x| x.parse()).transpose()?,
+                rad_hash: rad_hash.map(|x| x.parse()).transpose()?,
+                stage,
+                from_epoch,
+                to_epoch,
+                last_epochs,
+                offset,
+                limit,
+            };
+            rpc::query_data_requests(node.unwrap_or(config.jsonrpc.server_address), query, json)
+        }
         Command::GetPeers { node } => rpc::get_peers(node.unwrap_or(config.jsonrpc.server_address)),
         Command::GetKnownPeers { node } => {
             rpc::get_known_peers(node.unwrap_or(config.jsonrpc.server_address))
@@ -223,6 +302,9 @@ pub fn exec_cmd(
         Command::GetNodeStats { node } => {
             rpc::get_node_stats(node.unwrap_or(config.jsonrpc.server_address))
         }
+        Command::GetAvailableRange { node, json } => {
+            rpc::get_available_range(node.unwrap_or(config.jsonrpc.server_address), json)
+        }
         Command::AddPeers { node, peers } => {
             rpc::add_peers(node.unwrap_or(config.jsonrpc.server_address), peers)
         }
@@ -504,6 +586,103 @@ pub enum Command {
         #[structopt(long = "run")]
         run: bool,
     },
+    #[structopt(
+        name = "estimateRequest",
+        alias = "estimate-request",
+        about = "Estimate the cost of a serialized data request, and warn about settings that are likely to fail"
+    )]
+    EstimateRequest {
+        /// Socket address of the Witnet node to query
+        #[structopt(short = "n", long = "node")]
+        node: Option<SocketAddr>,
+        #[structopt(long = "hex")]
+        hex: String,
+        #[structopt(long = "fee", default_value = "0")]
+        fee: u64,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
+    #[structopt(
+        name = "lintRequest",
+        alias = "lint-request",
+        about = "Check the RADON scripts of a serialized data request for type errors, without sending it"
+    )]
+    LintRequest {
+        #[structopt(long = "hex")]
+        hex: String,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
+    #[structopt(
+        name = "simulateTally",
+        alias = "simulate-tally",
+        about = "Compute the tally and the rewards of a data request from a list of synthetic reveals"
+    )]
+    SimulateTally {
+        /// Socket address of the Witnet node to query
+        #[structopt(short = "n", long = "node")]
+        node: Option<SocketAddr>,
+        /// Hash of the data request transaction, which is fetched from the node
+        #[structopt(long = "dr-tx-hash")]
+        dr_tx_hash: Option<String>,
+        /// JSON file containing the data request output, in the format of `sendRequest`
+        #[structopt(long = "file", parse(from_os_str))]
+        file: Option<PathBuf>,
+        /// JSON file with an array containing the reveal of each witness: `null` if the witness
+        /// did not reveal, `{"value": 1.5}` if it revealed a value, or
+        /// `{"error": "HTTPError", "args": [404]}` if it revealed an error
+        #[structopt(long = "reveals", parse(from_os_str))]
+        reveals: PathBuf,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
+    #[structopt(
+        name = "replayRequest",
+        alias = "replay-request",
+        about = "Run a data request offline, feeding recorded payloads to its retrieval scripts"
+    )]
+    ReplayRequest {
+        /// Socket address of the Witnet node to query
+        #[structopt(short = "n", long = "node")]
+        node: Option<SocketAddr>,
+        /// Hash of the data request transaction, which is fetched from the node
+        #[structopt(long = "dr-tx-hash")]
+        dr_tx_hash: Option<String>,
+        /// JSON file containing the data request output, in the format of `sendRequest`
+        #[structopt(long = "file", parse(from_os_str))]
+        file: Option<PathBuf>,
+        /// Directory with the recorded response of each source, in files named after the index
+        /// of the source (e.g. `0.json`, `1.json`)
+        #[structopt(long = "payloads", parse(from_os_str))]
+        payloads: PathBuf,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
+    #[structopt(
+        name = "compileScript",
+        alias = "compile-script",
+        about = "Compile a RADON retrieval script written as text, e.g. '.parseJSONMap().getFloat(\"price\")', into hex-encoded CBOR"
+    )]
+    CompileScript {
+        /// Textual representation of the script
+        #[structopt(name = "script")]
+        script: String,
+        /// The script belongs to a RNG source, so its input is bytes instead of a string
+        #[structopt(long = "rng")]
+        rng: bool,
+    },
+    #[structopt(
+        name = "decompileScript",
+        alias = "decompile-script",
+        about = "Show the textual representation of a hex-encoded RADON retrieval script"
+    )]
+    DecompileScript {
+        #[structopt(long = "hex")]
+        hex: String,
+        /// The script belongs to a RNG source, so its input is bytes instead of a string
+        #[structopt(long = "rng")]
+        rng: bool,
+    },
     #[structopt(
         name = "config",
         alias = "show-config",
@@ -538,6 +717,55 @@ pub enum Command {
         #[structopt(long = "show-dr", help = "Print data request")]
         print_data_request: bool,
     },
+    #[structopt(
+        name = "dataRequestAudit",
+        about = "Show the audit record of a data request committed to by this node"
+    )]
+    DataRequestAudit {
+        /// Socket address of the Witnet node to query
+        #[structopt(short = "n", long = "node")]
+        node: Option<SocketAddr>,
+        #[structopt(name = "dr-tx-hash", help = "Data request transaction hash")]
+        dr_tx_hash: String,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
+    #[structopt(
+        name = "queryDataRequests",
+        alias = "query-data-requests",
+        about = "Query the data request index of the node, most recent data requests first"
+    )]
+    QueryDataRequests {
+        /// Socket address of the Witnet node to query
+        #[structopt(short = "n", long = "node")]
+        node: Option<SocketAddr>,
+        /// Only show data requests posted by this address
+        #[structopt(long = "requester")]
+        requester: Option<String>,
+        /// Only show data requests with this RAD request hash
+        #[structopt(long = "rad-hash")]
+        rad_hash: Option<String>,
+        /// Only show data requests in this stage: commit, reveal, tally or finished
+        #[structopt(long = "stage")]
+        stage: Option<String>,
+        /// Only show data requests unlocked at this epoch or later
+        #[structopt(long = "from-epoch")]
+        from_epoch: Option<u32>,
+        /// Only show data requests unlocked at this epoch or earlier
+        #[structopt(long = "to-epoch")]
+        to_epoch: Option<u32>,
+        /// Only show data requests unlocked in the last N epochs. Overrides --from-epoch
+        #[structopt(long = "last-epochs")]
+        last_epochs: Option<u32>,
+        /// Number of matching data requests to skip
+        #[structopt(long = "offset", default_value = "0")]
+        offset: usize,
+        /// Maximum number of data requests to show
+        #[structopt(long = "limit", default_value = "100")]
+        limit: usize,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
     #[structopt(
         name = "peers",
         alias = "getPeers",
@@ -568,6 +796,18 @@ pub enum Command {
         #[structopt(short = "n", long = "node")]
         node: Option<SocketAddr>,
     },
+    #[structopt(
+        name = "availableRange",
+        alias = "getAvailableRange",
+        about = "Get the range of blocks and superblocks stored by the node, which is limited in pruned mode"
+    )]
+    GetAvailableRange {
+        /// Socket address of the Witnet node to query
+        #[structopt(short = "n", long = "node")]
+        node: Option<SocketAddr>,
+        #[structopt(long = "json", help = "Show output in JSON format")]
+        json: bool,
+    },
     #[structopt(
         name = "addPeers",
         about = "Add new peer addresses for the node to try to connect to"
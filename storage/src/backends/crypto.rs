@@ -2,26 +2,74 @@
 //!
 //! High-order storage backend that hashes the key and
 //! encrypts/decrypts the value when putting/getting it.
-use crate::storage::{Result, Storage};
+//!
+//! As hashed keys cannot be iterated in order, the original key of every entry is also stored
+//! encrypted in a key index. The keys in the index are encrypted with a secret that is derived
+//! only once per backend, from a salt stored in the inner backend, so that iterating does not
+//! derive a secret for every entry. Storages written before the key index existed cannot be
+//! opened, because the original keys of their entries cannot be recovered from the hashes.
+use failure::bail;
+
+use crate::storage::{Result, Storage, StorageIterator, StorageSnapshot, WriteBatch, WriteOp};
 use witnet_crypto::{cipher, hash::calculate_sha256, pbkdf2::pbkdf2_sha256};
 use witnet_protected::Protected;
 
 const IV_LENGTH: usize = 16;
 const SALT_LENGTH: usize = 32;
 const HASH_ITER_COUNT: u32 = 10_000;
+/// Prefix of the entries of the key index. Those entries never collide with the entries that
+/// store values, because the keys of the latter are hashes, which are shorter.
+const KEY_INDEX_PREFIX: &[u8] = b"key-index/";
+/// Key of the entry that stores the salt of the secret of the key index. It does not collide with
+/// the entries of the key index, nor with the hashed keys.
+const KEY_INDEX_SALT_KEY: &[u8] = b"key-index-salt";
 
 /// Backend that stores values encrypted.
 pub struct Backend<T> {
     backend: T,
     password: Protected,
+    key_index_secret: Protected,
 }
 
 impl<T: Storage> Backend<T> {
     /// Create encrypted backend which will use `backend` as the
     /// actual storage backend but will encrypt the data with
     /// `password`
-    pub fn new(password: Protected, backend: T) -> Self {
-        Backend { password, backend }
+    ///
+    /// The first time, the salt of the secret of the key index is generated and stored, and the
+    /// key index entries that were encrypted like the values are encrypted again with that secret.
+    /// Fails if the inner backend has entries that are not in the key index.
+    pub fn new(password: Protected, mut backend: T) -> Result<Self> {
+        let key_index_secret = match backend.get(KEY_INDEX_SALT_KEY)? {
+            Some(salt) => get_secret(&password, &salt),
+            None => {
+                let salt = cipher::generate_random(SALT_LENGTH)?;
+                let key_index_secret = get_secret(&password, &salt);
+                let mut batch = WriteBatch::new();
+                for (inner_key, inner_value) in backend.prefix_iterator(b"")? {
+                    if inner_key.starts_with(KEY_INDEX_PREFIX) {
+                        let key = decrypt(&password, &inner_value)?;
+                        batch.put(inner_key, encrypt_key(&key_index_secret, &key)?);
+                    } else if backend.get(&key_index_key(&inner_key))?.is_none() {
+                        bail!(
+                            "The encrypted storage was written by a version without a key index, \
+                             and its entries cannot be indexed. Export the master key with that \
+                             version, and import it into a new storage"
+                        );
+                    }
+                }
+                batch.put(KEY_INDEX_SALT_KEY.to_vec(), salt);
+                backend.write(batch)?;
+
+                key_index_secret
+            }
+        };
+
+        Ok(Backend {
+            password,
+            backend,
+            key_index_secret,
+        })
     }
 
     /// Get a reference to the inner storage backend
@@ -30,6 +78,86 @@ impl<T: Storage> Backend<T> {
     }
 }
 
+fn encrypt(password: &[u8], value: &[u8]) -> Result<Vec<u8>> {
+    let iv = cipher::generate_random(IV_LENGTH)?;
+    let salt = cipher::generate_random(SALT_LENGTH)?;
+    let secret = get_secret(password, &salt);
+    let encrypted = cipher::encrypt_aes_cbc(&secret, value, iv.as_ref())?;
+    let mut final_value = iv;
+    final_value.extend(encrypted);
+    final_value.extend(salt);
+
+    Ok(final_value)
+}
+
+fn decrypt(password: &[u8], encrypted_bytes: &[u8]) -> Result<Vec<u8>> {
+    let len = encrypted_bytes.len();
+    let iv = &encrypted_bytes[0..IV_LENGTH];
+    let data = &encrypted_bytes[IV_LENGTH..len - SALT_LENGTH];
+    let salt = &encrypted_bytes[len - SALT_LENGTH..];
+    let secret = get_secret(password, salt);
+
+    cipher::decrypt_aes_cbc(&secret, data, iv).map_err(Into::into)
+}
+
+/// Encrypt a key of the key index with the secret of the key index
+fn encrypt_key(key_index_secret: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let iv = cipher::generate_random(IV_LENGTH)?;
+    let encrypted = cipher::encrypt_aes_cbc(key_index_secret, key, iv.as_ref())?;
+    let mut final_value = iv;
+    final_value.extend(encrypted);
+
+    Ok(final_value)
+}
+
+/// Decrypt a key of the key index with the secret of the key index
+fn decrypt_key(key_index_secret: &[u8], encrypted_bytes: &[u8]) -> Result<Vec<u8>> {
+    if encrypted_bytes.len() < IV_LENGTH {
+        bail!("Encrypted key is shorter than its initialization vector");
+    }
+    let iv = &encrypted_bytes[0..IV_LENGTH];
+    let data = &encrypted_bytes[IV_LENGTH..];
+
+    cipher::decrypt_aes_cbc(key_index_secret, data, iv).map_err(Into::into)
+}
+
+/// Decrypt all the keys in the key index of an inner snapshot that match the filter, and fetch
+/// their values. The pairs are collected before returning them, so that they are sorted by the
+/// original keys instead of by their hashes.
+fn decrypted_pairs<S, F>(
+    snapshot: &S,
+    password: &[u8],
+    key_index_secret: &[u8],
+    filter: F,
+) -> Result<StorageIterator<'static>>
+where
+    S: StorageSnapshot + ?Sized,
+    F: Fn(&[u8]) -> bool,
+{
+    let mut pairs = vec![];
+    for (index_key, encrypted_key) in snapshot.prefix_iterator(KEY_INDEX_PREFIX)? {
+        let key = decrypt_key(key_index_secret, &encrypted_key)?;
+        if !filter(&key) {
+            continue;
+        }
+        let hash_key = &index_key[KEY_INDEX_PREFIX.len()..];
+        if let Some(encrypted_value) = snapshot.get(hash_key)? {
+            pairs.push((key, decrypt(password, &encrypted_value)?));
+        }
+    }
+    pairs.sort();
+
+    Ok(Box::new(pairs.into_iter()))
+}
+
+/// Key of the entry of the key index that stores the encrypted key of an entry
+fn key_index_key(hash_key: &[u8]) -> Vec<u8> {
+    let mut index_key = KEY_INDEX_PREFIX.to_vec();
+    index_key.extend(hash_key);
+
+    index_key
+}
+
 impl<T: Storage> Storage for Backend<T> {
     fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         let hash_key = calculate_sha256(key);
@@ -37,37 +165,113 @@ impl<T: Storage> Storage for Backend<T> {
         self.backend
             .get(hash_key.as_ref())
             .and_then(|opt| match opt {
-                Some(encrypted_bytes) => {
-                    let len = encrypted_bytes.len();
-                    let iv = &encrypted_bytes[0..IV_LENGTH];
-                    let data = &encrypted_bytes[IV_LENGTH..len - SALT_LENGTH];
-                    let salt = &encrypted_bytes[len - SALT_LENGTH..];
-                    let secret = get_secret(&self.password, salt);
-
-                    cipher::decrypt_aes_cbc(&secret, data, iv)
-                        .map(Some)
-                        .map_err(Into::into)
-                }
+                Some(encrypted_bytes) => decrypt(&self.password, &encrypted_bytes).map(Some),
                 None => Ok(None),
             })
     }
 
     fn put(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
-        let hash_key = calculate_sha256(key.as_ref());
-        let iv = cipher::generate_random(IV_LENGTH)?;
-        let salt = cipher::generate_random(SALT_LENGTH)?;
-        let secret = get_secret(&self.password, &salt);
-        let encrypted = cipher::encrypt_aes_cbc(&secret, value.as_ref(), iv.as_ref())?;
-        let mut final_value = iv;
-        final_value.extend(encrypted);
-        final_value.extend(salt);
+        let mut batch = WriteBatch::new();
+        batch.put(key, value);
 
-        self.backend.put(hash_key.as_ref().to_vec(), final_value)
+        self.write(batch)
     }
 
     fn delete(&mut self, key: &[u8]) -> Result<()> {
+        let mut batch = WriteBatch::new();
+        batch.delete(key.to_vec());
+
+        self.write(batch)
+    }
+
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>> {
+        decrypted_pairs(
+            &*self.backend.snapshot()?,
+            &self.password,
+            &self.key_index_secret,
+            |key| key.starts_with(prefix),
+        )
+    }
+
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>> {
+        decrypted_pairs(
+            &*self.backend.snapshot()?,
+            &self.password,
+            &self.key_index_secret,
+            |key| key >= start && key < end,
+        )
+    }
+
+    /// Every operation is translated into the operations on the hashed key and on the key index,
+    /// and all of them are written to the inner backend in a single batch, so that the key index
+    /// never gets out of sync with the values.
+    fn write(&mut self, batch: WriteBatch) -> Result<()> {
+        let mut inner_batch = WriteBatch::new();
+        for op in batch {
+            match op {
+                WriteOp::Put(key, value) => {
+                    let hash_key = calculate_sha256(key.as_ref());
+                    let encrypted_key = encrypt_key(&self.key_index_secret, &key)?;
+                    let encrypted_value = encrypt(&self.password, &value)?;
+                    inner_batch.put(key_index_key(hash_key.as_ref()), encrypted_key);
+                    inner_batch.put(hash_key.as_ref().to_vec(), encrypted_value);
+                }
+                WriteOp::Delete(key) => {
+                    let hash_key = calculate_sha256(key.as_ref());
+                    inner_batch.delete(hash_key.as_ref().to_vec());
+                    inner_batch.delete(key_index_key(hash_key.as_ref()));
+                }
+            }
+        }
+
+        self.backend.write(inner_batch)
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn StorageSnapshot + 'a>> {
+        Ok(Box::new(Snapshot {
+            snapshot: self.backend.snapshot()?,
+            password: &self.password,
+            key_index_secret: &self.key_index_secret,
+        }))
+    }
+}
+
+/// Snapshot of an encrypted backend, which decrypts the values read from a snapshot of the inner
+/// backend.
+pub struct Snapshot<'a> {
+    snapshot: Box<dyn StorageSnapshot + 'a>,
+    password: &'a Protected,
+    key_index_secret: &'a Protected,
+}
+
+impl<'a> StorageSnapshot for Snapshot<'a> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
         let hash_key = calculate_sha256(key);
-        self.backend.delete(hash_key.as_ref())
+
+        self.snapshot
+            .get(hash_key.as_ref())
+            .and_then(|opt| match opt {
+                Some(encrypted_bytes) => decrypt(self.password, &encrypted_bytes).map(Some),
+                None => Ok(None),
+            })
+    }
+
+    fn prefix_iterator<'b>(&'b self, prefix: &[u8]) -> Result<StorageIterator<'b>> {
+        decrypted_pairs(
+            &*self.snapshot,
+            self.password,
+            self.key_index_secret,
+            |key| key.starts_with(prefix),
+        )
+    }
+
+    fn range_iterator<'b>(&'b self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'b>> {
+        decrypted_pairs(
+            &*self.snapshot,
+            self.password,
+            self.key_index_secret,
+            |key| key >= start && key < end,
+        )
     }
 }
 
@@ -83,7 +287,7 @@ mod tests {
     #[test]
     fn test_encrypt_decrypt() {
         let password = "".into();
-        let mut backend = Backend::new(password, hashmap::Backend::new());
+        let mut backend = Backend::new(password, hashmap::Backend::new()).unwrap();
 
         assert_eq!(None, backend.get(b"name").unwrap());
         backend.put("name".into(), "johnny".into()).unwrap();
@@ -95,11 +299,11 @@ mod tests {
         let password1 = "pass1".into();
         let password2 = "pass2".into();
 
-        let mut backend1 = Backend::new(password1, hashmap::Backend::new());
+        let mut backend1 = Backend::new(password1, hashmap::Backend::new()).unwrap();
 
         backend1.put("name".into(), "johnny".into()).unwrap();
 
-        let backend2 = Backend::new(password2, backend1.inner().clone());
+        let backend2 = Backend::new(password2, backend1.inner().clone()).unwrap();
 
         assert_ne!(
             backend2.get(b"name").unwrap_or(None),
@@ -110,12 +314,109 @@ mod tests {
     #[test]
     fn test_delete() {
         let password = "".into();
-        let mut backend = Backend::new(password, hashmap::Backend::new());
+        let mut backend = Backend::new(password, hashmap::Backend::new()).unwrap();
 
         assert_eq!(None, backend.get(b"name").unwrap());
         backend.put("name".into(), "johnny".into()).unwrap();
         assert_eq!(Some("johnny".into()), backend.get(b"name").unwrap());
         backend.delete(b"name").unwrap();
         assert_eq!(None, backend.get(b"name").unwrap());
+        assert_eq!(backend.prefix_iterator(b"").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let password = "".into();
+        let mut backend = Backend::new(password, hashmap::Backend::new()).unwrap();
+        for key in &["b-2", "a-1", "b-1", "c-1"] {
+            backend
+                .put(key.as_bytes().to_vec(), key.as_bytes().to_vec())
+                .unwrap();
+        }
+
+        let pairs: Vec<_> = backend.prefix_iterator(b"b-").unwrap().collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (b"b-1".to_vec(), b"b-1".to_vec()),
+                (b"b-2".to_vec(), b"b-2".to_vec())
+            ]
+        );
+
+        let keys: Vec<_> = backend
+            .range_iterator(b"a-1", b"b-2")
+            .unwrap()
+            .map(|(key, _value)| key)
+            .collect();
+        assert_eq!(keys, vec![b"a-1".to_vec(), b"b-1".to_vec()]);
+    }
+
+    #[test]
+    fn test_write_batch_and_snapshot() {
+        let password = "".into();
+        let mut backend = Backend::new(password, hashmap::Backend::new()).unwrap();
+        backend.put(b"a".to_vec(), b"1".to_vec()).unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch.put(b"b".to_vec(), b"2".to_vec());
+        batch.delete(b"a".to_vec());
+        backend.write(batch).unwrap();
+        assert_eq!(None, backend.get(b"a").unwrap());
+        assert_eq!(Some(b"2".to_vec()), backend.get(b"b").unwrap());
+
+        let snapshot = backend.snapshot().unwrap();
+        assert_eq!(Some(b"2".to_vec()), snapshot.get(b"b").unwrap());
+        let pairs: Vec<_> = snapshot.prefix_iterator(b"").unwrap().collect();
+        assert_eq!(pairs, vec![(b"b".to_vec(), b"2".to_vec())]);
+    }
+
+    #[test]
+    fn test_migrate_key_index() {
+        let password: Protected = "".into();
+        let mut inner = hashmap::Backend::new();
+        // Entry written when the keys of the key index were encrypted like the values
+        let hash_key = calculate_sha256(b"a");
+        inner
+            .put(
+                key_index_key(hash_key.as_ref()),
+                encrypt(&password, b"a").unwrap(),
+            )
+            .unwrap();
+        inner
+            .put(
+                hash_key.as_ref().to_vec(),
+                encrypt(&password, b"1").unwrap(),
+            )
+            .unwrap();
+        let backend = Backend::new(password, inner).unwrap();
+        let pairs: Vec<_> = backend.prefix_iterator(b"").unwrap().collect();
+        assert_eq!(pairs, vec![(b"a".to_vec(), b"1".to_vec())]);
+
+        // The salt is only generated once
+        let backend = Backend::new("".into(), backend.inner().clone()).unwrap();
+        assert_eq!(backend.prefix_iterator(b"").unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_refuse_storage_without_key_index() {
+        let password: Protected = "".into();
+        let mut inner = hashmap::Backend::new();
+        // Entry written before the key index existed
+        let hash_key = calculate_sha256(b"b");
+        inner
+            .put(
+                hash_key.as_ref().to_vec(),
+                encrypt(&password, b"2").unwrap(),
+            )
+            .unwrap();
+
+        assert!(Backend::new(password, inner).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_short_key() {
+        let secret = get_secret(b"", b"salt");
+
+        assert!(decrypt_key(&secret, &[0; IV_LENGTH - 1]).is_err());
     }
 }
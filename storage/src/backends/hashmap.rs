@@ -3,7 +3,7 @@
 //! Storage backend that keeps data in a heap-allocated HashMap.
 use std::collections::HashMap;
 
-use crate::storage::{Result, Storage};
+use crate::storage::{Result, Storage, StorageIterator, StorageSnapshot, WriteBatch, WriteOp};
 
 /// HashMap backend
 pub type Backend = HashMap<Vec<u8>, Vec<u8>>;
@@ -22,6 +22,65 @@ impl Storage for Backend {
         Backend::remove(self, key);
         Ok(())
     }
+
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>> {
+        Ok(sorted_pairs(self, |key| key.starts_with(prefix)))
+    }
+
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>> {
+        Ok(sorted_pairs(self, |key| key >= start && key < end))
+    }
+
+    fn write(&mut self, batch: WriteBatch) -> Result<()> {
+        for op in batch {
+            match op {
+                WriteOp::Put(key, value) => {
+                    Backend::insert(self, key, value);
+                }
+                WriteOp::Delete(key) => {
+                    Backend::remove(self, &key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn StorageSnapshot + 'a>> {
+        Ok(Box::new(Snapshot(self.clone())))
+    }
+}
+
+/// Snapshot of a HashMap backend, which is simply a copy of it
+pub struct Snapshot(Backend);
+
+impl StorageSnapshot for Snapshot {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.0.get(key).map(|slice| slice.to_vec()))
+    }
+
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>> {
+        Ok(sorted_pairs(&self.0, |key| key.starts_with(prefix)))
+    }
+
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>> {
+        Ok(sorted_pairs(&self.0, |key| key >= start && key < end))
+    }
+}
+
+/// Copy the key/value pairs whose key matches the filter, sorted by key, as the entries of a
+/// HashMap are not ordered.
+fn sorted_pairs<'a, F>(backend: &Backend, filter: F) -> StorageIterator<'a>
+where
+    F: Fn(&[u8]) -> bool,
+{
+    let mut pairs: Vec<(Vec<u8>, Vec<u8>)> = backend
+        .iter()
+        .filter(|(key, _value)| filter(key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    pairs.sort();
+
+    Box::new(pairs.into_iter())
 }
 
 #[cfg(test)]
@@ -42,4 +101,44 @@ mod tests {
         storage.delete(b"name").unwrap();
         assert_eq!(None, storage.get(b"name").unwrap());
     }
+
+    #[test]
+    fn test_hashmap_iterators() {
+        let mut storage = backend();
+        for key in &["b-2", "a-1", "b-1", "c-1"] {
+            storage.put(key.as_bytes().to_vec(), b"x".to_vec()).unwrap();
+        }
+
+        let keys: Vec<_> = storage
+            .prefix_iterator(b"b-")
+            .unwrap()
+            .map(|(key, _value)| key)
+            .collect();
+        assert_eq!(keys, vec![b"b-1".to_vec(), b"b-2".to_vec()]);
+
+        let keys: Vec<_> = storage
+            .range_iterator(b"a-1", b"b-2")
+            .unwrap()
+            .map(|(key, _value)| key)
+            .collect();
+        assert_eq!(keys, vec![b"a-1".to_vec(), b"b-1".to_vec()]);
+    }
+
+    #[test]
+    fn test_hashmap_write_batch_and_snapshot() {
+        let mut storage = backend();
+        storage.put(b"name".to_vec(), b"john".to_vec()).unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch.put(b"age".to_vec(), b"42".to_vec());
+        batch.delete(b"name".to_vec());
+        storage.write(batch).unwrap();
+
+        assert_eq!(Some("42".into()), storage.get(b"age").unwrap());
+        assert_eq!(None, storage.get(b"name").unwrap());
+
+        let snapshot = storage.snapshot().unwrap();
+        assert_eq!(Some("42".into()), snapshot.get(b"age").unwrap());
+        assert_eq!(snapshot.prefix_iterator(b"").unwrap().count(), 1);
+    }
 }
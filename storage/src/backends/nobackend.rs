@@ -3,7 +3,7 @@
 //! This backend performs no storage at all and always fails to do any operation.
 use failure::bail;
 
-use crate::storage::{Result, Storage};
+use crate::storage::{Result, Storage, StorageIterator, StorageSnapshot, WriteBatch};
 
 /// A Backend that is not persisted
 ///
@@ -23,4 +23,20 @@ impl Storage for Backend {
     fn delete(&mut self, _key: &[u8]) -> Result<()> {
         bail!("This is a no backend storage")
     }
+
+    fn prefix_iterator<'a>(&'a self, _prefix: &[u8]) -> Result<StorageIterator<'a>> {
+        bail!("This is a no backend storage")
+    }
+
+    fn range_iterator<'a>(&'a self, _start: &[u8], _end: &[u8]) -> Result<StorageIterator<'a>> {
+        bail!("This is a no backend storage")
+    }
+
+    fn write(&mut self, _batch: WriteBatch) -> Result<()> {
+        bail!("This is a no backend storage")
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn StorageSnapshot + 'a>> {
+        bail!("This is a no backend storage")
+    }
 }
@@ -5,7 +5,7 @@ use failure::Fail;
 #[cfg(test)]
 use rocksdb_mock as rocksdb;
 
-use crate::storage::{Result, Storage};
+use crate::storage::{Result, Storage, StorageIterator, StorageSnapshot, WriteBatch, WriteOp};
 
 /// Rocksdb backend
 pub type Backend = rocksdb::DB;
@@ -31,6 +31,95 @@ impl Storage for Backend {
         Backend::delete(self, &key).map_err(Error)?;
         Ok(())
     }
+
+    // RocksDB iterators already see an implicit snapshot of the database, taken when they are
+    // created, so there is no need to create an explicit one
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>> {
+        let iter = Backend::iterator(
+            self,
+            rocksdb::IteratorMode::From(prefix, rocksdb::Direction::Forward),
+        );
+
+        Ok(prefix_pairs(iter, prefix))
+    }
+
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>> {
+        let iter = Backend::iterator(
+            self,
+            rocksdb::IteratorMode::From(start, rocksdb::Direction::Forward),
+        );
+
+        Ok(range_pairs(iter, end))
+    }
+
+    fn write(&mut self, batch: WriteBatch) -> Result<()> {
+        let mut rocksdb_batch = rocksdb::WriteBatch::default();
+        for op in batch {
+            match op {
+                WriteOp::Put(key, value) => rocksdb_batch.put(key, value).map_err(Error)?,
+                WriteOp::Delete(key) => rocksdb_batch.delete(key).map_err(Error)?,
+            }
+        }
+        Backend::write(self, rocksdb_batch).map_err(Error)?;
+        Ok(())
+    }
+
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn StorageSnapshot + 'a>> {
+        Ok(Box::new(Backend::snapshot(self)))
+    }
+}
+
+impl<'s> StorageSnapshot for rocksdb::Snapshot<'s> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let result = rocksdb::Snapshot::get(self, &key)
+            .map(|opt| opt.map(|dbvec| dbvec.to_vec()))
+            .map_err(Error)?;
+        Ok(result)
+    }
+
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>> {
+        let iter = rocksdb::Snapshot::iterator(
+            self,
+            rocksdb::IteratorMode::From(prefix, rocksdb::Direction::Forward),
+        );
+
+        Ok(prefix_pairs(iter, prefix))
+    }
+
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>> {
+        let iter = rocksdb::Snapshot::iterator(
+            self,
+            rocksdb::IteratorMode::From(start, rocksdb::Direction::Forward),
+        );
+
+        Ok(range_pairs(iter, end))
+    }
+}
+
+/// Take the pairs of a RocksDB iterator while their keys start with `prefix`.
+fn prefix_pairs<'a, I>(iter: I, prefix: &[u8]) -> StorageIterator<'a>
+where
+    I: Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a,
+{
+    let prefix = prefix.to_vec();
+
+    Box::new(
+        iter.map(|(key, value)| (key.into_vec(), value.into_vec()))
+            .take_while(move |(key, _value)| key.starts_with(&prefix)),
+    )
+}
+
+/// Take the pairs of a RocksDB iterator while their keys are lower than `end`.
+fn range_pairs<'a, I>(iter: I, end: &[u8]) -> StorageIterator<'a>
+where
+    I: Iterator<Item = (Box<[u8]>, Box<[u8]>)> + 'a,
+{
+    let end = end.to_vec();
+
+    Box::new(
+        iter.map(|(key, value)| (key.into_vec(), value.into_vec()))
+            .take_while(move |(key, _value)| *key < end),
+    )
 }
 
 #[cfg(test)]
@@ -51,6 +140,46 @@ mod tests {
         storage.delete(b"name").unwrap();
         assert_eq!(None, storage.get(b"name").unwrap());
     }
+
+    #[test]
+    fn test_rocksdb_iterators() {
+        let mut storage = backend();
+        for key in &["b-2", "a-1", "b-1", "c-1"] {
+            storage.put(key.as_bytes().to_vec(), b"x".to_vec()).unwrap();
+        }
+
+        let keys: Vec<_> = storage
+            .prefix_iterator(b"b-")
+            .unwrap()
+            .map(|(key, _value)| key)
+            .collect();
+        assert_eq!(keys, vec![b"b-1".to_vec(), b"b-2".to_vec()]);
+
+        let keys: Vec<_> = storage
+            .range_iterator(b"a-1", b"b-2")
+            .unwrap()
+            .map(|(key, _value)| key)
+            .collect();
+        assert_eq!(keys, vec![b"a-1".to_vec(), b"b-1".to_vec()]);
+    }
+
+    #[test]
+    fn test_rocksdb_write_batch_and_snapshot() {
+        let mut storage = backend();
+        storage.put(b"name".to_vec(), b"john".to_vec()).unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch.put(b"age".to_vec(), b"42".to_vec());
+        batch.delete(b"name".to_vec());
+        storage.write(batch).unwrap();
+
+        assert_eq!(Some("42".into()), storage.get(b"age").unwrap());
+        assert_eq!(None, storage.get(b"name").unwrap());
+
+        let snapshot = storage.snapshot().unwrap();
+        assert_eq!(Some("42".into()), snapshot.get(b"age").unwrap());
+        assert_eq!(snapshot.prefix_iterator(b"").unwrap().count(), 1);
+    }
 }
 
 #[cfg(test)]
@@ -59,11 +188,55 @@ mod rocksdb_mock {
 
     pub type Error = failure::Error;
 
-    #[derive(Default)]
+    pub enum Direction {
+        Forward,
+    }
+
+    pub enum IteratorMode<'a> {
+        From(&'a [u8], Direction),
+    }
+
+    #[derive(Clone, Default)]
     pub struct DB {
         data: Vec<(Vec<u8>, Vec<u8>)>,
     }
 
+    #[derive(Default)]
+    pub struct WriteBatch {
+        ops: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    }
+
+    impl WriteBatch {
+        pub fn put<K: AsRef<[u8]>, V: AsRef<[u8]>>(&mut self, key: K, value: V) -> Result<()> {
+            self.ops
+                .push((key.as_ref().to_vec(), Some(value.as_ref().to_vec())));
+            Ok(())
+        }
+
+        pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<()> {
+            self.ops.push((key.as_ref().to_vec(), None));
+            Ok(())
+        }
+    }
+
+    pub struct Snapshot<'a> {
+        db: DB,
+        _db: std::marker::PhantomData<&'a DB>,
+    }
+
+    impl<'a> Snapshot<'a> {
+        pub fn get<K: AsRef<[u8]>>(&self, key: &K) -> Result<Option<Vec<u8>>> {
+            self.db.get(key)
+        }
+
+        pub fn iterator(
+            &self,
+            mode: IteratorMode<'_>,
+        ) -> impl Iterator<Item = (Box<[u8]>, Box<[u8]>)> {
+            self.db.iterator(mode)
+        }
+    }
+
     impl DB {
         pub fn new() -> Self {
             DB::default()
@@ -96,5 +269,43 @@ mod rocksdb_mock {
             self.search(key).map(|idx| self.data.remove(idx));
             Ok(())
         }
+
+        pub fn write(&mut self, batch: WriteBatch) -> Result<()> {
+            for (key, value) in batch.ops {
+                match value {
+                    Some(value) => self.put(key, value)?,
+                    None => self.delete(&key)?,
+                }
+            }
+            Ok(())
+        }
+
+        pub fn snapshot(&self) -> Snapshot<'_> {
+            Snapshot {
+                db: self.clone(),
+                _db: std::marker::PhantomData,
+            }
+        }
+
+        pub fn iterator(
+            &self,
+            mode: IteratorMode<'_>,
+        ) -> impl Iterator<Item = (Box<[u8]>, Box<[u8]>)> {
+            let IteratorMode::From(start, Direction::Forward) = mode;
+            let mut data: Vec<_> = self
+                .data
+                .iter()
+                .filter(|(key, _value)| key.as_slice() >= start)
+                .map(|(key, value)| {
+                    (
+                        key.clone().into_boxed_slice(),
+                        value.clone().into_boxed_slice(),
+                    )
+                })
+                .collect();
+            data.sort();
+
+            data.into_iter()
+        }
     }
 }
@@ -7,6 +7,75 @@ use std::result;
 /// Result with error set to `failure::Error`
 pub type Result<T> = result::Result<T, failure::Error>;
 
+/// Iterator over key/value pairs of a storage, in ascending order of keys
+pub type StorageIterator<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
+/// Operation of a `WriteBatch`
+#[derive(Clone, Debug, PartialEq)]
+pub enum WriteOp {
+    /// Put a value in the storage
+    Put(Vec<u8>, Vec<u8>),
+    /// Delete a value from the storage
+    Delete(Vec<u8>),
+}
+
+/// Set of writes that are applied atomically: either all of them are applied, or none of them is,
+/// even if the process crashes while writing them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WriteBatch {
+    ops: Vec<WriteOp>,
+}
+
+impl WriteBatch {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a put operation to the batch
+    pub fn put(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        self.ops.push(WriteOp::Put(key, value));
+    }
+
+    /// Add a delete operation to the batch
+    pub fn delete(&mut self, key: Vec<u8>) {
+        self.ops.push(WriteOp::Delete(key));
+    }
+
+    /// Number of operations in the batch
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    /// Returns `true` if the batch has no operations
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+impl IntoIterator for WriteBatch {
+    type Item = WriteOp;
+    type IntoIter = std::vec::IntoIter<WriteOp>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ops.into_iter()
+    }
+}
+
+/// Read-only view of a storage at the moment it was taken. The writes done after taking a
+/// snapshot are not visible through it.
+pub trait StorageSnapshot {
+    /// Get a value from the snapshot given a key
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Iterate over all the key/value pairs of the snapshot whose key starts with `prefix`.
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>>;
+
+    /// Iterate over all the key/value pairs of the snapshot whose key is in the range
+    /// `start..end`.
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>>;
+}
+
 /// Generic trait that exposes a very simple key/value CRUD API for data storage.
 ///
 /// This trait can be easily implemented for any specific storage
@@ -20,4 +89,21 @@ pub trait Storage {
 
     /// Delete a value from the storage
     fn delete(&mut self, key: &[u8]) -> Result<()>;
+
+    /// Iterate over all the key/value pairs whose key starts with `prefix`.
+    ///
+    /// The iterator sees a consistent snapshot of the storage, taken when it is created.
+    fn prefix_iterator<'a>(&'a self, prefix: &[u8]) -> Result<StorageIterator<'a>>;
+
+    /// Iterate over all the key/value pairs whose key is in the range `start..end`, that is,
+    /// greater or equal than `start` and lower than `end` when comparing them byte by byte.
+    ///
+    /// The iterator sees a consistent snapshot of the storage, taken when it is created.
+    fn range_iterator<'a>(&'a self, start: &[u8], end: &[u8]) -> Result<StorageIterator<'a>>;
+
+    /// Apply all the operations of a batch atomically
+    fn write(&mut self, batch: WriteBatch) -> Result<()>;
+
+    /// Take a snapshot of the current state of the storage
+    fn snapshot<'a>(&'a self) -> Result<Box<dyn StorageSnapshot + 'a>>;
 }
@@ -0,0 +1,321 @@
+//! Estimation of the cost of a data request, and of its chances of being resolved
+//!
+//! The estimation applies the same rules as `validate_dr_transaction` and the mempool, so that
+//! requests that would be rejected are detected before spending any tokens. Optionally, the
+//! current state of the network is used to detect settings that are unlikely to get witnesses.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use witnet_data_structures::{
+    chain::{DataRequestOutput, Input, ValueTransferOutput},
+    data_request::calculate_tally_change,
+    transaction::DRTransactionBody,
+};
+
+use crate::validations::{validate_data_request_output, validate_rad_request};
+
+/// Statistics of the network that decide whether a data request is likely to get witnesses
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStats {
+    /// Number of identities in the Active Reputation Set
+    pub active_identities: u32,
+    /// Number of identities with enough mature unspent outputs to pay the collateral
+    pub collateralized_identities: u32,
+}
+
+/// Issues found in the parameters of a data request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DataRequestWarning {
+    /// The data request would be rejected when validating it
+    Invalid {
+        /// Reason of the rejection
+        reason: String,
+    },
+    /// The weight of the data request transaction is over the limit of a block
+    WeightLimitExceeded {
+        /// Weight of the data request transaction
+        weight: u32,
+        /// Maximum weight of the data requests included in a block
+        max_weight: u32,
+    },
+    /// Miners include data requests ordered by fee, so a zero fee may never be included
+    ZeroFee,
+    /// There are less active identities than required witnesses
+    NotEnoughActiveIdentities {
+        /// Number of witnesses of the data request
+        witnesses: u16,
+        /// Number of active identities
+        active_identities: u32,
+    },
+    /// There are less identities able to pay the collateral than required witnesses
+    NotEnoughCollateralizedIdentities {
+        /// Number of witnesses of the data request
+        witnesses: u16,
+        /// Collateral required to each witness
+        collateral: u64,
+        /// Number of identities with enough mature unspent outputs to pay the collateral
+        collateralized_identities: u32,
+    },
+}
+
+impl fmt::Display for DataRequestWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataRequestWarning::Invalid { reason } => {
+                write!(f, "The data request will be rejected: {}", reason)
+            }
+            DataRequestWarning::WeightLimitExceeded { weight, max_weight } => write!(
+                f,
+                "The data request will be rejected: its weight ({}) exceeds the limit of {}",
+                weight, max_weight
+            ),
+            DataRequestWarning::ZeroFee => write!(
+                f,
+                "Miners prioritize data requests by fee, one without fee may never be included in a block"
+            ),
+            DataRequestWarning::NotEnoughActiveIdentities {
+                witnesses,
+                active_identities,
+            } => write!(
+                f,
+                "The data request needs {} witnesses, but there are only {} active identities",
+                witnesses, active_identities
+            ),
+            DataRequestWarning::NotEnoughCollateralizedIdentities {
+                witnesses,
+                collateral,
+                collateralized_identities,
+            } => write!(
+                f,
+                "The data request needs {} witnesses, but only {} identities have {} nanowits of mature collateral",
+                witnesses, collateralized_identities, collateral
+            ),
+        }
+    }
+}
+
+/// Cost of a data request and the issues found in its parameters
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DataRequestEstimate {
+    /// Weight of the data request transaction, including the weight of its commits, reveals and
+    /// tally
+    pub weight: u32,
+    /// Collateral required to each witness
+    pub collateral: u64,
+    /// Sum of the rewards of all the witnesses
+    pub witness_rewards: u64,
+    /// Sum of the fees of all the commits and reveals
+    pub commit_and_reveal_fees: u64,
+    /// Fee paid to the miner that includes the data request transaction
+    pub fee: u64,
+    /// Value locked by the data request transaction: rewards, commit and reveal fees, and fee
+    pub total_cost: u64,
+    /// Value returned by the tally if nobody reveals in consensus, and nobody commits
+    pub max_tally_change: u64,
+    /// Issues found in the parameters of the data request
+    pub warnings: Vec<DataRequestWarning>,
+}
+
+impl DataRequestEstimate {
+    /// Returns `true` if the data request would be rejected by the network
+    pub fn is_rejected(&self) -> bool {
+        self.warnings.iter().any(|warning| match warning {
+            DataRequestWarning::Invalid { .. } | DataRequestWarning::WeightLimitExceeded { .. } => {
+                true
+            }
+            _ => false,
+        })
+    }
+}
+
+/// Estimate the cost of a data request, and check its parameters.
+///
+/// The weight is calculated for a transaction with one input and one change output. The checks
+/// that depend on the state of the network are skipped if `network_stats` is `None`.
+pub fn estimate_data_request(
+    dr_output: &DataRequestOutput,
+    fee: u64,
+    collateral_minimum: u64,
+    max_dr_weight: u32,
+    network_stats: Option<NetworkStats>,
+) -> DataRequestEstimate {
+    let mut warnings = vec![];
+
+    if let Err(e) = validate_data_request_output(dr_output) {
+        warnings.push(DataRequestWarning::Invalid {
+            reason: e.to_string(),
+        });
+    }
+    if let Err(e) = validate_rad_request(&dr_output.data_request) {
+        warnings.push(DataRequestWarning::Invalid {
+            reason: e.to_string(),
+        });
+    }
+    // If collateral is equal to 0 means that is equal to collateral_minimum value
+    let collateral = if dr_output.collateral == 0 {
+        collateral_minimum
+    } else {
+        if dr_output.collateral < collateral_minimum {
+            warnings.push(DataRequestWarning::Invalid {
+                reason: format!(
+                    "the collateral ({}) is lower than the minimum of {}",
+                    dr_output.collateral, collateral_minimum
+                ),
+            });
+        }
+        dr_output.collateral
+    };
+
+    let weight = DRTransactionBody::new(
+        vec![Input::default()],
+        vec![ValueTransferOutput::default()],
+        dr_output.clone(),
+    )
+    .weight();
+    if weight > max_dr_weight {
+        warnings.push(DataRequestWarning::WeightLimitExceeded {
+            weight,
+            max_weight: max_dr_weight,
+        });
+    }
+    if fee == 0 {
+        warnings.push(DataRequestWarning::ZeroFee);
+    }
+
+    if let Some(stats) = network_stats {
+        let witnesses = dr_output.witnesses;
+        if u32::from(witnesses) > stats.active_identities {
+            warnings.push(DataRequestWarning::NotEnoughActiveIdentities {
+                witnesses,
+                active_identities: stats.active_identities,
+            });
+        }
+        if u32::from(witnesses) > stats.collateralized_identities {
+            warnings.push(DataRequestWarning::NotEnoughCollateralizedIdentities {
+                witnesses,
+                collateral,
+                collateralized_identities: stats.collateralized_identities,
+            });
+        }
+    }
+
+    let witnesses = u64::from(dr_output.witnesses);
+    let witness_rewards = dr_output.witness_reward.saturating_mul(witnesses);
+    let commit_and_reveal_fees = dr_output
+        .commit_and_reveal_fee
+        .saturating_mul(2)
+        .saturating_mul(witnesses);
+    let max_tally_change = match dr_output.checked_total_value() {
+        Ok(_) => calculate_tally_change(0, 0, 0, dr_output),
+        Err(e) => {
+            warnings.push(DataRequestWarning::Invalid {
+                reason: e.to_string(),
+            });
+            // `calculate_tally_change` would overflow
+            witness_rewards.saturating_add(commit_and_reveal_fees)
+        }
+    };
+
+    DataRequestEstimate {
+        weight,
+        collateral,
+        witness_rewards,
+        commit_and_reveal_fees,
+        fee,
+        total_cost: witness_rewards
+            .saturating_add(commit_and_reveal_fees)
+            .saturating_add(fee),
+        max_tally_change,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use witnet_data_structures::chain::{RADAggregate, RADRequest, RADRetrieve, RADTally, RADType};
+
+    use super::*;
+
+    fn dr_output() -> DataRequestOutput {
+        DataRequestOutput {
+            data_request: RADRequest {
+                time_lock: 0,
+                retrieve: vec![RADRetrieve {
+                    kind: RADType::HttpGet,
+                    url: "https://example.com".to_string(),
+                    script: vec![128],
+                    body: vec![],
+                    headers: vec![],
+                }],
+                aggregate: RADAggregate::default(),
+                tally: RADTally::default(),
+            },
+            witness_reward: 100,
+            witnesses: 4,
+            commit_and_reveal_fee: 10,
+            min_consensus_percentage: 51,
+            collateral: 0,
+        }
+    }
+
+    #[test]
+    fn cost() {
+        let estimate = estimate_data_request(&dr_output(), 5, 1000, u32::MAX, None);
+
+        assert_eq!(estimate.collateral, 1000);
+        assert_eq!(estimate.witness_rewards, 400);
+        assert_eq!(estimate.commit_and_reveal_fees, 80);
+        assert_eq!(estimate.total_cost, 485);
+        assert_eq!(estimate.max_tally_change, 480);
+        assert_eq!(estimate.warnings, vec![]);
+        assert!(!estimate.is_rejected());
+    }
+
+    #[test]
+    fn rejected_settings() {
+        let dr_output = DataRequestOutput {
+            min_consensus_percentage: 50,
+            collateral: 1,
+            ..dr_output()
+        };
+        let estimate = estimate_data_request(&dr_output, 0, 1000, 1, None);
+
+        assert!(estimate.is_rejected());
+        assert_eq!(estimate.warnings.len(), 4);
+        assert!(estimate.warnings.contains(&DataRequestWarning::ZeroFee));
+    }
+
+    #[test]
+    fn overflowing_value() {
+        let dr_output = DataRequestOutput {
+            witness_reward: u64::MAX,
+            ..dr_output()
+        };
+        let estimate = estimate_data_request(&dr_output, 5, 1000, u32::MAX, None);
+
+        assert!(estimate.is_rejected());
+        assert_eq!(estimate.total_cost, u64::MAX);
+        assert_eq!(estimate.max_tally_change, u64::MAX);
+    }
+
+    #[test]
+    fn unlikely_to_get_witnesses() {
+        let stats = NetworkStats {
+            active_identities: 10,
+            collateralized_identities: 3,
+        };
+        let estimate = estimate_data_request(&dr_output(), 5, 1000, u32::MAX, Some(stats));
+
+        assert!(!estimate.is_rejected());
+        assert_eq!(
+            estimate.warnings,
+            vec![DataRequestWarning::NotEnoughCollateralizedIdentities {
+                witnesses: 4,
+                collateral: 1000,
+                collateralized_identities: 3,
+            }]
+        );
+    }
+}
@@ -4,5 +4,11 @@
 /// Module containing validations
 pub mod validations;
 
+/// Module containing the simulation of the tally stage of data requests
+pub mod simulation;
+
+/// Module containing the estimation of the cost of data requests
+pub mod estimation;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,299 @@
+//! Simulation of the tally stage of a data request with synthetic reveals
+//!
+//! The tally is computed exactly like miners compute it: the reveals are serialized and decoded,
+//! the tally precondition clause is evaluated, the tally script is run, and the reward and
+//! collateral of every witness are distributed by `create_tally`.
+
+use std::{collections::HashSet, convert::TryFrom};
+
+use failure::Fail;
+use serde::Serialize;
+
+use witnet_data_structures::{
+    chain::{DataRequestOutput, Hash, PublicKeyHash},
+    data_request::create_tally,
+    radon_report::{RadonReport, ReportContext, Stage},
+    transaction::TallyTransaction,
+};
+use witnet_rad::{
+    error::RadError,
+    types::{serial_iter_decode, RadonTypes},
+};
+
+use crate::validations::{construct_report_from_clause_result, evaluate_tally_precondition_clause};
+
+/// Errors that prevent a tally from being simulated
+#[derive(Debug, Fail)]
+pub enum SimulationError {
+    /// There are more reveals than witnesses
+    #[fail(
+        display = "The data request has {} witnesses, but {} reveals were simulated",
+        witnesses, reveals
+    )]
+    TooManyReveals {
+        /// Number of witnesses of the data request
+        witnesses: u16,
+        /// Number of simulated reveals
+        reveals: usize,
+    },
+    /// At least one witness is needed
+    #[fail(display = "At least one reveal must be simulated")]
+    NoWitnesses,
+}
+
+/// Outcome of the commitment of a witness, as decided by the tally
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum WitnessOutcome {
+    /// The revealed value or error is in consensus, so the witness is rewarded
+    Honest,
+    /// The revealed value is out of consensus, so the collateral of the witness is slashed
+    Liar,
+    /// The revealed error is out of consensus, so the collateral of the witness is refunded
+    Error,
+    /// The witness did not reveal, so its collateral is slashed
+    NoReveal,
+}
+
+/// A witness of a simulated tally
+#[derive(Clone, Debug, Serialize)]
+pub struct SimulatedWitness {
+    /// Synthetic address of the witness
+    pub pkh: PublicKeyHash,
+    /// Revealed value, or `None` if the witness did not reveal
+    pub reveal: Option<RadonTypes>,
+    /// Outcome of the commitment
+    pub outcome: WitnessOutcome,
+    /// Value paid to the witness by the tally transaction, including its collateral if it is
+    /// returned
+    pub value: u64,
+}
+
+/// Result of a simulated tally
+#[derive(Clone, Debug, Serialize)]
+pub struct TallySimulation {
+    /// Result of the tally
+    pub tally: RadonTypes,
+    /// Ratio of the reveals that are in consensus
+    pub consensus: f32,
+    /// Collateral that each witness had to lock
+    pub collateral: u64,
+    /// Witnesses, in the same order as the simulated reveals
+    pub witnesses: Vec<SimulatedWitness>,
+    /// Value returned to the creator of the data request
+    pub change: u64,
+    /// The tally transaction that a miner would include in a block
+    pub tally_transaction: TallyTransaction,
+}
+
+/// Synthetic address of the witness at the given position.
+fn witness_pkh(i: usize) -> PublicKeyHash {
+    let mut bytes = [0; 20];
+    bytes[12..].copy_from_slice(&(i as u64 + 1).to_be_bytes());
+
+    PublicKeyHash::from_bytes(&bytes).unwrap()
+}
+
+/// Synthetic address of the creator of the simulated data request, which receives the change.
+fn creator_pkh() -> PublicKeyHash {
+    PublicKeyHash::from_bytes(&[0; 20]).unwrap()
+}
+
+/// Simulate the tally of a data request.
+///
+/// Each of the `reveals` is a witness that committed to the data request, and revealed the given
+/// value or error, or did not reveal if it is `None`. A zero collateral in the data request means
+/// that `collateral_minimum` is used, like in the block chain.
+pub fn simulate_tally(
+    dr_output: &DataRequestOutput,
+    reveals: Vec<Option<RadonTypes>>,
+    collateral_minimum: u64,
+) -> Result<TallySimulation, failure::Error> {
+    if reveals.is_empty() {
+        return Err(SimulationError::NoWitnesses.into());
+    }
+    if reveals.len() > usize::from(dr_output.witnesses) {
+        return Err(SimulationError::TooManyReveals {
+            witnesses: dr_output.witnesses,
+            reveals: reveals.len(),
+        }
+        .into());
+    }
+
+    let committers: HashSet<PublicKeyHash> = (0..reveals.len()).map(witness_pkh).collect();
+    let commits_count = committers.len();
+    // Serialize the reveals like witnesses do, so that values that cannot be revealed are handled
+    // in the same way as in the block chain
+    let mut revealers = vec![];
+    let mut reveal_bytes = vec![];
+    for (i, reveal) in reveals.iter().enumerate() {
+        if let Some(reveal) = reveal {
+            revealers.push(witness_pkh(i));
+            reveal_bytes.push(Vec::<u8>::try_from(reveal.clone()).unwrap_or_default());
+        }
+    }
+
+    let reports = serial_iter_decode(
+        &mut reveal_bytes.iter().map(|bytes| (bytes.as_slice(), &())),
+        |_: RadError, _: &[u8], _: &()| {
+            Some(RadonReport::from_result(
+                Err(RadError::MalformedReveal),
+                &ReportContext::default(),
+            ))
+        },
+    );
+    let reports_len = reports.len();
+    let min_consensus_ratio = f64::from(dr_output.min_consensus_percentage) / 100.0;
+    let clause_result =
+        evaluate_tally_precondition_clause(reports, min_consensus_ratio, commits_count);
+    let report = construct_report_from_clause_result(
+        clause_result,
+        &dr_output.data_request.tally,
+        reports_len,
+    );
+
+    let tally_transaction = create_tally(
+        Hash::default(),
+        dr_output,
+        creator_pkh(),
+        &report,
+        revealers,
+        committers,
+        collateral_minimum,
+    )?;
+
+    let consensus = match &report.context.stage {
+        Stage::Tally(metadata) => metadata.consensus,
+        _ => 0.0,
+    };
+    let value_paid_to = |pkh: PublicKeyHash| -> u64 {
+        tally_transaction
+            .outputs
+            .iter()
+            .filter(|output| output.pkh == pkh)
+            .map(|output| output.value)
+            .sum()
+    };
+    let witnesses = reveals
+        .into_iter()
+        .enumerate()
+        .map(|(i, reveal)| {
+            let pkh = witness_pkh(i);
+            let outcome = if reveal.is_none() {
+                WitnessOutcome::NoReveal
+            } else if !tally_transaction.out_of_consensus.contains(&pkh) {
+                WitnessOutcome::Honest
+            } else if tally_transaction.error_committers.contains(&pkh) {
+                WitnessOutcome::Error
+            } else {
+                WitnessOutcome::Liar
+            };
+
+            SimulatedWitness {
+                pkh,
+                reveal,
+                outcome,
+                value: value_paid_to(pkh),
+            }
+        })
+        .collect();
+
+    Ok(TallySimulation {
+        tally: report.into_inner(),
+        consensus,
+        collateral: if dr_output.collateral == 0 {
+            collateral_minimum
+        } else {
+            dr_output.collateral
+        },
+        witnesses,
+        change: value_paid_to(creator_pkh()),
+        tally_transaction,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use witnet_data_structures::{
+        chain::{RADAggregate, RADFilter, RADRequest, RADTally},
+        radon_error::RadonError,
+    };
+    use witnet_rad::{
+        filters::RadonFilters, reducers::RadonReducers, types::integer::RadonInteger,
+    };
+
+    use super::*;
+
+    fn dr_output(witnesses: u16) -> DataRequestOutput {
+        DataRequestOutput {
+            data_request: RADRequest {
+                time_lock: 0,
+                retrieve: vec![],
+                aggregate: RADAggregate::default(),
+                tally: RADTally {
+                    filters: vec![RADFilter {
+                        op: RadonFilters::Mode as u32,
+                        args: vec![],
+                    }],
+                    reducer: RadonReducers::Mode as u32,
+                },
+            },
+            witness_reward: 100,
+            witnesses,
+            commit_and_reveal_fee: 10,
+            min_consensus_percentage: 51,
+            collateral: 1000,
+        }
+    }
+
+    fn int(i: i128) -> Option<RadonTypes> {
+        Some(RadonTypes::Integer(RadonInteger::from(i)))
+    }
+
+    #[test]
+    fn liars_and_non_revealers_are_slashed() {
+        let simulation =
+            simulate_tally(&dr_output(5), vec![int(1), int(1), int(1), int(2), None], 1).unwrap();
+
+        assert_eq!(simulation.tally, int(1).unwrap());
+        let outcomes: Vec<_> = simulation.witnesses.iter().map(|w| w.outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                WitnessOutcome::Honest,
+                WitnessOutcome::Honest,
+                WitnessOutcome::Honest,
+                WitnessOutcome::Liar,
+                WitnessOutcome::NoReveal,
+            ]
+        );
+        // The slashed collateral is shared between the 3 honest witnesses
+        assert_eq!(simulation.witnesses[0].value, 100 + 1000 + 2000 / 3);
+        assert_eq!(simulation.witnesses[3].value, 0);
+        assert_eq!(simulation.witnesses[4].value, 0);
+        // Rewards of the 2 dishonest witnesses, and the reveal fee of the non revealer
+        assert_eq!(simulation.change, 2 * 100 + 10);
+    }
+
+    #[test]
+    fn majority_of_errors() {
+        let error = || {
+            Some(RadonTypes::RadonError(RadonError::new(
+                RadError::HttpStatus { status_code: 404 },
+            )))
+        };
+        let simulation = simulate_tally(&dr_output(3), vec![int(1), error(), error()], 1).unwrap();
+
+        // The mode of the errors is the result of the tally, and nobody is penalized
+        assert_eq!(simulation.tally, error().unwrap());
+        for witness in &simulation.witnesses {
+            assert_eq!(witness.outcome, WitnessOutcome::Honest);
+            assert_eq!(witness.value, 100 + 1000);
+        }
+    }
+
+    #[test]
+    fn too_many_reveals() {
+        assert!(simulate_tally(&dr_output(1), vec![int(1), int(1)], 1).is_err());
+        assert!(simulate_tally(&dr_output(1), vec![], 1).is_err());
+    }
+}
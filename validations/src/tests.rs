@@ -1679,6 +1679,8 @@ fn example_data_request() -> RADRequest {
             kind: RADType::HttpGet,
             url: "https://blockchain.info/q/latesthash".to_string(),
             script: vec![128],
+            body: vec![],
+            headers: vec![],
         }],
         aggregate: RADAggregate {
             filters: vec![],
@@ -1698,6 +1700,8 @@ fn example_data_request_with_mode_filter() -> RADRequest {
             kind: RADType::HttpGet,
             url: "".to_string(),
             script: vec![0x80],
+            body: vec![],
+            headers: vec![],
         }],
         aggregate: RADAggregate {
             filters: vec![],
@@ -1761,6 +1765,8 @@ fn data_request_empty_scripts() {
             kind: RADType::HttpGet,
             url: "".to_string(),
             script: vec![0x80],
+            body: vec![],
+            headers: vec![],
         }],
         aggregate: RADAggregate {
             filters: vec![],
@@ -1780,6 +1786,77 @@ fn data_request_empty_scripts() {
     );
 }
 
+#[test]
+fn data_request_http_get_with_body() {
+    let mut data_request = example_data_request();
+    data_request.retrieve[0].body = b"{\"jsonrpc\":\"2.0\"}".to_vec();
+
+    let x = test_rad_request(data_request);
+    // The data request should be invalid since only HTTP-POST retrievals can have a body
+    assert_eq!(
+        x.unwrap_err().downcast::<DataRequestError>().unwrap(),
+        DataRequestError::UnexpectedRetrievalBody {
+            kind: RADType::HttpGet
+        },
+    );
+}
+
+#[test]
+fn data_request_http_post_with_body_and_headers() {
+    let mut data_request = example_data_request();
+    data_request.retrieve[0].kind = RADType::HttpPost;
+    data_request.retrieve[0].body = b"{\"jsonrpc\":\"2.0\"}".to_vec();
+    data_request.retrieve[0].headers = vec![
+        ("Content-Type".to_string(), "application/json".to_string()),
+        ("X-Api-Key".to_string(), "secret".to_string()),
+    ];
+
+    assert!(validate_rad_request(&data_request).is_ok());
+}
+
+#[test]
+fn data_request_rng() {
+    let mut data_request = example_data_request();
+    data_request.retrieve[0] = RADRetrieve {
+        kind: RADType::Rng,
+        url: "".to_string(),
+        script: vec![0x80],
+        body: vec![],
+        headers: vec![],
+    };
+    data_request.tally.reducer = RadonReducers::HashConcatenate as u32;
+
+    assert!(validate_rad_request(&data_request).is_ok());
+}
+
+#[test]
+fn data_request_rng_with_url() {
+    let mut data_request = example_data_request();
+    data_request.retrieve[0].kind = RADType::Rng;
+
+    let x = test_rad_request(data_request);
+    // RNG retrievals do not fetch anything, so having an URL makes no sense
+    assert_eq!(
+        x.unwrap_err().downcast::<DataRequestError>().unwrap(),
+        DataRequestError::MalformedRngRetrieval,
+    );
+}
+
+#[test]
+fn data_request_invalid_header() {
+    let mut data_request = example_data_request();
+    data_request.retrieve[0].headers = vec![("Content Type".to_string(), "text".to_string())];
+
+    let x = test_rad_request(data_request);
+    assert_eq!(
+        x.unwrap_err().downcast::<RadError>().unwrap(),
+        RadError::InvalidHttpHeader {
+            name: "Content Type".to_string(),
+            value: "text".to_string(),
+        },
+    );
+}
+
 #[test]
 fn data_request_witnesses_0() {
     // A data request with 0 witnesses is invalid
@@ -18,7 +18,7 @@ use witnet_data_structures::{
         Block, BlockMerkleRoots, CheckpointBeacon, CheckpointVRF, ConsensusConstants,
         DataRequestOutput, DataRequestStage, DataRequestState, Epoch, EpochConstants, Hash,
         Hashable, Input, KeyedSignature, OutputPointer, PublicKeyHash, RADRequest, RADTally,
-        Reputation, ReputationEngine, SignaturesToVerify, ValueTransferOutput,
+        RADType, Reputation, ReputationEngine, SignaturesToVerify, ValueTransferOutput,
     },
     data_request::{
         calculate_tally_change, calculate_witness_reward, create_tally, DataRequestPool,
@@ -38,6 +38,7 @@ use witnet_data_structures::{
 };
 use witnet_rad::{
     error::RadError,
+    parse_headers,
     reducers::mode::mode,
     run_tally_report,
     script::{
@@ -245,9 +246,27 @@ pub fn validate_rad_request(rad_request: &RADRequest) -> Result<(), failure::Err
         return Err(DataRequestError::NoRetrievalSources.into());
     }
     for path in retrieval_paths {
-        // If the sources are empty the data request is set as invalid
-        if path.url == "" {
-            return Err(DataRequestError::NoRetrievalSources.into());
+        match path.kind {
+            RADType::HttpGet | RADType::HttpPost => {
+                // If the sources are empty the data request is set as invalid
+                if path.url == "" {
+                    return Err(DataRequestError::NoRetrievalSources.into());
+                }
+                // Only HTTP-POST retrievals are allowed to carry a body
+                if path.kind == RADType::HttpGet && !path.body.is_empty() {
+                    return Err(DataRequestError::UnexpectedRetrievalBody {
+                        kind: path.kind.clone(),
+                    }
+                    .into());
+                }
+                parse_headers(&path.headers)?;
+            }
+            RADType::Rng => {
+                // Randomness is generated locally by each witness, so there is nothing to fetch
+                if !path.url.is_empty() || !path.body.is_empty() || !path.headers.is_empty() {
+                    return Err(DataRequestError::MalformedRngRetrieval.into());
+                }
+            }
         }
         unpack_radon_script(path.script.as_slice())?;
     }
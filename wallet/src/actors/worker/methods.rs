@@ -10,7 +10,7 @@ use crate::types::{
 use crate::{account, constants, crypto, db::Database as _, model, params};
 use witnet_crypto::key::ExtendedSK;
 use witnet_data_structures::chain::ValueTransferOutput;
-use witnet_rad::script::RadonScriptExecutionSettings;
+use witnet_rad::{script::RadonScriptExecutionSettings, transport::RetrievalClient};
 
 use super::*;
 
@@ -25,6 +25,7 @@ impl Worker {
         db: Arc<rocksdb::DB>,
         node: params::NodeParams,
         params: params::Params,
+        retrieval_client: RetrievalClient,
     ) -> Addr<Self> {
         let engine = types::CryptoEngine::new();
         let wallets = Arc::new(repository::Wallets::new(db::PlainDb::new(db.clone())));
@@ -36,11 +37,17 @@ impl Worker {
             params: params.clone(),
             rng: rand::rngs::OsRng,
             engine: engine.clone(),
+            retrieval_client: retrieval_client.clone(),
         })
     }
 
     pub fn run_rad_request(&self, request: types::RADRequest) -> types::RADRequestExecutionReport {
-        witnet_rad::try_data_request(&request, RadonScriptExecutionSettings::enable_all(), None)
+        witnet_rad::try_data_request_with_client(
+            &request,
+            RadonScriptExecutionSettings::enable_all(),
+            None,
+            &self.retrieval_client,
+        )
     }
 
     pub fn gen_mnemonic(&self, length: types::MnemonicLength) -> String {
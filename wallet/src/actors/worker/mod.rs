@@ -3,6 +3,8 @@ use std::sync::Arc;
 
 use actix::prelude::*;
 
+use witnet_rad::transport::RetrievalClient;
+
 use crate::{db, params, repository, types};
 
 pub mod error;
@@ -21,6 +23,7 @@ pub struct Worker {
     params: params::Params,
     engine: types::CryptoEngine,
     rng: rand::rngs::OsRng,
+    retrieval_client: RetrievalClient,
 }
 
 impl Actor for Worker {
@@ -24,6 +24,7 @@ use rand::seq::SliceRandom;
 use witnet_config::config::Config;
 use witnet_data_structures::chain::{CheckpointBeacon, EpochConstants};
 use witnet_net::client::tcp::JsonRpcClient;
+use witnet_rad::transport::RetrievalClient;
 
 use crate::actors::app;
 use crate::actors::app::NodeClient;
@@ -82,6 +83,9 @@ pub fn run(conf: Config) -> Result<(), Error> {
     // Size of the address synchronization batch
     let sync_address_batch_length = conf.wallet.sync_address_batch_length;
 
+    // Transport used for trying data requests
+    let retrieval_client = RetrievalClient::new(&conf.retrieval_transport)?;
+
     let system = System::new("witnet-wallet");
 
     let node_jsonrpc_server_address = conf.jsonrpc.server_address;
@@ -148,7 +152,13 @@ pub fn run(conf: Config) -> Result<(), Error> {
     };
 
     // Start wallet actors
-    let worker = actors::Worker::start(concurrency, db.clone(), node_params, params);
+    let worker = actors::Worker::start(
+        concurrency,
+        db.clone(),
+        node_params,
+        params,
+        retrieval_client,
+    );
     let app = actors::App::start(actors::app::Params {
         testnet,
         worker,
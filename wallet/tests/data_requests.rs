@@ -76,6 +76,8 @@ fn test_data_request_report_json_serialization() {
                 kind: RADType::HttpGet,
                 url: String::from("https://www.bitstamp.net/api/ticker/"),
                 script: vec![130, 24, 119, 130, 24, 100, 100, 108, 97, 115, 116],
+                body: vec![],
+                headers: vec![],
             },
             RADRetrieve {
                 kind: RADType::HttpGet,
@@ -84,6 +86,8 @@ fn test_data_request_report_json_serialization() {
                     132, 24, 119, 130, 24, 102, 99, 98, 112, 105, 130, 24, 102, 99, 85, 83, 68,
                     130, 24, 100, 106, 114, 97, 116, 101, 95, 102, 108, 111, 97, 116,
                 ],
+                body: vec![],
+                headers: vec![],
             },
         ],
         aggregate: RADAggregate {